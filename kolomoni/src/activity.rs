@@ -0,0 +1,330 @@
+//! Tracking of user activity (i.e. keeping `last_active_at` up to date).
+//!
+//! Any request that is successfully authenticated through
+//! [`UserAuthenticationExtractor`][crate::authentication::UserAuthenticationExtractor]
+//! counts as activity. The extractor marks such requests (see [`mark_request_as_authenticated`]),
+//! and the [`UserActivityTracking`] middleware then records the activity
+//! in the application-wide [`UserActivityTracker`].
+//!
+//! The tracker throttles updates per user and periodically writes them
+//! to the database in a single batch, so authenticated requests don't
+//! cause a database write each.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{HttpMessage, HttpRequest};
+use chrono::{DateTime, Utc};
+use futures_util::future::{self, LocalBoxFuture, Ready};
+use futures_util::FutureExt;
+use kolomoni_configuration::ActivityTrackingConfiguration;
+use kolomoni_database::mutation::UserMutation;
+use sea_orm::DatabaseConnection;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, error};
+
+use crate::state::ApplicationState;
+
+
+/// Request extension marking the request as authenticated by the given user.
+#[derive(Clone, Copy, Debug)]
+struct AuthenticatedRequestUser {
+    user_id: i32,
+}
+
+/// Marks the request as authenticated by the given user,
+/// which will make [`UserActivityTracking`] record their activity.
+pub(crate) fn mark_request_as_authenticated(request: &HttpRequest, user_id: i32) {
+    request
+        .extensions_mut()
+        .insert(AuthenticatedRequestUser { user_id });
+}
+
+
+
+struct UserActivityTrackerState {
+    /// How often each user's activity is recorded at most.
+    update_interval: Duration,
+
+    /// When each user's activity was last recorded (used for throttling).
+    last_recorded_at: HashMap<i32, DateTime<Utc>>,
+
+    /// Activity times that have not been written to the database yet.
+    pending_updates: HashMap<i32, DateTime<Utc>>,
+}
+
+impl UserActivityTrackerState {
+    fn new(update_interval: Duration) -> Self {
+        Self {
+            update_interval,
+            last_recorded_at: HashMap::new(),
+            pending_updates: HashMap::new(),
+        }
+    }
+
+    /// Records that the given user was active at `now`, unless their activity
+    /// has already been recorded within the last `update_interval`.
+    fn record_activity(&mut self, user_id: i32, now: DateTime<Utc>) {
+        if let Some(last_recorded_at) = self.last_recorded_at.get(&user_id) {
+            if (now - *last_recorded_at).to_std().unwrap_or_default() < self.update_interval {
+                return;
+            }
+        }
+
+        self.last_recorded_at.insert(user_id, now);
+        self.pending_updates.insert(user_id, now);
+    }
+
+    /// Takes all pending activity updates, additionally forgetting
+    /// about users whose throttling interval has passed.
+    fn take_pending_updates(&mut self, now: DateTime<Utc>) -> Vec<(i32, DateTime<Utc>)> {
+        let update_interval = self.update_interval;
+        self.last_recorded_at.retain(|_, last_recorded_at| {
+            (now - *last_recorded_at).to_std().unwrap_or_default() < update_interval
+        });
+
+        self.pending_updates.drain().collect()
+    }
+
+    /// Puts back updates that could not be written to the database, so they are retried
+    /// on the next flush. Activity recorded in the meantime takes precedence, as it is newer.
+    fn requeue_updates(&mut self, updates: Vec<(i32, DateTime<Utc>)>) {
+        for (user_id, active_at) in updates {
+            self.pending_updates.entry(user_id).or_insert(active_at);
+        }
+    }
+}
+
+
+/// Keeps track of user activity and periodically writes
+/// the last activity times to the database.
+///
+/// The background flushing task is aborted when this is dropped,
+/// so [`Self::flush`] should be called before that to avoid losing recent activity.
+pub struct UserActivityTracker {
+    state: Arc<Mutex<UserActivityTrackerState>>,
+
+    database: DatabaseConnection,
+
+    flush_task_handle: JoinHandle<()>,
+}
+
+impl UserActivityTracker {
+    /// Initialize a new [`UserActivityTracker`], starting a background async task
+    /// that writes recorded activity to the database every `flush_interval`.
+    pub fn start(
+        configuration: &ActivityTrackingConfiguration,
+        database: DatabaseConnection,
+    ) -> Self {
+        let state = Arc::new(Mutex::new(UserActivityTrackerState::new(
+            configuration.update_interval,
+        )));
+
+        let flush_task_handle = tokio::spawn(Self::flush_loop(
+            state.clone(),
+            database.clone(),
+            configuration.flush_interval,
+        ));
+
+        Self {
+            state,
+            database,
+            flush_task_handle,
+        }
+    }
+
+    /// Records that the given user has just been active.
+    ///
+    /// If the user's activity has already been recorded within the last `update_interval`,
+    /// this does nothing. Otherwise, the activity time will be written to the database
+    /// the next time pending updates are flushed.
+    pub async fn record_activity(&self, user_id: i32) {
+        self.state.lock().await.record_activity(user_id, Utc::now());
+    }
+
+    /// Immediately writes all pending activity updates to the database
+    /// (e.g. when the server is shutting down).
+    pub async fn flush(&self) {
+        Self::flush_pending_updates(&self.state, &self.database).await;
+    }
+
+    /// Writes all pending activity updates to the database. If that fails,
+    /// the updates are put back, so they can be retried on the next flush.
+    async fn flush_pending_updates(
+        state: &Mutex<UserActivityTrackerState>,
+        database: &DatabaseConnection,
+    ) {
+        let pending_updates = state.lock().await.take_pending_updates(Utc::now());
+        if pending_updates.is_empty() {
+            return;
+        }
+
+        debug!(
+            num_updates = pending_updates.len(),
+            "Writing user activity times to the database."
+        );
+
+        if let Err(error) =
+            UserMutation::update_last_active_at_in_bulk(database, &pending_updates).await
+        {
+            error!(
+                error = error.to_string(),
+                "Failed to write user activity times to the database, will retry."
+            );
+
+            state.lock().await.requeue_updates(pending_updates);
+        }
+    }
+
+    /// The main flushing loop. This task is spawned inside [`Self::start`].
+    async fn flush_loop(
+        state: Arc<Mutex<UserActivityTrackerState>>,
+        database: DatabaseConnection,
+        flush_interval: Duration,
+    ) {
+        let mut interval = tokio::time::interval(flush_interval);
+
+        loop {
+            interval.tick().await;
+
+            Self::flush_pending_updates(&state, &database).await;
+        }
+    }
+}
+
+impl Drop for UserActivityTracker {
+    fn drop(&mut self) {
+        self.flush_task_handle.abort();
+    }
+}
+
+
+
+/// Actix middleware that records activity for every request that has been
+/// authenticated through
+/// [`UserAuthenticationExtractor`][crate::authentication::UserAuthenticationExtractor].
+pub struct UserActivityTracking;
+
+impl<S, B> Transform<S, ServiceRequest> for UserActivityTracking
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = UserActivityTrackingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ok(UserActivityTrackingMiddleware { service })
+    }
+}
+
+
+pub struct UserActivityTrackingMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for UserActivityTrackingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let response_future = self.service.call(request);
+
+        async move {
+            let response = response_future.await?;
+
+            let authenticated_user = response
+                .request()
+                .extensions()
+                .get::<AuthenticatedRequestUser>()
+                .copied();
+
+            if let Some(authenticated_user) = authenticated_user {
+                match response.request().app_data::<ApplicationState>().cloned() {
+                    Some(state) => {
+                        state
+                            .activity_tracker
+                            .record_activity(authenticated_user.user_id)
+                            .await
+                    }
+                    None => {
+                        error!("BUG: No AppState injected, can't record user activity.");
+                    }
+                }
+            }
+
+            Ok(response)
+        }
+        .boxed_local()
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn activity_inside_update_interval_is_not_recorded_again() {
+        let mut state = UserActivityTrackerState::new(Duration::from_secs(60));
+
+        state.record_activity(1, time(0));
+        state.record_activity(1, time(30));
+        state.record_activity(2, time(30));
+
+        let mut updates = state.take_pending_updates(time(45));
+        updates.sort();
+
+        assert_eq!(updates, vec![(1, time(0)), (2, time(30))]);
+
+
+        // Still inside the interval of the first recording, even after a flush.
+        state.record_activity(1, time(59));
+        assert!(state.take_pending_updates(time(59)).is_empty());
+
+        state.record_activity(1, time(61));
+        assert_eq!(
+            state.take_pending_updates(time(61)),
+            vec![(1, time(61))]
+        );
+    }
+
+    #[test]
+    fn requeued_updates_do_not_overwrite_newer_activity() {
+        let mut state = UserActivityTrackerState::new(Duration::from_secs(60));
+
+        state.record_activity(1, time(0));
+        state.record_activity(2, time(0));
+        let failed_updates = state.take_pending_updates(time(10));
+
+        state.record_activity(1, time(70));
+        state.requeue_updates(failed_updates);
+
+        let mut updates = state.take_pending_updates(time(80));
+        updates.sort();
+
+        assert_eq!(updates, vec![(1, time(70)), (2, time(0))]);
+    }
+}
@@ -429,6 +429,10 @@ macro_rules! require_permission_with_optional_authentication {
 /// The early return essentially generates a `403 Forbidden` with a JSON-encoded reason
 /// in the body of the response (see [`APIError`] for more information).
 ///
+/// If the user authenticated with a personal API token, the permission must be
+/// granted by both the user's current roles *and* the token's scope
+/// (see [`AuthenticatedUser::permissions`][crate::authentication::AuthenticatedUser::permissions]).
+///
 /// # Arguments and examples
 /// ## Variant 1 (three arguments, most common)
 /// - The first argument must be the [`ApplicationState`][crate::state::ApplicationState].
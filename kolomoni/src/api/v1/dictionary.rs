@@ -2,7 +2,8 @@ use std::str::FromStr;
 
 use actix_web::{web, Scope};
 use chrono::{DateTime, Utc};
-use kolomoni_database::entities;
+use kolomoni_configuration::DictionaryConfiguration;
+use kolomoni_database::{entities, query::WordPageOptions};
 use miette::IntoDiagnostic;
 use sea_orm::prelude::Uuid;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,11 @@ use utoipa::ToSchema;
 use self::{
     categories::categories_router,
     english_word::english_dictionary_router,
+    export::export_router,
+    import::import_router,
+    links::links_router,
+    references::references_router,
+    revisions::revisions_router,
     search::search_router,
     slovene_word::slovene_dictionary_router,
     suggestions::suggested_translations_router,
@@ -20,6 +26,12 @@ use crate::api::errors::APIError;
 
 pub mod categories;
 pub mod english_word;
+pub mod export;
+pub mod grammar;
+pub mod import;
+pub mod links;
+pub mod references;
+pub mod revisions;
 pub mod search;
 pub mod slovene_word;
 pub mod suggestions;
@@ -60,6 +72,44 @@ pub fn parse_string_into_uuid(potential_uuid: &str) -> Result<Uuid, APIError> {
 }
 
 
+/// Treats an empty (or whitespace-only) disambiguation as no disambiguation at all,
+/// so that homonyms can't be told apart by an invisible difference.
+pub fn normalize_disambiguation(disambiguation: String) -> Option<String> {
+    match disambiguation.trim().is_empty() {
+        true => None,
+        false => Some(disambiguation),
+    }
+}
+
+
+/// Validates the pagination fields of a word listing request and turns them
+/// into [`WordPageOptions`], falling back to the configured default page size.
+///
+/// The cursor is the `next_cursor` of the previous page, i.e. the ID of its last word.
+pub fn parse_word_page_options(
+    configuration: &DictionaryConfiguration,
+    page_size: Option<u64>,
+    cursor: Option<&str>,
+) -> Result<WordPageOptions, APIError> {
+    let page_size = page_size.unwrap_or(configuration.default_page_size);
+    if page_size == 0 || page_size > configuration.maximum_page_size {
+        return Err(APIError::client_error(format!(
+            "Page size must be between 1 and {}.",
+            configuration.maximum_page_size
+        )));
+    }
+
+    let after_word_id = cursor
+        .map(|cursor| Uuid::from_str(cursor).map_err(|_| APIError::client_error("Invalid cursor.")))
+        .transpose()?;
+
+    Ok(WordPageOptions {
+        after_word_id,
+        page_size,
+    })
+}
+
+
 #[rustfmt::skip]
 pub fn dictionary_router() -> Scope {
     web::scope("/dictionary")
@@ -67,6 +117,11 @@ pub fn dictionary_router() -> Scope {
         .service(english_dictionary_router())
         .service(suggested_translations_router())
         .service(translations_router())
+        .service(links_router())
+        .service(references_router())
         .service(categories_router())
         .service(search_router())
+        .service(import_router())
+        .service(export_router())
+        .service(revisions_router())
 }
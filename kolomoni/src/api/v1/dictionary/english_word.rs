@@ -1,5 +1,5 @@
 use actix_http::StatusCode;
-use actix_web::{delete, get, patch, post, web, HttpResponse, Scope};
+use actix_web::{delete, get, patch, post, web, Scope};
 use chrono::{DateTime, Utc};
 use kolomoni_auth::Permission;
 use kolomoni_database::{
@@ -15,16 +15,32 @@ use kolomoni_database::{
 };
 use miette::Result;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 use utoipa::ToSchema;
 
-use super::{slovene_word::SloveneWord, Category};
+use super::{
+    grammar::{validate_english_word_grammar, EnglishWordGrammar},
+    links::WordLink,
+    references::{
+        render_description_if_requested,
+        validate_description_references,
+        words_referencing_word,
+        ReferencedWord,
+        WordDescriptionRenderingQuery,
+    },
+    slovene_word::SloveneWord,
+    Category,
+};
 use crate::{
     api::{
         errors::{APIError, EndpointResult},
         macros::ContextlessResponder,
         openapi,
-        v1::dictionary::parse_string_into_uuid,
+        v1::dictionary::{
+            normalize_disambiguation,
+            parse_string_into_uuid,
+            parse_word_page_options,
+        },
     },
     authentication::UserAuthenticationExtractor,
     error_response_with_reason,
@@ -33,6 +49,7 @@ use crate::{
     require_permission,
     require_permission_with_optional_authentication,
     state::ApplicationState,
+    word_references::update_word_references,
 };
 
 
@@ -44,6 +61,11 @@ use crate::{
         "lemma": "adventurer",
         "disambiguation": "character",
         "description": "Playable or non-playable character.",
+        "grammar": {
+            "part_of_speech": "noun",
+            "number_restriction": null,
+            "plural_form": "adventurers"
+        },
         "created_at": "2023-06-27T20:34:27.217273Z",
         "last_modified_at": "2023-06-27T20:34:27.217273Z",
         "suggested_translations": [],
@@ -56,6 +78,14 @@ use crate::{
                 "created_at": "2023-06-27T20:34:27.217273Z",
                 "last_modified_at": "2023-06-27T20:34:27.217273Z"
             }
+        ],
+        "links": [
+            {
+                "link_type": "synonym",
+                "word_id": "018dbe00-266e-7398-abd2-0906df0aa347",
+                "lemma": "hero",
+                "disambiguation": "character"
+            }
         ]
     })
 )]
@@ -70,12 +100,18 @@ pub struct EnglishWord {
     /// helps distinguish the word from other words at a glance.
     pub disambiguation: Option<String>,
 
-    /// A short description of the word. Supports Markdown.
-    ///
-    /// TODO Will need special Markdown support for linking to other dictionary words
-    ///      and possibly autocomplete in the frontend editor.
+    /// A short description of the word. Supports Markdown and references
+    /// to other dictionary words, e.g. `[[en:adventurer]]` or `[[sl:pustolovec|character]]`.
     pub description: Option<String>,
 
+    /// The description rendered into sanitised HTML.
+    /// Only present if it was requested with the `render_description` query parameter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_html: Option<String>,
+
+    /// Grammatical information about the word (e.g. its part of speech).
+    pub grammar: EnglishWordGrammar,
+
     /// When the word was created.
     pub created_at: DateTime<Utc>,
 
@@ -92,20 +128,28 @@ pub struct EnglishWord {
 
     /// Slovene translations of this word.
     pub translations: Vec<SloveneWord>,
+
+    /// Other english words this word is linked to (e.g. synonyms).
+    pub links: Vec<WordLink>,
 }
 
 impl EnglishWord {
     pub fn new_without_expanded_info(english_model: entities::word_english::Model) -> Self {
+        let grammar = EnglishWordGrammar::from_database_grammar(english_model.grammar());
+
         Self {
             id: english_model.word_id.to_string(),
             lemma: english_model.lemma,
             disambiguation: english_model.disambiguation,
             description: english_model.description,
+            description_html: None,
+            grammar,
             created_at: english_model.created_at.to_utc(),
             last_modified_at: english_model.last_modified_at.to_utc(),
             categories: Vec::new(),
             suggested_translations: Vec::new(),
             translations: Vec::new(),
+            links: Vec::new(),
         }
     }
 
@@ -131,17 +175,28 @@ impl EnglishWord {
             .map(SloveneWord::from_expanded_word_info)
             .collect();
 
+        let links = related_english_word_info
+            .links
+            .into_iter()
+            .map(WordLink::from_english_word_link)
+            .collect();
+
+        let grammar = EnglishWordGrammar::from_database_grammar(word_model.grammar());
+
 
         Self {
             id: word_model.word_id.to_string(),
             lemma: word_model.lemma,
             disambiguation: word_model.disambiguation,
             description: word_model.description,
+            description_html: None,
+            grammar,
             created_at: word_model.created_at.to_utc(),
             last_modified_at: word_model.last_modified_at.to_utc(),
             categories,
             suggested_translations,
             translations,
+            links,
         }
     }
 
@@ -164,17 +219,29 @@ impl EnglishWord {
             .map(SloveneWord::from_expanded_word_info)
             .collect();
 
+        let links = expanded_english_word_info
+            .links
+            .into_iter()
+            .map(WordLink::from_english_word_link)
+            .collect();
+
+        let grammar =
+            EnglishWordGrammar::from_database_grammar(expanded_english_word_info.word.grammar());
+
 
         Self {
             id: expanded_english_word_info.word.word_id.to_string(),
             lemma: expanded_english_word_info.word.lemma,
             disambiguation: expanded_english_word_info.word.disambiguation,
             description: expanded_english_word_info.word.description,
+            description_html: None,
+            grammar,
             created_at: expanded_english_word_info.word.created_at.to_utc(),
             last_modified_at: expanded_english_word_info.word.last_modified_at.to_utc(),
             categories,
             suggested_translations,
             translations,
+            links,
         }
     }
 }
@@ -184,6 +251,10 @@ impl EnglishWord {
 #[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
 pub struct EnglishWordsResponse {
     pub english_words: Vec<EnglishWord>,
+
+    /// Cursor to pass as `cursor` to get the next page of words
+    /// (`null` if this is the last page).
+    pub next_cursor: Option<String>,
 }
 
 impl_json_response_builder!(EnglishWordsResponse);
@@ -197,16 +268,27 @@ pub struct EnglishWordFilters {
 }
 
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
 #[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
 pub struct EnglishWordsListRequest {
     pub filters: Option<EnglishWordFilters>,
+
+    /// Amount of words on the page (the server's configured default if omitted).
+    pub page_size: Option<u64>,
+
+    /// The `next_cursor` value from the previous page.
+    pub cursor: Option<String>,
 }
 
 
 /// List all english words
 ///
-/// This endpoint returns a list of all english words.
+/// This endpoint returns a page of english words, ordered by their ID
+/// (which is roughly the order in which they were created).
+///
+/// The list is paginated with cursors: to get the next page, repeat the request
+/// with the `cursor` field set to `next_cursor` from the previous response.
+/// When `next_cursor` is `null`, there are no more words.
 ///
 /// # Authentication
 /// Authentication is *not required* on this endpoint due to blanket grant of
@@ -221,9 +303,15 @@ pub struct EnglishWordsListRequest {
     responses(
         (
             status = 200,
-            description = "A list of all english words.",
+            description = "A page of english words.",
             body = EnglishWordsResponse,
         ),
+        (
+            status = 400,
+            description = "Invalid page size or cursor.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid cursor." })
+        ),
         openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
         openapi::InternalServerErrorResponse,
     )
@@ -238,26 +326,34 @@ pub async fn get_all_english_words(
 
 
 
-    let word_query_options = match request_body {
-        Some(body) => {
-            let body = body.into_inner();
+    let request_body = request_body
+        .map(|body| body.into_inner())
+        .unwrap_or_default();
 
-            match body.filters {
-                Some(filters) => EnglishWordsQueryOptions {
-                    only_words_modified_after: filters.last_modified_after,
-                },
-                None => EnglishWordsQueryOptions::default(),
-            }
-        }
+    let word_query_options = match request_body.filters {
+        Some(filters) => EnglishWordsQueryOptions {
+            only_words_modified_after: filters.last_modified_after,
+        },
         None => EnglishWordsQueryOptions::default(),
     };
 
-    let words_with_additional_info =
-        query::EnglishWordQuery::all_words_expanded(&state.database, word_query_options)
-            .await
-            .map_err(APIError::InternalError)?;
+    let page_options = parse_word_page_options(
+        &state.configuration.dictionary,
+        request_body.page_size,
+        request_body.cursor.as_deref(),
+    )?;
+
+
+    let words_page = query::EnglishWordQuery::all_words_expanded_paginated(
+        &state.database,
+        word_query_options,
+        page_options,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
-    let words_as_api_structures = words_with_additional_info
+    let words_as_api_structures = words_page
+        .words
         .into_iter()
         .map(EnglishWord::from_expanded_word_info)
         .collect();
@@ -265,6 +361,9 @@ pub async fn get_all_english_words(
 
     Ok(EnglishWordsResponse {
         english_words: words_as_api_structures,
+        next_cursor: words_page
+            .next_cursor
+            .map(|word_id| word_id.to_string()),
     }
     .into_response())
 }
@@ -278,12 +377,22 @@ pub async fn get_all_english_words(
         "lemma": "adventurer",
         "disambiguation": "character",
         "description": "Playable or non-playable character.",
+        "grammar": {
+            "part_of_speech": "noun",
+            "plural_form": "adventurers"
+        }
     })
 )]
 pub struct EnglishWordCreationRequest {
     pub lemma: String,
+
+    /// An empty disambiguation is treated the same as a missing one.
     pub disambiguation: Option<String>,
+
     pub description: Option<String>,
+
+    /// Grammatical information about the word (none if not provided).
+    pub grammar: Option<EnglishWordGrammar>,
 }
 
 
@@ -312,6 +421,9 @@ impl_json_response_builder!(EnglishWordCreationResponse);
 ///
 /// This endpoint creates a new english word in the dictionary.
 ///
+/// The description can reference other dictionary words (e.g. `[[sl:pustolovec]]`),
+/// but only words that already exist.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:create` permission.
 #[utoipa::path(
@@ -327,11 +439,18 @@ impl_json_response_builder!(EnglishWordCreationResponse);
             description = "The newly-created english word.",
             body = EnglishWordCreationResponse,
         ),
+        (
+            status = 400,
+            description = "The description contains an invalid reference or references a word \
+                           that does not exist, or the grammatical information is inconsistent.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid description: the referenced word [[sl:pustolovec]] does not exist." })
+        ),
         (
             status = 409,
-            description = "English word with the given lemma already exists.",
+            description = "English word with the given lemma and disambiguation already exists.",
             body = ErrorReasonResponse,
-            example = json!({ "reason": "An english word with the given lemma already exists." })
+            example = json!({ "reason": "An english word with the given lemma and disambiguation already exists." })
         ),
         openapi::MissingOrInvalidJsonRequestBodyResponse,
         openapi::FailedAuthenticationResponses<openapi::RequiresWordCreate>,
@@ -352,28 +471,56 @@ pub async fn create_english_word(
 
 
     let creation_request = creation_request.into_inner();
+    let disambiguation = creation_request
+        .disambiguation
+        .and_then(normalize_disambiguation);
 
 
-    let lemma_already_exists =
-        EnglishWordQuery::word_exists_by_lemma(&state.database, creation_request.lemma.clone())
-            .await
-            .map_err(APIError::InternalError)?;
+    let word_already_exists = EnglishWordQuery::word_exists_by_lemma_and_disambiguation(
+        &state.database,
+        creation_request.lemma.clone(),
+        disambiguation.clone(),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
-    if lemma_already_exists {
+    if word_already_exists {
         return Ok(error_response_with_reason!(
             StatusCode::CONFLICT,
-            "An english word with the given lemma already exists."
+            "An english word with the given lemma and disambiguation already exists."
         ));
     }
 
+    validate_description_references(&state, creation_request.description.as_deref()).await?;
+
+    let grammar = validate_english_word_grammar(creation_request.grammar.unwrap_or_default())?;
+
 
     let newly_created_word = EnglishWordMutation::create(
         &state.database,
         NewEnglishWord {
             lemma: creation_request.lemma,
-            disambiguation: creation_request.disambiguation,
+            disambiguation,
             description: creation_request.description,
+            grammar,
         },
+        Some(authenticated_user.user_id()),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    // The word may have been created concurrently since the check above.
+    let Some(newly_created_word) = newly_created_word else {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "An english word with the given lemma and disambiguation already exists."
+        ));
+    };
+
+    update_word_references(
+        &state.database,
+        newly_created_word.word_id,
+        newly_created_word.description.as_deref(),
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -416,6 +563,10 @@ impl_json_response_builder!(EnglishWordInfoResponse);
 ///
 /// This endpoint returns information about a single english word from the dictionary.
 ///
+/// If the `render_description` query parameter is `true`, the word will also include
+/// its description rendered into sanitised HTML, with references to other words
+/// turned into `<a class="word-reference" data-word-id="..." data-language="...">` elements.
+///
 /// # Authentication
 /// Authentication is *not required* on this endpoint due to a blanket grant of
 /// the `word:read` permission to unauthenticated users.
@@ -428,6 +579,11 @@ impl_json_response_builder!(EnglishWordInfoResponse);
             "word_uuid" = String,
             Path,
             description = "UUID of the english word."
+        ),
+        (
+            "render_description" = Option<bool>,
+            Query,
+            description = "Whether to also return the description rendered into HTML (`false` by default)."
         )
     ),
     responses(
@@ -455,6 +611,7 @@ pub async fn get_specific_english_word(
     state: ApplicationState,
     authentication: UserAuthenticationExtractor,
     parameters: web::Path<(String,)>,
+    rendering_query: web::Query<WordDescriptionRenderingQuery>,
 ) -> EndpointResult {
     require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
 
@@ -471,18 +628,35 @@ pub async fn get_specific_english_word(
     };
 
 
-    Ok(EnglishWordInfoResponse {
-        word: EnglishWord::from_expanded_word_info(target_word),
-    }
-    .into_response())
+    let mut word = EnglishWord::from_expanded_word_info(target_word);
+    word.description_html = render_description_if_requested(
+        &state,
+        rendering_query.into_inner(),
+        word.description.as_deref(),
+    )
+    .await?;
+
+
+    Ok(EnglishWordInfoResponse { word }.into_response())
+}
+
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct EnglishWordsByLemmaResponse {
+    /// All english words with the requested lemma (homonyms), oldest first.
+    pub english_words: Vec<EnglishWord>,
 }
 
+impl_json_response_builder!(EnglishWordsByLemmaResponse);
 
 
-/// Find an english word by lemma
+/// Find english words by lemma
 ///
-/// This endpoint returns information about a single english word from the dictionary,
-/// but takes a lemma as a parameter instead of the word ID.
+/// This endpoint returns information about all english words from the dictionary
+/// with the given lemma. There can be more than one such word (homonyms),
+/// in which case they are distinguished by their disambiguation.
 ///
 /// Note that this is *not* intended as a search endpoint!
 ///
@@ -498,17 +672,22 @@ pub async fn get_specific_english_word(
             "word_lemma" = String,
             Path,
             description = "English word lemma to look up."
+        ),
+        (
+            "render_description" = Option<bool>,
+            Query,
+            description = "Whether to also return the descriptions rendered into HTML (`false` by default)."
         )
     ),
     responses(
         (
             status = 200,
-            description = "Information about the requested english word.",
-            body = EnglishWordInfoResponse,
+            description = "Information about all english words with the requested lemma.",
+            body = EnglishWordsByLemmaResponse,
         ),
         (
             status = 404,
-            description = "The requested english word does not exist."
+            description = "No english word with the requested lemma exists."
         ),
         openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
         openapi::InternalServerErrorResponse,
@@ -519,43 +698,79 @@ pub async fn get_specific_english_word_by_lemma(
     state: ApplicationState,
     authentication: UserAuthenticationExtractor,
     parameters: web::Path<(String,)>,
+    rendering_query: web::Query<WordDescriptionRenderingQuery>,
 ) -> EndpointResult {
     require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
 
 
     let target_word_lemma = parameters.into_inner().0;
+    let rendering_query = rendering_query.into_inner();
 
-    let target_word = EnglishWordQuery::expanded_word_by_lemma(&state.database, target_word_lemma)
-        .await
-        .map_err(APIError::InternalError)?;
+    let target_words =
+        EnglishWordQuery::expanded_words_by_lemma(&state.database, target_word_lemma)
+            .await
+            .map_err(APIError::InternalError)?;
 
-    let Some(target_word) = target_word else {
+    if target_words.is_empty() {
         return Err(APIError::not_found());
-    };
+    }
+
 
+    let mut english_words = Vec::with_capacity(target_words.len());
+    for target_word in target_words {
+        let mut word = EnglishWord::from_expanded_word_info(target_word);
+        word.description_html =
+            render_description_if_requested(&state, rendering_query, word.description.as_deref())
+                .await?;
 
-    Ok(EnglishWordInfoResponse {
-        word: EnglishWord::from_expanded_word_info(target_word),
+        english_words.push(word);
     }
-    .into_response())
+
+
+    Ok(EnglishWordsByLemmaResponse { english_words }.into_response())
 }
 
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
 pub struct EnglishWordUpdateRequest {
     pub lemma: Option<String>,
+
+    /// An empty disambiguation removes the word's disambiguation.
     pub disambiguation: Option<String>,
+
     pub description: Option<String>,
+
+    /// If set, replaces all grammatical information about the word.
+    pub grammar: Option<EnglishWordGrammar>,
 }
 
 impl_json_response_builder!(EnglishWordUpdateRequest);
 
 
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct EnglishWordUpdateResponse {
+    pub word: EnglishWord,
+
+    /// Words whose descriptions reference this word by its previous lemma and disambiguation.
+    /// Their references no longer resolve and should be updated.
+    ///
+    /// This is always empty if neither the lemma nor the disambiguation were changed.
+    pub broken_references: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(EnglishWordUpdateResponse);
+
+
 
 /// Update an english word
 ///
 /// This endpoint updates an existing english word in the dictionary.
 ///
+/// If the lemma or disambiguation are changed, references to this word in descriptions
+/// of other words (e.g. `[[en:adventurer]]`) will no longer resolve. Such words are listed
+/// in the `broken_references` field of the response.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:update` permission.
 #[utoipa::path(
@@ -576,11 +791,12 @@ impl_json_response_builder!(EnglishWordUpdateRequest);
         (
             status = 200,
             description = "Updated english word.",
-            body = EnglishWordInfoResponse,
+            body = EnglishWordUpdateResponse,
         ),
         (
             status = 400,
-            description = "Invalid word UUID provided.",
+            description = "Invalid word UUID, a description with an invalid reference \
+                           or inconsistent grammatical information provided.",
             body = ErrorReasonResponse,
             example = json!({ "reason": "Client error: invalid UUID." })
         ),
@@ -588,6 +804,12 @@ impl_json_response_builder!(EnglishWordUpdateRequest);
             status = 404,
             description = "The requested english word does not exist."
         ),
+        (
+            status = 409,
+            description = "Another english word with the new lemma and disambiguation already exists.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "An english word with the given lemma and disambiguation already exists." })
+        ),
         openapi::MissingOrInvalidJsonRequestBodyResponse,
         openapi::FailedAuthenticationResponses<openapi::RequiresWordUpdate>,
         openapi::InternalServerErrorResponse,
@@ -612,28 +834,106 @@ pub async fn update_specific_english_word(
     let request_data = request_data.into_inner();
 
 
-    let target_word_exists =
-        EnglishWordQuery::word_exists_by_uuid(&state.database, target_word_uuid)
-            .await
-            .map_err(APIError::InternalError)?;
+    let target_word = EnglishWordQuery::word_by_uuid(&state.database, target_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
 
-    if !target_word_exists {
+    let Some(target_word) = target_word else {
         return Err(APIError::not_found());
+    };
+
+    // An empty disambiguation becomes `Some(None)`, which removes it.
+    let disambiguation = request_data
+        .disambiguation
+        .map(normalize_disambiguation);
+
+    if request_data.lemma.is_some() || disambiguation.is_some() {
+        let conflicting_word = EnglishWordQuery::word_by_lemma_and_disambiguation(
+            &state.database,
+            request_data
+                .lemma
+                .clone()
+                .unwrap_or_else(|| target_word.lemma.clone()),
+            match &disambiguation {
+                Some(new_disambiguation) => new_disambiguation.clone(),
+                None => target_word.disambiguation.clone(),
+            },
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        if conflicting_word.is_some_and(|word| word.word_id != target_word_uuid) {
+            return Ok(error_response_with_reason!(
+                StatusCode::CONFLICT,
+                "An english word with the given lemma and disambiguation already exists."
+            ));
+        }
     }
 
+    validate_description_references(&state, request_data.description.as_deref()).await?;
+
+    let grammar = request_data
+        .grammar
+        .map(validate_english_word_grammar)
+        .transpose()?;
+
+    let description_changed = request_data.description.is_some();
+
 
     let updated_model = EnglishWordMutation::update(
         &state.database,
         target_word_uuid,
         UpdatedEnglishWord {
             lemma: request_data.lemma,
-            disambiguation: request_data.disambiguation,
+            disambiguation,
             description: request_data.description,
+            grammar,
         },
+        Some(authenticated_user.user_id()),
     )
     .await
     .map_err(APIError::InternalError)?;
 
+    // Another word may have taken the lemma and disambiguation since the check above.
+    let Some(updated_model) = updated_model else {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "An english word with the given lemma and disambiguation already exists."
+        ));
+    };
+
+    if description_changed {
+        update_word_references(
+            &state.database,
+            target_word_uuid,
+            updated_model.description.as_deref(),
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+    }
+
+
+    // References point to a lemma and disambiguation, so changing either of them breaks them.
+    let broken_references = if updated_model.lemma != target_word.lemma
+        || updated_model.disambiguation != target_word.disambiguation
+    {
+        words_referencing_word(&state, target_word_uuid).await?
+    } else {
+        Vec::new()
+    };
+
+    if !broken_references.is_empty() {
+        warn!(
+            word_id = target_word_uuid.to_string(),
+            previous_disambiguation = ?target_word.disambiguation,
+            new_disambiguation = ?updated_model.disambiguation,
+            "Renaming english word {} to {} broke references from {} other word(s).",
+            target_word.lemma,
+            updated_model.lemma,
+            broken_references.len()
+        );
+    }
+
 
     let target_word_additional_info =
         EnglishWordQuery::related_word_information_only(&state.database, target_word_uuid)
@@ -650,18 +950,33 @@ pub async fn update_specific_english_word(
         .map_err(APIError::InternalError)?;
 
 
-    Ok(EnglishWordInfoResponse {
+    Ok(EnglishWordUpdateResponse {
         word: EnglishWord::from_word_and_related_info(updated_model, target_word_additional_info),
+        broken_references,
     }
     .into_response())
 }
 
 
 
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct EnglishWordDeletionResponse {
+    /// Words whose descriptions referenced the deleted word.
+    /// Their references no longer resolve and should be updated.
+    pub broken_references: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(EnglishWordDeletionResponse);
+
+
 /// Delete an english word
 ///
 /// This endpoint deletes an english word from the dictionary.
 ///
+/// References to this word in descriptions of other words will no longer resolve.
+/// Such words are listed in the `broken_references` field of the response.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:delete` permission.
 #[utoipa::path(
@@ -679,6 +994,7 @@ pub async fn update_specific_english_word(
         (
             status = 200,
             description = "English word deleted.",
+            body = EnglishWordDeletionResponse,
         ),
         (
             status = 400,
@@ -718,11 +1034,22 @@ pub async fn delete_specific_english_word(
         return Err(APIError::not_found());
     }
 
+    // The references are removed along with the word, so they need to be looked up beforehand.
+    let broken_references = words_referencing_word(&state, target_word_uuid).await?;
+
 
     WordMutation::delete(&state.database, target_word_uuid)
         .await
         .map_err(APIError::InternalError)?;
 
+    if !broken_references.is_empty() {
+        warn!(
+            word_id = target_word_uuid.to_string(),
+            "Deleting english word broke references from {} other word(s).",
+            broken_references.len()
+        );
+    }
+
 
 
     // Signals to the the search indexer that the word has been removed.
@@ -733,14 +1060,11 @@ pub async fn delete_specific_english_word(
         .map_err(APIError::InternalError)?;
 
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(EnglishWordDeletionResponse { broken_references }.into_response())
 }
 
 
 
-// TODO Links.
-
-
 #[rustfmt::skip]
 pub fn english_dictionary_router() -> Scope {
     web::scope("/english")
@@ -0,0 +1,700 @@
+use std::collections::HashSet;
+
+use actix_web::{
+    get,
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::{self, Bytes},
+    HttpResponse,
+    Scope,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures_util::{stream, Stream};
+use kolomoni_auth::Permission;
+use kolomoni_database::query::{
+    CategoriesQueryOptions,
+    CategoryQuery,
+    EnglishWordQuery,
+    EnglishWordsQueryOptions,
+    SloveneWordQuery,
+    SloveneWordsQueryOptions,
+    WordPageOptions,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{english_word::EnglishWord, slovene_word::SloveneWord, Category};
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        openapi,
+    },
+    authentication::UserAuthenticationExtractor,
+    dictionary_import::join_delimited_field,
+    require_permission_with_optional_authentication,
+    state::ApplicationState,
+};
+
+
+
+/// Format of a dictionary export.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DictionaryExportFormat {
+    /// A single JSON object with all english words, slovene words and categories.
+    #[default]
+    Json,
+
+    /// One row per english word, in the same layout as the dictionary import expects.
+    Csv,
+
+    /// TermBase eXchange (ISO 30042), in the TBX-Basic dialect.
+    Tbx,
+}
+
+impl DictionaryExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            DictionaryExportFormat::Json => "application/json",
+            DictionaryExportFormat::Csv => "text/csv; charset=utf-8",
+            DictionaryExportFormat::Tbx => "application/x-tbx+xml; charset=utf-8",
+        }
+    }
+
+    fn file_extension(&self) -> &'static str {
+        match self {
+            DictionaryExportFormat::Json => "json",
+            DictionaryExportFormat::Csv => "csv",
+            DictionaryExportFormat::Tbx => "tbx",
+        }
+    }
+}
+
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+pub struct DictionaryExportQuery {
+    #[serde(default)]
+    pub format: DictionaryExportFormat,
+}
+
+
+/// Structure of the JSON export (the response is streamed, but has exactly this shape).
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct DictionaryJsonExport {
+    pub exported_at: DateTime<Utc>,
+    pub english_words: Vec<EnglishWord>,
+    pub slovene_words: Vec<SloveneWord>,
+    pub categories: Vec<Category>,
+}
+
+
+/// Which part of the dictionary a streamed export is currently writing out.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ExportStage {
+    Header,
+    EnglishWords { after_word_id: Option<Uuid> },
+    SloveneWords { after_word_id: Option<Uuid> },
+    Categories,
+    Footer,
+    Finished,
+}
+
+
+/// State of a streamed dictionary export.
+///
+/// Words are loaded from the database one page at a time (see [`WordPageOptions`]),
+/// and each page is only loaded and serialized once the client is ready to receive it.
+struct DictionaryExportStream {
+    state: ApplicationState,
+    format: DictionaryExportFormat,
+    exported_at: DateTime<Utc>,
+    stage: ExportStage,
+
+    /// How many elements of the current JSON array have already been written out.
+    elements_written_in_section: usize,
+
+    /// IDs of slovene words that were already exported as part of
+    /// an english word's concept (only used by the TBX format).
+    translated_slovene_word_ids: HashSet<String>,
+}
+
+impl DictionaryExportStream {
+    fn new(state: ApplicationState, format: DictionaryExportFormat) -> Self {
+        Self {
+            state,
+            format,
+            exported_at: Utc::now(),
+            stage: ExportStage::Header,
+            elements_written_in_section: 0,
+            translated_slovene_word_ids: HashSet::new(),
+        }
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<Bytes, APIError>> {
+        stream::unfold(self, |mut export| async move {
+            match export.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), export)),
+                Ok(None) => None,
+                Err(error) => {
+                    // The response has already started, so there is no way to recover.
+                    export.stage = ExportStage::Finished;
+                    Some((Err(error), export))
+                }
+            }
+        })
+    }
+
+    fn page_options(&self, after_word_id: Option<Uuid>) -> WordPageOptions {
+        WordPageOptions {
+            after_word_id,
+            page_size: self.state.configuration.dictionary.maximum_page_size,
+        }
+    }
+
+    /// Returns the next non-empty chunk of the export, or `None` once the export is complete.
+    async fn next_chunk(&mut self) -> Result<Option<Bytes>, APIError> {
+        loop {
+            let chunk = match self.stage {
+                ExportStage::Header => {
+                    self.stage = ExportStage::EnglishWords {
+                        after_word_id: None,
+                    };
+
+                    self.header()?
+                }
+                ExportStage::EnglishWords { after_word_id } => {
+                    self.english_words_chunk(after_word_id).await?
+                }
+                ExportStage::SloveneWords { after_word_id } => {
+                    self.slovene_words_chunk(after_word_id).await?
+                }
+                ExportStage::Categories => self.categories_chunk().await?,
+                ExportStage::Footer => {
+                    self.stage = ExportStage::Finished;
+
+                    self.footer()
+                }
+                ExportStage::Finished => return Ok(None),
+            };
+
+            if !chunk.is_empty() {
+                return Ok(Some(Bytes::from(chunk)));
+            }
+        }
+    }
+
+    fn header(&self) -> Result<Vec<u8>, APIError> {
+        match self.format {
+            DictionaryExportFormat::Json => Ok(format!(
+                "{{\"exported_at\":\"{}\",\"english_words\":[",
+                self.exported_at
+                    .to_rfc3339_opts(SecondsFormat::Micros, true)
+            )
+            .into_bytes()),
+            DictionaryExportFormat::Csv => csv_row([
+                "english_lemma",
+                "disambiguation",
+                "description",
+                "slovene_translations",
+                "categories",
+            ]),
+            DictionaryExportFormat::Tbx => Ok(tbx_header(self.exported_at).into_bytes()),
+        }
+    }
+
+    async fn english_words_chunk(
+        &mut self,
+        after_word_id: Option<Uuid>,
+    ) -> Result<Vec<u8>, APIError> {
+        let words_page = EnglishWordQuery::all_words_expanded_paginated(
+            &self.state.database,
+            EnglishWordsQueryOptions::default(),
+            self.page_options(after_word_id),
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        let words = words_page
+            .words
+            .into_iter()
+            .map(EnglishWord::from_expanded_word_info)
+            .collect::<Vec<_>>();
+
+
+        let mut chunk = Vec::new();
+
+        match self.format {
+            DictionaryExportFormat::Json => {
+                write_json_array_elements(
+                    &mut chunk,
+                    &words,
+                    self.elements_written_in_section,
+                )?;
+                self.elements_written_in_section += words.len();
+            }
+            DictionaryExportFormat::Csv => {
+                for word in words {
+                    chunk.extend(csv_english_word_row(word)?);
+                }
+            }
+            DictionaryExportFormat::Tbx => {
+                for word in words {
+                    // Slovene words that are (suggested) translations are already part of
+                    // the english word's concept, so only the rest need concepts of their own.
+                    self.translated_slovene_word_ids.extend(
+                        word.translations
+                            .iter()
+                            .chain(word.suggested_translations.iter())
+                            .map(|slovene_word| slovene_word.id.clone()),
+                    );
+
+                    chunk.extend(tbx_english_word_entry(word).into_bytes());
+                }
+            }
+        }
+
+
+        if let Some(next_cursor) = words_page.next_cursor {
+            self.stage = ExportStage::EnglishWords {
+                after_word_id: Some(next_cursor),
+            };
+
+            return Ok(chunk);
+        }
+
+        self.elements_written_in_section = 0;
+        self.stage = match self.format {
+            DictionaryExportFormat::Json => {
+                chunk.extend_from_slice(b"],\"slovene_words\":[");
+
+                ExportStage::SloveneWords {
+                    after_word_id: None,
+                }
+            }
+            // The CSV layout only contains english words.
+            DictionaryExportFormat::Csv => ExportStage::Footer,
+            DictionaryExportFormat::Tbx => ExportStage::SloveneWords {
+                after_word_id: None,
+            },
+        };
+
+        Ok(chunk)
+    }
+
+    async fn slovene_words_chunk(
+        &mut self,
+        after_word_id: Option<Uuid>,
+    ) -> Result<Vec<u8>, APIError> {
+        let words_page = SloveneWordQuery::all_words_expanded_paginated(
+            &self.state.database,
+            SloveneWordsQueryOptions::default(),
+            self.page_options(after_word_id),
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        let words = words_page
+            .words
+            .into_iter()
+            .map(SloveneWord::from_expanded_word_info)
+            .collect::<Vec<_>>();
+
+
+        let mut chunk = Vec::new();
+
+        match self.format {
+            DictionaryExportFormat::Json => {
+                write_json_array_elements(
+                    &mut chunk,
+                    &words,
+                    self.elements_written_in_section,
+                )?;
+                self.elements_written_in_section += words.len();
+            }
+            DictionaryExportFormat::Csv => {}
+            DictionaryExportFormat::Tbx => {
+                for word in words {
+                    if self.translated_slovene_word_ids.contains(&word.id) {
+                        continue;
+                    }
+
+                    chunk.extend(tbx_slovene_word_entry(word).into_bytes());
+                }
+            }
+        }
+
+
+        if let Some(next_cursor) = words_page.next_cursor {
+            self.stage = ExportStage::SloveneWords {
+                after_word_id: Some(next_cursor),
+            };
+
+            return Ok(chunk);
+        }
+
+        self.elements_written_in_section = 0;
+        self.stage = match self.format {
+            DictionaryExportFormat::Json => {
+                chunk.extend_from_slice(b"],\"categories\":[");
+
+                ExportStage::Categories
+            }
+            DictionaryExportFormat::Csv | DictionaryExportFormat::Tbx => ExportStage::Footer,
+        };
+
+        Ok(chunk)
+    }
+
+    async fn categories_chunk(&mut self) -> Result<Vec<u8>, APIError> {
+        // There are few enough categories that they don't need to be paged through.
+        let categories = CategoryQuery::all(
+            &self.state.database,
+            CategoriesQueryOptions::default(),
+        )
+        .await
+        .map_err(APIError::InternalError)?
+        .into_iter()
+        .map(Category::from_database_model)
+        .collect::<Vec<_>>();
+
+        let mut chunk = Vec::new();
+        write_json_array_elements(&mut chunk, &categories, 0)?;
+
+        self.stage = ExportStage::Footer;
+
+        Ok(chunk)
+    }
+
+    fn footer(&self) -> Vec<u8> {
+        match self.format {
+            DictionaryExportFormat::Json => b"]}".to_vec(),
+            DictionaryExportFormat::Csv => Vec::new(),
+            DictionaryExportFormat::Tbx => b"    </body>\n  </text>\n</martif>\n".to_vec(),
+        }
+    }
+}
+
+
+
+/// Export the entire dictionary
+///
+/// This endpoint streams the entire dictionary: all english and slovene words,
+/// their translations and suggested translations, and all categories.
+///
+/// The `format` query parameter selects the format of the export:
+/// - `json` *(default)*: a JSON object with the `english_words`, `slovene_words`
+///   and `categories` fields, structured the same way as the rest of the API.
+/// - `csv`: one row per english word, with the `english_lemma`, `disambiguation`, `description`,
+///   `slovene_translations` and `categories` columns (multiple values are separated with `;`,
+///   and a `;` or `\` inside a value is escaped with a backslash).
+///   This is the layout the dictionary import expects, so this export can be imported again.
+///   Suggested translations and slovene words without an english counterpart are not included.
+/// - `tbx`: a TermBase eXchange (ISO 30042) file in the TBX-Basic dialect, which can be imported
+///   into CAT tools such as OmegaT or Trados. Each english word is a concept (`termEntry`)
+///   with its translations as preferred and its suggestions as admitted slovene terms,
+///   and its categories as subject fields. Slovene words without an english
+///   counterpart become concepts of their own.
+///
+/// The dictionary is read page by page while the response is being sent, so changes made
+/// to the dictionary during the export may or may not be included in it.
+///
+/// # Authentication
+/// This endpoint does not require authentication.
+#[utoipa::path(
+    get,
+    path = "/dictionary/export",
+    tag = "dictionary:export",
+    params(
+        (
+            "format" = Option<DictionaryExportFormat>,
+            Query,
+            description = "Format of the export: `json` (default), `csv` or `tbx`."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The entire dictionary in the requested format \
+                           (the JSON format is shown here).",
+            body = DictionaryJsonExport,
+        ),
+        (
+            status = 400,
+            description = "Invalid export format.",
+            body = ErrorReasonResponse,
+        ),
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[get("")]
+pub async fn export_dictionary(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    query: web::Query<DictionaryExportQuery>,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
+
+    let export_format = query.into_inner().format;
+    let export = DictionaryExportStream::new(state, export_format);
+
+
+    Ok(HttpResponse::Ok()
+        .content_type(export_format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "kolomoni-dictionary.{}",
+                export_format.file_extension()
+            ))],
+        })
+        .streaming(export.into_stream()))
+}
+
+
+
+/// Serializes elements of a JSON array, continuing an array
+/// that already contains `elements_written_before` elements.
+fn write_json_array_elements<S: Serialize>(
+    chunk: &mut Vec<u8>,
+    elements: &[S],
+    elements_written_before: usize,
+) -> Result<(), APIError> {
+    for (index, element) in elements.iter().enumerate() {
+        if elements_written_before + index > 0 {
+            chunk.push(b',');
+        }
+
+        serde_json::to_writer(&mut *chunk, element).map_err(|error| {
+            APIError::internal_reason(format!(
+                "Failed to serialize dictionary export element: {}",
+                error
+            ))
+        })?;
+    }
+
+    Ok(())
+}
+
+
+
+fn csv_row<I, F>(fields: I) -> Result<Vec<u8>, APIError>
+where
+    I: IntoIterator<Item = F>,
+    F: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    writer.write_record(fields).map_err(|error| {
+        APIError::internal_reason(format!(
+            "Failed to write dictionary export CSV row: {}",
+            error
+        ))
+    })?;
+
+    writer.into_inner().map_err(|error| {
+        APIError::internal_reason(format!(
+            "Failed to write dictionary export CSV row: {}",
+            error
+        ))
+    })
+}
+
+fn csv_english_word_row(word: EnglishWord) -> Result<Vec<u8>, APIError> {
+    csv_row([
+        word.lemma,
+        word.disambiguation.unwrap_or_default(),
+        word.description.unwrap_or_default(),
+        join_delimited_field(
+            word.translations
+                .into_iter()
+                .map(|translation| translation.lemma),
+        ),
+        join_delimited_field(
+            word.categories
+                .into_iter()
+                .map(|category| category.english_name),
+        ),
+    ])
+}
+
+
+
+/// Escapes text for use in XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Administrative status of a slovene term in a TBX export.
+#[derive(Clone, Copy)]
+enum TbxTermStatus {
+    /// The term is a translation.
+    Preferred,
+
+    /// The term is only a suggested translation.
+    Admitted,
+}
+
+fn write_tbx_subject_fields(entry: &mut String, categories: &[Category]) {
+    for category in categories {
+        entry.push_str(&format!(
+            "        <descrip type=\"subjectField\">{}</descrip>\n",
+            escape_xml(&category.english_name)
+        ));
+    }
+}
+
+fn write_tbx_slovene_term(entry: &mut String, word: &SloveneWord, status: Option<TbxTermStatus>) {
+    entry.push_str("          <tig>\n");
+    entry.push_str(&format!(
+        "            <term>{}</term>\n",
+        escape_xml(&word.lemma)
+    ));
+
+    if let Some(status) = status {
+        let status_value = match status {
+            TbxTermStatus::Preferred => "preferredTerm-admn-sts",
+            TbxTermStatus::Admitted => "admittedTerm-admn-sts",
+        };
+
+        entry.push_str(&format!(
+            "            <termNote type=\"administrativeStatus\">{}</termNote>\n",
+            status_value
+        ));
+    }
+
+    if let Some(description) = &word.description {
+        entry.push_str(&format!(
+            "            <descrip type=\"definition\">{}</descrip>\n",
+            escape_xml(description)
+        ));
+    }
+
+    if let Some(disambiguation) = &word.disambiguation {
+        entry.push_str(&format!(
+            "            <note>{}</note>\n",
+            escape_xml(disambiguation)
+        ));
+    }
+
+    entry.push_str("          </tig>\n");
+}
+
+fn tbx_english_word_entry(word: EnglishWord) -> String {
+    let mut entry = format!(
+        "      <termEntry id=\"word-{}\">\n",
+        escape_xml(&word.id)
+    );
+
+    write_tbx_subject_fields(&mut entry, &word.categories);
+
+
+    entry.push_str("        <langSet xml:lang=\"en\">\n");
+
+    if let Some(description) = &word.description {
+        entry.push_str(&format!(
+            "          <descrip type=\"definition\">{}</descrip>\n",
+            escape_xml(description)
+        ));
+    }
+
+    entry.push_str("          <tig>\n");
+    entry.push_str(&format!(
+        "            <term>{}</term>\n",
+        escape_xml(&word.lemma)
+    ));
+
+    if let Some(disambiguation) = &word.disambiguation {
+        entry.push_str(&format!(
+            "            <note>{}</note>\n",
+            escape_xml(disambiguation)
+        ));
+    }
+
+    entry.push_str("          </tig>\n");
+    entry.push_str("        </langSet>\n");
+
+
+    if !word.translations.is_empty() || !word.suggested_translations.is_empty() {
+        entry.push_str("        <langSet xml:lang=\"sl\">\n");
+
+        for translation in &word.translations {
+            write_tbx_slovene_term(
+                &mut entry,
+                translation,
+                Some(TbxTermStatus::Preferred),
+            );
+        }
+
+        for suggestion in &word.suggested_translations {
+            write_tbx_slovene_term(
+                &mut entry,
+                suggestion,
+                Some(TbxTermStatus::Admitted),
+            );
+        }
+
+        entry.push_str("        </langSet>\n");
+    }
+
+    entry.push_str("      </termEntry>\n");
+
+    entry
+}
+
+fn tbx_slovene_word_entry(word: SloveneWord) -> String {
+    let mut entry = format!(
+        "      <termEntry id=\"word-{}\">\n",
+        escape_xml(&word.id)
+    );
+
+    write_tbx_subject_fields(&mut entry, &word.categories);
+
+    entry.push_str("        <langSet xml:lang=\"sl\">\n");
+    write_tbx_slovene_term(&mut entry, &word, None);
+    entry.push_str("        </langSet>\n");
+
+    entry.push_str("      </termEntry>\n");
+
+    entry
+}
+
+fn tbx_header(exported_at: DateTime<Utc>) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<martif type="TBX-Basic" xml:lang="en">
+  <martifHeader>
+    <fileDesc>
+      <titleStmt><title>Stari Kolomoni</title></titleStmt>
+      <sourceDesc><p>Exported from Stari Kolomoni at {}.</p></sourceDesc>
+    </fileDesc>
+    <encodingDesc><p type="XCSURI">TBXBasicXCSV02.xcs</p></encodingDesc>
+  </martifHeader>
+  <text>
+    <body>
+"#,
+        exported_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+    )
+}
+
+
+
+#[rustfmt::skip]
+pub fn export_router() -> Scope {
+    web::scope("/export")
+        .service(export_dictionary)
+}
@@ -0,0 +1,356 @@
+use kolomoni_database::{
+    entities,
+    mutation::{EnglishWordMutation, SloveneWordMutation},
+    shared::{self, GrammaticalCase, GrammaticalNumber, WordLanguage},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::errors::APIError;
+
+
+
+/// Part of speech of a dictionary word.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    Pronoun,
+    Numeral,
+    Preposition,
+    Conjunction,
+    Particle,
+    Interjection,
+}
+
+impl PartOfSpeech {
+    pub fn from_database_part_of_speech(part_of_speech: shared::PartOfSpeech) -> Self {
+        match part_of_speech {
+            shared::PartOfSpeech::Noun => Self::Noun,
+            shared::PartOfSpeech::Verb => Self::Verb,
+            shared::PartOfSpeech::Adjective => Self::Adjective,
+            shared::PartOfSpeech::Adverb => Self::Adverb,
+            shared::PartOfSpeech::Pronoun => Self::Pronoun,
+            shared::PartOfSpeech::Numeral => Self::Numeral,
+            shared::PartOfSpeech::Preposition => Self::Preposition,
+            shared::PartOfSpeech::Conjunction => Self::Conjunction,
+            shared::PartOfSpeech::Particle => Self::Particle,
+            shared::PartOfSpeech::Interjection => Self::Interjection,
+        }
+    }
+
+    pub fn into_database_part_of_speech(self) -> shared::PartOfSpeech {
+        match self {
+            Self::Noun => shared::PartOfSpeech::Noun,
+            Self::Verb => shared::PartOfSpeech::Verb,
+            Self::Adjective => shared::PartOfSpeech::Adjective,
+            Self::Adverb => shared::PartOfSpeech::Adverb,
+            Self::Pronoun => shared::PartOfSpeech::Pronoun,
+            Self::Numeral => shared::PartOfSpeech::Numeral,
+            Self::Preposition => shared::PartOfSpeech::Preposition,
+            Self::Conjunction => shared::PartOfSpeech::Conjunction,
+            Self::Particle => shared::PartOfSpeech::Particle,
+            Self::Interjection => shared::PartOfSpeech::Interjection,
+        }
+    }
+}
+
+
+/// Grammatical gender of a slovene noun.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GrammaticalGender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+impl GrammaticalGender {
+    pub fn from_database_gender(gender: shared::GrammaticalGender) -> Self {
+        match gender {
+            shared::GrammaticalGender::Masculine => Self::Masculine,
+            shared::GrammaticalGender::Feminine => Self::Feminine,
+            shared::GrammaticalGender::Neuter => Self::Neuter,
+        }
+    }
+
+    pub fn into_database_gender(self) -> shared::GrammaticalGender {
+        match self {
+            Self::Masculine => shared::GrammaticalGender::Masculine,
+            Self::Feminine => shared::GrammaticalGender::Feminine,
+            Self::Neuter => shared::GrammaticalGender::Neuter,
+        }
+    }
+}
+
+
+/// Restricts a noun to only some of the grammatical numbers.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberRestriction {
+    /// The noun is only used in the singular (e.g. "mleko" or "information").
+    SingularOnly,
+
+    /// The noun is only used in the plural (e.g. "vrata" or "scissors").
+    PluralOnly,
+}
+
+impl NumberRestriction {
+    pub fn from_database_number_restriction(restriction: shared::NumberRestriction) -> Self {
+        match restriction {
+            shared::NumberRestriction::SingularOnly => Self::SingularOnly,
+            shared::NumberRestriction::PluralOnly => Self::PluralOnly,
+        }
+    }
+
+    pub fn into_database_number_restriction(self) -> shared::NumberRestriction {
+        match self {
+            Self::SingularOnly => shared::NumberRestriction::SingularOnly,
+            Self::PluralOnly => shared::NumberRestriction::PluralOnly,
+        }
+    }
+}
+
+
+/// Forms of a slovene word in a single case.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default, ToSchema)]
+pub struct SloveneCaseForms {
+    pub singular: Option<String>,
+    pub dual: Option<String>,
+    pub plural: Option<String>,
+}
+
+impl SloveneCaseForms {
+    fn form_mut(&mut self, number: GrammaticalNumber) -> &mut Option<String> {
+        match number {
+            GrammaticalNumber::Singular => &mut self.singular,
+            GrammaticalNumber::Dual => &mut self.dual,
+            GrammaticalNumber::Plural => &mut self.plural,
+        }
+    }
+}
+
+
+/// Inflected forms of a slovene word, for each of the six cases.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default, ToSchema)]
+#[serde(default)]
+pub struct SloveneInflectionTable {
+    pub nominative: SloveneCaseForms,
+    pub genitive: SloveneCaseForms,
+    pub dative: SloveneCaseForms,
+    pub accusative: SloveneCaseForms,
+    pub locative: SloveneCaseForms,
+    pub instrumental: SloveneCaseForms,
+}
+
+impl SloveneInflectionTable {
+    fn case_forms_mut(&mut self, case: GrammaticalCase) -> &mut SloveneCaseForms {
+        match case {
+            GrammaticalCase::Nominative => &mut self.nominative,
+            GrammaticalCase::Genitive => &mut self.genitive,
+            GrammaticalCase::Dative => &mut self.dative,
+            GrammaticalCase::Accusative => &mut self.accusative,
+            GrammaticalCase::Locative => &mut self.locative,
+            GrammaticalCase::Instrumental => &mut self.instrumental,
+        }
+    }
+
+    pub fn from_database_inflection_table(table: &shared::SloveneInflectionTable) -> Self {
+        let mut inflection_table = Self::default();
+
+        for (case, number, form) in table.present_forms() {
+            *inflection_table.case_forms_mut(case).form_mut(number) = Some(form.to_string());
+        }
+
+        inflection_table
+    }
+
+    pub fn into_database_inflection_table(mut self) -> shared::SloveneInflectionTable {
+        let mut table = shared::SloveneInflectionTable::default();
+
+        for case in GrammaticalCase::ALL {
+            for number in GrammaticalNumber::ALL {
+                table.set_form(
+                    case,
+                    number,
+                    self.case_forms_mut(case).form_mut(number).take(),
+                );
+            }
+        }
+
+        table
+    }
+}
+
+
+/// Grammatical information about a slovene word. All of it is optional.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default, ToSchema)]
+#[serde(default)]
+#[schema(
+    example = json!({
+        "part_of_speech": "noun",
+        "gender": "masculine",
+        "number_restriction": null,
+        "inflection": {
+            "nominative": { "singular": "pustolovec", "dual": "pustolovca", "plural": "pustolovci" },
+            "genitive": { "singular": "pustolovca", "dual": null, "plural": null },
+            "dative": { "singular": null, "dual": null, "plural": null },
+            "accusative": { "singular": null, "dual": null, "plural": null },
+            "locative": { "singular": null, "dual": null, "plural": null },
+            "instrumental": { "singular": null, "dual": null, "plural": null }
+        }
+    })
+)]
+pub struct SloveneWordGrammar {
+    pub part_of_speech: Option<PartOfSpeech>,
+
+    /// Only nouns have a gender.
+    pub gender: Option<GrammaticalGender>,
+
+    /// Only nouns can have a number restriction.
+    pub number_restriction: Option<NumberRestriction>,
+
+    /// Only nouns, adjectives, pronouns and numerals can have inflected forms.
+    /// A noun with a number restriction can't have forms in the other numbers.
+    pub inflection: SloveneInflectionTable,
+}
+
+impl SloveneWordGrammar {
+    pub fn from_database_grammar(grammar: shared::SloveneWordGrammar) -> Self {
+        Self {
+            part_of_speech: grammar
+                .part_of_speech
+                .map(PartOfSpeech::from_database_part_of_speech),
+            gender: grammar.gender.map(GrammaticalGender::from_database_gender),
+            number_restriction: grammar
+                .number_restriction
+                .map(NumberRestriction::from_database_number_restriction),
+            inflection: SloveneInflectionTable::from_database_inflection_table(&grammar.inflection),
+        }
+    }
+
+    pub fn into_database_grammar(self) -> shared::SloveneWordGrammar {
+        shared::SloveneWordGrammar {
+            part_of_speech: self
+                .part_of_speech
+                .map(PartOfSpeech::into_database_part_of_speech),
+            gender: self.gender.map(GrammaticalGender::into_database_gender),
+            number_restriction: self
+                .number_restriction
+                .map(NumberRestriction::into_database_number_restriction),
+            inflection: self.inflection.into_database_inflection_table(),
+        }
+    }
+}
+
+
+/// Grammatical information about an english word. All of it is optional.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default, ToSchema)]
+#[serde(default)]
+#[schema(
+    example = json!({
+        "part_of_speech": "noun",
+        "number_restriction": null,
+        "plural_form": "adventurers"
+    })
+)]
+pub struct EnglishWordGrammar {
+    pub part_of_speech: Option<PartOfSpeech>,
+
+    /// Only nouns can have a number restriction.
+    pub number_restriction: Option<NumberRestriction>,
+
+    /// The plural form of a noun, if it is irregular or otherwise worth noting.
+    /// Nouns with a number restriction can't have a plural form.
+    pub plural_form: Option<String>,
+}
+
+impl EnglishWordGrammar {
+    pub fn from_database_grammar(grammar: shared::EnglishWordGrammar) -> Self {
+        Self {
+            part_of_speech: grammar
+                .part_of_speech
+                .map(PartOfSpeech::from_database_part_of_speech),
+            number_restriction: grammar
+                .number_restriction
+                .map(NumberRestriction::from_database_number_restriction),
+            plural_form: grammar.plural_form,
+        }
+    }
+
+    pub fn into_database_grammar(self) -> shared::EnglishWordGrammar {
+        shared::EnglishWordGrammar {
+            part_of_speech: self
+                .part_of_speech
+                .map(PartOfSpeech::into_database_part_of_speech),
+            number_restriction: self
+                .number_restriction
+                .map(NumberRestriction::into_database_number_restriction),
+            plural_form: self.plural_form,
+        }
+    }
+}
+
+
+
+/// Converts the grammatical information about a slovene word into its database form,
+/// returning a client error if it is inconsistent (e.g. a verb with a gender).
+pub(super) fn validate_slovene_word_grammar(
+    grammar: SloveneWordGrammar,
+) -> Result<shared::SloveneWordGrammar, APIError> {
+    let grammar = grammar.into_database_grammar();
+
+    SloveneWordMutation::validate_grammar(&grammar)
+        .map_err(|error| APIError::client_error(format!("Invalid grammar: {}.", error)))?;
+
+    Ok(grammar)
+}
+
+/// Converts the grammatical information about an english word into its database form,
+/// returning a client error if it is inconsistent (e.g. a verb with a plural form).
+pub(super) fn validate_english_word_grammar(
+    grammar: EnglishWordGrammar,
+) -> Result<shared::EnglishWordGrammar, APIError> {
+    let grammar = grammar.into_database_grammar();
+
+    EnglishWordMutation::validate_grammar(&grammar)
+        .map_err(|error| APIError::client_error(format!("Invalid grammar: {}.", error)))?;
+
+    Ok(grammar)
+}
+
+/// Checks that the grammatical information recorded in a word revision is still valid,
+/// returning a client error if reverting to the revision would make the word inconsistent.
+///
+/// Revisions recorded before grammatical information was tracked are always valid.
+pub(super) fn validate_revision_grammar(
+    language: WordLanguage,
+    revision: &entities::word_revision::Model,
+) -> Result<(), APIError> {
+    let Some(revision_grammar) = &revision.grammar else {
+        return Ok(());
+    };
+
+    let invalid_revision_grammar =
+        || APIError::internal_reason("Invalid grammatical information in word revision.");
+
+    let validation_result = match language {
+        WordLanguage::English => {
+            let grammar = shared::EnglishWordGrammar::from_revision_json(revision_grammar)
+                .ok_or_else(invalid_revision_grammar)?;
+
+            EnglishWordMutation::validate_grammar(&grammar)
+        }
+        WordLanguage::Slovene => {
+            let grammar = shared::SloveneWordGrammar::from_revision_json(revision_grammar)
+                .ok_or_else(invalid_revision_grammar)?;
+
+            SloveneWordMutation::validate_grammar(&grammar)
+        }
+    };
+
+    validation_result.map_err(|error| APIError::client_error(format!("Invalid grammar: {}.", error)))
+}
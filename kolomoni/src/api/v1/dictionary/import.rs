@@ -0,0 +1,443 @@
+use actix_web::{post, web, Scope};
+use kolomoni_auth::Permission;
+use kolomoni_database::query::{EnglishWordQuery, SloveneWordQuery};
+use sea_orm::prelude::Uuid;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+    },
+    authentication::UserAuthenticationExtractor,
+    dictionary_import::{
+        import_parsed_rows,
+        parse_import_file,
+        tbx::{import_tbx_entries, parse_tbx_file, TbxImportReport},
+        DictionaryImportFormat,
+        DictionaryImportReport,
+    },
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+    word_references::update_word_references,
+};
+
+
+
+/// Imported descriptions can reference other words (see [`crate::word_references`]),
+/// so the stored references of all imported words are brought up to date.
+async fn update_references_of_imported_words(
+    state: &ApplicationState,
+    english_word_ids: &[Uuid],
+    slovene_word_ids: &[Uuid],
+) -> Result<(), APIError> {
+    for english_word_id in english_word_ids {
+        let english_word = EnglishWordQuery::word_by_uuid(&state.database, *english_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        if let Some(english_word) = english_word {
+            update_word_references(
+                &state.database,
+                english_word.word_id,
+                english_word.description.as_deref(),
+            )
+            .await
+            .map_err(APIError::InternalError)?;
+        }
+    }
+
+    for slovene_word_id in slovene_word_ids {
+        let slovene_word = SloveneWordQuery::word_by_uuid(&state.database, *slovene_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        if let Some(slovene_word) = slovene_word {
+            update_word_references(
+                &state.database,
+                slovene_word.word_id,
+                slovene_word.description.as_deref(),
+            )
+            .await
+            .map_err(APIError::InternalError)?;
+        }
+    }
+
+    Ok(())
+}
+
+
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "format": "csv",
+        "data": "english_lemma,disambiguation,description,slovene_translations,categories\n\
+            critical hit,,A hit that deals extra damage.,kritični zadetek;usodni zadetek,Combat",
+        "dry_run": true
+    })
+)]
+pub struct DictionaryImportRequest {
+    /// Format of `data`.
+    pub format: DictionaryImportFormat,
+
+    /// Contents of the import file.
+    pub data: String,
+
+    /// If `true`, only reports what the import would do, without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "report": {
+            "dry_run": false,
+            "committed": true,
+            "summary": {
+                "new": 1,
+                "updated": 0,
+                "unchanged": 0,
+                "conflicting": 0,
+                "invalid": 0
+            },
+            "rows": [
+                {
+                    "row": 1,
+                    "english_lemma": "critical hit",
+                    "status": "new",
+                    "english_word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+                    "conflicting_fields": [],
+                    "errors": []
+                }
+            ]
+        }
+    })
+)]
+pub struct DictionaryImportResponse {
+    pub report: DictionaryImportReport,
+}
+
+impl_json_response_builder!(DictionaryImportResponse);
+
+
+/// Import dictionary entries
+///
+/// This endpoint imports many dictionary entries at once from a CSV, TSV or JSON file.
+/// Each entry is an english word with its disambiguation, description,
+/// slovene translations and categories.
+///
+/// CSV and TSV files need a header row with the columns `english_lemma` (required),
+/// `disambiguation`, `description`, `slovene_translations` and `categories`, where multiple
+/// translations or categories are separated with `;` (a `;` or `\` inside a value is escaped
+/// with a backslash). JSON files contain an array of objects with the same fields,
+/// where translations and categories are arrays of strings.
+///
+/// English words are matched to existing ones by lemma. Existing words whose disambiguation
+/// or description differ from the entry are reported as conflicting and left as they are.
+/// Missing slovene words are created, while categories must already exist.
+///
+/// The import runs in a single transaction: if any row is invalid, nothing is imported.
+/// With `dry_run` set, the report describes what the import would do without changing anything.
+///
+/// TBX glossaries are imported through `/dictionary/import/tbx` instead.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `word:create` and `word:update` permissions.
+#[utoipa::path(
+    post,
+    path = "/dictionary/import",
+    tag = "dictionary:import",
+    request_body(
+        content = DictionaryImportRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Import report (check `committed` to see whether the import was applied).",
+            body = DictionaryImportResponse,
+        ),
+        (
+            status = 400,
+            description = "The import file can not be read.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid import file: Missing the english_lemma column." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordCreate>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("")]
+pub async fn import_dictionary(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    request_body: web::Json<DictionaryImportRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(state, authenticated_user, Permission::WordCreate);
+    require_permission!(state, authenticated_user, Permission::WordUpdate);
+
+
+    let request_body = request_body.into_inner();
+
+    let parsed_rows = parse_import_file(request_body.format, &request_body.data).map_err(
+        |error| APIError::client_error(format!("Invalid import file: {}", error)),
+    )?;
+
+    let completed_import = import_parsed_rows(
+        &state.database,
+        parsed_rows,
+        Some(authenticated_user.user_id()),
+        request_body.dry_run,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    if completed_import.report.committed {
+        let summary = &completed_import.report.summary;
+
+        info!(
+            imported_by_user = authenticated_user.user_id(),
+            new = summary.new,
+            updated = summary.updated,
+            unchanged = summary.unchanged,
+            conflicting = summary.conflicting,
+            "Imported dictionary entries."
+        );
+
+        update_references_of_imported_words(
+            &state,
+            &completed_import.changed_english_word_ids,
+            &completed_import.changed_slovene_word_ids,
+        )
+        .await?;
+    }
+
+
+    // Signals to the search indexer that the words have been created or updated.
+    for english_word_id in completed_import.changed_english_word_ids {
+        state
+            .search
+            .signal_english_word_created_or_updated(english_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+    }
+
+    for slovene_word_id in completed_import.changed_slovene_word_ids {
+        state
+            .search
+            .signal_slovene_word_created_or_updated(slovene_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+    }
+
+
+    Ok(DictionaryImportResponse {
+        report: completed_import.report,
+    }
+    .into_response())
+}
+
+
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "data": "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <martif type=\"TBX-Basic\" xml:lang=\"en\"><text><body>\
+            <termEntry id=\"c1\">\
+            <descrip type=\"subjectField\">Combat</descrip>\
+            <langSet xml:lang=\"en\"><tig><term>critical hit</term></tig></langSet>\
+            <langSet xml:lang=\"sl\"><tig><term>kritični zadetek</term></tig></langSet>\
+            </termEntry>\
+            </body></text></martif>",
+        "preview": true
+    })
+)]
+pub struct TbxImportRequest {
+    /// Contents of the TBX file.
+    pub data: String,
+
+    /// If `true`, only reports what the import would do, without changing anything.
+    #[serde(default)]
+    pub preview: bool,
+}
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "report": {
+            "preview": false,
+            "committed": true,
+            "summary": {
+                "created": 1,
+                "merged": 0,
+                "skipped": 0
+            },
+            "entries": [
+                {
+                    "entry": 1,
+                    "entry_id": "c1",
+                    "english_lemma": "critical hit",
+                    "english_disambiguation": null,
+                    "status": "created",
+                    "english_word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+                    "ignored_subject_fields": [],
+                    "messages": []
+                }
+            ]
+        }
+    })
+)]
+pub struct TbxImportResponse {
+    pub report: TbxImportReport,
+}
+
+impl_json_response_builder!(TbxImportResponse);
+
+
+/// Import a TBX glossary
+///
+/// This endpoint imports a glossary shared as a TBX (TermBase eXchange) file,
+/// either TBX-Basic or TBX v3.
+///
+/// Each concept entry (`termEntry`) becomes an english word: its English term is the lemma,
+/// the term's `note` the disambiguation and the `definition` description the description.
+/// The entry's Slovene terms become translations (missing slovene words are created),
+/// and its subject fields are added as categories, matched by their english or slovene name.
+/// Subject fields without a matching category are ignored.
+///
+/// Entries are matched to existing english words by lemma and disambiguation. Matching
+/// entries are merged into the existing word, which receives any translations and categories
+/// it doesn't have yet. Entries whose lemma is used by a word with a different disambiguation,
+/// or that have no English term, are skipped. The report lists each entry with its status
+/// and explains why it has been merged or skipped.
+///
+/// With `preview` set, the report describes what the import would do without changing anything.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `word:create` and `word:update` permissions.
+#[utoipa::path(
+    post,
+    path = "/dictionary/import/tbx",
+    tag = "dictionary:import",
+    request_body(
+        content = TbxImportRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Import report (check `committed` to see whether the import was applied).",
+            body = TbxImportResponse,
+        ),
+        (
+            status = 400,
+            description = "The TBX file can not be read.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Invalid TBX file: Not a TBX file (the root element must be martif or tbx)."
+            })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordCreate>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/tbx")]
+pub async fn import_tbx_glossary(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    request_body: web::Json<TbxImportRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(state, authenticated_user, Permission::WordCreate);
+    require_permission!(state, authenticated_user, Permission::WordUpdate);
+
+
+    let request_body = request_body.into_inner();
+
+    let entries = parse_tbx_file(&request_body.data)
+        .map_err(|error| APIError::client_error(format!("Invalid TBX file: {}", error)))?;
+
+    let completed_import = import_tbx_entries(
+        &state.database,
+        entries,
+        Some(authenticated_user.user_id()),
+        request_body.preview,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    if completed_import.report.committed {
+        let summary = &completed_import.report.summary;
+
+        info!(
+            imported_by_user = authenticated_user.user_id(),
+            created = summary.created,
+            merged = summary.merged,
+            skipped = summary.skipped,
+            "Imported TBX glossary."
+        );
+
+        update_references_of_imported_words(
+            &state,
+            &completed_import.changed_english_word_ids,
+            &completed_import.changed_slovene_word_ids,
+        )
+        .await?;
+    }
+
+
+    // Signals to the search indexer that the words have been created or updated.
+    for english_word_id in completed_import.changed_english_word_ids {
+        state
+            .search
+            .signal_english_word_created_or_updated(english_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+    }
+
+    for slovene_word_id in completed_import.changed_slovene_word_ids {
+        state
+            .search
+            .signal_slovene_word_created_or_updated(slovene_word_id)
+            .await
+            .map_err(APIError::InternalError)?;
+    }
+
+
+    Ok(TbxImportResponse {
+        report: completed_import.report,
+    }
+    .into_response())
+}
+
+
+
+#[rustfmt::skip]
+pub fn import_router() -> Scope {
+    web::scope("/import")
+        .service(import_dictionary)
+        .service(import_tbx_glossary)
+}
@@ -0,0 +1,381 @@
+use actix_http::StatusCode;
+use actix_web::{delete, post, web, HttpResponse, Scope};
+use kolomoni_auth::Permission;
+use kolomoni_database::{
+    mutation::{NewWordLink, WordLinkMutation, WordLinkToDelete},
+    query::{EnglishWordLink, SloveneWordLink, WordLinkQuery, WordQuery},
+    shared::{self, WordLanguage},
+};
+use sea_orm::prelude::Uuid;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        openapi,
+        v1::dictionary::parse_string_into_uuid,
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+
+/// Type of a link between two words of the same language.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WordLinkType {
+    /// The words have the same meaning.
+    Synonym,
+
+    /// The words have opposite meanings.
+    Antonym,
+
+    /// The linked word has a broader meaning that includes this word.
+    Hypernym,
+
+    /// The linked word has a narrower meaning that is included in this word.
+    Hyponym,
+
+    /// The linked word is related in some other way.
+    SeeAlso,
+}
+
+impl WordLinkType {
+    pub fn from_database_link_type(link_type: shared::WordLinkType) -> Self {
+        match link_type {
+            shared::WordLinkType::Synonym => Self::Synonym,
+            shared::WordLinkType::Antonym => Self::Antonym,
+            shared::WordLinkType::Hypernym => Self::Hypernym,
+            shared::WordLinkType::Hyponym => Self::Hyponym,
+            shared::WordLinkType::SeeAlso => Self::SeeAlso,
+        }
+    }
+
+    pub fn into_database_link_type(self) -> shared::WordLinkType {
+        match self {
+            Self::Synonym => shared::WordLinkType::Synonym,
+            Self::Antonym => shared::WordLinkType::Antonym,
+            Self::Hypernym => shared::WordLinkType::Hypernym,
+            Self::Hyponym => shared::WordLinkType::Hyponym,
+            Self::SeeAlso => shared::WordLinkType::SeeAlso,
+        }
+    }
+}
+
+
+/// A link from a word to another word of the same language.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[schema(
+    example = json!({
+        "link_type": "synonym",
+        "word_id": "018dbe00-266e-7398-abd2-0906df0aa346",
+        "lemma": "hero",
+        "disambiguation": "character"
+    })
+)]
+pub struct WordLink {
+    pub link_type: WordLinkType,
+
+    /// Internal UUID of the linked word.
+    pub word_id: String,
+
+    pub lemma: String,
+    pub disambiguation: Option<String>,
+}
+
+impl WordLink {
+    pub fn from_english_word_link(link: EnglishWordLink) -> Self {
+        Self {
+            link_type: WordLinkType::from_database_link_type(link.link_type),
+            word_id: link.linked_word.word_id.to_string(),
+            lemma: link.linked_word.lemma,
+            disambiguation: link.linked_word.disambiguation,
+        }
+    }
+
+    pub fn from_slovene_word_link(link: SloveneWordLink) -> Self {
+        Self {
+            link_type: WordLinkType::from_database_link_type(link.link_type),
+            word_id: link.linked_word.word_id.to_string(),
+            lemma: link.linked_word.lemma,
+            disambiguation: link.linked_word.disambiguation,
+        }
+    }
+}
+
+
+/// Looks up the language of a word that is about to be linked (or unlinked),
+/// returning a client error if the word doesn't exist.
+async fn existing_word_language(
+    state: &ApplicationState,
+    word_uuid: Uuid,
+) -> Result<WordLanguage, APIError> {
+    let potential_base_word = WordQuery::get_by_uuid(&state.database, word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let Some(base_word) = potential_base_word else {
+        return Err(APIError::client_error(
+            "The provided word does not exist.",
+        ));
+    };
+
+    base_word.language().map_err(APIError::InternalError)
+}
+
+/// Signals to the search engine that a word has been updated.
+async fn signal_word_updated(
+    state: &ApplicationState,
+    language: WordLanguage,
+    word_uuid: Uuid,
+) -> Result<(), APIError> {
+    match language {
+        WordLanguage::English => state
+            .search
+            .signal_english_word_created_or_updated(word_uuid)
+            .await
+            .map_err(APIError::InternalError),
+        WordLanguage::Slovene => state
+            .search
+            .signal_slovene_word_created_or_updated(word_uuid)
+            .await
+            .map_err(APIError::InternalError),
+    }
+}
+
+
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[schema(
+    example = json!({
+        "word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+        "linked_word_id": "018dbe00-266e-7398-abd2-0906df0aa346",
+        "link_type": "synonym"
+    })
+)]
+pub struct WordLinkRequest {
+    pub word_id: String,
+    pub linked_word_id: String,
+    pub link_type: WordLinkType,
+}
+
+
+/// Create a new word link
+///
+/// This endpoint will link two words of the same language together,
+/// e.g. as synonyms or antonyms. Translations between english and slovene words
+/// are handled by `/dictionary/translation` instead.
+///
+/// The link is visible from both words: the linked word receives a link back with the
+/// inverse type (a hypernym becomes a hyponym and vice versa, other types stay the same).
+/// Two words can only be linked once.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `word:update` permission.
+#[utoipa::path(
+    post,
+    path = "/dictionary/link",
+    tag = "dictionary:link",
+    request_body(
+        content = WordLinkRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The link has been created."
+        ),
+        (
+            status = 400,
+            description = "The provided words do not exist, are the same word or are of different languages.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Only words of the same language can be linked." })
+        ),
+        (
+            status = 409,
+            description = "The words are already linked.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The words are already linked." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordUpdate>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("")]
+pub async fn create_word_link(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    request_body: web::Json<WordLinkRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(state, authenticated_user, Permission::WordUpdate);
+
+
+    let request_body = request_body.into_inner();
+
+    let word_uuid = parse_string_into_uuid(&request_body.word_id)?;
+    let linked_word_uuid = parse_string_into_uuid(&request_body.linked_word_id)?;
+
+    if word_uuid == linked_word_uuid {
+        return Err(APIError::client_error(
+            "A word can not be linked to itself.",
+        ));
+    }
+
+
+    let word_language = existing_word_language(&state, word_uuid).await?;
+    let linked_word_language = existing_word_language(&state, linked_word_uuid).await?;
+
+    if word_language != linked_word_language {
+        return Err(APIError::client_error(
+            "Only words of the same language can be linked.",
+        ));
+    }
+
+
+    let existing_link = WordLinkQuery::link_between(&state.database, word_uuid, linked_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if existing_link.is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "The words are already linked."
+        ));
+    }
+
+
+    WordLinkMutation::create(
+        &state.database,
+        NewWordLink {
+            language: word_language,
+            word_id: word_uuid,
+            linked_word_id: linked_word_uuid,
+            link_type: request_body.link_type.into_database_link_type(),
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+
+    // Signals to the search engine that both words have been updated.
+    signal_word_updated(&state, word_language, word_uuid).await?;
+    signal_word_updated(&state, word_language, linked_word_uuid).await?;
+
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+pub struct WordLinkDeletionRequest {
+    pub word_id: String,
+    pub linked_word_id: String,
+}
+
+
+/// Delete a word link
+///
+/// This endpoint will remove the link between two words of the same language
+/// (in both directions).
+///
+/// # Authentication
+/// This endpoint requires authentication and the `word:update` permission.
+#[utoipa::path(
+    delete,
+    path = "/dictionary/link",
+    tag = "dictionary:link",
+    request_body(
+        content = WordLinkDeletionRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The link has been deleted."
+        ),
+        (
+            status = 400,
+            description = "The provided words do not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The provided word does not exist." })
+        ),
+        (
+            status = 404,
+            description = "The words are not linked.",
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordUpdate>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("")]
+pub async fn delete_word_link(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    request_body: web::Json<WordLinkDeletionRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(state, authenticated_user, Permission::WordUpdate);
+
+
+    let request_body = request_body.into_inner();
+
+    let word_uuid = parse_string_into_uuid(&request_body.word_id)?;
+    let linked_word_uuid = parse_string_into_uuid(&request_body.linked_word_id)?;
+
+
+    let word_language = existing_word_language(&state, word_uuid).await?;
+    existing_word_language(&state, linked_word_uuid).await?;
+
+
+    let existing_link = WordLinkQuery::link_between(&state.database, word_uuid, linked_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if existing_link.is_none() {
+        return Err(APIError::not_found());
+    }
+
+
+    WordLinkMutation::delete(
+        &state.database,
+        WordLinkToDelete {
+            language: word_language,
+            word_id: word_uuid,
+            linked_word_id: linked_word_uuid,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+
+    // Signals to the search engine that both words have been updated.
+    signal_word_updated(&state, word_language, word_uuid).await?;
+    signal_word_updated(&state, word_language, linked_word_uuid).await?;
+
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+#[rustfmt::skip]
+pub fn links_router() -> Scope {
+    web::scope("/link")
+        .service(create_word_link)
+        .service(delete_word_link)
+}
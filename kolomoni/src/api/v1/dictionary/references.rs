@@ -0,0 +1,289 @@
+use actix_web::{get, web, Scope};
+use kolomoni_auth::Permission;
+use kolomoni_database::{
+    query::{self, WordQuery, WordReferenceQuery},
+    shared::WordLanguage,
+};
+use sea_orm::prelude::Uuid;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+        v1::dictionary::parse_string_into_uuid,
+    },
+    authentication::UserAuthenticationExtractor,
+    impl_json_response_builder,
+    require_permission_with_optional_authentication,
+    state::ApplicationState,
+    word_references::{
+        format_word_reference,
+        parse_word_references,
+        render_description_html,
+        resolve_word_reference,
+    },
+};
+
+
+
+/// Language of a word on either end of a description reference.
+///
+/// The values are the same as the prefixes used in references (e.g. `[[en:adventurer]]`).
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+pub enum ReferencedWordLanguage {
+    #[serde(rename = "en")]
+    English,
+
+    #[serde(rename = "sl")]
+    Slovene,
+}
+
+impl ReferencedWordLanguage {
+    pub fn from_database_language(language: WordLanguage) -> Self {
+        match language {
+            WordLanguage::English => Self::English,
+            WordLanguage::Slovene => Self::Slovene,
+        }
+    }
+}
+
+
+/// A word on either end of a description reference.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[schema(
+    example = json!({
+        "word_id": "018dbe00-266e-7398-abd2-0906df0aa346",
+        "language": "sl",
+        "lemma": "pustolovec",
+        "disambiguation": "lik"
+    })
+)]
+pub struct ReferencedWord {
+    /// Internal UUID of the word.
+    pub word_id: String,
+
+    pub language: ReferencedWordLanguage,
+
+    pub lemma: String,
+    pub disambiguation: Option<String>,
+}
+
+impl ReferencedWord {
+    pub fn from_database_summary(summary: query::WordSummary) -> Self {
+        Self {
+            word_id: summary.word_id.to_string(),
+            language: ReferencedWordLanguage::from_database_language(summary.language),
+            lemma: summary.lemma,
+            disambiguation: summary.disambiguation,
+        }
+    }
+}
+
+
+/// Query parameters of endpoints that return a word with its description.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct WordDescriptionRenderingQuery {
+    /// If `true`, the response will also contain the description rendered into HTML.
+    #[serde(default)]
+    pub render_description: bool,
+}
+
+
+/// Checks that all references in a new description are well-formed and point to existing words,
+/// returning a client error otherwise.
+pub(super) async fn validate_description_references(
+    state: &ApplicationState,
+    description: Option<&str>,
+) -> Result<(), APIError> {
+    let Some(description) = description else {
+        return Ok(());
+    };
+
+    let references = parse_word_references(description)
+        .map_err(|error| APIError::client_error(format!("Invalid description: {}.", error)))?;
+
+    for reference in references {
+        let referenced_word_uuid = resolve_word_reference(&state.database, &reference)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        if referenced_word_uuid.is_none() {
+            return Err(APIError::client_error(format!(
+                "Invalid description: the referenced word {} does not exist.",
+                format_word_reference(&reference)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the description into HTML if it was requested (see [`WordDescriptionRenderingQuery`]).
+pub(super) async fn render_description_if_requested(
+    state: &ApplicationState,
+    rendering_query: WordDescriptionRenderingQuery,
+    description: Option<&str>,
+) -> Result<Option<String>, APIError> {
+    if !rendering_query.render_description {
+        return Ok(None);
+    }
+
+    let Some(description) = description else {
+        return Ok(None);
+    };
+
+    let description_html = render_description_html(&state.database, description)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    Ok(Some(description_html))
+}
+
+/// Returns the words (other than the word itself) whose descriptions reference the given word.
+///
+/// This is used to warn about references that will no longer resolve
+/// after the word is renamed or deleted.
+pub(super) async fn words_referencing_word(
+    state: &ApplicationState,
+    word_uuid: Uuid,
+) -> Result<Vec<ReferencedWord>, APIError> {
+    let referencing_words = WordReferenceQuery::words_referencing_word(&state.database, word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    Ok(referencing_words
+        .into_iter()
+        .filter(|summary| summary.word_id != word_uuid)
+        .map(ReferencedWord::from_database_summary)
+        .collect())
+}
+
+
+
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "references": [
+            {
+                "word_id": "018dbe00-266e-7398-abd2-0906df0aa346",
+                "language": "sl",
+                "lemma": "pustolovec",
+                "disambiguation": "lik"
+            }
+        ],
+        "referenced_by": [
+            {
+                "word_id": "018dbe00-266e-7398-abd2-0906df0aa347",
+                "language": "en",
+                "lemma": "hero",
+                "disambiguation": null
+            }
+        ]
+    })
+)]
+pub struct WordReferencesResponse {
+    /// Words referenced from the description of this word.
+    pub references: Vec<ReferencedWord>,
+
+    /// Words whose descriptions reference this word.
+    pub referenced_by: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(WordReferencesResponse);
+
+
+/// Get the references of a word
+///
+/// This endpoint returns the words referenced from the description of the given word
+/// (using e.g. `[[en:adventurer]]`, `[[en:spell#magic]]` or `[[sl:pustolovec|character]]`), as well as
+/// the words whose descriptions reference the given word (its backlinks).
+///
+/// # Authentication
+/// Authentication is *not required* on this endpoint due to a blanket grant of
+/// the `word:read` permission to unauthenticated users.
+#[utoipa::path(
+    get,
+    path = "/dictionary/references/{word_uuid}",
+    tag = "dictionary:references",
+    params(
+        (
+            "word_uuid" = String,
+            Path,
+            description = "UUID of the english or slovene word."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "References from and to the requested word.",
+            body = WordReferencesResponse,
+        ),
+        (
+            status = 400,
+            description = "Invalid word UUID provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "The requested word does not exist."
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[get("/{word_uuid}")]
+pub async fn get_word_references(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
+
+
+    let target_word_uuid = parse_string_into_uuid(&parameters.into_inner().0)?;
+
+    let target_word_exists = WordQuery::exists_by_uuid(&state.database, target_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !target_word_exists {
+        return Err(APIError::not_found());
+    }
+
+
+    let references =
+        WordReferenceQuery::words_referenced_by_word(&state.database, target_word_uuid)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    let referenced_by =
+        WordReferenceQuery::words_referencing_word(&state.database, target_word_uuid)
+            .await
+            .map_err(APIError::InternalError)?;
+
+
+    Ok(WordReferencesResponse {
+        references: references
+            .into_iter()
+            .map(ReferencedWord::from_database_summary)
+            .collect(),
+        referenced_by: referenced_by
+            .into_iter()
+            .map(ReferencedWord::from_database_summary)
+            .collect(),
+    }
+    .into_response())
+}
+
+
+
+#[rustfmt::skip]
+pub fn references_router() -> Scope {
+    web::scope("/references")
+        .service(get_word_references)
+}
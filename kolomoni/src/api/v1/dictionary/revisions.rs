@@ -0,0 +1,697 @@
+use actix_http::StatusCode;
+use actix_web::{get, post, web, Scope};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::Permission;
+use kolomoni_database::{
+    entities,
+    mutation::{EnglishWordMutation, SloveneWordMutation},
+    query::{EnglishWordQuery, SloveneWordQuery, WordQuery, WordRevisionQuery},
+    shared::WordLanguage,
+};
+use sea_orm::prelude::Uuid;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use super::{
+    grammar::validate_revision_grammar,
+    references::{validate_description_references, words_referencing_word, ReferencedWord},
+};
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+        v1::dictionary::parse_string_into_uuid,
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    require_permission_with_optional_authentication,
+    state::ApplicationState,
+    word_references::update_word_references,
+};
+
+
+
+/// A single revision of a word: its lemma, disambiguation
+/// and description after a change, along with who made the change and when.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[schema(
+    example = json!({
+        "word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+        "revision_number": 2,
+        "lemma": "adventurer",
+        "disambiguation": "character",
+        "description": "Playable or non-playable character.",
+        "author_user_id": 1,
+        "created_at": "2023-06-27T20:34:27.217273Z"
+    })
+)]
+pub struct WordRevision {
+    pub word_id: String,
+
+    /// Revisions of each word are numbered from 1 onwards.
+    pub revision_number: i32,
+
+    pub lemma: String,
+    pub disambiguation: Option<String>,
+    pub description: Option<String>,
+
+    /// User that made the change. Missing for changes made outside of the API
+    /// (e.g. command-line imports). Changes made by since-deleted users are attributed
+    /// to the deleted user placeholder (`0`).
+    pub author_user_id: Option<i32>,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl WordRevision {
+    pub fn from_database_model(model: entities::word_revision::Model) -> Self {
+        Self {
+            word_id: model.word_id.to_string(),
+            revision_number: model.revision_number,
+            lemma: model.lemma,
+            disambiguation: model.disambiguation,
+            description: model.description,
+            author_user_id: model.author_user_id,
+            created_at: model.created_at.to_utc(),
+        }
+    }
+}
+
+
+/// Looks up a word and returns its language, or a 404 error if it doesn't exist.
+async fn target_word_language(
+    state: &ApplicationState,
+    word_uuid: Uuid,
+) -> Result<WordLanguage, APIError> {
+    let potential_base_word = WordQuery::get_by_uuid(&state.database, word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let Some(base_word) = potential_base_word else {
+        return Err(APIError::not_found_with_reason(
+            "word does not exist.",
+        ));
+    };
+
+    base_word.language().map_err(APIError::InternalError)
+}
+
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "revisions": [
+            {
+                "word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+                "revision_number": 1,
+                "lemma": "adventurer",
+                "disambiguation": null,
+                "description": null,
+                "author_user_id": 1,
+                "created_at": "2023-06-27T20:34:27.217273Z"
+            },
+            {
+                "word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+                "revision_number": 2,
+                "lemma": "adventurer",
+                "disambiguation": "character",
+                "description": "Playable or non-playable character.",
+                "author_user_id": 1,
+                "created_at": "2023-06-28T10:12:03.102443Z"
+            }
+        ]
+    })
+)]
+pub struct WordRevisionsResponse {
+    pub revisions: Vec<WordRevision>,
+}
+
+impl_json_response_builder!(WordRevisionsResponse);
+
+
+/// List revisions of a word
+///
+/// This endpoint returns all revisions of an english or slovene word, oldest first.
+/// A revision is recorded each time the word's lemma, disambiguation, description
+/// or grammatical information change.
+///
+/// # Authentication
+/// Authentication is *not required* on this endpoint due to a blanket grant of
+/// the `word:read` permission to unauthenticated users.
+#[utoipa::path(
+    get,
+    path = "/dictionary/revisions/{word_uuid}",
+    tag = "dictionary:revisions",
+    params(
+        (
+            "word_uuid" = String,
+            Path,
+            description = "UUID of the english or slovene word."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Revisions of the word, oldest first.",
+            body = WordRevisionsResponse,
+        ),
+        (
+            status = 400,
+            description = "Invalid word UUID provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "The requested word does not exist."
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[get("/{word_uuid}")]
+pub async fn get_word_revisions(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
+
+
+    let target_word_uuid = parse_string_into_uuid(&parameters.into_inner().0)?;
+
+    target_word_language(&state, target_word_uuid).await?;
+
+
+    let revisions = WordRevisionQuery::all_revisions_for_word(&state.database, target_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    Ok(WordRevisionsResponse {
+        revisions: revisions
+            .into_iter()
+            .map(WordRevision::from_database_model)
+            .collect(),
+    }
+    .into_response())
+}
+
+
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WordRevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+
+/// A field of a word that is tracked in its revisions.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WordRevisionField {
+    Lemma,
+    Disambiguation,
+    Description,
+
+    /// Grammatical information about the word, as a JSON object.
+    Grammar,
+}
+
+
+/// A field whose value differs between two revisions.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+pub struct WordRevisionFieldChange {
+    pub field: WordRevisionField,
+
+    /// Value of the field in the `from` revision.
+    pub from: Option<String>,
+
+    /// Value of the field in the `to` revision.
+    pub to: Option<String>,
+}
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "from_revision": 1,
+        "to_revision": 2,
+        "changes": [
+            {
+                "field": "disambiguation",
+                "from": null,
+                "to": "character"
+            },
+            {
+                "field": "description",
+                "from": null,
+                "to": "Playable or non-playable character."
+            }
+        ]
+    })
+)]
+pub struct WordRevisionDiffResponse {
+    pub from_revision: i32,
+    pub to_revision: i32,
+
+    /// Fields that differ between the two revisions (unchanged fields are left out).
+    pub changes: Vec<WordRevisionFieldChange>,
+}
+
+impl_json_response_builder!(WordRevisionDiffResponse);
+
+
+fn diff_revisions(
+    from_revision: &entities::word_revision::Model,
+    to_revision: &entities::word_revision::Model,
+) -> Vec<WordRevisionFieldChange> {
+    let fields = [
+        (
+            WordRevisionField::Lemma,
+            Some(&from_revision.lemma),
+            Some(&to_revision.lemma),
+        ),
+        (
+            WordRevisionField::Disambiguation,
+            from_revision.disambiguation.as_ref(),
+            to_revision.disambiguation.as_ref(),
+        ),
+        (
+            WordRevisionField::Description,
+            from_revision.description.as_ref(),
+            to_revision.description.as_ref(),
+        ),
+        (
+            WordRevisionField::Grammar,
+            from_revision.grammar.as_ref(),
+            to_revision.grammar.as_ref(),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, from_value, to_value)| from_value != to_value)
+        .map(|(field, from_value, to_value)| WordRevisionFieldChange {
+            field,
+            from: from_value.cloned(),
+            to: to_value.cloned(),
+        })
+        .collect()
+}
+
+
+/// Compare two revisions of a word
+///
+/// This endpoint returns a field-by-field diff between two revisions of a word
+/// (given with the `from` and `to` query parameters). Only fields whose values differ
+/// are included. The revisions can be given in any order, e.g. to see what
+/// reverting to an older revision would change.
+///
+/// # Authentication
+/// Authentication is *not required* on this endpoint due to a blanket grant of
+/// the `word:read` permission to unauthenticated users.
+#[utoipa::path(
+    get,
+    path = "/dictionary/revisions/{word_uuid}/diff",
+    tag = "dictionary:revisions",
+    params(
+        (
+            "word_uuid" = String,
+            Path,
+            description = "UUID of the english or slovene word."
+        ),
+        (
+            "from" = i32,
+            Query,
+            description = "Number of the revision to compare from."
+        ),
+        (
+            "to" = i32,
+            Query,
+            description = "Number of the revision to compare to."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Changes between the two revisions.",
+            body = WordRevisionDiffResponse,
+        ),
+        (
+            status = 400,
+            description = "Invalid word UUID or revision numbers provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "The requested word or one of the revisions does not exist."
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[get("/{word_uuid}/diff")]
+pub async fn diff_word_revisions(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+    query: web::Query<WordRevisionDiffQuery>,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
+
+
+    let target_word_uuid = parse_string_into_uuid(&parameters.into_inner().0)?;
+    let query = query.into_inner();
+
+    target_word_language(&state, target_word_uuid).await?;
+
+
+    let from_revision =
+        WordRevisionQuery::revision_by_number(&state.database, target_word_uuid, query.from)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    let to_revision =
+        WordRevisionQuery::revision_by_number(&state.database, target_word_uuid, query.to)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    let (Some(from_revision), Some(to_revision)) = (from_revision, to_revision) else {
+        return Err(APIError::not_found_with_reason(
+            "revision does not exist.",
+        ));
+    };
+
+
+    Ok(WordRevisionDiffResponse {
+        from_revision: from_revision.revision_number,
+        to_revision: to_revision.revision_number,
+        changes: diff_revisions(&from_revision, &to_revision),
+    }
+    .into_response())
+}
+
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(
+    example = json!({
+        "revision": {
+            "word_id": "018dbe00-266e-7398-abd2-0906df0aa345",
+            "revision_number": 3,
+            "lemma": "adventurer",
+            "disambiguation": null,
+            "description": null,
+            "author_user_id": 1,
+            "created_at": "2023-06-29T08:45:51.371902Z"
+        },
+        "broken_references": []
+    })
+)]
+pub struct WordRevisionRevertResponse {
+    /// The newest revision of the word after the revert.
+    pub revision: WordRevision,
+
+    /// Words whose descriptions reference this word by the lemma and disambiguation
+    /// it had before the revert. Their references no longer resolve and should be updated.
+    ///
+    /// This is always empty if the revert changed neither the lemma nor the disambiguation.
+    pub broken_references: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(WordRevisionRevertResponse);
+
+
+/// Revert a word to an earlier revision
+///
+/// This endpoint restores the lemma, disambiguation, description and grammatical information
+/// of a word to those of an earlier revision. The revert is itself recorded as a new revision,
+/// so it can be undone just like any other change.
+///
+/// The revision is validated just like an update would be, so a revert is rejected
+/// if e.g. the description of the revision references a word that no longer exists.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `word:update` permission.
+#[utoipa::path(
+    post,
+    path = "/dictionary/revisions/{word_uuid}/{revision_number}/revert",
+    tag = "dictionary:revisions",
+    params(
+        (
+            "word_uuid" = String,
+            Path,
+            description = "UUID of the english or slovene word."
+        ),
+        (
+            "revision_number" = i32,
+            Path,
+            description = "Number of the revision to revert the word to."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The word has been reverted.",
+            body = WordRevisionRevertResponse,
+        ),
+        (
+            status = 400,
+            description = "Invalid word UUID provided, or the revision's description \
+                           contains an invalid reference or its grammatical information \
+                           is no longer valid.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "The requested word or revision does not exist."
+        ),
+        (
+            status = 409,
+            description = "Another word now uses the lemma and disambiguation of the revision.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Another word with the lemma and disambiguation of this revision already exists." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresWordUpdate>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/{word_uuid}/{revision_number}/revert")]
+pub async fn revert_word_to_revision(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String, i32)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(state, authenticated_user, Permission::WordUpdate);
+
+
+    let (target_word_uuid, target_revision_number) = {
+        let parameters = parameters.into_inner();
+
+        let target_word_uuid = parse_string_into_uuid(&parameters.0)?;
+        let target_revision_number = parameters.1;
+
+        (target_word_uuid, target_revision_number)
+    };
+
+    let target_word_language = target_word_language(&state, target_word_uuid).await?;
+
+
+    let target_revision = WordRevisionQuery::revision_by_number(
+        &state.database,
+        target_word_uuid,
+        target_revision_number,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    let Some(target_revision) = target_revision else {
+        return Err(APIError::not_found_with_reason(
+            "revision does not exist.",
+        ));
+    };
+
+
+    let (current_lemma, current_disambiguation) = match target_word_language {
+        WordLanguage::English => {
+            EnglishWordQuery::word_by_uuid(&state.database, target_word_uuid)
+                .await
+                .map_err(APIError::InternalError)?
+                .map(|word| (word.lemma, word.disambiguation))
+        }
+        WordLanguage::Slovene => {
+            SloveneWordQuery::word_by_uuid(&state.database, target_word_uuid)
+                .await
+                .map_err(APIError::InternalError)?
+                .map(|word| (word.lemma, word.disambiguation))
+        }
+    }
+    .ok_or_else(APIError::not_found)?;
+
+
+    // The lemma and disambiguation may have been taken by another word
+    // since the revision was made.
+    let word_with_same_lemma_id = match target_word_language {
+        WordLanguage::English => {
+            EnglishWordQuery::word_by_lemma_and_disambiguation(
+                &state.database,
+                target_revision.lemma.clone(),
+                target_revision.disambiguation.clone(),
+            )
+            .await
+            .map_err(APIError::InternalError)?
+            .map(|word| word.word_id)
+        }
+        WordLanguage::Slovene => {
+            SloveneWordQuery::word_by_lemma_and_disambiguation(
+                &state.database,
+                target_revision.lemma.clone(),
+                target_revision.disambiguation.clone(),
+            )
+            .await
+            .map_err(APIError::InternalError)?
+            .map(|word| word.word_id)
+        }
+    };
+
+    if word_with_same_lemma_id.is_some_and(|word_id| word_id != target_word_uuid) {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Another word with the lemma and disambiguation of this revision already exists."
+        ));
+    }
+
+    // The revision is validated the same way an update would be, since e.g. the words
+    // its description references may have been deleted or renamed since it was made.
+    validate_description_references(&state, target_revision.description.as_deref()).await?;
+    validate_revision_grammar(target_word_language, &target_revision)?;
+
+
+    let author_user_id = Some(authenticated_user.user_id());
+
+    let has_been_reverted = match target_word_language {
+        WordLanguage::English => EnglishWordMutation::revert_to_revision(
+            &state.database,
+            target_word_uuid,
+            &target_revision,
+            author_user_id,
+        )
+        .await
+        .map_err(APIError::InternalError)?
+        .is_some(),
+        WordLanguage::Slovene => SloveneWordMutation::revert_to_revision(
+            &state.database,
+            target_word_uuid,
+            &target_revision,
+            author_user_id,
+        )
+        .await
+        .map_err(APIError::InternalError)?
+        .is_some(),
+    };
+
+    // Another word may have taken the lemma and disambiguation since the check above.
+    if !has_been_reverted {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Another word with the lemma and disambiguation of this revision already exists."
+        ));
+    }
+
+    match target_word_language {
+        WordLanguage::English => {
+            // Signals to the the search indexer that the word has been updated.
+            state
+                .search
+                .signal_english_word_created_or_updated(target_word_uuid)
+                .await
+                .map_err(APIError::InternalError)?;
+        }
+        WordLanguage::Slovene => {
+            // Signals to the the search indexer that the word has been updated.
+            state
+                .search
+                .signal_slovene_word_created_or_updated(target_word_uuid)
+                .await
+                .map_err(APIError::InternalError)?;
+        }
+    };
+
+    update_word_references(
+        &state.database,
+        target_word_uuid,
+        target_revision.description.as_deref(),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    let broken_references = if target_revision.lemma != current_lemma
+        || target_revision.disambiguation != current_disambiguation
+    {
+        words_referencing_word(&state, target_word_uuid).await?
+    } else {
+        Vec::new()
+    };
+
+    if !broken_references.is_empty() {
+        warn!(
+            word_id = target_word_uuid.to_string(),
+            previous_disambiguation = ?current_disambiguation,
+            new_disambiguation = ?target_revision.disambiguation,
+            "Reverting word from {} to {} broke references from {} other word(s).",
+            current_lemma,
+            target_revision.lemma,
+            broken_references.len()
+        );
+    }
+
+
+    let latest_revision =
+        WordRevisionQuery::latest_revision_for_word(&state.database, target_word_uuid)
+            .await
+            .map_err(APIError::InternalError)?
+            .ok_or_else(|| {
+                APIError::internal_reason("Word has no revisions after being reverted.")
+            })?;
+
+
+    info!(
+        reverted_by_user = authenticated_user.user_id(),
+        word_id = target_word_uuid.to_string(),
+        "Reverted word to revision {}.", target_revision_number
+    );
+
+
+    Ok(WordRevisionRevertResponse {
+        revision: WordRevision::from_database_model(latest_revision),
+        broken_references,
+    }
+    .into_response())
+}
+
+
+
+#[rustfmt::skip]
+pub fn revisions_router() -> Scope {
+    web::scope("/revisions")
+        .service(get_word_revisions)
+        .service(diff_word_revisions)
+        .service(revert_word_to_revision)
+}
@@ -1,5 +1,5 @@
 use actix_http::StatusCode;
-use actix_web::{delete, get, patch, post, web, HttpResponse, Scope};
+use actix_web::{delete, get, patch, post, web, Scope};
 use chrono::{DateTime, Utc};
 use kolomoni_auth::Permission;
 use kolomoni_database::{
@@ -14,15 +14,31 @@ use kolomoni_database::{
     },
 };
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 use utoipa::ToSchema;
 
-use super::Category;
+use super::{
+    grammar::{validate_slovene_word_grammar, SloveneWordGrammar},
+    links::WordLink,
+    references::{
+        render_description_if_requested,
+        validate_description_references,
+        words_referencing_word,
+        ReferencedWord,
+        WordDescriptionRenderingQuery,
+    },
+    Category,
+};
 use crate::{
     api::{
         errors::{APIError, EndpointResult},
         macros::ContextlessResponder,
         openapi,
-        v1::dictionary::parse_string_into_uuid,
+        v1::dictionary::{
+            normalize_disambiguation,
+            parse_string_into_uuid,
+            parse_word_page_options,
+        },
     },
     authentication::UserAuthenticationExtractor,
     error_response_with_reason,
@@ -31,6 +47,7 @@ use crate::{
     require_permission,
     require_permission_with_optional_authentication,
     state::ApplicationState,
+    word_references::update_word_references,
 };
 
 
@@ -42,8 +59,23 @@ use crate::{
         "lemma": "pustolovec",
         "disambiguation": "lik",
         "description": "Igrani ali neigrani liki, ki se odpravijo na pustolovščino.",
+        "grammar": {
+            "part_of_speech": "noun",
+            "gender": "masculine",
+            "number_restriction": null,
+            "inflection": {
+                "nominative": { "singular": "pustolovec", "dual": "pustolovca", "plural": "pustolovci" },
+                "genitive": { "singular": "pustolovca", "dual": null, "plural": null },
+                "dative": { "singular": null, "dual": null, "plural": null },
+                "accusative": { "singular": null, "dual": null, "plural": null },
+                "locative": { "singular": null, "dual": null, "plural": null },
+                "instrumental": { "singular": null, "dual": null, "plural": null }
+            }
+        },
         "created_at": "2023-06-27T20:34:27.217273Z",
-        "last_modified_at": "2023-06-27T20:34:27.217273Z"
+        "last_modified_at": "2023-06-27T20:34:27.217273Z",
+        "categories": [],
+        "links": []
     })
 )]
 pub struct SloveneWord {
@@ -57,9 +89,18 @@ pub struct SloveneWord {
     /// helps distinguish the word from other words at a glance.
     pub disambiguation: Option<String>,
 
-    /// A short description of the word. Supports Markdown.
+    /// A short description of the word. Supports Markdown and references
+    /// to other dictionary words, e.g. `[[en:adventurer]]` or `[[sl:pustolovec|lik]]`.
     pub description: Option<String>,
 
+    /// The description rendered into sanitised HTML.
+    /// Only present if it was requested with the `render_description` query parameter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_html: Option<String>,
+
+    /// Grammatical information about the word (e.g. its part of speech).
+    pub grammar: SloveneWordGrammar,
+
     /// When the word was created.
     pub created_at: DateTime<Utc>,
 
@@ -70,18 +111,26 @@ pub struct SloveneWord {
     pub last_modified_at: DateTime<Utc>,
 
     pub categories: Vec<Category>,
+
+    /// Other slovene words this word is linked to (e.g. synonyms).
+    pub links: Vec<WordLink>,
 }
 
 impl SloveneWord {
     pub fn new_without_expanded_info(slovene_model: entities::word_slovene::Model) -> Self {
+        let grammar = SloveneWordGrammar::from_database_grammar(slovene_model.grammar());
+
         Self {
             id: slovene_model.word_id.to_string(),
             lemma: slovene_model.lemma,
             disambiguation: slovene_model.disambiguation,
             description: slovene_model.description,
+            description_html: None,
+            grammar,
             created_at: slovene_model.created_at.to_utc(),
             last_modified_at: slovene_model.last_modified_at.to_utc(),
             categories: Vec::new(),
+            links: Vec::new(),
         }
     }
 
@@ -95,15 +144,26 @@ impl SloveneWord {
             .map(Category::from_database_model)
             .collect();
 
+        let links = related_slovene_word_info
+            .links
+            .into_iter()
+            .map(WordLink::from_slovene_word_link)
+            .collect();
+
+        let grammar = SloveneWordGrammar::from_database_grammar(word_model.grammar());
+
 
         Self {
             id: word_model.word_id.to_string(),
             lemma: word_model.lemma,
             disambiguation: word_model.disambiguation,
             description: word_model.description,
+            description_html: None,
+            grammar,
             created_at: word_model.created_at.to_utc(),
             last_modified_at: word_model.last_modified_at.to_utc(),
             categories,
+            links,
         }
     }
 
@@ -116,15 +176,26 @@ impl SloveneWord {
             .map(Category::from_database_model)
             .collect();
 
+        let links = expanded_slovene_word
+            .links
+            .into_iter()
+            .map(WordLink::from_slovene_word_link)
+            .collect();
+
+        let grammar = SloveneWordGrammar::from_database_grammar(word.grammar());
+
 
         Self {
             id: word.word_id.to_string(),
             lemma: word.lemma,
             disambiguation: word.disambiguation,
             description: word.description,
+            description_html: None,
+            grammar,
             created_at: word.created_at.to_utc(),
             last_modified_at: word.last_modified_at.to_utc(),
             categories,
+            links,
         }
     }
 }
@@ -135,6 +206,10 @@ impl SloveneWord {
 #[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
 pub struct SloveneWordsResponse {
     pub slovene_words: Vec<SloveneWord>,
+
+    /// Cursor to pass as `cursor` to get the next page of words
+    /// (`null` if this is the last page).
+    pub next_cursor: Option<String>,
 }
 
 impl_json_response_builder!(SloveneWordsResponse);
@@ -148,17 +223,28 @@ pub struct SloveneWordFilters {
 }
 
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
 #[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
 pub struct SloveneWordsListRequest {
     pub filters: Option<SloveneWordFilters>,
+
+    /// Amount of words on the page (the server's configured default if omitted).
+    pub page_size: Option<u64>,
+
+    /// The `next_cursor` value from the previous page.
+    pub cursor: Option<String>,
 }
 
 
 
 /// List all slovene words
 ///
-/// This endpoint returns a list of all slovene words.
+/// This endpoint returns a page of slovene words, ordered by their ID
+/// (which is roughly the order in which they were created).
+///
+/// The list is paginated with cursors: to get the next page, repeat the request
+/// with the `cursor` field set to `next_cursor` from the previous response.
+/// When `next_cursor` is `null`, there are no more words.
 ///
 /// # Authentication
 /// Authentication is *not required* on this endpoint due to blanket grant of
@@ -173,9 +259,15 @@ pub struct SloveneWordsListRequest {
     responses(
         (
             status = 200,
-            description = "A list of all slovene words.",
+            description = "A page of slovene words.",
             body = SloveneWordsResponse,
         ),
+        (
+            status = 400,
+            description = "Invalid page size or cursor.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid cursor." })
+        ),
         openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
         openapi::InternalServerErrorResponse,
     )
@@ -189,27 +281,36 @@ pub async fn get_all_slovene_words(
     require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
 
 
-    let word_query_options = match request_body {
-        Some(body) => {
-            let body = body.into_inner();
+    let request_body = request_body
+        .map(|body| body.into_inner())
+        .unwrap_or_default();
 
-            match body.filters {
-                Some(filters) => SloveneWordsQueryOptions {
-                    only_words_modified_after: filters.last_modified_after,
-                },
-                None => SloveneWordsQueryOptions::default(),
-            }
-        }
+    let word_query_options = match request_body.filters {
+        Some(filters) => SloveneWordsQueryOptions {
+            only_words_modified_after: filters.last_modified_after,
+        },
         None => SloveneWordsQueryOptions::default(),
     };
 
-    // Load words from the database.
-    let words = query::SloveneWordQuery::all_words_expanded(&state.database, word_query_options)
-        .await
-        .map_err(APIError::InternalError)?;
+    let page_options = parse_word_page_options(
+        &state.configuration.dictionary,
+        request_body.page_size,
+        request_body.cursor.as_deref(),
+    )?;
+
+
+    // Load a page of words from the database.
+    let words_page = query::SloveneWordQuery::all_words_expanded_paginated(
+        &state.database,
+        word_query_options,
+        page_options,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
 
-    let words_as_api_structures = words
+    let words_as_api_structures = words_page
+        .words
         .into_iter()
         .map(SloveneWord::from_expanded_word_info)
         .collect();
@@ -217,6 +318,9 @@ pub async fn get_all_slovene_words(
 
     Ok(SloveneWordsResponse {
         slovene_words: words_as_api_structures,
+        next_cursor: words_page
+            .next_cursor
+            .map(|word_id| word_id.to_string()),
     }
     .into_response())
 }
@@ -228,13 +332,26 @@ pub async fn get_all_slovene_words(
     example = json!({
         "lemma": "pustolovec",
         "disambiguation": "lik",
-        "description": "Igrani ali neigrani liki, ki se odpravijo na pustolovščino."
+        "description": "Igrani ali neigrani liki, ki se odpravijo na pustolovščino.",
+        "grammar": {
+            "part_of_speech": "noun",
+            "gender": "masculine",
+            "inflection": {
+                "nominative": { "singular": "pustolovec", "dual": "pustolovca", "plural": "pustolovci" }
+            }
+        }
     })
 )]
 pub struct SloveneWordCreationRequest {
     pub lemma: String,
+
+    /// An empty disambiguation is treated the same as a missing one.
     pub disambiguation: Option<String>,
+
     pub description: Option<String>,
+
+    /// Grammatical information about the word (none if not provided).
+    pub grammar: Option<SloveneWordGrammar>,
 }
 
 #[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
@@ -263,6 +380,9 @@ impl_json_response_builder!(SloveneWordCreationResponse);
 ///
 /// This endpoint creates a new slovene word in the dictionary.
 ///
+/// The description can reference other dictionary words (e.g. `[[en:adventurer]]`),
+/// but only words that already exist.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:create` permission.
 #[utoipa::path(
@@ -278,11 +398,18 @@ impl_json_response_builder!(SloveneWordCreationResponse);
             description = "The newly-created slovene word.",
             body = SloveneWordCreationResponse,
         ),
+        (
+            status = 400,
+            description = "The description contains an invalid reference or references a word \
+                           that does not exist, or the grammatical information is inconsistent.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid description: the referenced word [[en:adventurer]] does not exist." })
+        ),
         (
             status = 409,
-            description = "Slovene word with the given lemma already exists.",
+            description = "Slovene word with the given lemma and disambiguation already exists.",
             body = ErrorReasonResponse,
-            example = json!({ "reason": "A slovene word with the given lemma already exists." })
+            example = json!({ "reason": "A slovene word with the given lemma and disambiguation already exists." })
         ),
         openapi::MissingOrInvalidJsonRequestBodyResponse,
         openapi::FailedAuthenticationResponses<openapi::RequiresWordCreate>,
@@ -303,27 +430,55 @@ pub async fn create_slovene_word(
 
 
     let creation_request = creation_request.into_inner();
+    let disambiguation = creation_request
+        .disambiguation
+        .and_then(normalize_disambiguation);
 
-    let lemma_already_exists =
-        SloveneWordQuery::word_exists_by_lemma(&state.database, creation_request.lemma.clone())
-            .await
-            .map_err(APIError::InternalError)?;
+    let word_already_exists = SloveneWordQuery::word_exists_by_lemma_and_disambiguation(
+        &state.database,
+        creation_request.lemma.clone(),
+        disambiguation.clone(),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
-    if lemma_already_exists {
+    if word_already_exists {
         return Ok(error_response_with_reason!(
             StatusCode::CONFLICT,
-            "A slovene word with the given lemma already exists."
+            "A slovene word with the given lemma and disambiguation already exists."
         ));
     }
 
+    validate_description_references(&state, creation_request.description.as_deref()).await?;
+
+    let grammar = validate_slovene_word_grammar(creation_request.grammar.unwrap_or_default())?;
+
 
     let newly_created_word = SloveneWordMutation::create(
         &state.database,
         NewSloveneWord {
             lemma: creation_request.lemma,
-            disambiguation: creation_request.disambiguation,
+            disambiguation,
             description: creation_request.description,
+            grammar,
         },
+        Some(authenticated_user.user_id()),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    // The word may have been created concurrently since the check above.
+    let Some(newly_created_word) = newly_created_word else {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "An slovene word with the given lemma and disambiguation already exists."
+        ));
+    };
+
+    update_word_references(
+        &state.database,
+        newly_created_word.word_id,
+        newly_created_word.description.as_deref(),
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -361,6 +516,10 @@ impl_json_response_builder!(SloveneWordInfoResponse);
 ///
 /// This endpoint returns information about a single slovene word from the dictionary.
 ///
+/// If the `render_description` query parameter is `true`, the word will also include
+/// its description rendered into sanitised HTML, with references to other words
+/// turned into `<a class="word-reference" data-word-id="..." data-language="...">` elements.
+///
 /// # Authentication
 /// Authentication is *not required* on this endpoint due to a blanket grant of
 /// the `word:read` permission to unauthenticated users.
@@ -373,6 +532,11 @@ impl_json_response_builder!(SloveneWordInfoResponse);
             "word_uuid" = String,
             Path,
             description = "UUID of the slovene word."
+        ),
+        (
+            "render_description" = Option<bool>,
+            Query,
+            description = "Whether to also return the description rendered into HTML (`false` by default)."
         )
     ),
     responses(
@@ -400,6 +564,7 @@ pub async fn get_specific_slovene_word(
     state: ApplicationState,
     authentication: UserAuthenticationExtractor,
     parameters: web::Path<(String,)>,
+    rendering_query: web::Query<WordDescriptionRenderingQuery>,
 ) -> EndpointResult {
     require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
 
@@ -416,18 +581,35 @@ pub async fn get_specific_slovene_word(
     };
 
 
-    Ok(SloveneWordInfoResponse {
-        word: SloveneWord::from_expanded_word_info(target_word),
-    }
-    .into_response())
+    let mut word = SloveneWord::from_expanded_word_info(target_word);
+    word.description_html = render_description_if_requested(
+        &state,
+        rendering_query.into_inner(),
+        word.description.as_deref(),
+    )
+    .await?;
+
+
+    Ok(SloveneWordInfoResponse { word }.into_response())
+}
+
+
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct SloveneWordsByLemmaResponse {
+    /// All slovene words with the requested lemma (homonyms), oldest first.
+    pub slovene_words: Vec<SloveneWord>,
 }
 
+impl_json_response_builder!(SloveneWordsByLemmaResponse);
 
 
-/// Fina a slovene word by lemma
+/// Find slovene words by lemma
 ///
-/// This endpoint returns information about a single slovene word from the dictionary,
-/// but takes a lemma as a parameter instead of the word ID.
+/// This endpoint returns information about all slovene words from the dictionary
+/// with the given lemma. There can be more than one such word (homonyms),
+/// in which case they are distinguished by their disambiguation.
 ///
 /// Note that this is *not* intended as a search endpoint!
 ///
@@ -443,17 +625,22 @@ pub async fn get_specific_slovene_word(
             "word_lemma" = String,
             Path,
             description = "Slovene word lemma to look up."
+        ),
+        (
+            "render_description" = Option<bool>,
+            Query,
+            description = "Whether to also return the descriptions rendered into HTML (`false` by default)."
         )
     ),
     responses(
         (
             status = 200,
-            description = "Information about the requested slovene word.",
-            body = SloveneWordInfoResponse,
+            description = "Information about all slovene words with the requested lemma.",
+            body = SloveneWordsByLemmaResponse,
         ),
         (
             status = 404,
-            description = "The requested slovene word does not exist."
+            description = "No slovene word with the requested lemma exists."
         ),
         openapi::FailedAuthenticationResponses<openapi::RequiresWordRead>,
         openapi::InternalServerErrorResponse,
@@ -464,26 +651,36 @@ pub async fn get_specific_slovene_word_by_lemma(
     state: ApplicationState,
     authentication: UserAuthenticationExtractor,
     parameters: web::Path<(String,)>,
+    rendering_query: web::Query<WordDescriptionRenderingQuery>,
 ) -> EndpointResult {
     require_permission_with_optional_authentication!(state, authentication, Permission::WordRead);
 
 
     let target_word_lemma = parameters.into_inner().0;
+    let rendering_query = rendering_query.into_inner();
 
+    let target_words =
+        SloveneWordQuery::expanded_words_by_lemma(&state.database, target_word_lemma)
+            .await
+            .map_err(APIError::InternalError)?;
 
-    let target_word = SloveneWordQuery::expanded_word_by_lemma(&state.database, target_word_lemma)
-        .await
-        .map_err(APIError::InternalError)?;
-
-    let Some(target_word) = target_word else {
+    if target_words.is_empty() {
         return Err(APIError::not_found());
-    };
+    }
+
 
+    let mut slovene_words = Vec::with_capacity(target_words.len());
+    for target_word in target_words {
+        let mut word = SloveneWord::from_expanded_word_info(target_word);
+        word.description_html =
+            render_description_if_requested(&state, rendering_query, word.description.as_deref())
+                .await?;
 
-    Ok(SloveneWordInfoResponse {
-        word: SloveneWord::from_expanded_word_info(target_word),
+        slovene_words.push(word);
     }
-    .into_response())
+
+
+    Ok(SloveneWordsByLemmaResponse { slovene_words }.into_response())
 }
 
 
@@ -492,18 +689,43 @@ pub async fn get_specific_slovene_word_by_lemma(
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
 pub struct SloveneWordUpdateRequest {
     pub lemma: Option<String>,
+
+    /// An empty disambiguation removes the word's disambiguation.
     pub disambiguation: Option<String>,
+
     pub description: Option<String>,
+
+    /// If set, replaces all grammatical information about the word.
+    pub grammar: Option<SloveneWordGrammar>,
 }
 
 impl_json_response_builder!(SloveneWordUpdateRequest);
 
 
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct SloveneWordUpdateResponse {
+    pub word: SloveneWord,
+
+    /// Words whose descriptions reference this word by its previous lemma and disambiguation.
+    /// Their references no longer resolve and should be updated.
+    ///
+    /// This is always empty if neither the lemma nor the disambiguation were changed.
+    pub broken_references: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(SloveneWordUpdateResponse);
+
+
 
 /// Update a slovene word
 ///
 /// This endpoint updates an existing slovene word in the dictionary.
 ///
+/// If the lemma or disambiguation are changed, references to this word in descriptions
+/// of other words (e.g. `[[sl:pustolovec]]`) will no longer resolve. Such words are listed
+/// in the `broken_references` field of the response.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:update` permission.
 #[utoipa::path(
@@ -524,11 +746,12 @@ impl_json_response_builder!(SloveneWordUpdateRequest);
         (
             status = 200,
             description = "Updated slovene word.",
-            body = SloveneWordInfoResponse,
+            body = SloveneWordUpdateResponse,
         ),
         (
             status = 400,
-            description = "Invalid word UUID provided.",
+            description = "Invalid word UUID, a description with an invalid reference \
+                           or inconsistent grammatical information provided.",
             body = ErrorReasonResponse,
             example = json!({ "reason": "Client error: invalid UUID." })
         ),
@@ -536,6 +759,12 @@ impl_json_response_builder!(SloveneWordUpdateRequest);
             status = 404,
             description = "The requested slovene word does not exist."
         ),
+        (
+            status = 409,
+            description = "Another slovene word with the new lemma and disambiguation already exists.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "A slovene word with the given lemma and disambiguation already exists." })
+        ),
         openapi::MissingOrInvalidJsonRequestBodyResponse,
         openapi::FailedAuthenticationResponses<openapi::RequiresWordUpdate>,
         openapi::InternalServerErrorResponse,
@@ -560,28 +789,106 @@ pub async fn update_specific_slovene_word(
     let request_data = request_data.into_inner();
 
 
-    let target_word_exists =
-        SloveneWordQuery::word_exists_by_uuid(&state.database, target_word_uuid)
-            .await
-            .map_err(APIError::InternalError)?;
+    let target_word = SloveneWordQuery::word_by_uuid(&state.database, target_word_uuid)
+        .await
+        .map_err(APIError::InternalError)?;
 
-    if !target_word_exists {
+    let Some(target_word) = target_word else {
         return Err(APIError::not_found());
+    };
+
+    // An empty disambiguation becomes `Some(None)`, which removes it.
+    let disambiguation = request_data
+        .disambiguation
+        .map(normalize_disambiguation);
+
+    if request_data.lemma.is_some() || disambiguation.is_some() {
+        let conflicting_word = SloveneWordQuery::word_by_lemma_and_disambiguation(
+            &state.database,
+            request_data
+                .lemma
+                .clone()
+                .unwrap_or_else(|| target_word.lemma.clone()),
+            match &disambiguation {
+                Some(new_disambiguation) => new_disambiguation.clone(),
+                None => target_word.disambiguation.clone(),
+            },
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        if conflicting_word.is_some_and(|word| word.word_id != target_word_uuid) {
+            return Ok(error_response_with_reason!(
+                StatusCode::CONFLICT,
+                "A slovene word with the given lemma and disambiguation already exists."
+            ));
+        }
     }
 
+    validate_description_references(&state, request_data.description.as_deref()).await?;
+
+    let grammar = request_data
+        .grammar
+        .map(validate_slovene_word_grammar)
+        .transpose()?;
+
+    let description_changed = request_data.description.is_some();
+
 
     let updated_word = SloveneWordMutation::update(
         &state.database,
         target_word_uuid,
         UpdatedSloveneWord {
             lemma: request_data.lemma,
-            disambiguation: request_data.disambiguation,
+            disambiguation,
             description: request_data.description,
+            grammar,
         },
+        Some(authenticated_user.user_id()),
     )
     .await
     .map_err(APIError::InternalError)?;
 
+    // Another word may have taken the lemma and disambiguation since the check above.
+    let Some(updated_word) = updated_word else {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "An slovene word with the given lemma and disambiguation already exists."
+        ));
+    };
+
+    if description_changed {
+        update_word_references(
+            &state.database,
+            target_word_uuid,
+            updated_word.description.as_deref(),
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+    }
+
+
+    // References point to a lemma and disambiguation, so changing either of them breaks them.
+    let broken_references = if updated_word.lemma != target_word.lemma
+        || updated_word.disambiguation != target_word.disambiguation
+    {
+        words_referencing_word(&state, target_word_uuid).await?
+    } else {
+        Vec::new()
+    };
+
+    if !broken_references.is_empty() {
+        warn!(
+            word_id = target_word_uuid.to_string(),
+            previous_disambiguation = ?target_word.disambiguation,
+            new_disambiguation = ?updated_word.disambiguation,
+            "Renaming slovene word {} to {} broke references from {} other word(s).",
+            target_word.lemma,
+            updated_word.lemma,
+            broken_references.len()
+        );
+    }
+
 
     let related_word_info =
         SloveneWordQuery::related_word_information_only(&state.database, updated_word.word_id)
@@ -598,18 +905,33 @@ pub async fn update_specific_slovene_word(
         .map_err(APIError::InternalError)?;
 
 
-    Ok(SloveneWordInfoResponse {
+    Ok(SloveneWordUpdateResponse {
         word: SloveneWord::from_word_and_related_info(updated_word, related_word_info),
+        broken_references,
     }
     .into_response())
 }
 
 
 
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct SloveneWordDeletionResponse {
+    /// Words whose descriptions referenced the deleted word.
+    /// Their references no longer resolve and should be updated.
+    pub broken_references: Vec<ReferencedWord>,
+}
+
+impl_json_response_builder!(SloveneWordDeletionResponse);
+
+
 /// Delete a slovene word
 ///
 /// This endpoint deletes a slovene word from the dictionary.
 ///
+/// References to this word in descriptions of other words will no longer resolve.
+/// Such words are listed in the `broken_references` field of the response.
+///
 /// # Authentication
 /// This endpoint requires authentication and the `word:delete` permission.
 #[utoipa::path(
@@ -627,6 +949,7 @@ pub async fn update_specific_slovene_word(
         (
             status = 200,
             description = "Slovene word deleted.",
+            body = SloveneWordDeletionResponse,
         ),
         (
             status = 400,
@@ -667,11 +990,22 @@ pub async fn delete_specific_slovene_word(
         return Err(APIError::not_found());
     }
 
+    // The references are removed along with the word, so they need to be looked up beforehand.
+    let broken_references = words_referencing_word(&state, target_word_uuid).await?;
+
 
     WordMutation::delete(&state.database, target_word_uuid)
         .await
         .map_err(APIError::InternalError)?;
 
+    if !broken_references.is_empty() {
+        warn!(
+            word_id = target_word_uuid.to_string(),
+            "Deleting slovene word broke references from {} other word(s).",
+            broken_references.len()
+        );
+    }
+
 
     // Signals to the the search indexer that the word has been removed.
     state
@@ -681,13 +1015,10 @@ pub async fn delete_specific_slovene_word(
         .map_err(APIError::InternalError)?;
 
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(SloveneWordDeletionResponse { broken_references }.into_response())
 }
 
 
-// TODO Links.
-
-
 #[rustfmt::skip]
 pub fn slovene_dictionary_router() -> Scope {
     web::scope("/slovene")
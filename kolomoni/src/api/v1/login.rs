@@ -1,17 +1,32 @@
-use actix_web::{post, web, HttpResponse, Scope};
-use chrono::{Duration, Utc};
-use kolomoni_auth::{JWTClaims, JWTTokenType, JWTValidationError};
-use kolomoni_database::query;
+use std::net::IpAddr;
+
+use actix_web::http::{header, StatusCode};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Scope};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::{JWTTokenType, JWTValidationError, Permission};
+use kolomoni_database::entities;
+use kolomoni_database::mutation::{LoginThrottleMutation, NewUserSession, UserSessionMutation};
+use kolomoni_database::query::{self, LoginThrottleQuery};
+use kolomoni_database::shared::LoginThrottleSubjectType;
 use miette::Context;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use utoipa::ToSchema;
 
 use crate::api::errors::{APIError, EndpointResult, ErrorReasonResponse};
 use crate::api::macros::ContextlessResponder;
 use crate::api::openapi;
-use crate::impl_json_response_builder;
+use crate::authentication::{is_token_revoked, suspension_message, UserAuthenticationExtractor};
 use crate::state::ApplicationState;
+use crate::{
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+};
+
+pub mod oidc;
+pub mod two_factor;
 
 
 
@@ -76,6 +91,21 @@ impl_json_response_builder!(UserLoginResponse);
 /// a new access token. The refresh token is valid for longer than the access token,
 /// but only the access token can be used in the *Authorization* header. For login refreshing,
 /// see the `POST /api/v1/login/refresh` endpoint.
+///
+/// Each successful login starts a new session that the user can later inspect and revoke
+/// (see `GET /api/v1/users/me/sessions`). Both tokens are tied to that session:
+/// once it is revoked or the user logs out, neither token can be used anymore.
+///
+/// # Two-factor authentication
+/// If the user has enabled two-factor authentication (or it is mandatory for them),
+/// this endpoint responds with `202 Accepted` and a challenge token instead of the tokens.
+/// The login must then be completed with `POST /api/v1/login/2fa`.
+///
+/// # Throttling
+/// Failed login attempts are counted per username and per IP address. After too many of them,
+/// the username or IP address is temporarily locked out and all login attempts for it
+/// fail with `429 Too Many Requests` (see the `Retry-After` header for when to try again).
+/// Each further failed attempt after a lockout doubles its duration.
 #[utoipa::path(
     post,
     path = "/login",
@@ -89,11 +119,40 @@ impl_json_response_builder!(UserLoginResponse);
             description = "Login successful.",
             body = UserLoginResponse
         ),
+        (
+            status = 202,
+            description = "Credentials are valid, but a second factor is needed to complete the login.",
+            body = TwoFactorChallengeResponse
+        ),
         (
             status = 403,
-            description = "Invalid login information.",
+            description = "Invalid login information, or deactivated or suspended account.",
             body = ErrorReasonResponse,
-            example = json!({ "reason": "Invalid login credentials." })
+            examples(
+                ("Invalid credentials" = (
+                    summary = "The username or password is incorrect.",
+                    value = json!({ "reason": "Invalid login credentials." })
+                )),
+                ("Deactivated account" = (
+                    summary = "The account has been deactivated by an administrator.",
+                    value = json!({ "reason": "This account has been deactivated." })
+                )),
+                ("Suspended account" = (
+                    summary = "The account has been suspended by an administrator.",
+                    value = json!({
+                        "reason": "This account has been suspended until 2023-07-04T20:33:53Z. Reason: Vandalism."
+                    })
+                ))
+            )
+        ),
+        (
+            status = 429,
+            description = "Too many failed login attempts for this username or from this IP address.",
+            body = ErrorReasonResponse,
+            headers(
+                ("Retry-After" = u64, description = "Number of seconds until the lockout ends.")
+            ),
+            example = json!({ "reason": "Too many failed login attempts, try again later." })
         ),
         openapi::MissingOrInvalidJsonRequestBodyResponse,
         openapi::InternalServerErrorResponse,
@@ -102,8 +161,46 @@ impl_json_response_builder!(UserLoginResponse);
 #[post("")]
 pub async fn login(
     state: ApplicationState,
+    request: HttpRequest,
     login_info: web::Json<UserLoginRequest>,
 ) -> EndpointResult {
+    let ip_address = client_ip_address(&state, &request);
+
+
+    // Refuse to even check the credentials if the username or IP address is locked out.
+    let mut locked_until = LoginThrottleQuery::locked_until(
+        &state.database,
+        LoginThrottleSubjectType::Username,
+        &login_info.username,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if let Some(ip_address) = &ip_address {
+        let ip_address_locked_until = LoginThrottleQuery::locked_until(
+            &state.database,
+            LoginThrottleSubjectType::IpAddress,
+            ip_address,
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        locked_until = locked_until.max(ip_address_locked_until);
+    }
+
+    if let Some(locked_until) = locked_until {
+        debug!(
+            username = login_info.username,
+            ip_address = ip_address,
+            "Refusing login attempt during lockout."
+        );
+
+        return Ok(too_many_failed_login_attempts_response(
+            locked_until,
+        ));
+    }
+
+
     // Validate user login credentials.
     let login_result_details = query::UserQuery::validate_user_credentials(
         &state.database,
@@ -115,6 +212,13 @@ pub async fn login(
     .map_err(APIError::InternalError)?;
 
     let Some(logged_in_user) = login_result_details else {
+        record_failed_login_attempt(
+            &state,
+            &login_info.username,
+            ip_address.as_deref(),
+        )
+        .await?;
+
         return Ok(
             HttpResponse::Forbidden().json(ErrorReasonResponse::custom_reason(
                 "Invalid login credentials.",
@@ -122,19 +226,112 @@ pub async fn login(
         );
     };
 
+    // The credentials are correct, so the failed attempts for this username are forgotten.
+    // The ones for the IP address are not, otherwise a single valid account
+    // would be enough to keep guessing the passwords of others.
+    LoginThrottleMutation::clear(
+        &state.database,
+        LoginThrottleSubjectType::Username,
+        &login_info.username,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if logged_in_user.deactivated_at.is_some() {
+        debug!(
+            username = login_info.username,
+            "Refusing to log in deactivated user."
+        );
+
+        return Ok(
+            HttpResponse::Forbidden().json(ErrorReasonResponse::custom_reason(
+                "This account has been deactivated.",
+            )),
+        );
+    }
+
+    let active_suspension =
+        query::UserSuspensionQuery::active_suspension(&state.database, logged_in_user.id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if let Some(suspension) = active_suspension {
+        debug!(
+            username = login_info.username,
+            "Refusing to log in suspended user."
+        );
+
+        return Ok(
+            HttpResponse::Forbidden().json(ErrorReasonResponse::custom_reason(
+                suspension_message(&suspension),
+            )),
+        );
+    }
+
+
+    let response =
+        two_factor::issue_tokens_or_two_factor_challenge(&state, &request, logged_in_user.id)
+            .await?;
+
+
+    debug!(
+        username = login_info.username,
+        "User has successfully passed the password login step."
+    );
+
+
+    Ok(response)
+}
+
+
+
+/// Starts a new login session for the given user and issues
+/// an access and refresh token tied to it.
+///
+/// This is the final step of every successful login, regardless of how the user
+/// has proven their identity (with a password or through OpenID Connect, and possibly
+/// a second factor).
+pub(crate) async fn start_session_and_issue_tokens(
+    state: &ApplicationState,
+    request: &HttpRequest,
+    user_id: i32,
+) -> Result<UserLoginResponse, APIError> {
+    // Start a new session. The session lives exactly as long as the refresh token.
+    let login_time = Utc::now();
+
+    let ip_address = client_ip_address(state, request);
+
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|header_value| header_value.to_str().ok())
+        .map(str::to_string);
+
+    let session = UserSessionMutation::create(
+        &state.database,
+        NewUserSession {
+            user_id,
+            expires_at: login_time + state.jwt_manager.refresh_token_lifetime(),
+            user_agent,
+            ip_address,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
 
     // Generate access and refresh token.
-    let access_token_claims = JWTClaims::create(
-        logged_in_user.id,
-        Utc::now(),
-        Duration::days(1),
+    let access_token_claims = state.jwt_manager.create_claims(
+        user_id,
+        session.id,
+        login_time,
         JWTTokenType::Access,
     );
 
-    let refresh_token_claims = JWTClaims::create(
-        logged_in_user.id,
-        Utc::now(),
-        Duration::days(7),
+    let refresh_token_claims = state.jwt_manager.create_claims(
+        user_id,
+        session.id,
+        login_time,
         JWTTokenType::Refresh,
     );
 
@@ -152,17 +349,120 @@ pub async fn login(
         .map_err(APIError::InternalError)?;
 
 
-    debug!(
-        username = login_info.username,
-        "User has successfully logged in."
-    );
-
-
     Ok(UserLoginResponse {
         access_token,
         refresh_token,
+    })
+}
+
+
+
+
+/// Builds a `429 Too Many Requests` response for a login attempt during a lockout.
+fn too_many_failed_login_attempts_response(locked_until: DateTime<Utc>) -> HttpResponse {
+    // Round up, so that retrying after the given number of seconds is never too early.
+    let retry_after_milliseconds = (locked_until - Utc::now()).num_milliseconds().max(0);
+    let retry_after_seconds = (retry_after_milliseconds + 999) / 1000;
+
+    HttpResponse::TooManyRequests()
+        .insert_header((
+            header::RETRY_AFTER,
+            retry_after_seconds.max(1).to_string(),
+        ))
+        .json(ErrorReasonResponse::custom_reason(
+            "Too many failed login attempts, try again later.",
+        ))
+}
+
+/// Returns the IP address of the client that sent the `request`.
+///
+/// The `X-Forwarded-For` header is only taken into account if the connection's peer is
+/// one of the configured trusted proxies. The header is then read from right to left
+/// (each proxy appends the address it received the request from), stopping at the first
+/// address that is not a trusted proxy. This way clients can't choose their address
+/// by sending the header themselves.
+pub(crate) fn client_ip_address(state: &ApplicationState, request: &HttpRequest) -> Option<String> {
+    let http_configuration = &state.configuration.http;
+
+    let mut ip_address = request.peer_addr()?.ip();
+
+    if !http_configuration.is_trusted_proxy(&ip_address) {
+        return Some(ip_address.to_string());
+    }
+
+    let forwarded_addresses = request
+        .headers()
+        .get_all(header::X_FORWARDED_FOR)
+        .filter_map(|header_value| header_value.to_str().ok())
+        .flat_map(|header_value| header_value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+
+    for forwarded_address in forwarded_addresses.into_iter().rev() {
+        let Ok(forwarded_address) = forwarded_address.parse::<IpAddr>() else {
+            break;
+        };
+
+        ip_address = forwarded_address;
+
+        if !http_configuration.is_trusted_proxy(&ip_address) {
+            break;
+        }
+    }
+
+    Some(ip_address.to_string())
+}
+
+/// Counts a failed login attempt towards the limits of both the username and the IP address.
+async fn record_failed_login_attempt(
+    state: &ApplicationState,
+    username: &str,
+    ip_address: Option<&str>,
+) -> Result<(), APIError> {
+    let throttling_configuration = &state.configuration.login_throttling;
+
+    let username_throttle = LoginThrottleMutation::record_failed_attempt(
+        &state.database,
+        throttling_configuration,
+        LoginThrottleSubjectType::Username,
+        username,
+        throttling_configuration.max_failed_attempts_per_username,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if username_throttle.locked_until.is_some() {
+        info!(
+            username = username,
+            failed_attempt_count = username_throttle.failed_attempt_count,
+            "Username has been locked out after too many failed login attempts."
+        );
     }
-    .into_response())
+
+
+    let Some(ip_address) = ip_address else {
+        return Ok(());
+    };
+
+    let ip_address_throttle = LoginThrottleMutation::record_failed_attempt(
+        &state.database,
+        throttling_configuration,
+        LoginThrottleSubjectType::IpAddress,
+        ip_address,
+        throttling_configuration.max_failed_attempts_per_ip_address,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if ip_address_throttle.locked_until.is_some() {
+        info!(
+            ip_address = ip_address,
+            failed_attempt_count = ip_address_throttle.failed_attempt_count,
+            "IP address has been locked out after too many failed login attempts."
+        );
+    }
+
+    Ok(())
 }
 
 
@@ -170,6 +470,7 @@ pub async fn login(
 
 /// Information with which to refresh a user's login, generating a new access token.
 #[derive(Deserialize, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
 #[schema(
     example = json!({
         "refresh_token": "eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.eyJpc3MiOiJTdGFyaSBLb2xvbW9uaSIsInN\
@@ -187,6 +488,7 @@ pub struct UserLoginRefreshRequest {
 
 /// Response on successful login refresh.
 #[derive(Serialize, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
 #[schema(
     example = json!({
         "access_token": "eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.eyJpc3MiOiJTdGFyaSBLb2xvbW9uaSIsInN1\
@@ -207,7 +509,9 @@ impl_json_response_builder!(UserLoginRefreshResponse);
 /// Refresh a login
 ///
 /// The user must provide a refresh token given to them on an initial call to `/users/login`.
-/// "Refreshing a login" does not invalidate the refresh token.
+/// "Refreshing a login" does not invalidate the refresh token, but the refresh token
+/// stops working as soon as its session is revoked (by logging out, by revoking the session
+/// or by changing the password).
 ///
 /// The result of this is essentially a new JWT access token. Use when your initial access token
 /// from `/users/login` expires.
@@ -226,9 +530,22 @@ impl_json_response_builder!(UserLoginRefreshResponse);
         ),
         (
             status = 403,
-            description = "Refresh token has expired.",
+            description = "Refresh token has expired or has been revoked.",
             body = ErrorReasonResponse,
-            example = json!({ "reason": "Refresh token has expired." })
+            examples(
+                ("Expired token" = (
+                    summary = "The provided refresh token has expired.",
+                    value = json!({ "reason": "Refresh token has expired." })
+                )),
+                ("Revoked token" = (
+                    summary = "The provided refresh token has been revoked (e.g. by logging out).",
+                    value = json!({ "reason": "Refresh token has been revoked." })
+                )),
+                ("Suspended account" = (
+                    summary = "The account has been suspended by an administrator.",
+                    value = json!({ "reason": "This account has been suspended. Reason: Vandalism." })
+                ))
+            )
         ),
         (
             status = 400,
@@ -292,11 +609,50 @@ pub async fn refresh_login(
         );
     }
 
+    let refresh_token_is_revoked = is_token_revoked(&state.database, &refresh_token_claims)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if refresh_token_is_revoked {
+        debug!(
+            user_id = refresh_token_claims.user_id,
+            "Refusing to refresh revoked token."
+        );
+
+        return Ok(
+            HttpResponse::Forbidden().json(ErrorReasonResponse::custom_reason(
+                "Refresh token has been revoked.",
+            )),
+        );
+    }
+
+    let active_suspension =
+        query::UserSuspensionQuery::active_suspension(&state.database, refresh_token_claims.user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if let Some(suspension) = active_suspension {
+        debug!(
+            user_id = refresh_token_claims.user_id,
+            "Refusing to refresh token of suspended user."
+        );
+
+        return Ok(
+            HttpResponse::Forbidden().json(ErrorReasonResponse::custom_reason(
+                suspension_message(&suspension),
+            )),
+        );
+    }
+
+    UserSessionMutation::mark_refreshed(&state.database, refresh_token_claims.jti)
+        .await
+        .map_err(APIError::InternalError)?;
+
     // Refresh token is valid, create new access token.
-    let access_token_claims = JWTClaims::create(
+    let access_token_claims = state.jwt_manager.create_claims(
         refresh_token_claims.user_id,
+        refresh_token_claims.jti,
         Utc::now(),
-        Duration::days(1),
         JWTTokenType::Access,
     );
     let access_token = state
@@ -316,9 +672,296 @@ pub async fn refresh_login(
 
 
 
+/// Log out
+///
+/// Ends the session the provided access token belongs to. Both the access token and
+/// its accompanying refresh token stop working immediately.
+///
+/// To end other sessions, see `DELETE /api/v1/users/me/sessions/{session_id}`.
+///
+/// # Authentication
+/// This endpoint requires authentication.
+#[utoipa::path(
+    post,
+    path = "/login/logout",
+    tag = "login",
+    responses(
+        (
+            status = 200,
+            description = "Logout successful."
+        ),
+        (
+            status = 400,
+            description = "Authenticated with a personal API token instead of an access token.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Personal API tokens can not be logged out, revoke the token instead."
+            })
+        ),
+        (
+            status = 401,
+            description = "Missing user authentication, provide an `Authorization: Bearer your_token_here` header."
+        ),
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/logout")]
+pub async fn logout(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+
+    let Some(session_id) = authenticated_user.session_id() else {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Personal API tokens can not be logged out, revoke the token instead."
+        ));
+    };
+
+    UserSessionMutation::delete(&state.database, session_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    debug!(
+        user_id = authenticated_user.user_id(),
+        "User has logged out."
+    );
+
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+/// Information about a username or IP address that is locked out from logging in.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "subject_type": "username",
+    "subject": "janeznovak",
+    "failed_attempt_count": 6,
+    "last_failed_attempt_at": "2023-06-27T20:33:53.078789Z",
+    "locked_until": "2023-06-27T20:34:53.078789Z"
+}))]
+pub struct LoginLockout {
+    /// What is locked out: `username` or `ip_address`.
+    pub subject_type: String,
+
+    /// The username or IP address that is locked out.
+    pub subject: String,
+
+    /// Number of recent failed login attempts.
+    pub failed_attempt_count: i32,
+
+    /// When the last failed login attempt happened.
+    pub last_failed_attempt_at: DateTime<Utc>,
+
+    /// When the lockout ends.
+    pub locked_until: DateTime<Utc>,
+}
+
+impl LoginLockout {
+    /// Convert a login throttle database model into a [`LoginLockout`]
+    /// that can be exposed through the API. Returns `None` if the model has no lockout.
+    #[inline]
+    pub fn from_throttle_model(model: entities::login_throttle::Model) -> Option<Self> {
+        Some(Self {
+            locked_until: model.locked_until?.with_timezone(&Utc),
+            subject_type: model.subject_type,
+            subject: model.subject,
+            failed_attempt_count: model.failed_attempt_count,
+            last_failed_attempt_at: model.last_failed_attempt_at.with_timezone(&Utc),
+        })
+    }
+}
+
+
+/// Response containing a list of active login lockouts.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "lockouts": [
+        {
+            "subject_type": "username",
+            "subject": "janeznovak",
+            "failed_attempt_count": 6,
+            "last_failed_attempt_at": "2023-06-27T20:33:53.078789Z",
+            "locked_until": "2023-06-27T20:34:53.078789Z"
+        }
+    ]
+}))]
+pub struct LoginLockoutsResponse {
+    pub lockouts: Vec<LoginLockout>,
+}
+
+impl_json_response_builder!(LoginLockoutsResponse);
+
+
+
+/// List login lockouts
+///
+/// This endpoint returns all usernames and IP addresses that are currently locked out
+/// from logging in because of too many failed login attempts, ordered by when their lockout ends.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    get,
+    path = "/login/lockouts",
+    tag = "login",
+    responses(
+        (
+            status = 200,
+            description = "List of active login lockouts.",
+            body = LoginLockoutsResponse
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/lockouts")]
+pub async fn get_all_login_lockouts(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let lockouts = LoginThrottleQuery::all_active_lockouts(&state.database)
+        .await
+        .map_err(APIError::InternalError)?
+        .into_iter()
+        .filter_map(LoginLockout::from_throttle_model)
+        .collect();
+
+    Ok(LoginLockoutsResponse { lockouts }.into_response())
+}
+
+
+
+/// Clear a login lockout
+///
+/// This endpoint lifts the lockout of a username or IP address and forgets
+/// all of its recent failed login attempts.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    delete,
+    path = "/login/lockouts/{subject_type}/{subject}",
+    tag = "login",
+    params(
+        (
+            "subject_type" = String,
+            Path,
+            description = "What to clear the lockout for: `username` or `ip_address`."
+        ),
+        (
+            "subject" = String,
+            Path,
+            description = "The username or IP address to clear the lockout for."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The lockout has been cleared."
+        ),
+        (
+            status = 400,
+            description = "Invalid subject type.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "No such subject type: \"email\"." })
+        ),
+        (
+            status = 404,
+            description = "There are no recent failed login attempts for the given username or IP address.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "No recent failed login attempts for the given subject." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/lockouts/{subject_type}/{subject}")]
+pub async fn clear_login_lockout(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String, String)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let (subject_type_name, subject) = parameters.into_inner();
+
+    let Some(subject_type) = LoginThrottleSubjectType::from_name(&subject_type_name) else {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            format!("No such subject type: \"{subject_type_name}\".")
+        ));
+    };
+
+
+    let throttle_existed = LoginThrottleMutation::clear(&state.database, subject_type, &subject)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !throttle_existed {
+        return Err(APIError::not_found_with_reason(
+            "No recent failed login attempts for the given subject.",
+        ));
+    }
+
+
+    info!(
+        user_id = authenticated_user.user_id(),
+        subject_type = subject_type.name(),
+        subject = subject,
+        "User has cleared a login lockout."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
 #[rustfmt::skip]
 pub fn login_router() -> Scope {
     web::scope("/login")
         .service(login)
         .service(refresh_login)
+        .service(logout)
+        .service(get_all_login_lockouts)
+        .service(clear_login_lockout)
+        // oidc.rs
+        .service(oidc::start_oidc_login)
+        .service(oidc::finish_oidc_login)
+        // two_factor.rs
+        .service(two_factor::enroll_during_login)
+        .service(two_factor::complete_two_factor_login)
 }
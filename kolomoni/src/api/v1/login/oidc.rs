@@ -0,0 +1,555 @@
+//! Login through an external OpenID Connect identity provider.
+//!
+//! The login is an authorization code flow with PKCE: `POST /login/oidc/authorize`
+//! returns the URL of the identity provider the user should be redirected to, and once
+//! they are redirected back, the received code is passed on to `POST /login/oidc/callback`,
+//! which results in the usual access and refresh tokens.
+
+use actix_web::{http::StatusCode, post, web, HttpRequest};
+use chrono::Utc;
+use kolomoni_auth::{
+    generate_oidc_random_value,
+    pkce_code_challenge,
+    OpenIdConnectClient,
+    OpenIdConnectError,
+    OpenIdConnectIdentity,
+    Permission,
+};
+use kolomoni_configuration::RegistrationMode;
+use kolomoni_database::{
+    begin_transaction,
+    mutation::{
+        NewOidcAuthorizationRequest,
+        OidcAuthorizationRequestMutation,
+        UserMutation,
+        UserOidcIdentityMutation,
+        UserRegistrationInfo,
+    },
+    query::{UserOidcIdentityQuery, UserQuery, UserSuspensionQuery},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
+
+use super::two_factor::issue_tokens_or_two_factor_challenge;
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+    },
+    authentication::{suspension_message, UserAuthenticationExtractor},
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+/// How many numbered variants of a username or display name to try when creating
+/// an account for a new identity whose preferred name is already taken.
+const MAX_NAME_VARIANTS: usize = 100;
+
+/// Username to base new accounts on if the identity provider did not share one.
+const FALLBACK_USERNAME: &str = "user";
+
+
+
+/// Response containing the URL to start an OpenID Connect login at.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "authorization_url": "https://accounts.example.com/authorize?response_type=code&client_id=kolomoni&..."
+}))]
+pub struct OidcAuthorizationResponse {
+    /// URL of the identity provider to redirect the user to.
+    pub authorization_url: String,
+}
+
+impl_json_response_builder!(OidcAuthorizationResponse);
+
+
+/// Information the identity provider passed back after the user logged in.
+#[derive(Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "code": "SplxlOBeZQQYbYS6WxSbIA",
+    "state": "Qm9mLUk3OGxWc2t2b1p5c1RmT3l6dE1Cc0x3Y2xyR3g"
+}))]
+pub struct OidcCallbackRequest {
+    /// The authorization code (the `code` query parameter of the redirect).
+    pub code: String,
+
+    /// The `state` query parameter of the redirect.
+    pub state: String,
+}
+
+
+
+/// Returns the OpenID Connect client, or a `404 Not Found` error if OpenID Connect is not configured.
+fn require_oidc_client(state: &ApplicationState) -> Result<&OpenIdConnectClient, APIError> {
+    state
+        .oidc_client
+        .as_ref()
+        .ok_or_else(|| APIError::not_found_with_reason("OpenID Connect login is not configured."))
+}
+
+
+
+/// Start an OpenID Connect login
+///
+/// This endpoint starts a login through the configured external identity provider.
+/// Redirect the user to the returned URL; after they log in, the identity provider
+/// redirects them back to the configured redirect URL with the `code` and `state`
+/// query parameters, which should be passed on to `POST /api/v1/login/oidc/callback`.
+/// The login must be completed within a few minutes.
+///
+/// If you call this endpoint while authenticated, completing the login *links* the identity
+/// to your account instead, so you can log in with it in the future.
+///
+/// # Authentication
+/// Authentication is optional. If authenticated (to link an identity), this endpoint requires
+/// the `users.self:write` permission, and personal API tokens can not be used.
+#[utoipa::path(
+    post,
+    path = "/login/oidc/authorize",
+    tag = "login",
+    responses(
+        (
+            status = 200,
+            description = "The URL of the identity provider to redirect the user to.",
+            body = OidcAuthorizationResponse
+        ),
+        (
+            status = 403,
+            description = "Authenticated with a personal API token.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Identities can not be linked using a personal API token."
+            })
+        ),
+        (
+            status = 404,
+            description = "OpenID Connect login is not configured on this server.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "OpenID Connect login is not configured." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/oidc/authorize")]
+pub async fn start_oidc_login(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let oidc_client = require_oidc_client(&state)?;
+
+    let linking_user_id = match authentication.authenticated_user() {
+        Some(authenticated_user) => {
+            require_permission!(
+                state,
+                authenticated_user,
+                Permission::UserSelfWrite
+            );
+
+            if authenticated_user.session_id().is_none() {
+                return Ok(error_response_with_reason!(
+                    StatusCode::FORBIDDEN,
+                    "Identities can not be linked using a personal API token."
+                ));
+            }
+
+            Some(authenticated_user.user_id())
+        }
+        None => None,
+    };
+
+
+    let oauth_state = generate_oidc_random_value().map_err(APIError::InternalError)?;
+    let nonce = generate_oidc_random_value().map_err(APIError::InternalError)?;
+    let code_verifier = generate_oidc_random_value().map_err(APIError::InternalError)?;
+
+    let authorization_url = oidc_client
+        .authorization_url(
+            &oauth_state,
+            &nonce,
+            &pkce_code_challenge(&code_verifier),
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    let expires_at = Utc::now() + oidc_client.configuration().authorization_request_lifetime;
+
+    OidcAuthorizationRequestMutation::create(
+        &state.database,
+        NewOidcAuthorizationRequest {
+            state: oauth_state,
+            code_verifier,
+            nonce,
+            linking_user_id,
+            expires_at,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    Ok(OidcAuthorizationResponse { authorization_url }.into_response())
+}
+
+
+
+/// Finish an OpenID Connect login
+///
+/// This endpoint finishes a login started with `POST /api/v1/login/oidc/authorize`:
+/// it exchanges the authorization code for the user's identity at the identity provider
+/// and logs in the account the identity is linked to. The response is the same
+/// as with a password login (see `POST /api/v1/login`).
+///
+/// If the login was started while authenticated, the identity is linked to that account
+/// first. If the identity is not linked to any account and registration is open,
+/// a new account is created for it, based on the username and name the identity provider
+/// shares (numbered if taken). Such accounts have no usable password.
+///
+/// Just like with `POST /api/v1/login`, users with two-factor authentication receive
+/// a challenge token (`202 Accepted`) instead and must complete the login
+/// with `POST /api/v1/login/2fa`.
+#[utoipa::path(
+    post,
+    path = "/login/oidc/callback",
+    tag = "login",
+    request_body(
+        content = OidcCallbackRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Login successful.",
+            body = UserLoginResponse
+        ),
+        (
+            status = 202,
+            description = "The identity is valid, but a second factor is needed to complete the login.",
+            body = TwoFactorChallengeResponse
+        ),
+        (
+            status = 400,
+            description = "The login has expired or the identity provider rejected it.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Unknown or expired login" = (
+                    summary = "The state does not belong to a pending login (or it has expired).",
+                    value = json!({ "reason": "Unknown or expired login state." })
+                )),
+                ("Rejected login" = (
+                    summary = "The identity provider did not accept the authorization code.",
+                    value = json!({
+                        "reason": "OpenID Connect login failed: identity provider refused the authorization code: invalid_grant."
+                    })
+                ))
+            )
+        ),
+        (
+            status = 403,
+            description = "No account can be logged in with this identity.",
+            body = ErrorReasonResponse,
+            examples(
+                ("No linked account" = (
+                    summary = "The identity is not linked to any account and registration is not open.",
+                    value = json!({ "reason": "No account is linked to this identity." })
+                )),
+                ("Deactivated account" = (
+                    summary = "The account has been deactivated by an administrator.",
+                    value = json!({ "reason": "This account has been deactivated." })
+                )),
+                ("Suspended account" = (
+                    summary = "The account has been suspended by an administrator.",
+                    value = json!({ "reason": "This account has been suspended. Reason: Vandalism." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "OpenID Connect login is not configured on this server.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "OpenID Connect login is not configured." })
+        ),
+        (
+            status = 409,
+            description = "The identity is already linked to a different account.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "This identity is already linked to another account." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[post("/oidc/callback")]
+pub async fn finish_oidc_login(
+    state: ApplicationState,
+    request: HttpRequest,
+    callback_data: web::Json<OidcCallbackRequest>,
+) -> EndpointResult {
+    let oidc_client = require_oidc_client(&state)?;
+    let callback_data = callback_data.into_inner();
+
+
+    // Each started login can only be finished once.
+    let authorization_request =
+        OidcAuthorizationRequestMutation::consume(&state.database, &callback_data.state)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    let Some(authorization_request) = authorization_request else {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Unknown or expired login state."
+        ));
+    };
+
+
+    let identity = match oidc_client
+        .exchange_authorization_code(
+            &callback_data.code,
+            &authorization_request.code_verifier,
+            &authorization_request.nonce,
+        )
+        .await
+    {
+        Ok(identity) => identity,
+        Err(OpenIdConnectError::LoginRejected(reason)) => {
+            warn!(
+                reason = reason,
+                "OpenID Connect login was rejected."
+            );
+
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                format!("OpenID Connect login failed: {reason}.")
+            ));
+        }
+        Err(OpenIdConnectError::ProviderError(error)) => {
+            return Err(APIError::InternalError(error));
+        }
+    };
+
+
+    let linked_identity = UserOidcIdentityQuery::get_identity(
+        &state.database,
+        &identity.issuer,
+        &identity.subject,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    let user_id = match (
+        authorization_request.linking_user_id,
+        linked_identity,
+    ) {
+        // Linking an identity that is already linked to this account changes nothing.
+        (Some(linking_user_id), Some(linked_identity))
+            if linked_identity.user_id == linking_user_id =>
+        {
+            linking_user_id
+        }
+        (Some(_), Some(_)) => {
+            return Ok(error_response_with_reason!(
+                StatusCode::CONFLICT,
+                "This identity is already linked to another account."
+            ));
+        }
+        (Some(linking_user_id), None) => {
+            UserOidcIdentityMutation::link(
+                &state.database,
+                linking_user_id,
+                &identity.issuer,
+                &identity.subject,
+            )
+            .await
+            .map_err(APIError::InternalError)?;
+
+            info!(
+                user_id = linking_user_id,
+                issuer = identity.issuer,
+                subject = identity.subject,
+                "User has linked an OpenID Connect identity."
+            );
+
+            linking_user_id
+        }
+        (None, Some(linked_identity)) => linked_identity.user_id,
+        (None, None) => {
+            if state.configuration.registration.mode != RegistrationMode::Open {
+                return Ok(error_response_with_reason!(
+                    StatusCode::FORBIDDEN,
+                    "No account is linked to this identity."
+                ));
+            }
+
+            register_user_for_identity(&state, &identity).await?
+        }
+    };
+
+
+    let user = UserQuery::get_user_by_id(&state.database, user_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(|| APIError::internal_reason("User linked to identity does not exist."))?;
+
+    if user.deactivated_at.is_some() {
+        debug!(
+            user_id = user_id,
+            "Refusing to log in deactivated user through OpenID Connect."
+        );
+
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "This account has been deactivated."
+        ));
+    }
+
+    let active_suspension = UserSuspensionQuery::active_suspension(&state.database, user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if let Some(suspension) = active_suspension {
+        debug!(
+            user_id = user_id,
+            "Refusing to log in suspended user through OpenID Connect."
+        );
+
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            suspension_message(&suspension)
+        ));
+    }
+
+
+    let response = issue_tokens_or_two_factor_challenge(&state, &request, user_id).await?;
+
+
+    debug!(
+        user_id = user_id,
+        "User has successfully logged in through OpenID Connect."
+    );
+
+
+    Ok(response)
+}
+
+
+/// Creates a new account for an identity that is not linked to any account yet
+/// and links the identity to it. Returns the ID of the new user.
+async fn register_user_for_identity(
+    state: &ApplicationState,
+    identity: &OpenIdConnectIdentity,
+) -> Result<i32, APIError> {
+    let base_username = identity
+        .preferred_username
+        .as_deref()
+        .map(str::trim)
+        .filter(|username| !username.is_empty())
+        .unwrap_or(FALLBACK_USERNAME)
+        .to_string();
+
+    let base_display_name = identity
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&base_username)
+        .to_string();
+
+
+    let mut username = None;
+    for variant in 1..=MAX_NAME_VARIANTS {
+        let candidate = match variant {
+            1 => base_username.clone(),
+            _ => format!("{base_username}{variant}"),
+        };
+
+        let username_exists = UserQuery::user_exists_by_username(&state.database, &candidate)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        if !username_exists {
+            username = Some(candidate);
+            break;
+        }
+    }
+
+    let mut display_name = None;
+    for variant in 1..=MAX_NAME_VARIANTS {
+        let candidate = match variant {
+            1 => base_display_name.clone(),
+            _ => format!("{base_display_name} ({variant})"),
+        };
+
+        let display_name_exists =
+            UserQuery::user_exists_by_display_name(&state.database, &candidate)
+                .await
+                .map_err(APIError::InternalError)?;
+
+        if !display_name_exists {
+            display_name = Some(candidate);
+            break;
+        }
+    }
+
+    let (Some(username), Some(display_name)) = (username, display_name) else {
+        return Err(APIError::internal_reason(
+            "Could not find an unused username or display name for a new OpenID Connect user.",
+        ));
+    };
+
+
+    // Nobody knows this password, so the account can only be logged into through the identity.
+    let unusable_password = generate_oidc_random_value().map_err(APIError::InternalError)?;
+
+    let database_transaction =
+        begin_transaction!(&state.database).map_err(APIError::InternalError)?;
+
+    let new_user = UserMutation::create_user(
+        &database_transaction,
+        &state.hasher,
+        UserRegistrationInfo {
+            username,
+            display_name,
+            password: unusable_password,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    UserOidcIdentityMutation::link(
+        &database_transaction,
+        new_user.id,
+        &identity.issuer,
+        &identity.subject,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    database_transaction
+        .commit()
+        .await
+        .map_err(APIError::InternalDatabaseError)?;
+
+
+    info!(
+        user_id = new_user.id,
+        username = new_user.username,
+        issuer = identity.issuer,
+        subject = identity.subject,
+        "User has registered through OpenID Connect."
+    );
+
+    Ok(new_user.id)
+}
@@ -0,0 +1,472 @@
+//! The second login step for users with two-factor authentication.
+//!
+//! If two-factor authentication is enabled (or mandatory) for a user, a successful
+//! first login step (`POST /login` or `POST /login/oidc/callback`) does not issue tokens yet.
+//! It responds with `202 Accepted` and a short-lived challenge token instead,
+//! which must be passed to `POST /login/2fa` along with a code from the user's authenticator app.
+
+use actix_web::{http::StatusCode, post, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::{
+    generate_login_challenge_token,
+    hash_login_challenge_token,
+    hash_recovery_code,
+    verify_totp_code,
+};
+use kolomoni_database::{
+    entities,
+    mutation::{TwoFactorLoginChallengeMutation, UserTwoFactorMutation},
+    query::{LoginThrottleQuery, TwoFactorLoginChallengeQuery, UserQuery, UserTwoFactorQuery},
+    shared::LoginThrottleSubjectType,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use utoipa::ToSchema;
+
+use super::{
+    client_ip_address,
+    record_failed_login_attempt,
+    start_session_and_issue_tokens,
+    too_many_failed_login_attempts_response,
+};
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::{ContextlessResponder, IntoKolomoniResponseBuilder},
+        openapi,
+        v1::users::two_factor::{begin_two_factor_enrollment, is_two_factor_required},
+    },
+    error_response_with_reason,
+    impl_json_response_builder,
+    state::ApplicationState,
+};
+
+
+
+/// Response to a successful first login step when a second factor is needed.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "challenge_token": "Qm9mLUk3OGxWc2t2b1p5c1RmT3l6dE1Cc0x3Y2xyR3g",
+    "enrollment_required": false,
+    "expires_at": "2023-06-27T20:38:53.078789Z"
+}))]
+pub struct TwoFactorChallengeResponse {
+    /// Token identifying this login. Pass it to `POST /api/v1/login/2fa`.
+    pub challenge_token: String,
+
+    /// Whether two-factor authentication is mandatory for the user, but they have not
+    /// enrolled yet. In that case, they must enroll first (see `POST /api/v1/login/2fa/enrollment`).
+    pub enrollment_required: bool,
+
+    /// When the login must be completed by.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl_json_response_builder!(TwoFactorChallengeResponse);
+
+
+/// Request to enroll into mandatory two-factor authentication during a login.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "challenge_token": "Qm9mLUk3OGxWc2t2b1p5c1RmT3l6dE1Cc0x3Y2xyR3g"
+}))]
+pub struct TwoFactorLoginEnrollmentRequest {
+    /// The challenge token from the first login step.
+    pub challenge_token: String,
+}
+
+
+/// Request to complete a login with a second factor.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "challenge_token": "Qm9mLUk3OGxWc2t2b1p5c1RmT3l6dE1Cc0x3Y2xyR3g",
+    "code": "287082"
+}))]
+pub struct TwoFactorLoginRequest {
+    /// The challenge token from the first login step.
+    pub challenge_token: String,
+
+    /// The current code from the authenticator app, or one of the recovery codes.
+    pub code: String,
+}
+
+
+
+/// Finishes the first login step of the given user: if they need to provide a second factor,
+/// responds with a [`TwoFactorChallengeResponse`] (`202 Accepted`), otherwise starts
+/// a session and responds with the tokens (see [`start_session_and_issue_tokens`]).
+pub(crate) async fn issue_tokens_or_two_factor_challenge(
+    state: &ApplicationState,
+    request: &HttpRequest,
+    user_id: i32,
+) -> Result<HttpResponse, APIError> {
+    let two_factor_enabled = UserTwoFactorQuery::is_enabled_for_user(&state.database, user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let enrollment_required = !two_factor_enabled && is_two_factor_required(state, user_id).await?;
+
+    if !two_factor_enabled && !enrollment_required {
+        let login_response = start_session_and_issue_tokens(state, request, user_id).await?;
+        return Ok(login_response.into_response());
+    }
+
+
+    let challenge_token = generate_login_challenge_token().map_err(APIError::InternalError)?;
+    let expires_at = Utc::now()
+        + state
+            .configuration
+            .two_factor_authentication
+            .login_challenge_lifetime;
+
+    TwoFactorLoginChallengeMutation::create(
+        &state.database,
+        user_id,
+        hash_login_challenge_token(&challenge_token),
+        expires_at,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    debug!(
+        user_id = user_id,
+        enrollment_required = enrollment_required,
+        "Login is waiting for a second factor."
+    );
+
+    Ok(TwoFactorChallengeResponse {
+        challenge_token,
+        enrollment_required,
+        expires_at,
+    }
+    .into_response_builder()?
+    .status_code(StatusCode::ACCEPTED)
+    .build())
+}
+
+
+/// Looks up the unexpired login challenge with the given token.
+async fn find_login_challenge(
+    state: &ApplicationState,
+    challenge_token: &str,
+) -> Result<Option<entities::two_factor_login_challenge::Model>, APIError> {
+    TwoFactorLoginChallengeQuery::get_unexpired_by_token_hash(
+        &state.database,
+        &hash_login_challenge_token(challenge_token),
+    )
+    .await
+    .map_err(APIError::InternalError)
+}
+
+fn unknown_login_challenge_response() -> HttpResponse {
+    error_response_with_reason!(
+        StatusCode::BAD_REQUEST,
+        "Unknown or expired login challenge."
+    )
+}
+
+
+
+/// Enroll into two-factor authentication while logging in
+///
+/// If two-factor authentication is mandatory for a user who has not enrolled yet,
+/// the first login step responds with `enrollment_required` set. This endpoint then
+/// generates their TOTP secret and recovery codes (see `POST /api/v1/users/me/2fa`).
+/// To finish enrolling and logging in, pass a code from the authenticator app
+/// to `POST /api/v1/login/2fa`.
+#[utoipa::path(
+    post,
+    path = "/login/2fa/enrollment",
+    tag = "login",
+    request_body(
+        content = TwoFactorLoginEnrollmentRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The new secret and recovery codes.",
+            body = TwoFactorEnrollmentResponse
+        ),
+        (
+            status = 400,
+            description = "The login challenge is unknown or has expired.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Unknown or expired login challenge." })
+        ),
+        (
+            status = 409,
+            description = "Two-factor authentication is already enabled.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication is already enabled." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[post("/2fa/enrollment")]
+pub async fn enroll_during_login(
+    state: ApplicationState,
+    enrollment_request: web::Json<TwoFactorLoginEnrollmentRequest>,
+) -> EndpointResult {
+    let Some(challenge) = find_login_challenge(&state, &enrollment_request.challenge_token).await?
+    else {
+        return Ok(unknown_login_challenge_response());
+    };
+
+    let two_factor_enabled =
+        UserTwoFactorQuery::is_enabled_for_user(&state.database, challenge.user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if two_factor_enabled {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled."
+        ));
+    }
+
+
+    let enrollment = begin_two_factor_enrollment(&state, challenge.user_id).await?;
+
+    Ok(enrollment.into_response())
+}
+
+
+
+/// Complete a login with a second factor
+///
+/// This endpoint is the second login step for users with two-factor authentication.
+/// Provide the challenge token from the first step and either the current code
+/// from your authenticator app or one of your recovery codes (each can only be used once).
+/// The response is the same as with a successful `POST /api/v1/login`.
+///
+/// If you are enrolling during this login (see `POST /api/v1/login/2fa/enrollment`),
+/// only a code from the authenticator app is accepted, and two-factor authentication
+/// is enabled once it is.
+///
+/// # Throttling
+/// Invalid codes count as failed login attempts for the username (see `POST /api/v1/login`).
+/// After a few of them, the challenge stops working and you must log in again.
+#[utoipa::path(
+    post,
+    path = "/login/2fa",
+    tag = "login",
+    request_body(
+        content = TwoFactorLoginRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Login successful.",
+            body = UserLoginResponse
+        ),
+        (
+            status = 400,
+            description = "The login challenge is unknown or has expired, or enrollment has not been started.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Unknown or expired challenge" = (
+                    summary = "The challenge token does not belong to a pending login (or it has expired).",
+                    value = json!({ "reason": "Unknown or expired login challenge." })
+                )),
+                ("Not enrolled" = (
+                    summary = "Two-factor authentication is mandatory, but enrollment has not been started.",
+                    value = json!({
+                        "reason": "Two-factor authentication has not been set up yet, see POST /api/v1/login/2fa/enrollment."
+                    })
+                ))
+            )
+        ),
+        (
+            status = 403,
+            description = "Invalid code.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid two-factor authentication code." })
+        ),
+        (
+            status = 429,
+            description = "Too many failed login attempts for this username or from this IP address.",
+            body = ErrorReasonResponse,
+            headers(
+                ("Retry-After" = u64, description = "Number of seconds until the lockout ends.")
+            ),
+            example = json!({ "reason": "Too many failed login attempts, try again later." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::InternalServerErrorResponse,
+    )
+)]
+#[post("/2fa")]
+pub async fn complete_two_factor_login(
+    state: ApplicationState,
+    request: HttpRequest,
+    login_request: web::Json<TwoFactorLoginRequest>,
+) -> EndpointResult {
+    let Some(challenge) = find_login_challenge(&state, &login_request.challenge_token).await? else {
+        return Ok(unknown_login_challenge_response());
+    };
+
+    let user = UserQuery::get_user_by_id(&state.database, challenge.user_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(|| APIError::internal_reason("User of a login challenge does not exist."))?;
+
+    let ip_address = client_ip_address(&state, &request);
+
+
+    // Codes are counted towards the same lockouts as passwords,
+    // otherwise they could be guessed by repeatedly logging in.
+    let mut locked_until = LoginThrottleQuery::locked_until(
+        &state.database,
+        LoginThrottleSubjectType::Username,
+        &user.username,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if let Some(ip_address) = &ip_address {
+        let ip_address_locked_until = LoginThrottleQuery::locked_until(
+            &state.database,
+            LoginThrottleSubjectType::IpAddress,
+            ip_address,
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        locked_until = locked_until.max(ip_address_locked_until);
+    }
+
+    if let Some(locked_until) = locked_until {
+        debug!(
+            username = user.username,
+            ip_address = ip_address,
+            "Refusing second login step during lockout."
+        );
+
+        return Ok(too_many_failed_login_attempts_response(
+            locked_until,
+        ));
+    }
+
+
+    let Some(two_factor) = UserTwoFactorQuery::get_by_user_id(&state.database, user.id)
+        .await
+        .map_err(APIError::InternalError)?
+    else {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication has not been set up yet, \
+            see POST /api/v1/login/2fa/enrollment."
+        ));
+    };
+
+    let is_enrolling = two_factor.enabled_at.is_none();
+
+
+    let totp_time_step = verify_totp_code(
+        &two_factor.secret,
+        &login_request.code,
+        Utc::now(),
+        two_factor.last_used_time_step,
+    );
+
+    let code_is_valid = match totp_time_step {
+        Some(time_step) if is_enrolling => {
+            UserTwoFactorMutation::enable(&state.database, user.id, time_step)
+                .await
+                .map_err(APIError::InternalError)?;
+
+            info!(
+                user_id = user.id,
+                "User has enabled two-factor authentication while logging in."
+            );
+
+            true
+        }
+        // The same code must not be usable twice, even by concurrent requests.
+        Some(time_step) => {
+            UserTwoFactorMutation::record_used_time_step(&state.database, user.id, time_step)
+                .await
+                .map_err(APIError::InternalError)?
+        }
+        None if is_enrolling => false,
+        None => {
+            let recovery_code_was_used = UserTwoFactorMutation::consume_recovery_code(
+                &state.database,
+                user.id,
+                &hash_recovery_code(&login_request.code),
+            )
+            .await
+            .map_err(APIError::InternalError)?;
+
+            if recovery_code_was_used {
+                info!(
+                    user_id = user.id,
+                    "User has logged in with a recovery code."
+                );
+            }
+
+            recovery_code_was_used
+        }
+    };
+
+    if !code_is_valid {
+        let challenge_was_exhausted = TwoFactorLoginChallengeMutation::record_failed_attempt(
+            &state.database,
+            &challenge.token_hash,
+            state
+                .configuration
+                .two_factor_authentication
+                .max_failed_attempts_per_login_challenge,
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        if challenge_was_exhausted {
+            debug!(
+                user_id = user.id,
+                "Login challenge was removed after too many invalid codes."
+            );
+        }
+
+        record_failed_login_attempt(&state, &user.username, ip_address.as_deref()).await?;
+
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Invalid two-factor authentication code."
+        ));
+    }
+
+
+    // Each challenge can only be completed once.
+    let challenge_was_pending =
+        TwoFactorLoginChallengeMutation::consume(&state.database, &challenge.token_hash)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if !challenge_was_pending {
+        return Ok(unknown_login_challenge_response());
+    }
+
+
+    let login_response = start_session_and_issue_tokens(&state, &request, user.id).await?;
+
+
+    debug!(
+        username = user.username,
+        "User has successfully logged in with a second factor."
+    );
+
+
+    Ok(login_response.into_response())
+}
@@ -10,12 +10,20 @@
 
 pub mod dictionary;
 pub mod login;
+pub mod permissions;
 pub mod ping;
+pub mod roles;
 pub mod users;
 
 use actix_web::{web, Scope};
 
-use self::{dictionary::dictionary_router, login::login_router, users::users_router};
+use self::{
+    dictionary::dictionary_router,
+    login::login_router,
+    permissions::permissions_router,
+    roles::roles_router,
+    users::users_router,
+};
 
 /// Router for the entire V1 API.
 /// Lives under the `/api/v1` path.
@@ -24,5 +32,7 @@ pub fn v1_api_router() -> Scope {
         .service(ping::ping)
         .service(users_router())
         .service(login_router())
+        .service(roles_router())
+        .service(permissions_router())
         .service(dictionary_router())
 }
@@ -0,0 +1,162 @@
+//! Listing of available permissions.
+//!
+//! Unlike roles, permissions are defined in code (see [`Permission`])
+//! and can only change along with the server.
+
+use actix_web::{
+    get,
+    http::{header, StatusCode},
+    web,
+    HttpResponse,
+    Scope,
+};
+use kolomoni_auth::Permission;
+use kolomoni_database::query::PermissionQuery;
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::{construct_last_modified_header_value, IntoKolomoniResponseBuilder},
+        openapi,
+        OptionalIfModifiedSince,
+    },
+    authentication::UserAuthenticationExtractor,
+    impl_json_response_builder,
+    require_permission_with_optional_authentication,
+    state::ApplicationState,
+};
+
+
+
+/// Information about a single permission.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "name": "word:read",
+    "description": "Allows the user to read words in the dictionary."
+}))]
+pub struct PermissionInformation {
+    /// Permission name (e.g. `word:read`).
+    pub name: String,
+
+    /// Description of what the permission allows.
+    pub description: String,
+}
+
+impl PermissionInformation {
+    pub fn from_permission(permission: Permission) -> Self {
+        Self {
+            name: permission.name().to_string(),
+            description: permission.description().to_string(),
+        }
+    }
+}
+
+
+/// Response containing all permissions.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "permissions": [
+        {
+            "name": "user.self:read",
+            "description": "Allows the user to log in and view their account information."
+        },
+        {
+            "name": "word:read",
+            "description": "Allows the user to read words in the dictionary."
+        }
+    ]
+}))]
+pub struct PermissionsResponse {
+    pub permissions: Vec<PermissionInformation>,
+}
+
+impl_json_response_builder!(PermissionsResponse);
+
+
+
+/// List all permissions
+///
+/// This endpoint returns all permissions that can be granted through roles,
+/// along with their descriptions.
+///
+/// # Authentication
+/// Authentication is *not required* on this endpoint due to a blanket grant of
+/// the `users.any:read` permission to unauthenticated users.
+#[utoipa::path(
+    get,
+    path = "/permissions",
+    tag = "roles",
+    params(
+        openapi::IfModifiedSinceParameter
+    ),
+    responses(
+        (
+            status = 200,
+            description = "List of all permissions.",
+            body = PermissionsResponse,
+            headers(
+                (
+                    "Last-Modified" = String,
+                    description = "Last time any permission was added or changed. Use this value for caching."
+                )
+            )
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyRead>,
+        openapi::InternalServerErrorResponse,
+        openapi::UnmodifiedConditionalResponse,
+    )
+)]
+#[get("")]
+pub async fn get_all_permissions(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    if_modified_since: OptionalIfModifiedSince,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::UserAnyRead);
+
+
+    let last_modification_time = PermissionQuery::permissions_last_modified_at(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if if_modified_since.has_not_changed_since(&last_modification_time) {
+        let mut unchanged_response = HttpResponse::new(StatusCode::NOT_MODIFIED);
+
+        unchanged_response.headers_mut().append(
+            header::LAST_MODIFIED,
+            construct_last_modified_header_value(&last_modification_time)
+                .into_diagnostic()
+                .map_err(APIError::InternalError)?,
+        );
+
+        return Ok(unchanged_response);
+    }
+
+
+    let permissions = Permission::all()
+        .into_iter()
+        .map(PermissionInformation::from_permission)
+        .collect();
+
+    Ok(PermissionsResponse { permissions }
+        .into_response_builder()?
+        .last_modified_at(last_modification_time)?
+        .build())
+}
+
+
+
+#[rustfmt::skip]
+pub fn permissions_router() -> Scope {
+    web::scope("/permissions")
+        .service(get_all_permissions)
+}
@@ -0,0 +1,853 @@
+//! Management of user roles.
+//!
+//! Apart from the built-in `user` and `administrator` roles, roles are defined
+//! at runtime through these endpoints and stored in the database.
+
+use actix_web::{
+    delete,
+    get,
+    http::{header, StatusCode},
+    patch,
+    post,
+    web,
+    HttpResponse,
+    Scope,
+};
+use kolomoni_auth::{BuiltinRole, Permission, PermissionSet, RoleSet};
+use kolomoni_database::{
+    mutation::{NewRole, RoleMutation, UpdatedRole},
+    query::{RoleQuery, RoleWithPermissions},
+};
+use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::{
+            construct_last_modified_header_value,
+            ContextlessResponder,
+            IntoKolomoniResponseBuilder,
+        },
+        openapi,
+        OptionalIfModifiedSince,
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    require_permission_with_optional_authentication,
+    state::ApplicationState,
+};
+
+
+
+/// Information about a single role.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "id": 3,
+    "name": "translator",
+    "description": "Translates words and handles translation suggestions.",
+    "permissions": [
+        "word.suggestion:delete",
+        "word.translation:create",
+        "word.translation:delete"
+    ]
+}))]
+pub struct RoleInformation {
+    /// Internal role ID.
+    pub id: i32,
+
+    /// Unique role name.
+    pub name: String,
+
+    /// Description of the role.
+    pub description: String,
+
+    /// Names of permissions the role grants.
+    pub permissions: Vec<String>,
+}
+
+impl RoleInformation {
+    /// Convert a role database model (and its permissions) into a [`RoleInformation`]
+    /// that can be exposed through the API.
+    pub fn from_role_with_permissions(role_with_permissions: RoleWithPermissions) -> Self {
+        let mut permissions = role_with_permissions
+            .permissions
+            .into_permissions()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        permissions.sort_by_key(Permission::id);
+
+        Self {
+            id: role_with_permissions.role.id,
+            name: role_with_permissions.role.name,
+            description: role_with_permissions.role.description,
+            permissions: permissions
+                .into_iter()
+                .map(|permission| permission.name().to_string())
+                .collect(),
+        }
+    }
+}
+
+
+
+/// Response containing a single role.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "role": {
+        "id": 3,
+        "name": "translator",
+        "description": "Translates words and handles translation suggestions.",
+        "permissions": [
+            "word.suggestion:delete",
+            "word.translation:create",
+            "word.translation:delete"
+        ]
+    }
+}))]
+pub struct RoleInfoResponse {
+    pub role: RoleInformation,
+}
+
+impl_json_response_builder!(RoleInfoResponse);
+
+
+
+/// Response containing all roles.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "roles": [
+        {
+            "id": 1,
+            "name": "user",
+            "description": "Normal user with most read permissions.",
+            "permissions": [
+                "user.self:read",
+                "user.self:write",
+                "user.any:read",
+                "word:read",
+                "word.suggestion:create"
+            ]
+        },
+        {
+            "id": 3,
+            "name": "translator",
+            "description": "Translates words and handles translation suggestions.",
+            "permissions": [
+                "word.suggestion:delete",
+                "word.translation:create",
+                "word.translation:delete"
+            ]
+        }
+    ]
+}))]
+pub struct RolesResponse {
+    pub roles: Vec<RoleInformation>,
+}
+
+impl_json_response_builder!(RolesResponse);
+
+
+
+/// Parses a list of permission names into a sorted and deduplicated list of [`Permission`]s.
+///
+/// Returns `Err` with a reason suitable for the API caller if a name is not recognized.
+pub(crate) fn parse_permission_names(
+    permission_names: Vec<String>,
+) -> Result<Vec<Permission>, String> {
+    let mut permissions = permission_names
+        .into_iter()
+        .map(|permission_name| {
+            Permission::from_name(&permission_name)
+                .ok_or_else(|| format!("No such permission: \"{permission_name}\"."))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    permissions.sort_by_key(Permission::id);
+    permissions.dedup();
+
+    Ok(permissions)
+}
+
+/// Returns a permission from `permissions` that the caller does not have (if any).
+///
+/// Managing roles that grant permissions you don't have would allow for privilege
+/// escalation (or for e.g. a moderator to strip the administrator role), so such
+/// operations are refused.
+pub(crate) fn find_permission_missing_from_caller<'p, I>(
+    caller_permissions: &PermissionSet,
+    permissions: I,
+) -> Option<Permission>
+where
+    I: IntoIterator<Item = &'p Permission>,
+{
+    permissions
+        .into_iter()
+        .find(|permission| !caller_permissions.has_permission(**permission))
+        .copied()
+}
+
+
+/// Returns `true` if the caller may give out, take away or otherwise manage the given role:
+/// either they have the role themselves, or they have every permission it grants.
+///
+/// The latter allows e.g. administrators to hand out roles created at runtime
+/// without holding each one of them.
+pub(crate) fn caller_can_manage_role(
+    caller_roles: &RoleSet,
+    caller_permissions: &PermissionSet,
+    role: &RoleWithPermissions,
+) -> bool {
+    caller_roles.has_role_by_id(role.role.id)
+        || find_permission_missing_from_caller(caller_permissions, role.permissions.permissions())
+            .is_none()
+}
+
+
+
+/// List all roles
+///
+/// This endpoint returns all roles, including the built-in ones,
+/// along with their descriptions and the permissions they grant.
+///
+/// # Authentication
+/// Authentication is *not required* on this endpoint due to a blanket grant of
+/// the `users.any:read` permission to unauthenticated users.
+#[utoipa::path(
+    get,
+    path = "/roles",
+    tag = "roles",
+    params(
+        openapi::IfModifiedSinceParameter
+    ),
+    responses(
+        (
+            status = 200,
+            description = "List of all roles.",
+            body = RolesResponse,
+            headers(
+                (
+                    "Last-Modified" = String,
+                    description = "Last time any role was created, updated or deleted. Use this value for caching."
+                )
+            )
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyRead>,
+        openapi::InternalServerErrorResponse,
+        openapi::UnmodifiedConditionalResponse,
+    )
+)]
+#[get("")]
+pub async fn get_all_roles(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    if_modified_since: OptionalIfModifiedSince,
+) -> EndpointResult {
+    require_permission_with_optional_authentication!(state, authentication, Permission::UserAnyRead);
+
+
+    let last_modification_time = RoleQuery::roles_last_modified_at(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if if_modified_since.has_not_changed_since(&last_modification_time) {
+        let mut unchanged_response = HttpResponse::new(StatusCode::NOT_MODIFIED);
+
+        unchanged_response.headers_mut().append(
+            header::LAST_MODIFIED,
+            construct_last_modified_header_value(&last_modification_time)
+                .into_diagnostic()
+                .map_err(APIError::InternalError)?,
+        );
+
+        return Ok(unchanged_response);
+    }
+
+
+    let roles = RoleQuery::all_roles(&state.database)
+        .await
+        .map_err(APIError::InternalError)?
+        .into_iter()
+        .map(RoleInformation::from_role_with_permissions)
+        .collect();
+
+    Ok(RolesResponse { roles }
+        .into_response_builder()?
+        .last_modified_at(last_modification_time)?
+        .build())
+}
+
+
+
+#[derive(Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "name": "translator",
+        "description": "Translates words and handles translation suggestions.",
+        "permissions": [
+            "word.translation:create",
+            "word.translation:delete",
+            "word.suggestion:delete"
+        ]
+    })
+)]
+pub struct RoleCreationRequest {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+}
+
+
+/// Create a new role
+///
+/// This endpoint creates a new role that grants the given permissions.
+/// The role can then be given to users like any other role.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+/// Additionally, you can only create roles that grant permissions you have yourself --
+/// trying to do otherwise will fail with `403 Forbidden`.
+#[utoipa::path(
+    post,
+    path = "/roles",
+    tag = "roles",
+    request_body(
+        content = RoleCreationRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The newly-created role.",
+            body = RoleInfoResponse
+        ),
+        (
+            status = 400,
+            description = "Invalid role name or permission name.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Invalid permission name" = (
+                    summary = "Invalid permission name.",
+                    value = json!({ "reason": "No such permission: \"word:fly\"." })
+                )),
+                ("Empty role name" = (
+                    summary = "Empty role name.",
+                    value = json!({ "reason": "Role name must not be empty." })
+                )),
+            )
+        ),
+        (
+            status = 403,
+            description = "Can't grant permissions you don't have.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "You cannot grant permissions you do not have (missing permission: word:delete)." })
+        ),
+        (
+            status = 409,
+            description = "A role with the given name already exists.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Role with the provided name already exists." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("")]
+pub async fn create_role(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    json_data: web::Json<RoleCreationRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    require_permission!(
+        authenticated_user_permissions,
+        Permission::UserAnyWrite
+    );
+
+
+    let request_data = json_data.into_inner();
+
+    let role_name = request_data.name.trim().to_string();
+    if role_name.is_empty() {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Role name must not be empty."
+        ));
+    }
+
+    let permissions = match parse_permission_names(request_data.permissions) {
+        Ok(permissions) => permissions,
+        Err(error_reason) => {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                error_reason
+            ));
+        }
+    };
+
+    if let Some(missing_permission) =
+        find_permission_missing_from_caller(&authenticated_user_permissions, &permissions)
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot grant permissions you do not have (missing permission: {}).",
+                missing_permission.name()
+            )
+        ));
+    }
+
+
+    let role_name_already_exists = RoleQuery::get_role_by_name(&state.database, &role_name)
+        .await
+        .map_err(APIError::InternalError)?
+        .is_some();
+
+    if role_name_already_exists {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Role with the provided name already exists."
+        ));
+    }
+
+
+    let new_role = RoleMutation::create(
+        &state.database,
+        NewRole {
+            name: role_name,
+            description: request_data.description,
+            permissions,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    let new_role_with_permissions = RoleQuery::get_role_by_id(&state.database, new_role.id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(|| APIError::internal_reason("BUG: Newly-created role could not be found."))?;
+
+
+    info!(
+        user_id = authenticated_user.user_id(),
+        role_id = new_role.id,
+        role_name = new_role.name,
+        "User has created a role."
+    );
+
+    Ok(RoleInfoResponse {
+        role: RoleInformation::from_role_with_permissions(new_role_with_permissions),
+    }
+    .into_response())
+}
+
+
+
+
+#[derive(Deserialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "description": "Translates words, but can no longer remove translations.",
+        "permissions": [
+            "word.translation:create",
+            "word.suggestion:delete"
+        ]
+    })
+)]
+pub struct RoleUpdateRequest {
+    /// New name of the role.
+    pub name: Option<String>,
+
+    /// New description of the role.
+    pub description: Option<String>,
+
+    /// If set, replaces the entire list of permissions the role grants.
+    pub permissions: Option<Vec<String>>,
+}
+
+
+/// Update a role
+///
+/// This endpoint renames a role, changes its description and/or
+/// replaces the list of permissions it grants. Fields that are omitted are left unchanged.
+/// Changes to permissions apply immediately to all users that have the role.
+///
+/// # Restrictions
+/// Built-in roles (`user` and `administrator`) can not be renamed.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+/// Additionally, you must have all the permissions the role currently grants, as well as
+/// all the permissions it would grant after the update -- otherwise the request
+/// will fail with `403 Forbidden`.
+#[utoipa::path(
+    patch,
+    path = "/roles/{role_id}",
+    tag = "roles",
+    params(
+        (
+            "role_id" = i32,
+            Path,
+            description = "ID of the role to update."
+        )
+    ),
+    request_body(
+        content = RoleUpdateRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The updated role.",
+            body = RoleInfoResponse
+        ),
+        (
+            status = 400,
+            description = "Invalid role name or permission name.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Invalid permission name" = (
+                    summary = "Invalid permission name.",
+                    value = json!({ "reason": "No such permission: \"word:fly\"." })
+                )),
+                ("Empty role name" = (
+                    summary = "Empty role name.",
+                    value = json!({ "reason": "Role name must not be empty." })
+                )),
+            )
+        ),
+        (
+            status = 403,
+            description = "Not allowed to modify the role.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage permissions you don't have" = (
+                    summary = "Can't manage permissions you don't have.",
+                    value = json!({ "reason": "You cannot manage permissions you do not have (missing permission: word:delete)." })
+                )),
+                ("Can't rename built-in roles" = (
+                    summary = "Built-in roles can not be renamed.",
+                    value = json!({ "reason": "Built-in roles can not be renamed." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified role does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified role does not exist." })
+        ),
+        (
+            status = 409,
+            description = "A role with the given name already exists.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Role with the provided name already exists." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[patch("/{role_id}")]
+pub async fn update_role(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+    json_data: web::Json<RoleUpdateRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    require_permission!(
+        authenticated_user_permissions,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_role_id = path_info.into_inner().0;
+    let request_data = json_data.into_inner();
+
+
+    let Some(target_role) = RoleQuery::get_role_by_id(&state.database, target_role_id)
+        .await
+        .map_err(APIError::InternalError)?
+    else {
+        return Err(APIError::not_found_with_reason(
+            "The specified role does not exist.",
+        ));
+    };
+
+
+    let new_role_name = match request_data.name {
+        Some(name) => {
+            let name = name.trim().to_string();
+
+            if name.is_empty() {
+                return Ok(error_response_with_reason!(
+                    StatusCode::BAD_REQUEST,
+                    "Role name must not be empty."
+                ));
+            }
+
+            if name == target_role.role.name {
+                None
+            } else {
+                Some(name)
+            }
+        }
+        None => None,
+    };
+
+    let new_permissions = match request_data.permissions.map(parse_permission_names) {
+        Some(Ok(permissions)) => Some(permissions),
+        Some(Err(error_reason)) => {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                error_reason
+            ));
+        }
+        None => None,
+    };
+
+
+    if new_role_name.is_some() && BuiltinRole::from_id(target_role_id).is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Built-in roles can not be renamed."
+        ));
+    }
+
+    let missing_permission = find_permission_missing_from_caller(
+        &authenticated_user_permissions,
+        target_role
+            .permissions
+            .permissions()
+            .iter()
+            .chain(new_permissions.iter().flatten()),
+    );
+
+    if let Some(missing_permission) = missing_permission {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage permissions you do not have (missing permission: {}).",
+                missing_permission.name()
+            )
+        ));
+    }
+
+
+    if let Some(new_role_name) = new_role_name.as_ref() {
+        let role_name_already_exists = RoleQuery::get_role_by_name(&state.database, new_role_name)
+            .await
+            .map_err(APIError::InternalError)?
+            .is_some();
+
+        if role_name_already_exists {
+            return Ok(error_response_with_reason!(
+                StatusCode::CONFLICT,
+                "Role with the provided name already exists."
+            ));
+        }
+    }
+
+
+    RoleMutation::update(
+        &state.database,
+        target_role_id,
+        UpdatedRole {
+            name: new_role_name,
+            description: request_data.description,
+            permissions: new_permissions,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    let updated_role_with_permissions = RoleQuery::get_role_by_id(&state.database, target_role_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(|| APIError::internal_reason("BUG: Updated role could not be found."))?;
+
+
+    info!(
+        user_id = authenticated_user.user_id(),
+        role_id = target_role_id,
+        "User has updated a role."
+    );
+
+    Ok(RoleInfoResponse {
+        role: RoleInformation::from_role_with_permissions(updated_role_with_permissions),
+    }
+    .into_response())
+}
+
+
+
+
+/// Delete a role
+///
+/// This endpoint deletes a role. All users and registration invites that had the role lose it.
+///
+/// # Restrictions
+/// Built-in roles (`user` and `administrator`) can not be deleted.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+/// Additionally, you must have all the permissions the role grants -- otherwise
+/// the request will fail with `403 Forbidden`.
+#[utoipa::path(
+    delete,
+    path = "/roles/{role_id}",
+    tag = "roles",
+    params(
+        (
+            "role_id" = i32,
+            Path,
+            description = "ID of the role to delete."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The role has been deleted."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to delete the role.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage permissions you don't have" = (
+                    summary = "Can't manage permissions you don't have.",
+                    value = json!({ "reason": "You cannot manage permissions you do not have (missing permission: word:delete)." })
+                )),
+                ("Can't delete built-in roles" = (
+                    summary = "Built-in roles can not be deleted.",
+                    value = json!({ "reason": "Built-in roles can not be deleted." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified role does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified role does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/{role_id}")]
+pub async fn delete_role(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    require_permission!(
+        authenticated_user_permissions,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_role_id = path_info.into_inner().0;
+
+    let Some(target_role) = RoleQuery::get_role_by_id(&state.database, target_role_id)
+        .await
+        .map_err(APIError::InternalError)?
+    else {
+        return Err(APIError::not_found_with_reason(
+            "The specified role does not exist.",
+        ));
+    };
+
+    if BuiltinRole::from_id(target_role_id).is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Built-in roles can not be deleted."
+        ));
+    }
+
+    if let Some(missing_permission) = find_permission_missing_from_caller(
+        &authenticated_user_permissions,
+        target_role.permissions.permissions(),
+    ) {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage permissions you do not have (missing permission: {}).",
+                missing_permission.name()
+            )
+        ));
+    }
+
+
+    let role_existed = RoleMutation::delete(&state.database, target_role_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !role_existed {
+        return Err(APIError::not_found_with_reason(
+            "The specified role does not exist.",
+        ));
+    }
+
+
+    info!(
+        user_id = authenticated_user.user_id(),
+        role_id = target_role_id,
+        role_name = target_role.role.name,
+        "User has deleted a role."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+#[rustfmt::skip]
+pub fn roles_router() -> Scope {
+    web::scope("/roles")
+        .service(get_all_roles)
+        .service(create_role)
+        .service(update_role)
+        .service(delete_role)
+}
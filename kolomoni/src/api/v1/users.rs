@@ -5,28 +5,57 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use self::all::get_all_registered_users;
+use self::api_tokens::{
+    create_current_user_api_token,
+    get_current_user_api_tokens,
+    revoke_current_user_api_token,
+};
 use self::current::{
+    delete_current_user,
     get_current_user_effective_permissions,
     get_current_user_info,
     get_current_user_roles,
+    get_current_user_sessions,
+    revoke_current_user_session,
     update_current_user_display_name,
+    update_current_user_password,
 };
+use self::invites::{create_invite, get_all_invites, revoke_invite};
 use self::registration::register_user;
 use self::specific::{
     // add_permissions_to_specific_user,
     add_roles_to_specific_user,
+    deactivate_specific_user,
+    delete_specific_user,
     get_specific_user_effective_permissions,
     get_specific_user_info,
     get_specific_user_roles,
+    reactivate_specific_user,
     remove_roles_from_specific_user,
     update_specific_user_display_name,
 };
+use self::suspensions::{
+    get_specific_user_suspensions,
+    lift_specific_user_suspension,
+    suspend_specific_user,
+};
+use self::two_factor::{
+    begin_current_user_two_factor_enrollment,
+    confirm_current_user_two_factor_enrollment,
+    disable_current_user_two_factor,
+    get_current_user_two_factor_status,
+    regenerate_current_user_recovery_codes,
+};
 use crate::impl_json_response_builder;
 
 pub mod all;
+pub mod api_tokens;
 pub mod current;
+pub mod invites;
 pub mod registration;
 pub mod specific;
+pub mod suspensions;
+pub mod two_factor;
 
 
 
@@ -144,6 +173,129 @@ impl_json_response_builder!(UserDisplayNameChangeResponse);
 
 
 
+/// User (API caller) request to change their password.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "current_password": "verysecurepassword",
+        "new_password": "evenmoresecurepassword"
+    })
+)]
+pub struct UserPasswordChangeRequest {
+    /// The user's current password.
+    pub current_password: String,
+
+    /// Password to change to.
+    pub new_password: String,
+}
+
+
+
+/// User (API caller) request to delete their own account.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "current_password": "verysecurepassword"
+    })
+)]
+pub struct UserAccountDeletionRequest {
+    /// The user's current password.
+    pub current_password: String,
+}
+
+
+
+/// Information about a single login session.
+///
+/// A session is started on each login and is shared by the access and refresh token
+/// that were issued on that login.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+    "created_at": "2023-06-27T20:33:53.078789Z",
+    "last_refreshed_at": "2023-06-28T08:12:01.512417Z",
+    "expires_at": "2023-07-04T20:33:53.078789Z",
+    "user_agent": "Mozilla/5.0 (X11; Linux x86_64; rv:122.0) Gecko/20100101 Firefox/122.0",
+    "ip_address": "192.0.2.13",
+    "is_current": true
+}))]
+pub struct UserSession {
+    /// Session ID.
+    pub id: String,
+
+    /// When the session was started, i.e. when the user logged in.
+    pub created_at: DateTime<Utc>,
+
+    /// When the session's refresh token was last used.
+    pub last_refreshed_at: DateTime<Utc>,
+
+    /// When the session will expire.
+    pub expires_at: DateTime<Utc>,
+
+    /// User agent of the client that started the session, if known.
+    pub user_agent: Option<String>,
+
+    /// IP address of the client that started the session, if known.
+    pub ip_address: Option<String>,
+
+    /// Whether this is the session the current request was authenticated with.
+    pub is_current: bool,
+}
+
+impl UserSession {
+    /// Convert a session database model into a [`UserSession`]
+    /// that can be safely exposed through the API.
+    #[inline]
+    pub fn from_session_model(model: entities::user_session::Model, is_current: bool) -> Self {
+        Self {
+            id: model.id.to_string(),
+            created_at: model.created_at.with_timezone(&Utc),
+            last_refreshed_at: model.last_refreshed_at.with_timezone(&Utc),
+            expires_at: model.expires_at.with_timezone(&Utc),
+            user_agent: model.user_agent,
+            ip_address: model.ip_address,
+            is_current,
+        }
+    }
+}
+
+
+/// Response containing a list of the user's active login sessions.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "sessions": [
+        {
+            "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            "created_at": "2023-06-27T20:33:53.078789Z",
+            "last_refreshed_at": "2023-06-28T08:12:01.512417Z",
+            "expires_at": "2023-07-04T20:33:53.078789Z",
+            "user_agent": "Mozilla/5.0 (X11; Linux x86_64; rv:122.0) Gecko/20100101 Firefox/122.0",
+            "ip_address": "192.0.2.13",
+            "is_current": true
+        }
+    ]
+}))]
+pub struct UserSessionsResponse {
+    pub sessions: Vec<UserSession>,
+}
+
+impl_json_response_builder!(UserSessionsResponse);
+
+
+
+
 #[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
 #[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
 #[schema(
@@ -205,6 +357,28 @@ pub fn users_router() -> Scope {
         .service(get_current_user_roles)
         .service(get_current_user_effective_permissions)
         .service(update_current_user_display_name)
+        .service(update_current_user_password)
+        .service(get_current_user_sessions)
+        .service(revoke_current_user_session)
+        .service(delete_current_user)
+        // api_tokens.rs
+        .service(get_current_user_api_tokens)
+        .service(create_current_user_api_token)
+        .service(revoke_current_user_api_token)
+        // two_factor.rs
+        .service(get_current_user_two_factor_status)
+        .service(begin_current_user_two_factor_enrollment)
+        .service(confirm_current_user_two_factor_enrollment)
+        .service(disable_current_user_two_factor)
+        .service(regenerate_current_user_recovery_codes)
+        // invites.rs
+        .service(get_all_invites)
+        .service(create_invite)
+        .service(revoke_invite)
+        // suspensions.rs
+        .service(get_specific_user_suspensions)
+        .service(suspend_specific_user)
+        .service(lift_specific_user_suspension)
         // specific.rs
         .service(get_specific_user_info)
         .service(get_specific_user_effective_permissions)
@@ -212,4 +386,7 @@ pub fn users_router() -> Scope {
         .service(add_roles_to_specific_user)
         .service(remove_roles_from_specific_user)
         .service(update_specific_user_display_name)
+        .service(deactivate_specific_user)
+        .service(reactivate_specific_user)
+        .service(delete_specific_user)
 }
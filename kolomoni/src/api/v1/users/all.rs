@@ -1,7 +1,14 @@
-use actix_web::get;
+use actix_web::{get, web};
+use chrono::{DateTime, Utc};
 use kolomoni_auth::Permission;
-use kolomoni_database::query;
-use serde::Serialize;
+use kolomoni_database::query::{
+    self,
+    UserListCursor,
+    UserListSortDirection,
+    UserListSortField,
+    UsersQueryOptions,
+};
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use super::UserInformation;
@@ -18,7 +25,16 @@ use crate::{
     state::ApplicationState,
 };
 
-/// List of registered users.
+
+/// Amount of users on a single page, unless the caller requests otherwise.
+pub const DEFAULT_USERS_PAGE_SIZE: u64 = 50;
+
+/// Maximum amount of users the caller can request on a single page.
+pub const MAXIMUM_USERS_PAGE_SIZE: u64 = 200;
+
+
+
+/// A page of registered users.
 #[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
 #[cfg_attr(feature = "with_test_facilities", derive(serde::Deserialize))]
 #[schema(title = "RegisteredUsersListResponse")]
@@ -32,18 +48,126 @@ use crate::{
             "last_modified_at": "2023-06-27T20:34:27.217273Z",
             "last_active_at": "2023-06-27T20:34:27.253746Z"
         },
-    ]
+    ],
+    "next_cursor": null
 }))]
 pub struct RegisteredUsersListResponse {
     pub users: Vec<UserInformation>,
+
+    /// Cursor pointing to the next page of users. Pass it back in the `cursor` field
+    /// of the request to get the next page. `None` if this is the last page.
+    pub next_cursor: Option<String>,
 }
 
 impl_json_response_builder!(RegisteredUsersListResponse);
 
 
-/// List all registered users.
+
+/// Field to sort the user list by.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema, Default)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[serde(rename_all = "snake_case")]
+pub enum RegisteredUsersSortField {
+    #[default]
+    JoinedAt,
+    LastActiveAt,
+    Username,
+    DisplayName,
+}
+
+impl RegisteredUsersSortField {
+    fn into_query_sort_field(self) -> UserListSortField {
+        match self {
+            RegisteredUsersSortField::JoinedAt => UserListSortField::JoinedAt,
+            RegisteredUsersSortField::LastActiveAt => UserListSortField::LastActiveAt,
+            RegisteredUsersSortField::Username => UserListSortField::Username,
+            RegisteredUsersSortField::DisplayName => UserListSortField::DisplayName,
+        }
+    }
+}
+
+
+/// Direction to sort the user list in.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema, Default)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[serde(rename_all = "snake_case")]
+pub enum RegisteredUsersSortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl RegisteredUsersSortDirection {
+    fn into_query_sort_direction(self) -> UserListSortDirection {
+        match self {
+            RegisteredUsersSortDirection::Ascending => UserListSortDirection::Ascending,
+            RegisteredUsersSortDirection::Descending => UserListSortDirection::Descending,
+        }
+    }
+}
+
+
+/// Filters for the registered user list. All filters are optional and are combined.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+pub struct RegisteredUsersFilters {
+    /// Only list users whose username or display name contains this string (case-insensitive).
+    pub search: Option<String>,
+
+    /// Only list users that have the role with this name.
+    pub role: Option<String>,
+
+    pub joined_after: Option<DateTime<Utc>>,
+    pub joined_before: Option<DateTime<Utc>>,
+
+    pub last_active_after: Option<DateTime<Utc>>,
+    pub last_active_before: Option<DateTime<Utc>>,
+}
+
+
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, ToSchema, Default)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(
+    example = json!({
+        "filters": {
+            "search": "novak",
+            "role": "user",
+            "joined_after": "2023-06-01T00:00:00Z"
+        },
+        "sort_by": "last_active_at",
+        "sort_direction": "descending",
+        "page_size": 50,
+        "cursor": null
+    })
+)]
+pub struct RegisteredUsersListRequest {
+    pub filters: Option<RegisteredUsersFilters>,
+
+    /// Field to sort by (`joined_at` by default).
+    #[serde(default)]
+    pub sort_by: RegisteredUsersSortField,
+
+    /// Direction to sort in (`ascending` by default).
+    #[serde(default)]
+    pub sort_direction: RegisteredUsersSortDirection,
+
+    /// Amount of users on the page (50 by default, 200 at most).
+    pub page_size: Option<u64>,
+
+    /// The `next_cursor` value from the previous page. The sorting options
+    /// must be the same as the ones used for the previous page.
+    pub cursor: Option<String>,
+}
+
+
+/// List registered users
 ///
-/// This endpoint returns a list of all registered users.
+/// This endpoint returns a page of registered users, optionally filtered by
+/// username or display name, role, registration time and last activity time.
+///
+/// The list is paginated with cursors: to get the next page, repeat the request
+/// with the `cursor` field set to `next_cursor` from the previous response.
+/// When `next_cursor` is `null`, there are no more users.
 ///
 ///
 /// # Authentication
@@ -52,12 +176,20 @@ impl_json_response_builder!(RegisteredUsersListResponse);
     get,
     path = "/users",
     tag = "users",
+    request_body(
+        content = Option<RegisteredUsersListRequest>
+    ),
     responses(
         (
             status = 200,
-            description = "List of registered users.",
+            description = "A page of registered users.",
             body = RegisteredUsersListResponse
         ),
+        (
+            status = 400,
+            description = "Invalid cursor or page size.",
+            body = ErrorReasonResponse,
+        ),
         openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyRead>,
         openapi::InternalServerErrorResponse,
     ),
@@ -69,6 +201,7 @@ impl_json_response_builder!(RegisteredUsersListResponse);
 pub async fn get_all_registered_users(
     state: ApplicationState,
     authentication: UserAuthenticationExtractor,
+    request_body: Option<web::Json<RegisteredUsersListRequest>>,
 ) -> EndpointResult {
     // User MUST provide their authentication token AND
     // have the `user.any:read` permission to access this endpoint.
@@ -76,19 +209,69 @@ pub async fn get_all_registered_users(
     require_permission!(state, authenticated_user, Permission::UserAnyRead);
 
 
-    // Load all users from the database and parse them info `UserInformation` instances.
-    let all_users = query::UserQuery::get_all_users(&state.database)
-        .await
-        .map_err(APIError::InternalError)?;
+    let request = request_body
+        .map(|body| body.into_inner())
+        .unwrap_or_default();
+
+    let page_size = request.page_size.unwrap_or(DEFAULT_USERS_PAGE_SIZE);
+    if page_size == 0 || page_size > MAXIMUM_USERS_PAGE_SIZE {
+        return Err(APIError::client_error(format!(
+            "Page size must be between 1 and {MAXIMUM_USERS_PAGE_SIZE}."
+        )));
+    }
+
+    let sort_field = request.sort_by.into_query_sort_field();
+    let sort_direction = request.sort_direction.into_query_sort_direction();
+
+    let after_cursor = match request.cursor {
+        Some(encoded_cursor) => {
+            let cursor = UserListCursor::decode(&encoded_cursor)
+                .ok_or_else(|| APIError::client_error("Invalid cursor."))?;
+
+            if !cursor.matches_ordering(sort_field, sort_direction) {
+                return Err(APIError::client_error(
+                    "Cursor was created with different sorting options.",
+                ));
+            }
+
+            Some(cursor)
+        }
+        None => None,
+    };
+
+    let filters = request.filters.unwrap_or_default();
+
+
+    // Load the requested page of users from the database
+    // and parse them info `UserInformation` instances.
+    let users_page = query::UserQuery::users_paginated(
+        &state.database,
+        UsersQueryOptions {
+            search: filters.search,
+            role_name: filters.role,
+            joined_after: filters.joined_after,
+            joined_before: filters.joined_before,
+            last_active_after: filters.last_active_after,
+            last_active_before: filters.last_active_before,
+            sort_field,
+            sort_direction,
+            after_cursor,
+            page_size,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
-    let all_users_as_public_struct: Vec<UserInformation> = all_users
+    let users_as_public_struct: Vec<UserInformation> = users_page
+        .users
         .into_iter()
         .map(UserInformation::from_user_model)
         .collect();
 
 
     Ok(RegisteredUsersListResponse {
-        users: all_users_as_public_struct,
+        users: users_as_public_struct,
+        next_cursor: users_page.next_cursor.map(|cursor| cursor.encode()),
     }
     .into_response())
 }
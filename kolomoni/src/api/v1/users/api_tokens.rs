@@ -0,0 +1,508 @@
+use actix_web::{delete, get, http::StatusCode, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::{generate_api_token, hash_api_token, Permission, PermissionSet};
+use kolomoni_database::{
+    mutation::{NewUserApiToken, UserApiTokenMutation},
+    query::{UserApiTokenQuery, UserApiTokenWithPermissions},
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+        v1::{
+            dictionary::parse_string_into_uuid,
+            roles::{find_permission_missing_from_caller, parse_permission_names},
+        },
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+/// Information about a single personal API token.
+///
+/// The token value itself is only ever returned once, when the token is created.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+    "name": "Nightly import script",
+    "permissions": [
+        "word:create",
+        "word:update"
+    ],
+    "created_at": "2023-06-27T20:33:53.078789Z",
+    "expires_at": "2024-06-27T20:33:53.078789Z",
+    "is_current": false
+}))]
+pub struct UserApiToken {
+    /// Token ID.
+    pub id: String,
+
+    /// Name of the token.
+    pub name: String,
+
+    /// Names of permissions the token is limited to.
+    pub permissions: Vec<String>,
+
+    /// When the token was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the token expires (`null` if it never does).
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Whether this is the token the current request was authenticated with.
+    pub is_current: bool,
+}
+
+impl UserApiToken {
+    /// Convert an API token database model (and its permissions) into a [`UserApiToken`]
+    /// that can be safely exposed through the API.
+    pub fn from_token_with_permissions(
+        token_with_permissions: UserApiTokenWithPermissions,
+        is_current: bool,
+    ) -> Self {
+        let token = token_with_permissions.token;
+
+        let mut permissions = token_with_permissions
+            .permissions
+            .into_permissions()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        permissions.sort_by_key(Permission::id);
+
+        Self {
+            id: token.id.to_string(),
+            name: token.name,
+            permissions: permissions
+                .into_iter()
+                .map(|permission| permission.name().to_string())
+                .collect(),
+            created_at: token.created_at.with_timezone(&Utc),
+            expires_at: token
+                .expires_at
+                .map(|expires_at| expires_at.with_timezone(&Utc)),
+            is_current,
+        }
+    }
+}
+
+
+
+/// Response containing a list of the user's personal API tokens.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "api_tokens": [
+        {
+            "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            "name": "Nightly import script",
+            "permissions": [
+                "word:create",
+                "word:update"
+            ],
+            "created_at": "2023-06-27T20:33:53.078789Z",
+            "expires_at": null,
+            "is_current": false
+        }
+    ]
+}))]
+pub struct UserApiTokensResponse {
+    pub api_tokens: Vec<UserApiToken>,
+}
+
+impl_json_response_builder!(UserApiTokensResponse);
+
+
+
+/// Request to create a new personal API token.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "name": "Nightly import script",
+    "permissions": [
+        "word:create",
+        "word:update"
+    ],
+    "expires_at": "2024-06-27T20:33:53.078789Z"
+}))]
+pub struct UserApiTokenCreationRequest {
+    /// Name of the token, to help you tell your tokens apart.
+    pub name: String,
+
+    /// Names of permissions to limit the token to. You must have all of them yourself.
+    pub permissions: Vec<String>,
+
+    /// When the token should expire. If omitted, the token never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+
+
+/// Response containing a newly-created personal API token.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "api_token": {
+        "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+        "name": "Nightly import script",
+        "permissions": [
+            "word:create",
+            "word:update"
+        ],
+        "created_at": "2023-06-27T20:33:53.078789Z",
+        "expires_at": "2024-06-27T20:33:53.078789Z",
+        "is_current": false
+    },
+    "token": "kolomoni_pat_Qm9mLUk3OGxWc2t2b1p5c1RmT3l6dE1Cc0x3Y2xyR3g"
+}))]
+pub struct UserApiTokenCreationResponse {
+    pub api_token: UserApiToken,
+
+    /// The token value. Provide it in the `Authorization: Bearer <token>` header.
+    /// This is the only time the value is returned, so store it somewhere safe.
+    pub token: String,
+}
+
+impl_json_response_builder!(UserApiTokenCreationResponse);
+
+
+
+
+/// Get your personal API tokens
+///
+/// This endpoint returns a list of your personal API tokens (including expired ones), oldest first.
+/// Token values are not included.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:read` permission.
+#[utoipa::path(
+    get,
+    path = "/users/me/api-tokens",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "A list of your personal API tokens.",
+            body = UserApiTokensResponse
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfRead>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/me/api-tokens")]
+async fn get_current_user_api_tokens(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfRead
+    );
+
+
+    let current_api_token_id = authenticated_user.api_token_id();
+
+    let api_tokens =
+        UserApiTokenQuery::tokens_for_user(&state.database, authenticated_user.user_id())
+            .await
+            .map_err(APIError::InternalError)?
+            .into_iter()
+            .map(|token_with_permissions| {
+                let is_current = Some(token_with_permissions.token.id) == current_api_token_id;
+                UserApiToken::from_token_with_permissions(token_with_permissions, is_current)
+            })
+            .collect();
+
+
+    Ok(UserApiTokensResponse { api_tokens }.into_response())
+}
+
+
+
+/// Create a personal API token
+///
+/// This endpoint creates a new long-lived personal API token, e.g. for use in scripts and bots.
+/// The token is limited to the provided permissions: a request authenticated with it
+/// has only those permissions your roles grant *and* the token includes.
+///
+/// The token value is only returned in this response -- it is stored hashed and can
+/// not be retrieved again.
+///
+/// # Restrictions
+/// You can not create API tokens while authenticated with another API token -- log in instead.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Additionally, you can not create a token with a permission you do not have yourself
+/// -- trying to do so will fail with `403 Forbidden`.
+#[utoipa::path(
+    post,
+    path = "/users/me/api-tokens",
+    tag = "users:self",
+    request_body(
+        content = UserApiTokenCreationRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The newly-created API token, including its value.",
+            body = UserApiTokenCreationResponse
+        ),
+        (
+            status = 400,
+            description = "Invalid token parameters.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Empty name" = (
+                    summary = "Empty token name.",
+                    value = json!({ "reason": "Token name must not be empty." })
+                )),
+                ("Invalid permission name" = (
+                    summary = "Invalid permission name.",
+                    value = json!({ "reason": "No such permission: \"word:fly\"." })
+                )),
+                ("Expiration time in the past" = (
+                    summary = "Expiration time in the past.",
+                    value = json!({ "reason": "The expiration time must be in the future." })
+                )),
+            )
+        ),
+        (
+            status = 403,
+            description = "Not allowed to create the token.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't grant permissions you don't have" = (
+                    summary = "Can't grant permissions you don't have.",
+                    value = json!({ "reason": "You cannot grant permissions you do not have (missing permission: word:delete)." })
+                )),
+                ("Can't create tokens with a token" = (
+                    summary = "Can't create API tokens while authenticated with an API token.",
+                    value = json!({ "reason": "API tokens can not be created using another API token." })
+                ))
+            )
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/me/api-tokens")]
+async fn create_current_user_api_token(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    json_data: web::Json<UserApiTokenCreationRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    require_permission!(
+        authenticated_user_permissions,
+        Permission::UserSelfWrite
+    );
+
+    // A (possibly leaked) token could otherwise be used to create
+    // new tokens that outlive it.
+    if authenticated_user.api_token_id().is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "API tokens can not be created using another API token."
+        ));
+    }
+
+
+    let request_data = json_data.into_inner();
+
+    let token_name = request_data.name.trim().to_string();
+    if token_name.is_empty() {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Token name must not be empty."
+        ));
+    }
+
+    if let Some(expires_at) = request_data.expires_at {
+        if expires_at <= Utc::now() {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                "The expiration time must be in the future."
+            ));
+        }
+    }
+
+    let permissions = match parse_permission_names(request_data.permissions) {
+        Ok(permissions) => permissions,
+        Err(reason) => {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                reason
+            ));
+        }
+    };
+
+    if let Some(missing_permission) =
+        find_permission_missing_from_caller(&authenticated_user_permissions, &permissions)
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot grant permissions you do not have (missing permission: {}).",
+                missing_permission.name()
+            )
+        ));
+    }
+
+
+    let token_value = generate_api_token().map_err(APIError::InternalError)?;
+
+    let new_token = UserApiTokenMutation::create(
+        &state.database,
+        NewUserApiToken {
+            user_id: authenticated_user_id,
+            name: token_name,
+            token_hash: hash_api_token(&token_value),
+            expires_at: request_data.expires_at,
+            permissions: permissions.clone(),
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        api_token_id = new_token.id.to_string(),
+        "User has created a personal API token."
+    );
+
+    let api_token = UserApiToken::from_token_with_permissions(
+        UserApiTokenWithPermissions {
+            token: new_token,
+            permissions: PermissionSet::from_permission_set(permissions.into_iter().collect()),
+        },
+        false,
+    );
+
+    Ok(UserApiTokenCreationResponse {
+        api_token,
+        token: token_value,
+    }
+    .into_response())
+}
+
+
+
+/// Revoke one of your personal API tokens
+///
+/// This endpoint deletes one of your personal API tokens. The token immediately stops working.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+#[utoipa::path(
+    delete,
+    path = "/users/me/api-tokens/{token_id}",
+    tag = "users:self",
+    params(
+        (
+            "token_id" = String,
+            Path,
+            description = "ID of the API token to revoke."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The API token has been revoked."
+        ),
+        (
+            status = 400,
+            description = "Invalid API token ID provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "You have no API token with the given ID."
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/me/api-tokens/{token_id}")]
+async fn revoke_current_user_api_token(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+
+    let target_token_id = parse_string_into_uuid(&parameters.into_inner().0)?;
+
+    // Users can only see (and revoke) their own tokens.
+    let target_token = UserApiTokenQuery::get_token_for_user(
+        &state.database,
+        authenticated_user_id,
+        target_token_id,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if target_token.is_none() {
+        return Err(APIError::not_found());
+    }
+
+
+    UserApiTokenMutation::delete(&state.database, target_token_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        api_token_id = target_token_id.to_string(),
+        "User has revoked one of their personal API tokens."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
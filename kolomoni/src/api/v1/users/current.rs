@@ -1,4 +1,5 @@
 use actix_web::{
+    delete,
     get,
     http::{header, StatusCode},
     patch,
@@ -9,7 +10,7 @@ use kolomoni_auth::Permission;
 use kolomoni_database::{
     begin_transaction,
     mutation,
-    query::{self, UserQuery, UserRoleQuery},
+    query::{self, UserQuery, UserRoleQuery, UserSessionQuery},
 };
 use miette::IntoDiagnostic;
 use tracing::info;
@@ -23,13 +24,20 @@ use crate::{
             IntoKolomoniResponseBuilder,
         },
         openapi,
-        v1::users::{
-            UserDisplayNameChangeRequest,
-            UserDisplayNameChangeResponse,
-            UserInfoResponse,
-            UserInformation,
-            UserPermissionsResponse,
-            UserRolesResponse,
+        v1::{
+            dictionary::parse_string_into_uuid,
+            users::{
+                UserAccountDeletionRequest,
+                UserDisplayNameChangeRequest,
+                UserDisplayNameChangeResponse,
+                UserInfoResponse,
+                UserInformation,
+                UserPasswordChangeRequest,
+                UserPermissionsResponse,
+                UserRolesResponse,
+                UserSession,
+                UserSessionsResponse,
+            },
         },
         OptionalIfModifiedSince,
     },
@@ -361,3 +369,399 @@ async fn update_current_user_display_name(
     }
     .into_response())
 }
+
+
+
+
+/// Change your password
+///
+/// This endpoint allows you to change your own password. You must provide your current password
+/// along with the new one.
+///
+/// After a successful change, all of your sessions are revoked and all access and refresh tokens
+/// issued before the change stop working, meaning you (and any other device you were logged in on)
+/// must log in again.
+///
+/// # Authentication
+/// This endpoint requires the `users.self:write` permission.
+#[utoipa::path(
+    patch,
+    path = "/users/me/password",
+    tag = "users:self",
+    request_body(
+        content = UserPasswordChangeRequest,
+        example = json!({
+            "current_password": "verysecurepassword",
+            "new_password": "evenmoresecurepassword"
+        })
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Your password has been changed."
+        ),
+        (
+            status = 403,
+            description = "The provided current password is incorrect.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid current password." })
+        ),
+        (
+            status = 404,
+            description = "You do not exist."
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[patch("/me/password")]
+async fn update_current_user_password(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    json_data: web::Json<UserPasswordChangeRequest>,
+) -> EndpointResult {
+    // User must be authenticated and have
+    // the `user.self:write` permission to access this endpoint.
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+
+    let json_data = json_data.into_inner();
+    let database_transaction =
+        begin_transaction!(&state.database).map_err(APIError::InternalError)?;
+
+    let user = query::UserQuery::get_user_by_id(&database_transaction, authenticated_user_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(APIError::not_found)?;
+
+
+    // Ensure the user knows their current password.
+    let is_valid_current_password = state
+        .hasher
+        .verify_password_against_hash(&json_data.current_password, &user.hashed_password)
+        .map_err(APIError::InternalError)?;
+
+    if !is_valid_current_password {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Invalid current password."
+        ));
+    }
+
+
+    // Update the password in the database (this also invalidates all previously-issued tokens)
+    // and end all of the user's sessions.
+    mutation::UserMutation::change_password_by_user_id(
+        &database_transaction,
+        &state.hasher,
+        authenticated_user_id,
+        &json_data.new_password,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    mutation::UserSessionMutation::delete_all_sessions_for_user(
+        &database_transaction,
+        authenticated_user_id,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    database_transaction
+        .commit()
+        .await
+        .map_err(APIError::InternalDatabaseError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        "User has changed their password."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+
+/// Delete your account
+///
+/// Permanently deletes your account, along with your roles and sessions.
+/// This can not be undone. You must provide your current password.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Personal API tokens can not be used.
+#[utoipa::path(
+    delete,
+    path = "/users/me",
+    tag = "users:self",
+    request_body(
+        content = UserAccountDeletionRequest,
+        example = json!({
+            "current_password": "verysecurepassword"
+        })
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Your account has been deleted."
+        ),
+        (
+            status = 403,
+            description = "Invalid current password or authenticated with a personal API token.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Invalid current password" = (
+                    summary = "The provided current password is incorrect.",
+                    value = json!({ "reason": "Invalid current password." })
+                )),
+                ("Personal API token" = (
+                    summary = "Authenticated with a personal API token.",
+                    value = json!({
+                        "reason": "Your account can not be deleted using a personal API token."
+                    })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "You do not exist."
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/me")]
+async fn delete_current_user(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    json_data: web::Json<UserAccountDeletionRequest>,
+) -> EndpointResult {
+    // User must be authenticated and have
+    // the `user.self:write` permission to access this endpoint.
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+    // A leaked personal API token must not be enough to delete the account.
+    if authenticated_user.session_id().is_none() {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Your account can not be deleted using a personal API token."
+        ));
+    }
+
+
+    let json_data = json_data.into_inner();
+
+    let user = query::UserQuery::get_user_by_id(&state.database, authenticated_user_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(APIError::not_found)?;
+
+
+    // Ensure the user knows their current password.
+    let validated_user = query::UserQuery::validate_user_credentials(
+        &state.database,
+        &state.hasher,
+        &user.username,
+        &json_data.current_password,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if validated_user.is_none() {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Invalid current password."
+        ));
+    }
+
+
+    let user_existed =
+        mutation::UserMutation::delete_by_user_id(&state.database, authenticated_user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if !user_existed {
+        return Err(APIError::not_found());
+    }
+
+
+    info!(
+        user_id = authenticated_user_id,
+        "User has deleted their account."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+
+/// Get your active sessions
+///
+/// This endpoint returns a list of your active login sessions, oldest first.
+/// A new session is started on each login; the session the current request was authenticated
+/// with is marked with `is_current`.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:read` permission.
+#[utoipa::path(
+    get,
+    path = "/users/me/sessions",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "A list of your active sessions.",
+            body = UserSessionsResponse
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfRead>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/me/sessions")]
+async fn get_current_user_sessions(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    // User must be authenticated and have
+    // the `user.self:read` permission to access this endpoint.
+    let authenticated_user = require_authentication!(authentication_extractor);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfRead
+    );
+
+
+    let current_session_id = authenticated_user.session_id();
+
+    let sessions =
+        UserSessionQuery::active_sessions_for_user(&state.database, authenticated_user.user_id())
+            .await
+            .map_err(APIError::InternalError)?
+            .into_iter()
+            .map(|session| {
+                let is_current = Some(session.id) == current_session_id;
+                UserSession::from_session_model(session, is_current)
+            })
+            .collect();
+
+
+    Ok(UserSessionsResponse { sessions }.into_response())
+}
+
+
+
+/// Revoke one of your sessions
+///
+/// This endpoint ends one of your login sessions, e.g. one started on a lost device.
+/// The access and refresh token belonging to that session immediately stop working.
+///
+/// To end the current session, you may also use `POST /api/v1/login/logout`.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+#[utoipa::path(
+    delete,
+    path = "/users/me/sessions/{session_id}",
+    tag = "users:self",
+    params(
+        (
+            "session_id" = String,
+            Path,
+            description = "ID of the session to revoke."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The session has been revoked."
+        ),
+        (
+            status = 400,
+            description = "Invalid session ID provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "You have no active session with the given ID."
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/me/sessions/{session_id}")]
+async fn revoke_current_user_session(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+) -> EndpointResult {
+    // User must be authenticated and have
+    // the `user.self:write` permission to access this endpoint.
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+
+    let target_session_id = parse_string_into_uuid(&parameters.into_inner().0)?;
+
+    // Users can only see (and revoke) their own sessions.
+    let target_session = UserSessionQuery::get_active_session(
+        &state.database,
+        authenticated_user_id,
+        target_session_id,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if target_session.is_none() {
+        return Err(APIError::not_found());
+    }
+
+
+    mutation::UserSessionMutation::delete(&state.database, target_session_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        session_id = target_session_id.to_string(),
+        "User has revoked one of their sessions."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,486 @@
+use actix_web::{delete, get, http::StatusCode, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::Permission;
+use kolomoni_database::{
+    mutation::{self, NewUserInvite},
+    query::{self, UserInviteWithRoles},
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+        v1::{dictionary::parse_string_into_uuid, roles::caller_can_manage_role},
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+/// Information about a single registration invite.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+    "code": "0c1d1bd5f3d3a0a1f26c2f1a5a8e2b47",
+    "created_by_user_id": 1,
+    "created_at": "2023-06-27T20:33:53.078789Z",
+    "expires_at": "2023-07-04T20:33:53.078789Z",
+    "max_uses": 1,
+    "use_count": 0,
+    "revoked_at": null,
+    "roles": ["administrator"]
+}))]
+pub struct UserInvite {
+    /// Invite ID.
+    pub id: String,
+
+    /// The code to register with.
+    pub code: String,
+
+    /// ID of the user that created the invite. If their account has since been deleted,
+    /// this is the ID of the deleted user placeholder (`0`).
+    pub created_by_user_id: Option<i32>,
+
+    /// When the invite was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the invite expires (`null` if it never does).
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// How many times the invite can be used (`null` if unlimited).
+    pub max_uses: Option<i32>,
+
+    /// How many times the invite has been used.
+    pub use_count: i32,
+
+    /// When the invite was revoked (`null` if it hasn't been).
+    pub revoked_at: Option<DateTime<Utc>>,
+
+    /// Additional roles given to users that register with the invite.
+    pub roles: Vec<String>,
+}
+
+impl UserInvite {
+    /// Convert an invite database model (and its roles) into a [`UserInvite`]
+    /// that can be exposed through the API.
+    #[inline]
+    pub fn from_invite_with_roles(invite_with_roles: UserInviteWithRoles) -> Self {
+        let invite = invite_with_roles.invite;
+
+        Self {
+            id: invite.id.to_string(),
+            code: invite.code,
+            created_by_user_id: invite.created_by_user_id,
+            created_at: invite.created_at.with_timezone(&Utc),
+            expires_at: invite
+                .expires_at
+                .map(|expires_at| expires_at.with_timezone(&Utc)),
+            max_uses: invite.max_uses,
+            use_count: invite.use_count,
+            revoked_at: invite
+                .revoked_at
+                .map(|revoked_at| revoked_at.with_timezone(&Utc)),
+            roles: invite_with_roles.roles.role_names(),
+        }
+    }
+}
+
+
+
+/// Response containing a single registration invite.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "invite": {
+        "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+        "code": "0c1d1bd5f3d3a0a1f26c2f1a5a8e2b47",
+        "created_by_user_id": 1,
+        "created_at": "2023-06-27T20:33:53.078789Z",
+        "expires_at": null,
+        "max_uses": null,
+        "use_count": 0,
+        "revoked_at": null,
+        "roles": []
+    }
+}))]
+pub struct UserInviteResponse {
+    pub invite: UserInvite,
+}
+
+impl_json_response_builder!(UserInviteResponse);
+
+
+
+/// Response containing a list of registration invites.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "invites": [
+        {
+            "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            "code": "0c1d1bd5f3d3a0a1f26c2f1a5a8e2b47",
+            "created_by_user_id": 1,
+            "created_at": "2023-06-27T20:33:53.078789Z",
+            "expires_at": "2023-07-04T20:33:53.078789Z",
+            "max_uses": 1,
+            "use_count": 1,
+            "revoked_at": null,
+            "roles": ["administrator"]
+        }
+    ]
+}))]
+pub struct UserInvitesResponse {
+    pub invites: Vec<UserInvite>,
+}
+
+impl_json_response_builder!(UserInvitesResponse);
+
+
+
+/// Request to create a new registration invite.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "expires_at": "2023-07-04T20:33:53.078789Z",
+    "max_uses": 1,
+    "roles": ["administrator"]
+}))]
+pub struct UserInviteCreationRequest {
+    /// When the invite should expire. If omitted, the invite never expires.
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// How many times the invite can be used, e.g. `1` for a single-use invite.
+    /// If omitted, the invite can be used any number of times.
+    pub max_uses: Option<i32>,
+
+    /// Additional roles to give to users that register with the invite.
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+
+
+/// List registration invites
+///
+/// This endpoint returns all registration invites, including revoked, expired
+/// and used up ones, oldest first.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    get,
+    path = "/users/invites",
+    tag = "users",
+    responses(
+        (
+            status = 200,
+            description = "List of registration invites.",
+            body = UserInvitesResponse
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/invites")]
+pub async fn get_all_invites(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let invites = query::UserInviteQuery::all_invites(&state.database)
+        .await
+        .map_err(APIError::InternalError)?
+        .into_iter()
+        .map(UserInvite::from_invite_with_roles)
+        .collect();
+
+    Ok(UserInvitesResponse { invites }.into_response())
+}
+
+
+
+/// Create a registration invite
+///
+/// This endpoint creates a new registration invite with a randomly-generated code.
+/// The invite can be limited to a number of uses and to a point in time,
+/// and can give additional roles to users that register with it.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+/// Additionally, you can not create an invite giving out a role you do not have yourself
+/// (unless you have all the permissions it grants) -- trying to do so will fail with `403 Forbidden`.
+#[utoipa::path(
+    post,
+    path = "/users/invites",
+    tag = "users",
+    request_body(
+        content = UserInviteCreationRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The newly-created invite.",
+            body = UserInviteResponse
+        ),
+        (
+            status = 400,
+            description = "Invalid invite parameters.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Invalid role name" = (
+                    summary = "Invalid role name.",
+                    value = json!({ "reason": "No such role: \"non-existent-role-name\"." })
+                )),
+                ("Invalid maximum number of uses" = (
+                    summary = "Invalid maximum number of uses.",
+                    value = json!({ "reason": "The maximum number of uses must be at least 1." })
+                )),
+                ("Expiration time in the past" = (
+                    summary = "Expiration time in the past.",
+                    value = json!({ "reason": "The expiration time must be in the future." })
+                )),
+            )
+        ),
+        (
+            status = 403,
+            description = "Can't give out roles you don't have.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "You cannot give out roles you do not have (missing role: administrator)." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/invites")]
+pub async fn create_invite(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    json_data: web::Json<UserInviteCreationRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    let authenticated_user_roles = authenticated_user
+        .roles(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let request_data = json_data.into_inner();
+
+    if let Some(max_uses) = request_data.max_uses {
+        if max_uses < 1 {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                "The maximum number of uses must be at least 1."
+            ));
+        }
+    }
+
+    if let Some(expires_at) = request_data.expires_at {
+        if expires_at <= Utc::now() {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                "The expiration time must be in the future."
+            ));
+        }
+    }
+
+
+    let mut roles = Vec::with_capacity(request_data.roles.len());
+
+    for role_name in request_data.roles {
+        let role = query::RoleQuery::get_role_by_name(&state.database, &role_name)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        let Some(role) = role else {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                format!("No such role: \"{role_name}\".")
+            ));
+        };
+
+        roles.push(role);
+    }
+
+    roles.sort_by_key(|role| role.role.id);
+    roles.dedup_by_key(|role| role.role.id);
+
+
+    // Just like when adding roles to a user directly, the invite can only give out
+    // roles the caller has, otherwise it could be used for privilege escalation.
+    for role in roles.iter() {
+        if !caller_can_manage_role(
+            &authenticated_user_roles,
+            &authenticated_user_permissions,
+            role,
+        ) {
+            return Ok(error_response_with_reason!(
+                StatusCode::FORBIDDEN,
+                format!(
+                    "You cannot give out roles you do not have (missing role: {}).",
+                    role.role.name
+                )
+            ));
+        }
+    }
+
+
+    let new_invite = mutation::UserInviteMutation::create(
+        &state.database,
+        NewUserInvite {
+            created_by_user_id: authenticated_user_id,
+            expires_at: request_data.expires_at,
+            max_uses: request_data.max_uses,
+            role_ids: roles.iter().map(|role| role.role.id).collect(),
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    let new_invite_with_roles =
+        query::UserInviteQuery::get_invite_by_id(&state.database, new_invite.id)
+            .await
+            .map_err(APIError::InternalError)?
+            .ok_or_else(|| {
+                APIError::internal_reason("BUG: Newly-created invite could not be found.")
+            })?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        invite_id = new_invite.id.to_string(),
+        "User has created a registration invite."
+    );
+
+    Ok(UserInviteResponse {
+        invite: UserInvite::from_invite_with_roles(new_invite_with_roles),
+    }
+    .into_response())
+}
+
+
+
+/// Revoke a registration invite
+///
+/// This endpoint revokes a registration invite, after which it can no longer be used to register.
+/// Users that have already registered with the invite are not affected.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    delete,
+    path = "/users/invites/{invite_id}",
+    tag = "users",
+    params(
+        (
+            "invite_id" = String,
+            Path,
+            description = "ID of the invite to revoke."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The invite has been revoked."
+        ),
+        (
+            status = 400,
+            description = "Invalid invite ID provided.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Client error: invalid UUID." })
+        ),
+        (
+            status = 404,
+            description = "The specified invite does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified invite does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/invites/{invite_id}")]
+pub async fn revoke_invite(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    parameters: web::Path<(String,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_invite_id = parse_string_into_uuid(&parameters.into_inner().0)?;
+
+    let target_invite = query::UserInviteQuery::get_invite_by_id(&state.database, target_invite_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if target_invite.is_none() {
+        return Err(APIError::not_found_with_reason(
+            "The specified invite does not exist.",
+        ));
+    }
+
+
+    mutation::UserInviteMutation::revoke(&state.database, target_invite_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user.user_id(),
+        invite_id = target_invite_id.to_string(),
+        "User has revoked a registration invite."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
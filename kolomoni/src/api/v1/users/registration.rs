@@ -1,9 +1,12 @@
 use actix_web::{http::StatusCode, post, web};
+use kolomoni_configuration::RegistrationMode;
 use kolomoni_database::{
-    mutation::{self, UserRegistrationInfo},
+    begin_transaction,
+    mutation::{self, RoleGrantDetails, UserRegistrationInfo},
     query,
 };
 use serde::{Deserialize, Serialize};
+use tracing::info;
 use utoipa::ToSchema;
 
 use super::UserInformation;
@@ -23,7 +26,8 @@ use crate::{
 #[schema(example = json!({
     "username": "janeznovak",
     "display_name": "Janez Novak",
-    "password": "perica_reže_raci_rep"
+    "password": "perica_reže_raci_rep",
+    "invite_code": "0c1d1bd5f3d3a0a1f26c2f1a5a8e2b47"
 }))]
 #[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
 pub struct UserRegistrationRequest {
@@ -35,6 +39,12 @@ pub struct UserRegistrationRequest {
 
     /// Password for this user account.
     pub password: String,
+
+    /// Invite code to register with.
+    ///
+    /// Required when registration is invite-only, optional otherwise.
+    /// Invites can grant additional roles to the newly-registered user.
+    pub invite_code: Option<String>,
 }
 
 /// Conversion into the backend-specific struct for registration
@@ -80,6 +90,11 @@ impl_json_response_builder!(UserRegistrationResponse);
 /// Both the username and the display name must be unique across all users,
 /// i.e. no two users can share the same username or display name.
 ///
+/// Depending on the server configuration, registration can be open to anyone,
+/// require a valid invite code, or be closed entirely. An invite code can be provided
+/// even when registration is open, in which case the user also receives any
+/// additional roles the invite grants.
+///
 /// # Authentication
 /// This endpoint does not require authentication.
 #[utoipa::path(
@@ -95,6 +110,25 @@ impl_json_response_builder!(UserRegistrationResponse);
             description = "Registration successful.",
             body = UserRegistrationResponse
         ),
+        (
+            status = 403,
+            description = "Registration is not allowed.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Registration is closed" = (
+                    summary = "Registration is closed.",
+                    value = json!({ "reason": "Registration is closed." })
+                )),
+                ("Invite code is required" = (
+                    summary = "Registration is invite-only, but no invite code was provided.",
+                    value = json!({ "reason": "An invite code is required to register." })
+                )),
+                ("Invalid invite code" = (
+                    summary = "The invite code does not exist or can no longer be used.",
+                    value = json!({ "reason": "The provided invite code is invalid, has expired or has been used up." })
+                )),
+            )
+        ),
         (
             status = 409,
             description = "User with given username already exists.",
@@ -119,9 +153,31 @@ pub async fn register_user(
     state: ApplicationState,
     json_data: web::Json<UserRegistrationRequest>,
 ) -> EndpointResult {
+    let request_data = json_data.into_inner();
+
+    // Ensure the registration mode allows this registration.
+    match state.configuration.registration.mode {
+        RegistrationMode::Open => {}
+        RegistrationMode::InviteOnly => {
+            if request_data.invite_code.is_none() {
+                return Ok(error_response_with_reason!(
+                    StatusCode::FORBIDDEN,
+                    "An invite code is required to register."
+                ));
+            }
+        }
+        RegistrationMode::Closed => {
+            return Ok(error_response_with_reason!(
+                StatusCode::FORBIDDEN,
+                "Registration is closed."
+            ));
+        }
+    }
+
+
     // Ensure the provided username is unique.
     let username_already_exists =
-        query::UserQuery::user_exists_by_username(&state.database, &json_data.username)
+        query::UserQuery::user_exists_by_username(&state.database, &request_data.username)
             .await
             .map_err(APIError::InternalError)?;
 
@@ -135,7 +191,7 @@ pub async fn register_user(
 
     // Ensure the provided display name is unique.
     let display_name_already_exists =
-        query::UserQuery::user_exists_by_display_name(&state.database, &json_data.display_name)
+        query::UserQuery::user_exists_by_display_name(&state.database, &request_data.display_name)
             .await
             .map_err(APIError::InternalError)?;
 
@@ -147,15 +203,74 @@ pub async fn register_user(
     }
 
 
+    let database_transaction =
+        begin_transaction!(&state.database).map_err(APIError::InternalError)?;
+
+    // Use up the invite, if one was provided. If registration fails after this point,
+    // the transaction is rolled back, so the use is not counted.
+    let redeemed_invite = match &request_data.invite_code {
+        Some(invite_code) => {
+            let invite = mutation::UserInviteMutation::redeem(&database_transaction, invite_code)
+                .await
+                .map_err(APIError::InternalError)?;
+
+            let Some(invite) = invite else {
+                return Ok(error_response_with_reason!(
+                    StatusCode::FORBIDDEN,
+                    "The provided invite code is invalid, has expired or has been used up."
+                ));
+            };
+
+            Some(invite)
+        }
+        None => None,
+    };
+
+
     // Create new user.
     let new_user = mutation::UserMutation::create_user(
-        &state.database,
+        &database_transaction,
         &state.hasher,
-        json_data.clone().into(),
+        request_data.into(),
     )
     .await
     .map_err(APIError::InternalError)?;
 
+
+    // Give the user any additional roles the invite grants.
+    if let Some(invite) = redeemed_invite {
+        let invite_role_ids =
+            query::UserInviteQuery::get_invite_by_id(&database_transaction, invite.id)
+                .await
+                .map_err(APIError::InternalError)?
+                .map(|invite_with_roles| invite_with_roles.roles.role_ids())
+                .unwrap_or_default();
+
+        mutation::UserRoleMutation::add_roles_to_user(
+            &database_transaction,
+            new_user.id,
+            &invite_role_ids,
+            RoleGrantDetails {
+                granted_by: invite.created_by_user_id,
+                expires_at: None,
+            },
+        )
+        .await
+        .map_err(APIError::InternalError)?;
+
+        info!(
+            user_id = new_user.id,
+            invite_id = invite.id.to_string(),
+            "User has registered with an invite."
+        );
+    }
+
+
+    database_transaction
+        .commit()
+        .await
+        .map_err(APIError::InternalDatabaseError)?;
+
     Ok(UserRegistrationResponse {
         user: UserInformation::from_user_model(new_user),
     }
@@ -1,9 +1,11 @@
 use actix_web::{delete, get, http::StatusCode, patch, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
 use kolomoni_auth::{Permission, Role};
 use kolomoni_database::{
     begin_transaction,
-    mutation,
-    query::{self, UserQuery, UserRoleQuery},
+    mutation::{self, RoleGrantDetails},
+    query::{self, RoleQuery, UserQuery, UserRoleQuery},
+    shared::DELETED_USER_ID,
 };
 use serde::Deserialize;
 use tracing::info;
@@ -14,16 +16,19 @@ use crate::{
         errors::{APIError, EndpointResult},
         macros::ContextlessResponder,
         openapi,
-        v1::users::{
-            UserDisplayNameChangeRequest,
-            UserDisplayNameChangeResponse,
-            UserInfoResponse,
-            UserInformation,
-            UserPermissionsResponse,
-            UserRolesResponse,
+        v1::{
+            roles::caller_can_manage_role,
+            users::{
+                UserDisplayNameChangeRequest,
+                UserDisplayNameChangeResponse,
+                UserInfoResponse,
+                UserInformation,
+                UserPermissionsResponse,
+                UserRolesResponse,
+            },
         },
     },
-    authentication::UserAuthenticationExtractor,
+    authentication::{AuthenticatedUser, UserAuthenticationExtractor},
     error_response_with_reason,
     require_authentication,
     require_permission,
@@ -171,7 +176,7 @@ pub async fn get_specific_user_roles(
     let target_user_role_names = target_user_roles
         .into_roles()
         .into_iter()
-        .map(|role| role.name().to_string())
+        .map(|role| role.name)
         .collect();
 
 
@@ -388,7 +393,7 @@ async fn update_specific_user_display_name(
 
 
     // Update requested user's display name.
-    mutation::UserMutation::update_display_name_by_user_id(
+    let updated_user = mutation::UserMutation::update_display_name_by_user_id(
         &database_transaction,
         target_user_id,
         json_data.new_display_name.clone(),
@@ -396,14 +401,6 @@ async fn update_specific_user_display_name(
     .await
     .map_err(APIError::InternalError)?;
 
-    let updated_user = mutation::UserMutation::update_last_active_at_by_user_id(
-        &database_transaction,
-        target_user_id,
-        None,
-    )
-    .await
-    .map_err(APIError::InternalError)?;
-
     database_transaction
         .commit()
         .await
@@ -430,25 +427,35 @@ async fn update_specific_user_display_name(
 #[cfg_attr(feature = "with_test_facilities", derive(serde::Serialize))]
 #[schema(
     example = json!({
-        "roles_to_add": ["administrator"]
+        "roles_to_add": ["administrator"],
+        "expires_at": "2023-07-04T20:33:53.078789Z"
     })
 )]
 pub struct UserRoleAddRequest {
     pub roles_to_add: Vec<String>,
+
+    /// When the granted roles should expire. If omitted, the roles are granted permanently.
+    ///
+    /// Re-granting a role the user already has can only extend its expiration time,
+    /// never shorten it.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 
 /// Add roles to a user
 ///
 /// This endpoint allows a user with enough permissions to add roles to another user.
+/// Roles can optionally be granted only until a specific point in time, after which
+/// they no longer apply and are eventually removed.
 ///
 /// # Restrictions
 /// You can not modify your own roles on this endpoint.
 ///
 /// # Authentication
 /// This endpoint requires authentication and the `users.any:write` permission.
-/// Additionally, you can not give out a role you do not have yourself -- trying to do
-/// so will fail with `403 Forbidden`.
+/// Additionally, you can not give out a role you do not have yourself (unless you have all
+/// the permissions it grants) -- trying to do so will fail with `403 Forbidden`.
 #[utoipa::path(
     post,
     path = "/users/{user_id}/roles",
@@ -471,9 +478,18 @@ pub struct UserRoleAddRequest {
         ),
         (
             status = 400,
-            description = "Invalid role name.",
+            description = "Invalid role name or expiration time.",
             body = ErrorReasonResponse,
-            example = json!({ "reason": "No such role: \"non-existent-role-name\"." })
+            examples(
+                ("Invalid role name" = (
+                    summary = "Invalid role name.",
+                    value = json!({ "reason": "No such role: \"non-existent-role-name\"." })
+                )),
+                ("Expiration time in the past" = (
+                    summary = "Expiration time in the past.",
+                    value = json!({ "reason": "The expiration time must be in the future." })
+                )),
+            )
         ),
         (
             status = 403,
@@ -520,6 +536,10 @@ pub async fn add_roles_to_specific_user(
         .roles(&state.database)
         .await
         .map_err(APIError::InternalError)?;
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
 
     require_permission!(
         state,
@@ -540,36 +560,48 @@ pub async fn add_roles_to_specific_user(
         ));
     }
 
-
-    let parsed_roles_to_add_result = request_data
-        .roles_to_add
-        .into_iter()
-        .map(|role_name| {
-            Role::from_name(&role_name).ok_or_else(|| format!("No such role: \"{role_name}\"."))
-        })
-        .collect::<Result<Vec<_>, _>>();
-
-    let roles_to_add = match parsed_roles_to_add_result {
-        Ok(roles) => roles,
-        Err(error_reason) => {
+    if let Some(expires_at) = request_data.expires_at {
+        if expires_at <= Utc::now() {
             return Ok(error_response_with_reason!(
                 StatusCode::BAD_REQUEST,
-                error_reason
+                "The expiration time must be in the future."
             ));
         }
-    };
+    }
+
+
+    let mut roles_to_add = Vec::with_capacity(request_data.roles_to_add.len());
+
+    for role_name in request_data.roles_to_add {
+        let role = RoleQuery::get_role_by_name(&state.database, &role_name)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        let Some(role) = role else {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                format!("No such role: \"{role_name}\".")
+            ));
+        };
+
+        roles_to_add.push(role);
+    }
 
 
     // Validate that the authenticated user has all of the roles
-    // they wish to assign to other users. Not checking for this would
-    // be dangerous as it would essentially allow for privilege escalation.
+    // they wish to assign to other users (or at least all of the permissions those roles grant).
+    // Not checking for this would be dangerous as it would essentially allow for privilege escalation.
     for role in roles_to_add.iter() {
-        if !authenticated_user_roles.has_role(role) {
+        if !caller_can_manage_role(
+            &authenticated_user_roles,
+            &authenticated_user_permissions,
+            role,
+        ) {
             return Ok(error_response_with_reason!(
                 StatusCode::FORBIDDEN,
                 format!(
                     "You cannot give out roles you do not have (missing role: {}).",
-                    role.name()
+                    role.role.name
                 )
             ));
         }
@@ -587,9 +619,22 @@ pub async fn add_roles_to_specific_user(
     }
 
 
-    mutation::UserRoleMutation::add_roles_to_user(&state.database, target_user_id, &roles_to_add)
-        .await
-        .map_err(APIError::InternalError)?;
+    let role_ids_to_add = roles_to_add
+        .iter()
+        .map(|role| role.role.id)
+        .collect::<Vec<_>>();
+
+    mutation::UserRoleMutation::add_roles_to_user(
+        &state.database,
+        target_user_id,
+        &role_ids_to_add,
+        RoleGrantDetails {
+            granted_by: Some(authenticated_user_id),
+            expires_at: request_data.expires_at,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
 
     let updated_role_set = query::UserRoleQuery::user_roles(&state.database, target_user_id)
         .await
@@ -626,8 +671,8 @@ pub struct UserRoleRemoveRequest {
 ///
 /// # Authentication
 /// This endpoint requires authentication and the `users.any:write` permission.
-/// Additionally, you can not remove a role you do not have yourself -- trying to do
-/// so will fail with `403 Forbidden`.
+/// Additionally, you can not remove a role you do not have yourself (unless you have all
+/// the permissions it grants) -- trying to do so will fail with `403 Forbidden`.
 #[utoipa::path(
     delete,
     path = "/users/{user_id}/roles",
@@ -699,6 +744,10 @@ pub async fn remove_roles_from_specific_user(
         .roles(&state.database)
         .await
         .map_err(APIError::InternalError)?;
+    let authenticated_user_permissions = authenticated_user
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
 
     require_permission!(
         state,
@@ -720,35 +769,38 @@ pub async fn remove_roles_from_specific_user(
     }
 
 
-    let parsed_roles_to_remove_result = request_data
-        .roles_to_remove
-        .into_iter()
-        .map(|role_name| {
-            Role::from_name(&role_name).ok_or_else(|| format!("No such role: \"{role_name}\"."))
-        })
-        .collect::<Result<Vec<_>, _>>();
-
-    let roles_to_remove = match parsed_roles_to_remove_result {
-        Ok(roles) => roles,
-        Err(error_reason) => {
+    let mut roles_to_remove = Vec::with_capacity(request_data.roles_to_remove.len());
+
+    for role_name in request_data.roles_to_remove {
+        let role = RoleQuery::get_role_by_name(&state.database, &role_name)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        let Some(role) = role else {
             return Ok(error_response_with_reason!(
                 StatusCode::BAD_REQUEST,
-                error_reason
+                format!("No such role: \"{role_name}\".")
             ));
-        }
-    };
+        };
+
+        roles_to_remove.push(role);
+    }
 
 
     // Validate that the authenticated user (caller) has all of the roles
-    // they wish to remove from the target user. Not checking for this would
-    // be dangerous as it would essentially allow for privilege de-escalation.
+    // they wish to remove from the target user (or at least all of the permissions those roles grant).
+    // Not checking for this would be dangerous as it would essentially allow for privilege de-escalation.
     for role in roles_to_remove.iter() {
-        if !authenticated_user_roles.has_role(role) {
+        if !caller_can_manage_role(
+            &authenticated_user_roles,
+            &authenticated_user_permissions,
+            role,
+        ) {
             return Ok(error_response_with_reason!(
                 StatusCode::FORBIDDEN,
                 format!(
                     "You cannot remove others' roles which you do not have (missing role: {}).",
-                    role.name()
+                    role.role.name
                 )
             ));
         }
@@ -766,10 +818,15 @@ pub async fn remove_roles_from_specific_user(
     }
 
 
+    let role_ids_to_remove = roles_to_remove
+        .iter()
+        .map(|role| role.role.id)
+        .collect::<Vec<_>>();
+
     mutation::UserRoleMutation::remove_roles_from_user(
         &state.database,
         target_user_id,
-        &roles_to_remove,
+        &role_ids_to_remove,
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -784,3 +841,436 @@ pub async fn remove_roles_from_specific_user(
     }
     .into_response())
 }
+
+
+
+/// Returns a role the target user has, but the caller can not manage (if any).
+///
+/// Managing the account of a user with roles you don't have would allow
+/// e.g. a moderator to lock out an administrator, so such operations are refused.
+/// Roles whose permissions the caller has in full are fine (see [`caller_can_manage_role`]).
+pub(super) async fn find_target_role_missing_from_caller(
+    state: &ApplicationState,
+    caller: &AuthenticatedUser,
+    target_user_id: i32,
+) -> Result<Option<Role>, APIError> {
+    let caller_roles = caller
+        .roles(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let caller_permissions = caller
+        .permissions(&state.database)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let target_user_roles = UserRoleQuery::user_roles(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    for role in target_user_roles.into_roles() {
+        if caller_roles.has_role(&role) {
+            continue;
+        }
+
+        let role_with_permissions = RoleQuery::get_role_by_id(&state.database, role.id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+        let Some(role_with_permissions) = role_with_permissions else {
+            // The role has been deleted in the meantime.
+            continue;
+        };
+
+        if !caller_can_manage_role(
+            &caller_roles,
+            &caller_permissions,
+            &role_with_permissions,
+        ) {
+            return Ok(Some(role));
+        }
+    }
+
+    Ok(None)
+}
+
+
+
+/// Deactivate a user's account
+///
+/// Deactivated users can no longer log in, and all of their existing sessions and tokens stop
+/// working immediately. Their account and data are kept; see `POST /users/{user_id}/reactivate`
+/// to undo this and `DELETE /users/{user_id}` to remove the account permanently.
+///
+/// # Restrictions
+/// You can not deactivate your own account, or the account of a user with roles you do not have.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/deactivate",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to deactivate."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The user's account has been deactivated."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to deactivate this account.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage users with roles you don't have" = (
+                    summary = "The user has a role you do not have.",
+                    value = json!({ "reason": "You cannot manage the accounts of users with roles which you do not have (missing role: administrator)." })
+                )),
+                ("Can't modify yourself" = (
+                    summary = "You're not allowed to modify your own account.",
+                    value = json!({ "reason": "Can't modify your own account on this endpoint." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/{user_id}/deactivate")]
+pub async fn deactivate_specific_user(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+
+    // Disallow modifying your own user account on this endpoint.
+    if authenticated_user_id == target_user_id {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Can't modify your own account on this endpoint."
+        ));
+    }
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+    if let Some(missing_role) =
+        find_target_role_missing_from_caller(&state, &authenticated_user, target_user_id).await?
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage the accounts of users with roles which you do not have (missing role: {}).",
+                missing_role.name
+            )
+        ));
+    }
+
+
+    let database_transaction =
+        begin_transaction!(&state.database).map_err(APIError::InternalError)?;
+
+    mutation::UserMutation::deactivate_by_user_id(&database_transaction, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    database_transaction
+        .commit()
+        .await
+        .map_err(APIError::InternalDatabaseError)?;
+
+
+    info!(
+        operator_id = authenticated_user_id,
+        target_user_id = target_user_id,
+        "User has been deactivated."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+/// Reactivate a user's account
+///
+/// Undoes a previous deactivation (see `POST /users/{user_id}/deactivate`),
+/// allowing the user to log in again.
+///
+/// # Restrictions
+/// You can not reactivate your own account, or the account of a user with roles you do not have.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/reactivate",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to reactivate."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The user's account has been reactivated."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to reactivate this account.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage users with roles you don't have" = (
+                    summary = "The user has a role you do not have.",
+                    value = json!({ "reason": "You cannot manage the accounts of users with roles which you do not have (missing role: administrator)." })
+                )),
+                ("Can't modify yourself" = (
+                    summary = "You're not allowed to modify your own account.",
+                    value = json!({ "reason": "Can't modify your own account on this endpoint." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/{user_id}/reactivate")]
+pub async fn reactivate_specific_user(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+
+    // Disallow modifying your own user account on this endpoint.
+    if authenticated_user_id == target_user_id {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Can't modify your own account on this endpoint."
+        ));
+    }
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+    if let Some(missing_role) =
+        find_target_role_missing_from_caller(&state, &authenticated_user, target_user_id).await?
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage the accounts of users with roles which you do not have (missing role: {}).",
+                missing_role.name
+            )
+        ));
+    }
+
+
+    mutation::UserMutation::reactivate_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        operator_id = authenticated_user_id,
+        target_user_id = target_user_id,
+        "User has been reactivated."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+/// Delete a user's account
+///
+/// Permanently deletes a user's account, along with their roles and sessions.
+/// Their contributions are kept, but attributed to the deleted user placeholder
+/// (user ID 0) from then on.
+///
+/// If you only wish to prevent the user from logging in, see `POST /users/{user_id}/deactivate`.
+///
+/// To delete your own account, see `DELETE /users/me`.
+///
+/// # Restrictions
+/// You can not delete your own account on this endpoint, or the account of a user
+/// with roles you do not have.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    delete,
+    path = "/users/{user_id}",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to delete."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The user's account has been deleted."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to delete this account.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage users with roles you don't have" = (
+                    summary = "The user has a role you do not have.",
+                    value = json!({ "reason": "You cannot manage the accounts of users with roles which you do not have (missing role: administrator)." })
+                )),
+                ("Can't modify yourself" = (
+                    summary = "You're not allowed to modify your own account.",
+                    value = json!({ "reason": "Can't modify your own account on this endpoint." })
+                )),
+                ("Can't delete the placeholder" = (
+                    summary = "The deleted user placeholder can't be deleted.",
+                    value = json!({ "reason": "The deleted user placeholder can't be deleted." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/{user_id}")]
+pub async fn delete_specific_user(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+
+    // Disallow modifying your own user account on this endpoint.
+    if authenticated_user_id == target_user_id {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Can't modify your own account on this endpoint."
+        ));
+    }
+
+    if target_user_id == DELETED_USER_ID {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "The deleted user placeholder can't be deleted."
+        ));
+    }
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+    if let Some(missing_role) =
+        find_target_role_missing_from_caller(&state, &authenticated_user, target_user_id).await?
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage the accounts of users with roles which you do not have (missing role: {}).",
+                missing_role.name
+            )
+        ));
+    }
+
+
+    mutation::UserMutation::delete_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        operator_id = authenticated_user_id,
+        target_user_id = target_user_id,
+        "User has been deleted."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,575 @@
+use actix_web::{get, http::StatusCode, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::Permission;
+use kolomoni_database::{
+    entities,
+    mutation::{self, NewUserSuspension},
+    query,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use super::specific::find_target_role_missing_from_caller;
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+    },
+    authentication::UserAuthenticationExtractor,
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+/// Information about a single suspension of a user.
+///
+/// This struct is used as part of a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+    "reason": "Repeatedly vandalised dictionary entries.",
+    "suspended_by_user_id": 1,
+    "suspended_at": "2023-06-27T20:33:53.078789Z",
+    "ends_at": "2023-07-04T20:33:53.078789Z",
+    "lifted_by_user_id": null,
+    "lifted_at": null,
+    "is_active": true
+}))]
+pub struct UserSuspension {
+    /// Suspension ID.
+    pub id: String,
+
+    /// Why the user has been suspended.
+    pub reason: String,
+
+    /// ID of the user that issued the suspension. If their account has since been deleted,
+    /// this is the ID of the deleted user placeholder (`0`).
+    pub suspended_by_user_id: Option<i32>,
+
+    /// When the suspension was issued.
+    pub suspended_at: DateTime<Utc>,
+
+    /// When the suspension ends (`null` if it lasts until lifted).
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// ID of the user that lifted the suspension (`null` if it hasn't been lifted).
+    /// If their account has since been deleted, this is the ID of
+    /// the deleted user placeholder (`0`).
+    pub lifted_by_user_id: Option<i32>,
+
+    /// When the suspension was lifted (`null` if it hasn't been).
+    pub lifted_at: Option<DateTime<Utc>>,
+
+    /// Whether the suspension is currently in effect.
+    pub is_active: bool,
+}
+
+impl UserSuspension {
+    /// Convert a suspension database model into a [`UserSuspension`]
+    /// that can be exposed through the API.
+    #[inline]
+    pub fn from_suspension_model(model: entities::user_suspension::Model) -> Self {
+        let ends_at = model.ends_at.map(|ends_at| ends_at.with_timezone(&Utc));
+        let lifted_at = model
+            .lifted_at
+            .map(|lifted_at| lifted_at.with_timezone(&Utc));
+
+        let is_active = lifted_at.is_none()
+            && ends_at
+                .map(|ends_at| ends_at > Utc::now())
+                .unwrap_or(true);
+
+        Self {
+            id: model.id.to_string(),
+            reason: model.reason,
+            suspended_by_user_id: model.suspended_by_user_id,
+            suspended_at: model.suspended_at.with_timezone(&Utc),
+            ends_at,
+            lifted_by_user_id: model.lifted_by_user_id,
+            lifted_at,
+            is_active,
+        }
+    }
+}
+
+
+
+/// Response containing a single user suspension.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "suspension": {
+        "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+        "reason": "Repeatedly vandalised dictionary entries.",
+        "suspended_by_user_id": 1,
+        "suspended_at": "2023-06-27T20:33:53.078789Z",
+        "ends_at": "2023-07-04T20:33:53.078789Z",
+        "lifted_by_user_id": null,
+        "lifted_at": null,
+        "is_active": true
+    }
+}))]
+pub struct UserSuspensionResponse {
+    pub suspension: UserSuspension,
+}
+
+impl_json_response_builder!(UserSuspensionResponse);
+
+
+
+/// Response containing the suspension history of a user.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "suspensions": [
+        {
+            "id": "018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            "reason": "Repeatedly vandalised dictionary entries.",
+            "suspended_by_user_id": 1,
+            "suspended_at": "2023-06-27T20:33:53.078789Z",
+            "ends_at": "2023-07-04T20:33:53.078789Z",
+            "lifted_by_user_id": 1,
+            "lifted_at": "2023-06-28T10:12:01.513370Z",
+            "is_active": false
+        }
+    ]
+}))]
+pub struct UserSuspensionsResponse {
+    /// Suspensions of the user, newest first.
+    pub suspensions: Vec<UserSuspension>,
+}
+
+impl_json_response_builder!(UserSuspensionsResponse);
+
+
+
+/// Request to suspend a user.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "reason": "Repeatedly vandalised dictionary entries.",
+    "ends_at": "2023-07-04T20:33:53.078789Z"
+}))]
+pub struct UserSuspensionCreationRequest {
+    /// Why the user is being suspended. This is shown to the user when they try to log in.
+    pub reason: String,
+
+    /// When the suspension should end. If omitted, the suspension lasts until it is lifted.
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+
+
+/// Get a user's suspension history
+///
+/// This endpoint returns all suspensions of the given user,
+/// including lifted and ended ones, newest first.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    get,
+    path = "/users/{user_id}/suspensions",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to get the suspension history of."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Suspension history of the user.",
+            body = UserSuspensionsResponse
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/{user_id}/suspensions")]
+pub async fn get_specific_user_suspensions(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+
+    let suspensions =
+        query::UserSuspensionQuery::all_suspensions_for_user(&state.database, target_user_id)
+            .await
+            .map_err(APIError::InternalError)?
+            .into_iter()
+            .map(UserSuspension::from_suspension_model)
+            .collect();
+
+    Ok(UserSuspensionsResponse { suspensions }.into_response())
+}
+
+
+
+/// Suspend a user
+///
+/// While suspended, the user can not log in (the login fails with `403 Forbidden`
+/// and the suspension reason), and all of their existing access and API tokens are refused.
+/// Their sessions are kept, so they can continue where they left off once the suspension
+/// ends or is lifted (see `POST /users/{user_id}/suspensions/lift`).
+///
+/// # Restrictions
+/// You can not suspend yourself, or a user with roles you do not have.
+/// A user can only have one suspension in effect at a time.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/suspensions",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to suspend."
+        )
+    ),
+    request_body(
+        content = UserSuspensionCreationRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The newly-created suspension.",
+            body = UserSuspensionResponse
+        ),
+        (
+            status = 400,
+            description = "Invalid suspension parameters.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Missing reason" = (
+                    summary = "No reason has been given.",
+                    value = json!({ "reason": "A suspension reason is required." })
+                )),
+                ("End time in the past" = (
+                    summary = "End time in the past.",
+                    value = json!({ "reason": "The suspension end time must be in the future." })
+                )),
+            )
+        ),
+        (
+            status = 403,
+            description = "Not allowed to suspend this user.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage users with roles you don't have" = (
+                    summary = "The user has a role you do not have.",
+                    value = json!({ "reason": "You cannot manage the accounts of users with roles which you do not have (missing role: administrator)." })
+                )),
+                ("Can't modify yourself" = (
+                    summary = "You're not allowed to modify your own account.",
+                    value = json!({ "reason": "Can't modify your own account on this endpoint." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        (
+            status = 409,
+            description = "The user is already suspended.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The user is already suspended." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/{user_id}/suspensions")]
+pub async fn suspend_specific_user(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+    json_data: web::Json<UserSuspensionCreationRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+    let request_data = json_data.into_inner();
+
+    // Disallow modifying your own user account on this endpoint.
+    if authenticated_user_id == target_user_id {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Can't modify your own account on this endpoint."
+        ));
+    }
+
+    let reason = request_data.reason.trim().to_string();
+    if reason.is_empty() {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "A suspension reason is required."
+        ));
+    }
+
+    if let Some(ends_at) = request_data.ends_at {
+        if ends_at <= Utc::now() {
+            return Ok(error_response_with_reason!(
+                StatusCode::BAD_REQUEST,
+                "The suspension end time must be in the future."
+            ));
+        }
+    }
+
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+    if let Some(missing_role) =
+        find_target_role_missing_from_caller(&state, &authenticated_user, target_user_id).await?
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage the accounts of users with roles which you do not have (missing role: {}).",
+                missing_role.name
+            )
+        ));
+    }
+
+    let active_suspension =
+        query::UserSuspensionQuery::active_suspension(&state.database, target_user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if active_suspension.is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "The user is already suspended."
+        ));
+    }
+
+
+    let suspension = mutation::UserSuspensionMutation::create(
+        &state.database,
+        NewUserSuspension {
+            user_id: target_user_id,
+            reason,
+            suspended_by_user_id: authenticated_user_id,
+            ends_at: request_data.ends_at,
+        },
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    info!(
+        operator_id = authenticated_user_id,
+        target_user_id = target_user_id,
+        suspension_id = suspension.id.to_string(),
+        "User has been suspended."
+    );
+
+    Ok(UserSuspensionResponse {
+        suspension: UserSuspension::from_suspension_model(suspension),
+    }
+    .into_response())
+}
+
+
+
+/// Lift a user's suspension
+///
+/// Ends the suspension that is currently in effect for the given user,
+/// allowing them to log in and use their existing tokens again.
+/// The suspension remains in the user's suspension history.
+///
+/// # Restrictions
+/// You can not lift your own suspension, or the suspension of a user with roles you do not have.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.any:write` permission.
+#[utoipa::path(
+    post,
+    path = "/users/{user_id}/suspensions/lift",
+    tag = "users",
+    params(
+        (
+            "user_id" = i32,
+            Path,
+            description = "ID of the user to lift the suspension of."
+        )
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The user's suspension has been lifted."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to lift the suspension of this user.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Can't manage users with roles you don't have" = (
+                    summary = "The user has a role you do not have.",
+                    value = json!({ "reason": "You cannot manage the accounts of users with roles which you do not have (missing role: administrator)." })
+                )),
+                ("Can't modify yourself" = (
+                    summary = "You're not allowed to modify your own account.",
+                    value = json!({ "reason": "Can't modify your own account on this endpoint." })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "The specified user does not exist.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The specified user does not exist." })
+        ),
+        (
+            status = 409,
+            description = "The user is not suspended.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "The user is not suspended." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserAnyWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/{user_id}/suspensions/lift")]
+pub async fn lift_specific_user_suspension(
+    state: ApplicationState,
+    authentication: UserAuthenticationExtractor,
+    path_info: web::Path<(i32,)>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserAnyWrite
+    );
+
+
+    let target_user_id = path_info.into_inner().0;
+
+    // Disallow modifying your own user account on this endpoint.
+    if authenticated_user_id == target_user_id {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Can't modify your own account on this endpoint."
+        ));
+    }
+
+    let user_exists = query::UserQuery::user_exists_by_user_id(&state.database, target_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !user_exists {
+        return Err(APIError::not_found_with_reason(
+            "The specified user does not exist.",
+        ));
+    }
+
+    if let Some(missing_role) =
+        find_target_role_missing_from_caller(&state, &authenticated_user, target_user_id).await?
+    {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            format!(
+                "You cannot manage the accounts of users with roles which you do not have (missing role: {}).",
+                missing_role.name
+            )
+        ));
+    }
+
+
+    let was_suspended = mutation::UserSuspensionMutation::lift_active_suspensions(
+        &state.database,
+        target_user_id,
+        authenticated_user_id,
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+    if !was_suspended {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "The user is not suspended."
+        ));
+    }
+
+
+    info!(
+        operator_id = authenticated_user_id,
+        target_user_id = target_user_id,
+        "User's suspension has been lifted."
+    );
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,699 @@
+//! Two-factor authentication (TOTP) management for the current user.
+//!
+//! Enrolling is a two-step process: `POST /users/me/2fa` generates a new secret and
+//! a set of recovery codes, and `POST /users/me/2fa/confirm` enables two-factor authentication
+//! once the user proves their authenticator app works by entering a code from it.
+//! From then on, logging in requires a second step (see `POST /api/v1/login/2fa`).
+
+use actix_web::{delete, get, http::StatusCode, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use kolomoni_auth::{
+    generate_recovery_code,
+    generate_totp_secret,
+    hash_recovery_code,
+    totp_provisioning_uri,
+    verify_totp_code,
+    BuiltinRole,
+    Permission,
+};
+use kolomoni_database::{
+    mutation::UserTwoFactorMutation,
+    query::{UserQuery, UserRoleQuery, UserTwoFactorQuery},
+};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::{
+    api::{
+        errors::{APIError, EndpointResult},
+        macros::ContextlessResponder,
+        openapi,
+    },
+    authentication::{AuthenticatedUser, UserAuthenticationExtractor},
+    error_response_with_reason,
+    impl_json_response_builder,
+    require_authentication,
+    require_permission,
+    state::ApplicationState,
+};
+
+
+/// How many recovery codes are generated at once.
+const RECOVERY_CODE_COUNT: usize = 10;
+
+
+
+/// Two-factor authentication status of the current user.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "enabled": true,
+    "enabled_at": "2023-06-27T20:33:53.078789Z",
+    "remaining_recovery_codes": 9,
+    "required": true
+}))]
+pub struct TwoFactorStatusResponse {
+    /// Whether logging in requires a second factor.
+    pub enabled: bool,
+
+    /// When two-factor authentication was enabled (`null` if it is not).
+    pub enabled_at: Option<DateTime<Utc>>,
+
+    /// How many unused recovery codes are left.
+    pub remaining_recovery_codes: u64,
+
+    /// Whether two-factor authentication is mandatory for this account
+    /// (and thus can not be disabled).
+    pub required: bool,
+}
+
+impl_json_response_builder!(TwoFactorStatusResponse);
+
+
+/// A newly-generated two-factor authentication secret and recovery codes.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "secret": "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP",
+    "provisioning_uri": "otpauth://totp/Stari%20Kolomoni:janeznovak?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP\
+                         &issuer=Stari%20Kolomoni&algorithm=SHA1&digits=6&period=30",
+    "recovery_codes": [
+        "k3v9-x2qa",
+        "7mnd-p4tz"
+    ]
+}))]
+pub struct TwoFactorEnrollmentResponse {
+    /// The TOTP secret, encoded as base32 (for entering into an authenticator app by hand).
+    pub secret: String,
+
+    /// The `otpauth://` URI of the secret. Show it as a QR code for authenticator apps to scan.
+    pub provisioning_uri: String,
+
+    /// Single-use recovery codes that can be entered instead of a code from the authenticator app.
+    /// This is the only time they are returned, so they should be stored somewhere safe.
+    pub recovery_codes: Vec<String>,
+}
+
+impl_json_response_builder!(TwoFactorEnrollmentResponse);
+
+
+/// Request to confirm a two-factor authentication enrollment.
+///
+/// This struct is used as a request in the public API.
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Serialize))]
+#[schema(example = json!({
+    "code": "287082"
+}))]
+pub struct TwoFactorConfirmationRequest {
+    /// The current code from the authenticator app.
+    pub code: String,
+}
+
+
+/// Newly-generated recovery codes.
+///
+/// This struct is used as a response in the public API.
+#[derive(Serialize, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+#[schema(example = json!({
+    "recovery_codes": [
+        "k3v9-x2qa",
+        "7mnd-p4tz"
+    ]
+}))]
+pub struct TwoFactorRecoveryCodesResponse {
+    /// Single-use recovery codes. Any previous recovery codes no longer work.
+    pub recovery_codes: Vec<String>,
+}
+
+impl_json_response_builder!(TwoFactorRecoveryCodesResponse);
+
+
+
+/// Returns whether two-factor authentication is mandatory for the given user,
+/// i.e. whether it is required for privileged users (see configuration) and the user
+/// has the `administrator` role or any permission to delete content.
+pub(crate) async fn is_two_factor_required(
+    state: &ApplicationState,
+    user_id: i32,
+) -> Result<bool, APIError> {
+    if !state
+        .configuration
+        .two_factor_authentication
+        .required_for_privileged_users
+    {
+        return Ok(false);
+    }
+
+
+    let user_roles = UserRoleQuery::user_roles(&state.database, user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if user_roles.has_role_by_id(BuiltinRole::Administrator.id()) {
+        return Ok(true);
+    }
+
+    let user_permissions =
+        UserRoleQuery::effective_user_permissions_from_user_id(&state.database, user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    Ok(user_permissions
+        .permissions()
+        .iter()
+        .any(|permission| permission.name().ends_with(":delete")))
+}
+
+
+/// Generates a new (pending) two-factor authentication secret and recovery codes for the user,
+/// replacing any previous ones.
+pub(crate) async fn begin_two_factor_enrollment(
+    state: &ApplicationState,
+    user_id: i32,
+) -> Result<TwoFactorEnrollmentResponse, APIError> {
+    let user = UserQuery::get_user_by_id(&state.database, user_id)
+        .await
+        .map_err(APIError::InternalError)?
+        .ok_or_else(|| APIError::internal_reason("User enrolling into 2FA does not exist."))?;
+
+
+    let secret = generate_totp_secret().map_err(APIError::InternalError)?;
+    let recovery_codes = generate_recovery_codes()?;
+
+    UserTwoFactorMutation::begin_enrollment(
+        &state.database,
+        user_id,
+        secret.clone(),
+        recovery_codes
+            .iter()
+            .map(|recovery_code| hash_recovery_code(recovery_code))
+            .collect(),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    let provisioning_uri = totp_provisioning_uri(
+        &state.configuration.two_factor_authentication.issuer_name,
+        &user.username,
+        &secret,
+    );
+
+    Ok(TwoFactorEnrollmentResponse {
+        secret,
+        provisioning_uri,
+        recovery_codes,
+    })
+}
+
+
+fn generate_recovery_codes() -> Result<Vec<String>, APIError> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code().map_err(APIError::InternalError))
+        .collect()
+}
+
+
+/// Two-factor authentication can only be managed with a regular login session,
+/// so a leaked personal API token can not be used to turn it off.
+fn reject_api_token_authentication(authenticated_user: &AuthenticatedUser) -> Option<HttpResponse> {
+    if authenticated_user.session_id().is_none() {
+        return Some(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Two-factor authentication can not be managed using a personal API token."
+        ));
+    }
+
+    None
+}
+
+
+
+/// Get your two-factor authentication status
+///
+/// This endpoint returns whether two-factor authentication is enabled for your account,
+/// how many recovery codes you have left and whether two-factor authentication
+/// is mandatory for you.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:read` permission.
+#[utoipa::path(
+    get,
+    path = "/users/me/2fa",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "Your two-factor authentication status.",
+            body = TwoFactorStatusResponse
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfRead>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[get("/me/2fa")]
+async fn get_current_user_two_factor_status(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfRead
+    );
+
+
+    let two_factor = UserTwoFactorQuery::get_by_user_id(&state.database, authenticated_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    let enabled_at = two_factor
+        .and_then(|two_factor| two_factor.enabled_at)
+        .map(|enabled_at| enabled_at.with_timezone(&Utc));
+
+    let remaining_recovery_codes = match enabled_at {
+        Some(_) => {
+            UserTwoFactorQuery::remaining_recovery_code_count(&state.database, authenticated_user_id)
+                .await
+                .map_err(APIError::InternalError)?
+        }
+        None => 0,
+    };
+
+    let required = is_two_factor_required(&state, authenticated_user_id).await?;
+
+
+    Ok(TwoFactorStatusResponse {
+        enabled: enabled_at.is_some(),
+        enabled_at,
+        remaining_recovery_codes,
+        required,
+    }
+    .into_response())
+}
+
+
+
+/// Start enrolling into two-factor authentication
+///
+/// This endpoint generates a new TOTP secret and a set of single-use recovery codes.
+/// Add the secret to an authenticator app (e.g. by showing the provisioning URI
+/// as a QR code), then confirm the enrollment with a code from the app
+/// (see `POST /api/v1/users/me/2fa/confirm`). Until then, two-factor authentication
+/// is not enabled.
+///
+/// Calling this again before confirming discards the previous secret and recovery codes.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Personal API tokens can not be used.
+#[utoipa::path(
+    post,
+    path = "/users/me/2fa",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "The new secret and recovery codes.",
+            body = TwoFactorEnrollmentResponse
+        ),
+        (
+            status = 403,
+            description = "Authenticated with a personal API token.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Two-factor authentication can not be managed using a personal API token."
+            })
+        ),
+        (
+            status = 409,
+            description = "Two-factor authentication is already enabled.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication is already enabled." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/me/2fa")]
+async fn begin_current_user_two_factor_enrollment(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+    if let Some(response) = reject_api_token_authentication(&authenticated_user) {
+        return Ok(response);
+    }
+
+
+    let two_factor_enabled =
+        UserTwoFactorQuery::is_enabled_for_user(&state.database, authenticated_user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if two_factor_enabled {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled."
+        ));
+    }
+
+    let enrollment = begin_two_factor_enrollment(&state, authenticated_user_id).await?;
+
+
+    Ok(enrollment.into_response())
+}
+
+
+
+/// Confirm two-factor authentication enrollment
+///
+/// This endpoint enables two-factor authentication, started with `POST /api/v1/users/me/2fa`,
+/// once you provide a current code from your authenticator app. From then on,
+/// logging in requires a code from the app (or one of the recovery codes).
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Personal API tokens can not be used.
+#[utoipa::path(
+    post,
+    path = "/users/me/2fa/confirm",
+    tag = "users:self",
+    request_body(
+        content = TwoFactorConfirmationRequest
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Two-factor authentication has been enabled."
+        ),
+        (
+            status = 400,
+            description = "Invalid code.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Invalid two-factor authentication code." })
+        ),
+        (
+            status = 403,
+            description = "Authenticated with a personal API token.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Two-factor authentication can not be managed using a personal API token."
+            })
+        ),
+        (
+            status = 404,
+            description = "Enrollment has not been started.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication enrollment has not been started." })
+        ),
+        (
+            status = 409,
+            description = "Two-factor authentication is already enabled.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication is already enabled." })
+        ),
+        openapi::MissingOrInvalidJsonRequestBodyResponse,
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/me/2fa/confirm")]
+async fn confirm_current_user_two_factor_enrollment(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+    json_data: web::Json<TwoFactorConfirmationRequest>,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+    if let Some(response) = reject_api_token_authentication(&authenticated_user) {
+        return Ok(response);
+    }
+
+
+    let Some(two_factor) =
+        UserTwoFactorQuery::get_by_user_id(&state.database, authenticated_user_id)
+            .await
+            .map_err(APIError::InternalError)?
+    else {
+        return Err(APIError::not_found_with_reason(
+            "Two-factor authentication enrollment has not been started.",
+        ));
+    };
+
+    if two_factor.enabled_at.is_some() {
+        return Ok(error_response_with_reason!(
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled."
+        ));
+    }
+
+
+    let Some(time_step) = verify_totp_code(
+        &two_factor.secret,
+        &json_data.code,
+        Utc::now(),
+        two_factor.last_used_time_step,
+    ) else {
+        return Ok(error_response_with_reason!(
+            StatusCode::BAD_REQUEST,
+            "Invalid two-factor authentication code."
+        ));
+    };
+
+    UserTwoFactorMutation::enable(&state.database, authenticated_user_id, time_step)
+        .await
+        .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        "User has enabled two-factor authentication."
+    );
+
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+/// Disable two-factor authentication
+///
+/// This endpoint turns off two-factor authentication for your account (or cancels
+/// an unconfirmed enrollment), removing the secret and all recovery codes.
+///
+/// # Restrictions
+/// Two-factor authentication can not be disabled if it is mandatory for your account
+/// (see `required` in `GET /api/v1/users/me/2fa`).
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Personal API tokens can not be used.
+#[utoipa::path(
+    delete,
+    path = "/users/me/2fa",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "Two-factor authentication has been disabled."
+        ),
+        (
+            status = 403,
+            description = "Not allowed to disable two-factor authentication.",
+            body = ErrorReasonResponse,
+            examples(
+                ("Mandatory" = (
+                    summary = "Two-factor authentication is mandatory for your account.",
+                    value = json!({ "reason": "Two-factor authentication is mandatory for your account." })
+                )),
+                ("Personal API token" = (
+                    summary = "Authenticated with a personal API token.",
+                    value = json!({
+                        "reason": "Two-factor authentication can not be managed using a personal API token."
+                    })
+                ))
+            )
+        ),
+        (
+            status = 404,
+            description = "Two-factor authentication is not enabled.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication is not enabled." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[delete("/me/2fa")]
+async fn disable_current_user_two_factor(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+    if let Some(response) = reject_api_token_authentication(&authenticated_user) {
+        return Ok(response);
+    }
+
+    if is_two_factor_required(&state, authenticated_user_id).await? {
+        return Ok(error_response_with_reason!(
+            StatusCode::FORBIDDEN,
+            "Two-factor authentication is mandatory for your account."
+        ));
+    }
+
+
+    let was_enrolled = UserTwoFactorMutation::disable(&state.database, authenticated_user_id)
+        .await
+        .map_err(APIError::InternalError)?;
+
+    if !was_enrolled {
+        return Err(APIError::not_found_with_reason(
+            "Two-factor authentication is not enabled.",
+        ));
+    }
+
+
+    info!(
+        user_id = authenticated_user_id,
+        "User has disabled two-factor authentication."
+    );
+
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+
+
+/// Regenerate your recovery codes
+///
+/// This endpoint generates a new set of single-use recovery codes.
+/// All previous recovery codes stop working.
+///
+/// # Authentication
+/// This endpoint requires authentication and the `users.self:write` permission.
+/// Personal API tokens can not be used.
+#[utoipa::path(
+    post,
+    path = "/users/me/2fa/recovery-codes",
+    tag = "users:self",
+    responses(
+        (
+            status = 200,
+            description = "The new recovery codes.",
+            body = TwoFactorRecoveryCodesResponse
+        ),
+        (
+            status = 403,
+            description = "Authenticated with a personal API token.",
+            body = ErrorReasonResponse,
+            example = json!({
+                "reason": "Two-factor authentication can not be managed using a personal API token."
+            })
+        ),
+        (
+            status = 404,
+            description = "Two-factor authentication is not enabled.",
+            body = ErrorReasonResponse,
+            example = json!({ "reason": "Two-factor authentication is not enabled." })
+        ),
+        openapi::FailedAuthenticationResponses<openapi::RequiresUserSelfWrite>,
+        openapi::InternalServerErrorResponse,
+    ),
+    security(
+        ("access_token" = [])
+    )
+)]
+#[post("/me/2fa/recovery-codes")]
+async fn regenerate_current_user_recovery_codes(
+    state: ApplicationState,
+    authentication_extractor: UserAuthenticationExtractor,
+) -> EndpointResult {
+    let authenticated_user = require_authentication!(authentication_extractor);
+    let authenticated_user_id = authenticated_user.user_id();
+    require_permission!(
+        state,
+        authenticated_user,
+        Permission::UserSelfWrite
+    );
+
+    if let Some(response) = reject_api_token_authentication(&authenticated_user) {
+        return Ok(response);
+    }
+
+
+    let two_factor_enabled =
+        UserTwoFactorQuery::is_enabled_for_user(&state.database, authenticated_user_id)
+            .await
+            .map_err(APIError::InternalError)?;
+
+    if !two_factor_enabled {
+        return Err(APIError::not_found_with_reason(
+            "Two-factor authentication is not enabled.",
+        ));
+    }
+
+
+    let recovery_codes = generate_recovery_codes()?;
+
+    UserTwoFactorMutation::replace_recovery_codes(
+        &state.database,
+        authenticated_user_id,
+        recovery_codes
+            .iter()
+            .map(|recovery_code| hash_recovery_code(recovery_code))
+            .collect(),
+    )
+    .await
+    .map_err(APIError::InternalError)?;
+
+
+    info!(
+        user_id = authenticated_user_id,
+        "User has regenerated their recovery codes."
+    );
+
+
+    Ok(TwoFactorRecoveryCodesResponse { recovery_codes }.into_response())
+}
@@ -1,19 +1,36 @@
 //! Authentication-related code.
 
-use actix_utils::future;
-use actix_utils::future::Ready;
 use actix_web::dev::Payload;
 use actix_web::http::{header, StatusCode};
 use actix_web::web::Data;
 use actix_web::{FromRequest, HttpRequest};
-use chrono::{DateTime, Utc};
-use kolomoni_auth::{JWTClaims, JWTValidationError, RoleSet, BLANKET_PERMISSION_GRANT};
+use chrono::{DateTime, SecondsFormat, SubsecRound, Utc};
+use futures_util::future::{self, LocalBoxFuture};
+use futures_util::FutureExt;
+use kolomoni_auth::{
+    hash_api_token,
+    is_api_token,
+    JWTClaims,
+    JWTValidationError,
+    JsonWebTokenManager,
+    RoleSet,
+    BLANKET_PERMISSION_GRANT,
+};
 use kolomoni_auth::{Permission, PermissionSet};
-use kolomoni_database::query::UserRoleQuery;
+use kolomoni_database::entities::user_suspension;
+use kolomoni_database::query::{
+    UserApiTokenQuery,
+    UserQuery,
+    UserRoleQuery,
+    UserSessionQuery,
+    UserSuspensionQuery,
+};
 use miette::{Context, Result};
+use sea_orm::prelude::Uuid;
 use sea_orm::ConnectionTrait;
 use tracing::{debug, error, info};
 
+use crate::activity::mark_request_as_authenticated;
 use crate::state::ApplicationStateInner;
 
 
@@ -42,121 +59,449 @@ pub enum UserAuthenticationExtractor {
 
     /// Valid JWT token provided as authentication.
     Authenticated { token: JWTClaims },
+
+    /// Valid personal API token provided as authentication.
+    AuthenticatedWithApiToken { token: ApiTokenDetails },
 }
 
 impl UserAuthenticationExtractor {
     /// Returns an `Some(`[`AuthenticatedUser`]`)` if the API caller
-    /// provided a JWT authentication token with the request.
+    /// provided a JWT authentication token or a personal API token with the request.
     pub fn authenticated_user(&self) -> Option<AuthenticatedUser> {
         match self {
             UserAuthenticationExtractor::Unauthenticated => None,
             UserAuthenticationExtractor::Authenticated { token } => Some(AuthenticatedUser {
-                token: token.clone(),
+                method: AuthenticationMethod::AccessToken(token.clone()),
             }),
+            UserAuthenticationExtractor::AuthenticatedWithApiToken { token } => {
+                Some(AuthenticatedUser {
+                    method: AuthenticationMethod::ApiToken(token.clone()),
+                })
+            }
         }
     }
 
     pub fn is_permission_granted_to_all(&self, permission: Permission) -> bool {
         BLANKET_PERMISSION_GRANT.contains(&permission)
     }
+
+    /// Parses the `Authorization` header (if any). JWT tokens are decoded,
+    /// while personal API tokens are returned as-is.
+    ///
+    /// This only validates JWT tokens themselves, not whether they have been revoked since
+    /// they were issued (that requires a database lookup, see [`is_token_revoked`]).
+    /// The same goes for API tokens, see [`look_up_api_token`].
+    fn decode_authorization_header(
+        req: &HttpRequest,
+        jwt_manager: &JsonWebTokenManager,
+    ) -> Result<Option<BearerToken>, actix_web::Error> {
+        let Some(authorization_header_value) = req.headers().get(header::AUTHORIZATION) else {
+            return Ok(None);
+        };
+
+        let header_value = match authorization_header_value.to_str() {
+            Ok(header_value) => header_value,
+            Err(_) => return Err(actix_web::error::ParseError::Header.into()),
+        };
+
+        // Strip Bearer prefix
+        if !header_value.starts_with("Bearer ") {
+            return Err(actix_web::error::ParseError::Header.into());
+        }
+
+        let token_string = header_value
+            .strip_prefix("Bearer ")
+            .expect("BUG: String started with \"Bearer \", but couldn't strip prefix.");
+
+        if is_api_token(token_string) {
+            return Ok(Some(BearerToken::ApiToken(
+                token_string.to_string(),
+            )));
+        }
+
+        match jwt_manager.decode_token(token_string) {
+            Ok(token) => Ok(Some(BearerToken::AccessToken(token))),
+            Err(error) => match error {
+                JWTValidationError::Expired(token) => {
+                    debug!(
+                        user_id = token.user_id,
+                        "User tried authenticating with expired token."
+                    );
+
+                    Err(actix_web::error::ErrorForbidden(
+                        "Authentication token expired.",
+                    ))
+                }
+                JWTValidationError::InvalidToken(error) => {
+                    info!(
+                        error = error,
+                        "User tried authenticating with invalid token."
+                    );
+
+                    Err(actix_web::error::ErrorBadRequest(
+                        "Invalid token.",
+                    ))
+                }
+            },
+        }
+    }
+}
+
+/// A token provided in the `Authorization` header.
+enum BearerToken {
+    /// A decoded (but not yet checked for revocation) JWT access token.
+    AccessToken(JWTClaims),
+
+    /// A raw personal API token.
+    ApiToken(String),
 }
 
 impl FromRequest for UserAuthenticationExtractor {
     type Error = actix_web::Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
-    #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        match req.headers().get(header::AUTHORIZATION) {
-            Some(authorization_header_value) => {
-                let jwt_manager = match req.app_data::<Data<ApplicationStateInner>>() {
-                    Some(app_state) => &app_state.jwt_manager,
-                    None => {
-                        error!("BUG: No AppState injected, all UserAuth extractors will fail!");
-
-                        return future::err(
-                            actix_web::error::InternalError::new(
-                                "Missing AppState.",
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                            )
-                            .into(),
+        let state = match req.app_data::<Data<ApplicationStateInner>>() {
+            Some(app_state) => app_state.clone(),
+            None => {
+                error!("BUG: No AppState injected, all UserAuth extractors will fail!");
+
+                return future::err(
+                    actix_web::error::InternalError::new(
+                        "Missing AppState.",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    )
+                    .into(),
+                )
+                .boxed_local();
+            }
+        };
+
+        let bearer_token = match Self::decode_authorization_header(req, &state.jwt_manager) {
+            Ok(Some(bearer_token)) => bearer_token,
+            Ok(None) => return future::ok(Self::Unauthenticated).boxed_local(),
+            Err(error) => return future::err(error).boxed_local(),
+        };
+
+        let request = req.clone();
+
+
+        async move {
+            let extractor = match bearer_token {
+                BearerToken::AccessToken(token) => {
+                    let token_is_revoked =
+                        is_token_revoked(&state.database, &token)
+                            .await
+                            .map_err(|error| {
+                                error!(
+                                error = error.to_string(),
+                                "Failed to check whether the authentication token has been revoked."
+                            );
+
+                                actix_web::error::ErrorInternalServerError("Internal server error.")
+                            })?;
+
+                    if token_is_revoked {
+                        debug!(
+                            user_id = token.user_id,
+                            "User tried authenticating with revoked token."
                         );
-                    }
-                };
 
-                let header_value = match authorization_header_value.to_str() {
-                    Ok(header_value) => header_value,
-                    Err(_) => return future::err(actix_web::error::ParseError::Header.into()),
-                };
+                        return Err(actix_web::error::ErrorForbidden(
+                            "Authentication token has been revoked.",
+                        ));
+                    }
 
-                // Strip Bearer prefix
-                if !header_value.starts_with("Bearer ") {
-                    return future::err(actix_web::error::ParseError::Header.into());
+                    Self::Authenticated { token }
                 }
+                BearerToken::ApiToken(raw_token) => {
+                    let lookup_result = look_up_api_token(&state.database, &raw_token)
+                        .await
+                        .map_err(|error| {
+                            error!(
+                                error = error.to_string(),
+                                "Failed to look up personal API token."
+                            );
+
+                            actix_web::error::ErrorInternalServerError("Internal server error.")
+                        })?;
+
+                    match lookup_result {
+                        ApiTokenLookup::Valid(token) => Self::AuthenticatedWithApiToken { token },
+                        ApiTokenLookup::Unknown => {
+                            info!("User tried authenticating with unknown API token.");
+
+                            return Err(actix_web::error::ErrorBadRequest(
+                                "Invalid token.",
+                            ));
+                        }
+                        ApiTokenLookup::Expired { user_id } => {
+                            debug!(
+                                user_id = user_id,
+                                "User tried authenticating with expired API token."
+                            );
 
-                let token_string = header_value
-                    .strip_prefix("Bearer ")
-                    .expect("BUG: String started with \"Bearer \", but couldn't strip prefix.");
-
-                let token = match jwt_manager.decode_token(token_string) {
-                    Ok(token) => token,
-                    Err(error) => {
-                        return match error {
-                            JWTValidationError::Expired(token) => {
-                                debug!(
-                                    user_id = token.user_id,
-                                    "User tried authenticating with expired token."
-                                );
-
-                                future::err(actix_web::error::ErrorForbidden(
-                                    "Authentication token expired.",
-                                ))
-                            }
-                            JWTValidationError::InvalidToken(error) => {
-                                info!(
-                                    error = error,
-                                    "User tried authenticating with invalid token."
-                                );
-
-                                future::err(actix_web::error::ErrorBadRequest(
-                                    "Invalid token.",
-                                ))
-                            }
+                            return Err(actix_web::error::ErrorForbidden(
+                                "Authentication token expired.",
+                            ));
+                        }
+                        ApiTokenLookup::Revoked { user_id } => {
+                            debug!(
+                                user_id = user_id,
+                                "User tried authenticating with revoked API token."
+                            );
+
+                            return Err(actix_web::error::ErrorForbidden(
+                                "Authentication token has been revoked.",
+                            ));
                         }
                     }
-                };
+                }
+            };
+
+            if let Some(authenticated_user) = extractor.authenticated_user() {
+                let user_id = authenticated_user.user_id();
+
+                let active_suspension =
+                    UserSuspensionQuery::active_suspension(&state.database, user_id)
+                        .await
+                        .map_err(|error| {
+                            error!(
+                                error = error.to_string(),
+                                "Failed to check whether the user is suspended."
+                            );
+
+                            actix_web::error::ErrorInternalServerError("Internal server error.")
+                        })?;
 
-                future::ok(Self::Authenticated { token })
+                if let Some(suspension) = active_suspension {
+                    debug!(
+                        user_id = user_id,
+                        "Suspended user tried authenticating."
+                    );
+
+                    return Err(actix_web::error::ErrorForbidden(
+                        suspension_message(&suspension),
+                    ));
+                }
+
+                // Lets the `UserActivityTracking` middleware record the user's activity.
+                mark_request_as_authenticated(&request, user_id);
             }
-            None => future::ok(Self::Unauthenticated),
+
+            Ok(extractor)
+        }
+        .boxed_local()
+    }
+}
+
+
+/// Returns `true` if the given (otherwise valid) token has been revoked after it was issued.
+///
+/// A token is considered revoked when:
+/// - the login session it belongs to no longer exists (the user logged out or revoked the session),
+/// - its owner's account has been deactivated, or
+/// - its owner has changed their password after the token was issued.
+///   Because token issue times have a precision of one second, tokens issued in the
+///   same second as the password change are still considered valid.
+///
+/// This operation performs database lookups.
+pub async fn is_token_revoked<C: ConnectionTrait>(database: &C, token: &JWTClaims) -> Result<bool> {
+    let session = UserSessionQuery::get_active_session(database, token.user_id, token.jti)
+        .await
+        .wrap_err("Could not look up the session of the token.")?;
+
+    if session.is_none() {
+        return Ok(true);
+    }
+
+    are_credentials_revoked(database, token.user_id, &token.iat).await
+}
+
+/// Returns `true` if credentials of the given user that were issued at `issued_at`
+/// should no longer be accepted, i.e. if the user has been deactivated
+/// or has changed their password since.
+///
+/// This operation performs a database lookup.
+async fn are_credentials_revoked<C: ConnectionTrait>(
+    database: &C,
+    user_id: i32,
+    issued_at: &DateTime<Utc>,
+) -> Result<bool> {
+    let Some(user) = UserQuery::get_user_by_id(database, user_id)
+        .await
+        .wrap_err("Could not look up the owner of the token.")?
+    else {
+        // Endpoints themselves decide what to do when the user no longer exists.
+        return Ok(false);
+    };
+
+    if user.deactivated_at.is_some() {
+        return Ok(true);
+    }
+
+    let Some(password_last_changed_at) = user.password_last_changed_at else {
+        return Ok(false);
+    };
+
+    Ok(issued_at.trunc_subsecs(0) < password_last_changed_at.to_utc().trunc_subsecs(0))
+}
+
+
+/// Returns a human-readable explanation of why a suspended user is refused,
+/// including the reason for the suspension and, if set, when it ends.
+pub(crate) fn suspension_message(suspension: &user_suspension::Model) -> String {
+    match suspension.ends_at {
+        Some(ends_at) => format!(
+            "This account has been suspended until {}. Reason: {}",
+            ends_at.to_utc().to_rfc3339_opts(SecondsFormat::Secs, true),
+            suspension.reason
+        ),
+        None => format!(
+            "This account has been suspended. Reason: {}",
+            suspension.reason
+        ),
+    }
+}
+
+
+/// Details of a valid personal API token.
+#[derive(Clone, Debug)]
+pub struct ApiTokenDetails {
+    /// ID of the token.
+    pub token_id: Uuid,
+
+    /// ID of the user who owns the token.
+    pub user_id: i32,
+
+    /// Permissions the token is limited to. The user's effective permissions
+    /// are the intersection of these and the permissions granted by their roles.
+    pub permission_scope: PermissionSet,
+}
+
+/// Result of [`look_up_api_token`].
+enum ApiTokenLookup {
+    Valid(ApiTokenDetails),
+
+    /// No such token exists (it may also have been deleted).
+    Unknown,
+
+    Expired {
+        user_id: i32,
+    },
+
+    /// The token's owner has been deactivated or has changed
+    /// their password after the token was created.
+    Revoked {
+        user_id: i32,
+    },
+}
+
+/// Looks up a raw personal API token by its hash and checks whether it is still valid.
+///
+/// This operation performs database lookups.
+async fn look_up_api_token<C: ConnectionTrait>(
+    database: &C,
+    raw_token: &str,
+) -> Result<ApiTokenLookup> {
+    let Some(token) = UserApiTokenQuery::get_token_by_hash(database, &hash_api_token(raw_token))
+        .await
+        .wrap_err("Could not look up the API token.")?
+    else {
+        return Ok(ApiTokenLookup::Unknown);
+    };
+
+    let user_id = token.token.user_id;
+
+    if let Some(expires_at) = token.token.expires_at {
+        if expires_at.to_utc() <= Utc::now() {
+            return Ok(ApiTokenLookup::Expired { user_id });
         }
     }
+
+    if are_credentials_revoked(
+        database,
+        user_id,
+        &token.token.created_at.to_utc(),
+    )
+    .await?
+    {
+        return Ok(ApiTokenLookup::Revoked { user_id });
+    }
+
+    Ok(ApiTokenLookup::Valid(ApiTokenDetails {
+        token_id: token.token.id,
+        user_id,
+        permission_scope: token.permissions,
+    }))
 }
 
 
 
-/// An authenticated user with a valid JWT token.
+/// How an [`AuthenticatedUser`] has authenticated.
+enum AuthenticationMethod {
+    /// With a JWT access token obtained by logging in.
+    AccessToken(JWTClaims),
+
+    /// With a personal API token.
+    ApiToken(ApiTokenDetails),
+}
+
+
+/// An authenticated user with a valid JWT token or personal API token.
 pub struct AuthenticatedUser {
-    token: JWTClaims,
+    method: AuthenticationMethod,
 }
 
 impl AuthenticatedUser {
     /// Returns the date and time the user's access token was created,
     /// i.e. when the user logged in.
+    ///
+    /// Returns `None` if the user authenticated with a personal API token.
     #[allow(dead_code)]
-    pub fn logged_in_at(&self) -> &DateTime<Utc> {
-        &self.token.iat
+    pub fn logged_in_at(&self) -> Option<&DateTime<Utc>> {
+        match &self.method {
+            AuthenticationMethod::AccessToken(token) => Some(&token.iat),
+            AuthenticationMethod::ApiToken(_) => None,
+        }
     }
 
     /// Returns the date and time the user's access token will expire.
+    ///
+    /// Returns `None` if the user authenticated with a personal API token.
     #[allow(dead_code)]
-    pub fn login_expires_at(&self) -> &DateTime<Utc> {
-        &self.token.exp
+    pub fn login_expires_at(&self) -> Option<&DateTime<Utc>> {
+        match &self.method {
+            AuthenticationMethod::AccessToken(token) => Some(&token.exp),
+            AuthenticationMethod::ApiToken(_) => None,
+        }
     }
 
     /// Returns the ID of the user who owns the token.
     pub fn user_id(&self) -> i32 {
-        self.token.user_id
+        match &self.method {
+            AuthenticationMethod::AccessToken(token) => token.user_id,
+            AuthenticationMethod::ApiToken(token) => token.user_id,
+        }
+    }
+
+    /// Returns the ID of the login session the token belongs to.
+    ///
+    /// Returns `None` if the user authenticated with a personal API token,
+    /// as those are not tied to a login session.
+    pub fn session_id(&self) -> Option<Uuid> {
+        match &self.method {
+            AuthenticationMethod::AccessToken(token) => Some(token.jti),
+            AuthenticationMethod::ApiToken(_) => None,
+        }
+    }
+
+    /// Returns the ID of the personal API token the user authenticated with, if any.
+    pub fn api_token_id(&self) -> Option<Uuid> {
+        match &self.method {
+            AuthenticationMethod::AccessToken(_) => None,
+            AuthenticationMethod::ApiToken(token) => Some(token.token_id),
+        }
     }
 
     /// Returns a list of permissions this user effectively has.
@@ -164,21 +509,32 @@ impl AuthenticatedUser {
     /// for each role the user has (since standalone permissions don't exist,
     /// only in combination with roles).
     ///
+    /// If the user authenticated with a personal API token, the permissions
+    /// are additionally limited to the token's scope.
+    ///
     /// This operation performs a database lookup.
     ///
     /// Prefer using [`Self::has_permission`] if you'll be checking for a single permission,
     /// and this method if you're checking for multiple or doing advanced permission logic.
     pub async fn permissions<C: ConnectionTrait>(&self, database: &C) -> Result<PermissionSet> {
         let permission_set =
-            UserRoleQuery::effective_user_permissions_from_user_id(database, self.token.user_id)
+            UserRoleQuery::effective_user_permissions_from_user_id(database, self.user_id())
                 .await
                 .wrap_err("Could not query effective permissions for user.")?;
 
-        Ok(permission_set)
+        match &self.method {
+            AuthenticationMethod::AccessToken(_) => Ok(permission_set),
+            AuthenticationMethod::ApiToken(token) => {
+                Ok(permission_set.intersection(&token.permission_scope))
+            }
+        }
     }
 
     /// Returns a boolean indicating whether the authenticated user has the provided permission.
     ///
+    /// If the user authenticated with a personal API token, the permission
+    /// must also be in the token's scope.
+    ///
     /// This operation performs a database lookup.
     pub async fn has_permission<C: ConnectionTrait>(
         &self,
@@ -189,7 +545,13 @@ impl AuthenticatedUser {
             return Ok(true);
         }
 
-        UserRoleQuery::user_has_permission(database, self.token.user_id, permission)
+        if let AuthenticationMethod::ApiToken(token) = &self.method {
+            if !token.permission_scope.has_permission(permission) {
+                return Ok(false);
+            }
+        }
+
+        UserRoleQuery::user_has_permission(database, self.user_id(), permission)
             .await
             .wrap_err("Could not query whether the user has a specific permission.")
     }
@@ -198,7 +560,7 @@ impl AuthenticatedUser {
     ///
     /// This operation performs a database lookup.
     pub async fn roles<C: ConnectionTrait>(&self, database: &C) -> Result<RoleSet> {
-        let role_set = UserRoleQuery::user_roles(database, self.token.user_id)
+        let role_set = UserRoleQuery::user_roles(database, self.user_id())
             .await
             .wrap_err("Could not query roles for user.")?;
 
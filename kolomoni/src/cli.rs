@@ -2,7 +2,9 @@
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+
+use crate::dictionary_import::DictionaryImportFormat;
 
 
 /// Server command-line arguments.
@@ -22,4 +24,47 @@ pub struct CLIArgs {
         help = "Path to the configuration file to use. Defaults to ./data/configuration.toml"
     )]
     pub configuration_file_path: Option<PathBuf>,
+
+    /// Command to run instead of the server (if any).
+    #[command(subcommand)]
+    pub command: Option<CLICommand>,
+}
+
+
+/// Commands that can be run instead of starting the server.
+#[derive(Subcommand)]
+pub enum CLICommand {
+    /// Imports dictionary entries from a CSV, TSV or JSON file
+    /// (see the [`dictionary_import`][crate::dictionary_import] module for the file structure).
+    ///
+    /// The search index of a running server is not updated,
+    /// it picks up the imported words the next time it is started.
+    #[command(
+        name = "import-dictionary",
+        about = "Import dictionary entries from a CSV, TSV or JSON file."
+    )]
+    ImportDictionary {
+        /// Path to the file to import.
+        #[arg(
+            short = 'i',
+            long = "inputFilePath",
+            help = "Path to the CSV, TSV or JSON file to import."
+        )]
+        input_file_path: PathBuf,
+
+        /// Format of the file. If unspecified, this is inferred from the file extension.
+        #[arg(
+            short = 'f',
+            long = "format",
+            help = "Format of the file to import. Defaults to the one matching the file extension."
+        )]
+        format: Option<DictionaryImportFormat>,
+
+        /// If set, only reports what the import would do, without changing anything.
+        #[arg(
+            long = "dryRun",
+            help = "Only report what the import would do, without changing anything."
+        )]
+        dry_run: bool,
+    },
 }
@@ -0,0 +1,547 @@
+//! Bulk dictionary import from CSV, TSV and JSON files.
+//!
+//! Each row (or JSON object) of an import file describes one english word:
+//!
+//! | Column / field         | Required | Contents                                          |
+//! |------------------------|----------|---------------------------------------------------|
+//! | `english_lemma`        | yes      | Lemma of the english word.                        |
+//! | `disambiguation`       | no       | Disambiguation of the english word.               |
+//! | `description`          | no       | Description of the english word.                  |
+//! | `slovene_translations` | no       | Lemmas of slovene translations.                   |
+//! | `categories`           | no       | English or slovene names of existing categories.  |
+//!
+//! CSV and TSV files must start with a header row naming the columns.
+//! In them, multiple translations or categories are separated with `;`,
+//! and a `;` or `\` that is part of a value is escaped with a backslash (`\;` or `\\`).
+//! JSON files contain an array of objects with the fields above,
+//! where translations and categories are arrays of strings.
+//!
+//! The import itself is performed by [`DictionaryImportMutation`].
+//! TBX glossaries are imported through the [`tbx`] submodule instead.
+
+use std::{collections::HashSet, path::Path};
+
+use clap::ValueEnum;
+use kolomoni_database::mutation::{
+    DictionaryImportMatching,
+    DictionaryImportMutation,
+    DictionaryImportRow,
+    DictionaryImportRowOutcome,
+};
+use miette::Result;
+use sea_orm::{prelude::Uuid, ConnectionTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+pub mod tbx;
+
+
+/// Format of a dictionary import file.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DictionaryImportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+
+    /// Tab-separated values with a header row.
+    Tsv,
+
+    /// An array of JSON objects.
+    Json,
+}
+
+impl DictionaryImportFormat {
+    /// Guesses the format from a file extension (`.csv`, `.tsv` or `.json`).
+    pub fn from_file_path(file_path: &Path) -> Option<Self> {
+        let extension = file_path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" | "tab" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+
+/// The import file as a whole could not be read
+/// (as opposed to only some of its rows being invalid).
+#[derive(Error, Debug)]
+#[error("{reason}")]
+pub struct InvalidImportFileError {
+    pub reason: String,
+}
+
+impl InvalidImportFileError {
+    fn new<S: Into<String>>(reason: S) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+
+/// A single row of an import file, either parsed or not.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ParsedImportRow {
+    Valid(DictionaryImportRow),
+    Invalid {
+        english_lemma: Option<String>,
+        error: String,
+    },
+}
+
+
+const DELIMITED_FILE_COLUMNS: [&str; 5] = [
+    "english_lemma",
+    "disambiguation",
+    "description",
+    "slovene_translations",
+    "categories",
+];
+
+/// Separator of multiple values in a single CSV or TSV field.
+const DELIMITED_FILE_VALUE_SEPARATOR: char = ';';
+
+/// Escapes [`DELIMITED_FILE_VALUE_SEPARATOR`] inside a single value of a CSV or TSV field.
+const DELIMITED_FILE_ESCAPE_CHARACTER: char = '\\';
+
+
+#[derive(Deserialize)]
+struct DelimitedImportRecord {
+    english_lemma: Option<String>,
+
+    #[serde(default)]
+    disambiguation: Option<String>,
+
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    slovene_translations: Option<String>,
+
+    #[serde(default)]
+    categories: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonImportRecord {
+    english_lemma: String,
+
+    #[serde(default)]
+    disambiguation: Option<String>,
+
+    #[serde(default)]
+    description: Option<String>,
+
+    #[serde(default)]
+    slovene_translations: Vec<String>,
+
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+
+/// Parses the contents of an import file into rows.
+///
+/// Rows that can't be parsed are returned as [`ParsedImportRow::Invalid`],
+/// while an error is only returned if the file can't be read at all.
+pub fn parse_import_file(
+    format: DictionaryImportFormat,
+    content: &str,
+) -> Result<Vec<ParsedImportRow>, InvalidImportFileError> {
+    match format {
+        DictionaryImportFormat::Csv => parse_delimited_import_file(content, b','),
+        DictionaryImportFormat::Tsv => parse_delimited_import_file(content, b'\t'),
+        DictionaryImportFormat::Json => parse_json_import_file(content),
+    }
+}
+
+fn parse_delimited_import_file(
+    content: &str,
+    delimiter: u8,
+) -> Result<Vec<ParsedImportRow>, InvalidImportFileError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|error| InvalidImportFileError::new(format!("Invalid header row: {}", error)))?;
+
+    if let Some(unknown_column) = headers
+        .iter()
+        .find(|column| !DELIMITED_FILE_COLUMNS.contains(column))
+    {
+        return Err(InvalidImportFileError::new(format!(
+            "Unknown column: \"{}\".",
+            unknown_column
+        )));
+    }
+
+    if !headers.iter().any(|column| column == "english_lemma") {
+        return Err(InvalidImportFileError::new(
+            "Missing the english_lemma column.",
+        ));
+    }
+
+
+    let parsed_rows = reader
+        .deserialize::<DelimitedImportRecord>()
+        .map(|record| match record {
+            Ok(record) => finalize_row(
+                record.english_lemma,
+                record.disambiguation,
+                record.description,
+                split_delimited_field(record.slovene_translations),
+                split_delimited_field(record.categories),
+            ),
+            Err(error) => ParsedImportRow::Invalid {
+                english_lemma: None,
+                error: error.to_string(),
+            },
+        })
+        .collect();
+
+    Ok(parsed_rows)
+}
+
+fn split_delimited_field(field: Option<String>) -> Vec<String> {
+    let Some(field) = field else {
+        return Vec::new();
+    };
+
+    let mut values = Vec::new();
+    let mut current_value = String::new();
+
+    let mut characters = field.chars().peekable();
+    while let Some(character) = characters.next() {
+        match character {
+            DELIMITED_FILE_ESCAPE_CHARACTER => {
+                let escaped_character = characters.next_if(|next_character| {
+                    *next_character == DELIMITED_FILE_VALUE_SEPARATOR
+                        || *next_character == DELIMITED_FILE_ESCAPE_CHARACTER
+                });
+
+                // A backslash that doesn't escape anything is kept as is.
+                current_value.push(escaped_character.unwrap_or(character));
+            }
+            DELIMITED_FILE_VALUE_SEPARATOR => values.push(std::mem::take(&mut current_value)),
+            _ => current_value.push(character),
+        }
+    }
+
+    values.push(current_value);
+    values
+}
+
+/// Joins multiple values into a single CSV or TSV field, escaping them
+/// so that [`parse_import_file`] splits them back into the same values.
+pub fn join_delimited_field<I, S>(values: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut field = String::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        if index > 0 {
+            field.push(DELIMITED_FILE_VALUE_SEPARATOR);
+        }
+
+        for character in value.as_ref().chars() {
+            if character == DELIMITED_FILE_VALUE_SEPARATOR
+                || character == DELIMITED_FILE_ESCAPE_CHARACTER
+            {
+                field.push(DELIMITED_FILE_ESCAPE_CHARACTER);
+            }
+
+            field.push(character);
+        }
+    }
+
+    field
+}
+
+fn parse_json_import_file(content: &str) -> Result<Vec<ParsedImportRow>, InvalidImportFileError> {
+    let values = serde_json::from_str::<Vec<serde_json::Value>>(content).map_err(|error| {
+        InvalidImportFileError::new(format!(
+            "Expected a JSON array of objects: {}",
+            error
+        ))
+    })?;
+
+    let parsed_rows = values
+        .into_iter()
+        .map(|value| {
+            let english_lemma = value
+                .get("english_lemma")
+                .and_then(|lemma| lemma.as_str())
+                .map(str::to_string);
+
+            match serde_json::from_value::<JsonImportRecord>(value) {
+                Ok(record) => finalize_row(
+                    Some(record.english_lemma),
+                    record.disambiguation,
+                    record.description,
+                    record.slovene_translations,
+                    record.categories,
+                ),
+                Err(error) => ParsedImportRow::Invalid {
+                    english_lemma,
+                    error: error.to_string(),
+                },
+            }
+        })
+        .collect();
+
+    Ok(parsed_rows)
+}
+
+/// Trims all values, treats empty values as missing and removes duplicate
+/// translations and categories.
+fn finalize_row(
+    english_lemma: Option<String>,
+    disambiguation: Option<String>,
+    description: Option<String>,
+    slovene_lemmas: Vec<String>,
+    category_names: Vec<String>,
+) -> ParsedImportRow {
+    fn non_empty(value: Option<String>) -> Option<String> {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn non_empty_unique(values: Vec<String>) -> Vec<String> {
+        let mut seen_values = HashSet::new();
+
+        values
+            .into_iter()
+            .filter_map(|value| non_empty(Some(value)))
+            .filter(|value| seen_values.insert(value.clone()))
+            .collect()
+    }
+
+
+    let Some(english_lemma) = non_empty(english_lemma) else {
+        return ParsedImportRow::Invalid {
+            english_lemma: None,
+            error: "The english lemma is missing.".to_string(),
+        };
+    };
+
+    ParsedImportRow::Valid(DictionaryImportRow {
+        english_lemma,
+        english_disambiguation: non_empty(disambiguation),
+        english_description: non_empty(description),
+        slovene_lemmas: non_empty_unique(slovene_lemmas),
+        category_names: non_empty_unique(category_names),
+    })
+}
+
+
+
+/// Status of a single imported row.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DictionaryImportRowStatus {
+    /// A new english word has been created.
+    New,
+
+    /// An existing english word has received new translations or categories.
+    Updated,
+
+    /// An existing english word already matched the row entirely.
+    Unchanged,
+
+    /// An existing english word differs from the row; the row has been skipped.
+    Conflicting,
+
+    /// The row is invalid; the import can not be committed.
+    Invalid,
+}
+
+
+/// Report about a single imported row.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct DictionaryImportRowReport {
+    /// Row number, starting with 1 (not counting the header row of CSV and TSV files).
+    pub row: usize,
+
+    pub english_lemma: Option<String>,
+
+    pub status: DictionaryImportRowStatus,
+
+    /// ID of the new or existing english word.
+    pub english_word_id: Option<String>,
+
+    /// For conflicting rows: fields whose values differ from the existing english word.
+    pub conflicting_fields: Vec<String>,
+
+    /// For invalid rows: reasons why the row can't be imported.
+    pub errors: Vec<String>,
+}
+
+
+/// Number of rows with each status.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug, Default, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct DictionaryImportSummary {
+    pub new: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub conflicting: usize,
+    pub invalid: usize,
+}
+
+
+/// Report of a (potentially dry-run) dictionary import.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct DictionaryImportReport {
+    pub dry_run: bool,
+
+    /// Whether the import has been written to the database. This is never the case
+    /// on dry runs, and also not when any of the rows are invalid.
+    pub committed: bool,
+
+    pub summary: DictionaryImportSummary,
+
+    pub rows: Vec<DictionaryImportRowReport>,
+}
+
+
+/// A finished dictionary import: the report and
+/// the words that need to be refreshed in the search index.
+pub struct CompletedDictionaryImport {
+    pub report: DictionaryImportReport,
+    pub changed_english_word_ids: Vec<Uuid>,
+    pub changed_slovene_word_ids: Vec<Uuid>,
+}
+
+
+/// Imports the parsed rows into the dictionary (see [`DictionaryImportMutation::import`]).
+///
+/// Rows that could not be parsed are reported as invalid and,
+/// like any other invalid row, prevent the import from being committed.
+/// New words are recorded as authored by `author_user_id`, if known.
+pub async fn import_parsed_rows<C: ConnectionTrait + TransactionTrait>(
+    database: &C,
+    parsed_rows: Vec<ParsedImportRow>,
+    author_user_id: Option<i32>,
+    dry_run: bool,
+) -> Result<CompletedDictionaryImport> {
+    let has_unparsable_rows = parsed_rows
+        .iter()
+        .any(|row| matches!(row, ParsedImportRow::Invalid { .. }));
+
+    let valid_rows = parsed_rows
+        .iter()
+        .filter_map(|row| match row {
+            ParsedImportRow::Valid(row) => Some(row.clone()),
+            ParsedImportRow::Invalid { .. } => None,
+        })
+        .collect();
+
+    let import_result = DictionaryImportMutation::import(
+        database,
+        valid_rows,
+        DictionaryImportMatching::Lemma,
+        author_user_id,
+        dry_run || has_unparsable_rows,
+    )
+    .await?;
+
+
+    let mut summary = DictionaryImportSummary::default();
+    let mut row_reports = Vec::with_capacity(parsed_rows.len());
+    let mut valid_row_outcomes = import_result.row_outcomes.into_iter();
+
+    for (row_index, parsed_row) in parsed_rows.into_iter().enumerate() {
+        let (english_lemma, outcome) = match parsed_row {
+            ParsedImportRow::Valid(row) => (
+                Some(row.english_lemma),
+                // PANIC SAFETY: The import returns exactly one outcome per valid row.
+                valid_row_outcomes.next().unwrap(),
+            ),
+            ParsedImportRow::Invalid {
+                english_lemma,
+                error,
+            } => (
+                english_lemma,
+                DictionaryImportRowOutcome::Invalid {
+                    errors: vec![error],
+                },
+            ),
+        };
+
+        let (status, english_word_id, conflicting_fields, errors) = match outcome {
+            DictionaryImportRowOutcome::New { english_word_id } => {
+                summary.new += 1;
+                (DictionaryImportRowStatus::New, Some(english_word_id), Vec::new(), Vec::new())
+            }
+            DictionaryImportRowOutcome::Updated { english_word_id } => {
+                summary.updated += 1;
+                (DictionaryImportRowStatus::Updated, Some(english_word_id), Vec::new(), Vec::new())
+            }
+            DictionaryImportRowOutcome::Unchanged { english_word_id } => {
+                summary.unchanged += 1;
+                (DictionaryImportRowStatus::Unchanged, Some(english_word_id), Vec::new(), Vec::new())
+            }
+            DictionaryImportRowOutcome::Conflicting {
+                english_word_id,
+                conflicting_fields,
+            } => {
+                summary.conflicting += 1;
+                (
+                    DictionaryImportRowStatus::Conflicting,
+                    Some(english_word_id),
+                    conflicting_fields,
+                    Vec::new(),
+                )
+            }
+            DictionaryImportRowOutcome::Invalid { errors } => {
+                summary.invalid += 1;
+                (DictionaryImportRowStatus::Invalid, None, Vec::new(), errors)
+            }
+        };
+
+        row_reports.push(DictionaryImportRowReport {
+            row: row_index + 1,
+            english_lemma,
+            status,
+            english_word_id: english_word_id.map(|word_id| word_id.to_string()),
+            conflicting_fields: conflicting_fields
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            errors,
+        });
+    }
+
+
+    let (changed_english_word_ids, changed_slovene_word_ids) = match import_result.committed {
+        true => (
+            import_result.changed_english_word_ids,
+            import_result.changed_slovene_word_ids,
+        ),
+        false => (Vec::new(), Vec::new()),
+    };
+
+    Ok(CompletedDictionaryImport {
+        report: DictionaryImportReport {
+            dry_run,
+            committed: import_result.committed,
+            summary,
+            rows: row_reports,
+        },
+        changed_english_word_ids,
+        changed_slovene_word_ids,
+    })
+}
@@ -0,0 +1,727 @@
+//! Import of TBX (TermBase eXchange) glossaries.
+//!
+//! Both TBX-Basic (`martif`, `termEntry`, `langSet`, `tig` / `ntig`) and TBX v3
+//! (`tbx`, `conceptEntry`, `langSec`, `termSec`) files are understood.
+//! Each concept entry becomes one dictionary entry:
+//!
+//! | TBX                                                       | Dictionary                   |
+//! |-----------------------------------------------------------|------------------------------|
+//! | English term (`xml:lang="en"`)                            | english word                 |
+//! | `note` of the English term                                | disambiguation               |
+//! | `descrip type="definition"` (on the term, language or entry) | description               |
+//! | Slovene terms (`xml:lang="sl"`)                           | translations                 |
+//! | `descrip type="subjectField"`                             | categories                   |
+//!
+//! This is the same layout the TBX export produces, which means exported glossaries
+//! can be imported back.
+//!
+//! Entries are matched to existing english words by lemma and disambiguation
+//! (see [`DictionaryImportMatching::LemmaAndDisambiguation`]), and entries of the same file
+//! that describe the same english word are merged together. An entry whose term matches
+//! an existing word, but whose note doesn't, creates a new homonym. Unlike with the other import formats,
+//! entries that can't be imported are skipped instead of failing the entire import:
+//! the import report lists which entries have been skipped or merged, and why.
+
+use std::collections::{HashMap, HashSet};
+
+use kolomoni_database::{
+    mutation::{
+        DictionaryImportMatching,
+        DictionaryImportMutation,
+        DictionaryImportRow,
+        DictionaryImportRowOutcome,
+    },
+    query::{CategoriesQueryOptions, CategoryQuery},
+};
+use miette::{Context, Result};
+use sea_orm::{prelude::Uuid, ConnectionTrait, TransactionTrait};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use xml::{attribute::OwnedAttribute, reader::XmlEvent, EventReader};
+
+use super::InvalidImportFileError;
+
+
+/// Administrative statuses of terms that should not be imported.
+const IGNORED_ADMINISTRATIVE_STATUSES: [&str; 2] =
+    ["deprecatedTerm-admn-sts", "supersededTerm-admn-sts"];
+
+const PREFERRED_ADMINISTRATIVE_STATUS: &str = "preferredTerm-admn-sts";
+
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+struct TbxTerm {
+    term: String,
+    note: Option<String>,
+    definition: Option<String>,
+    administrative_status: Option<String>,
+}
+
+impl TbxTerm {
+    fn is_ignored(&self) -> bool {
+        self.administrative_status
+            .as_deref()
+            .map(|status| IGNORED_ADMINISTRATIVE_STATUSES.contains(&status))
+            .unwrap_or(false)
+    }
+
+    fn is_preferred(&self) -> bool {
+        self.administrative_status.as_deref() == Some(PREFERRED_ADMINISTRATIVE_STATUS)
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum TbxLanguage {
+    English,
+    Slovene,
+    Other,
+}
+
+impl TbxLanguage {
+    fn from_language_tag(language_tag: &str) -> Self {
+        let primary_subtag = language_tag
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match primary_subtag.as_str() {
+            "en" | "eng" => Self::English,
+            "sl" | "slv" => Self::Slovene,
+            _ => Self::Other,
+        }
+    }
+}
+
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct TbxLanguageSection {
+    language: TbxLanguage,
+    definition: Option<String>,
+    terms: Vec<TbxTerm>,
+}
+
+
+/// A single concept entry (`termEntry` or `conceptEntry`) of a TBX file.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct TbxConceptEntry {
+    id: Option<String>,
+    subject_fields: Vec<String>,
+    definition: Option<String>,
+    language_sections: Vec<TbxLanguageSection>,
+}
+
+impl TbxConceptEntry {
+    fn terms_in_language(&self, language: TbxLanguage) -> impl Iterator<Item = &TbxTerm> {
+        self.language_sections
+            .iter()
+            .filter(move |section| section.language == language)
+            .flat_map(|section| section.terms.iter())
+    }
+
+    /// Returns the definition of the given term (in any language), falling back to
+    /// the definition of its language section and then to that of the entire entry.
+    pub fn term_definition(&self, term: &str) -> Option<&str> {
+        let (section, tbx_term) = self.language_sections.iter().find_map(|section| {
+            section
+                .terms
+                .iter()
+                .find(|tbx_term| tbx_term.term == term)
+                .map(|tbx_term| (section, tbx_term))
+        })?;
+
+        tbx_term
+            .definition
+            .as_deref()
+            .or(section.definition.as_deref())
+            .or(self.definition.as_deref())
+    }
+}
+
+
+/// An element whose text content is being collected.
+struct TextElement {
+    kind: TextElementKind,
+
+    /// Depth of the element, used to find its end tag
+    /// (inline markup inside it is flattened into text).
+    depth: usize,
+
+    text: String,
+}
+
+enum TextElementKind {
+    Term,
+    Note,
+    TermNote { note_type: Option<String> },
+    Descrip { descrip_type: Option<String> },
+}
+
+
+fn attribute_value<'a>(
+    attributes: &'a [OwnedAttribute],
+    prefix: Option<&str>,
+    local_name: &str,
+) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| {
+            attribute.name.local_name == local_name && attribute.name.prefix.as_deref() == prefix
+        })
+        .map(|attribute| attribute.value.as_str())
+}
+
+/// Trims the text and collapses any inner whitespace (e.g. line breaks) into single spaces.
+fn normalize_text(text: &str) -> Option<String> {
+    let normalized_text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    match normalized_text.is_empty() {
+        true => None,
+        false => Some(normalized_text),
+    }
+}
+
+
+/// Parses the concept entries of a TBX file.
+///
+/// Elements that aren't relevant to the dictionary are ignored, while an error
+/// is returned if the file isn't well-formed XML or doesn't have a TBX root element.
+pub fn parse_tbx_file(content: &str) -> Result<Vec<TbxConceptEntry>, InvalidImportFileError> {
+    let mut entries = Vec::new();
+
+    let mut current_entry: Option<TbxConceptEntry> = None;
+    let mut current_language_section: Option<TbxLanguageSection> = None;
+    let mut current_term: Option<TbxTerm> = None;
+    let mut current_text_element: Option<TextElement> = None;
+
+    let mut depth: usize = 0;
+
+    for event in EventReader::from_str(content) {
+        let event = event
+            .map_err(|error| InvalidImportFileError::new(format!("Invalid XML: {}", error)))?;
+
+        match event {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                depth += 1;
+
+                if depth == 1 && name.local_name != "martif" && name.local_name != "tbx" {
+                    return Err(InvalidImportFileError::new(
+                        "Not a TBX file (the root element must be martif or tbx).",
+                    ));
+                }
+
+                if current_text_element.is_some() {
+                    continue;
+                }
+
+                let text_element_kind = match name.local_name.as_str() {
+                    "termEntry" | "conceptEntry" => {
+                        current_entry = Some(TbxConceptEntry {
+                            id: attribute_value(&attributes, None, "id").map(str::to_string),
+                            ..Default::default()
+                        });
+
+                        None
+                    }
+                    "langSet" | "langSec" if current_entry.is_some() => {
+                        let language = attribute_value(&attributes, Some("xml"), "lang")
+                            .map(TbxLanguage::from_language_tag)
+                            .unwrap_or(TbxLanguage::Other);
+
+                        current_language_section = Some(TbxLanguageSection {
+                            language,
+                            definition: None,
+                            terms: Vec::new(),
+                        });
+
+                        None
+                    }
+                    "tig" | "ntig" | "termSec" if current_language_section.is_some() => {
+                        current_term = Some(TbxTerm::default());
+
+                        None
+                    }
+                    "term" if current_term.is_some() => Some(TextElementKind::Term),
+                    "note" if current_term.is_some() => Some(TextElementKind::Note),
+                    "termNote" if current_term.is_some() => Some(TextElementKind::TermNote {
+                        note_type: attribute_value(&attributes, None, "type").map(str::to_string),
+                    }),
+                    "descrip" if current_entry.is_some() => Some(TextElementKind::Descrip {
+                        descrip_type: attribute_value(&attributes, None, "type")
+                            .map(str::to_string),
+                    }),
+                    _ => None,
+                };
+
+                if let Some(kind) = text_element_kind {
+                    current_text_element = Some(TextElement {
+                        kind,
+                        depth,
+                        text: String::new(),
+                    });
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::CData(text) | XmlEvent::Whitespace(text) => {
+                if let Some(text_element) = current_text_element.as_mut() {
+                    text_element.text.push_str(&text);
+                }
+            }
+            XmlEvent::EndElement { name } => {
+                let ends_text_element = current_text_element
+                    .as_ref()
+                    .map(|text_element| text_element.depth == depth)
+                    .unwrap_or(false);
+
+                depth -= 1;
+
+                if ends_text_element {
+                    // PANIC SAFETY: We just checked that the text element exists.
+                    let text_element = current_text_element.take().unwrap();
+
+                    let Some(text) = normalize_text(&text_element.text) else {
+                        continue;
+                    };
+
+                    match text_element.kind {
+                        TextElementKind::Term => {
+                            if let Some(term) = current_term.as_mut() {
+                                term.term = text;
+                            }
+                        }
+                        TextElementKind::Note => {
+                            if let Some(term) = current_term.as_mut() {
+                                term.note.get_or_insert(text);
+                            }
+                        }
+                        TextElementKind::TermNote { note_type } => {
+                            if note_type.as_deref() == Some("administrativeStatus") {
+                                if let Some(term) = current_term.as_mut() {
+                                    term.administrative_status = Some(text);
+                                }
+                            }
+                        }
+                        TextElementKind::Descrip { descrip_type } => {
+                            match descrip_type.as_deref() {
+                                Some("subjectField") => {
+                                    if let Some(entry) = current_entry.as_mut() {
+                                        entry.subject_fields.push(text);
+                                    }
+                                }
+                                Some("definition") => {
+                                    // The definition belongs to the innermost open element.
+                                    let definition = if let Some(term) = current_term.as_mut() {
+                                        &mut term.definition
+                                    } else if let Some(section) = current_language_section.as_mut()
+                                    {
+                                        &mut section.definition
+                                    } else if let Some(entry) = current_entry.as_mut() {
+                                        &mut entry.definition
+                                    } else {
+                                        continue;
+                                    };
+
+                                    definition.get_or_insert(text);
+                                }
+                                _ => {}
+                            }
+                        }
+                    }
+
+                    continue;
+                }
+
+
+                match name.local_name.as_str() {
+                    "tig" | "ntig" | "termSec" => {
+                        if let (Some(term), Some(section)) =
+                            (current_term.take(), current_language_section.as_mut())
+                        {
+                            if !term.term.is_empty() {
+                                section.terms.push(term);
+                            }
+                        }
+                    }
+                    "langSet" | "langSec" => {
+                        if let (Some(section), Some(entry)) =
+                            (current_language_section.take(), current_entry.as_mut())
+                        {
+                            entry.language_sections.push(section);
+                        }
+                    }
+                    "termEntry" | "conceptEntry" => {
+                        if let Some(entry) = current_entry.take() {
+                            entries.push(entry);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::EndDocument => break,
+            _ => {}
+        }
+    }
+
+    Ok(entries)
+}
+
+
+
+/// Status of a single imported TBX entry.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TbxImportEntryStatus {
+    /// A new english word has been created.
+    Created,
+
+    /// The entry has been merged into an existing english word (or into an english word
+    /// created by an earlier entry of the same file). Translations and categories
+    /// the word didn't have yet have been added to it.
+    Merged,
+
+    /// The entry has been skipped.
+    Skipped,
+}
+
+
+/// Report about a single entry of an imported TBX file.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct TbxImportEntryReport {
+    /// Position of the entry in the file, starting with 1.
+    pub entry: usize,
+
+    /// The `id` attribute of the entry, if it has one.
+    pub entry_id: Option<String>,
+
+    pub english_lemma: Option<String>,
+
+    pub english_disambiguation: Option<String>,
+
+    pub status: TbxImportEntryStatus,
+
+    /// ID of the created or merged-into english word.
+    pub english_word_id: Option<String>,
+
+    /// Subject fields that don't match the english or slovene name
+    /// of any existing category and have been ignored.
+    pub ignored_subject_fields: Vec<String>,
+
+    /// Why the entry has been skipped or merged, and which parts of it have been ignored.
+    pub messages: Vec<String>,
+}
+
+
+/// Number of entries with each status.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug, Default, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct TbxImportSummary {
+    pub created: usize,
+    pub merged: usize,
+    pub skipped: usize,
+}
+
+
+/// Report of a TBX glossary import (or its preview).
+#[derive(Serialize, Clone, PartialEq, Eq, Debug, ToSchema)]
+#[cfg_attr(feature = "with_test_facilities", derive(Deserialize))]
+pub struct TbxImportReport {
+    pub preview: bool,
+
+    /// Whether the import has been written to the database (never the case for previews).
+    pub committed: bool,
+
+    pub summary: TbxImportSummary,
+
+    pub entries: Vec<TbxImportEntryReport>,
+}
+
+
+/// A finished TBX glossary import: the report and
+/// the words that need to be refreshed in the search index.
+pub struct CompletedTbxImport {
+    pub report: TbxImportReport,
+    pub changed_english_word_ids: Vec<Uuid>,
+    pub changed_slovene_word_ids: Vec<Uuid>,
+}
+
+
+/// How a single entry is going to be imported.
+enum PlannedEntry {
+    /// The entry is imported as the row with the given index.
+    Row { row_index: usize },
+
+    /// The entry has been merged into the row with the given index
+    /// (an earlier entry of the file describes the same english word).
+    MergedIntoRow { row_index: usize },
+
+    Skipped,
+}
+
+
+/// Imports the concept entries of a TBX file into the dictionary.
+///
+/// If `preview` is `true`, the report describes what the import would do,
+/// but nothing is written to the database. New words are recorded
+/// as authored by `author_user_id`, if known.
+pub async fn import_tbx_entries<C: ConnectionTrait + TransactionTrait>(
+    database: &C,
+    entries: Vec<TbxConceptEntry>,
+    author_user_id: Option<i32>,
+    preview: bool,
+) -> Result<CompletedTbxImport> {
+    let categories = CategoryQuery::all(database, CategoriesQueryOptions::default())
+        .await
+        .wrap_err("Failed while loading categories for TBX import.")?;
+
+    let category_names = categories
+        .into_iter()
+        .flat_map(|category| [category.english_name, category.slovene_name])
+        .collect::<HashSet<_>>();
+
+
+    let mut rows: Vec<DictionaryImportRow> = Vec::new();
+    let mut first_entries_of_rows: Vec<usize> = Vec::new();
+    let mut row_indices_by_lemma_and_disambiguation: HashMap<(String, Option<String>), usize> =
+        HashMap::new();
+
+    let mut planned_entries = Vec::with_capacity(entries.len());
+    let mut entry_reports = Vec::with_capacity(entries.len());
+
+    for (entry_index, entry) in entries.into_iter().enumerate() {
+        let mut entry_report = TbxImportEntryReport {
+            entry: entry_index + 1,
+            entry_id: entry.id.clone(),
+            english_lemma: None,
+            english_disambiguation: None,
+            status: TbxImportEntryStatus::Skipped,
+            english_word_id: None,
+            ignored_subject_fields: Vec::new(),
+            messages: Vec::new(),
+        };
+
+
+        let english_terms = entry
+            .terms_in_language(TbxLanguage::English)
+            .filter(|term| !term.is_ignored())
+            .collect::<Vec<_>>();
+
+        let Some(english_term) = english_terms
+            .iter()
+            .find(|term| term.is_preferred())
+            .or_else(|| english_terms.first())
+            .copied()
+        else {
+            entry_report
+                .messages
+                .push("The entry has no English term.".to_string());
+
+            planned_entries.push(PlannedEntry::Skipped);
+            entry_reports.push(entry_report);
+            continue;
+        };
+
+        entry_report.english_lemma = Some(english_term.term.clone());
+        entry_report.english_disambiguation = english_term.note.clone();
+
+        let other_english_terms = english_terms
+            .iter()
+            .filter(|term| term.term != english_term.term)
+            .map(|term| format!("\"{}\"", term.term))
+            .collect::<Vec<_>>();
+
+        if !other_english_terms.is_empty() {
+            entry_report.messages.push(format!(
+                "Only one English term per entry is imported, ignored: {}.",
+                other_english_terms.join(", ")
+            ));
+        }
+
+
+        let english_description = english_term.definition.clone().or_else(|| {
+            entry
+                .language_sections
+                .iter()
+                .filter(|section| section.language == TbxLanguage::English)
+                .find_map(|section| section.definition.clone())
+                .or_else(|| entry.definition.clone())
+        });
+
+        let slovene_lemmas = entry
+            .terms_in_language(TbxLanguage::Slovene)
+            .filter(|term| !term.is_ignored())
+            .map(|term| term.term.clone())
+            .collect::<Vec<_>>();
+
+        let ignored_slovene_terms = entry
+            .terms_in_language(TbxLanguage::Slovene)
+            .filter(|term| term.is_ignored())
+            .map(|term| format!("\"{}\"", term.term))
+            .collect::<Vec<_>>();
+
+        if !ignored_slovene_terms.is_empty() {
+            entry_report.messages.push(format!(
+                "Deprecated Slovene terms are not imported, ignored: {}.",
+                ignored_slovene_terms.join(", ")
+            ));
+        }
+
+        let mut known_subject_fields = Vec::with_capacity(entry.subject_fields.len());
+        for subject_field in &entry.subject_fields {
+            if category_names.contains(subject_field) {
+                known_subject_fields.push(subject_field.clone());
+            } else {
+                entry_report
+                    .ignored_subject_fields
+                    .push(subject_field.clone());
+            }
+        }
+
+
+        let lemma_and_disambiguation = (english_term.term.clone(), english_term.note.clone());
+        let existing_row_index = row_indices_by_lemma_and_disambiguation
+            .get(&lemma_and_disambiguation)
+            .copied();
+
+        match existing_row_index {
+            Some(row_index) => {
+                let row = &mut rows[row_index];
+
+                if row.english_description.is_none() {
+                    row.english_description = english_description;
+                }
+
+                for slovene_lemma in slovene_lemmas {
+                    if !row.slovene_lemmas.contains(&slovene_lemma) {
+                        row.slovene_lemmas.push(slovene_lemma);
+                    }
+                }
+
+                for category_name in known_subject_fields {
+                    if !row.category_names.contains(&category_name) {
+                        row.category_names.push(category_name);
+                    }
+                }
+
+                entry_report.messages.push(format!(
+                    "Merged with entry {} of the file, which describes the same English word.",
+                    first_entries_of_rows[row_index] + 1
+                ));
+
+                planned_entries.push(PlannedEntry::MergedIntoRow { row_index });
+            }
+            None => {
+                let mut unique_slovene_lemmas = HashSet::new();
+                let mut unique_category_names = HashSet::new();
+
+                row_indices_by_lemma_and_disambiguation.insert(lemma_and_disambiguation, rows.len());
+                planned_entries.push(PlannedEntry::Row {
+                    row_index: rows.len(),
+                });
+                first_entries_of_rows.push(entry_index);
+
+                rows.push(DictionaryImportRow {
+                    english_lemma: english_term.term.clone(),
+                    english_disambiguation: english_term.note.clone(),
+                    english_description,
+                    slovene_lemmas: slovene_lemmas
+                        .into_iter()
+                        .filter(|lemma| unique_slovene_lemmas.insert(lemma.clone()))
+                        .collect(),
+                    category_names: known_subject_fields
+                        .into_iter()
+                        .filter(|name| unique_category_names.insert(name.clone()))
+                        .collect(),
+                });
+            }
+        }
+
+        entry_reports.push(entry_report);
+    }
+
+
+    let import_result = DictionaryImportMutation::import(
+        database,
+        rows,
+        DictionaryImportMatching::LemmaAndDisambiguation,
+        author_user_id,
+        preview,
+    )
+    .await?;
+
+
+    let mut summary = TbxImportSummary::default();
+
+    for (entry_report, planned_entry) in entry_reports.iter_mut().zip(planned_entries) {
+        let (row_index, is_merged_into_row) = match planned_entry {
+            PlannedEntry::Row { row_index } => (row_index, false),
+            PlannedEntry::MergedIntoRow { row_index } => (row_index, true),
+            PlannedEntry::Skipped => {
+                summary.skipped += 1;
+                continue;
+            }
+        };
+
+        match &import_result.row_outcomes[row_index] {
+            DictionaryImportRowOutcome::New { english_word_id } => {
+                entry_report.english_word_id = Some(english_word_id.to_string());
+                entry_report.status = match is_merged_into_row {
+                    true => TbxImportEntryStatus::Merged,
+                    false => TbxImportEntryStatus::Created,
+                };
+            }
+            DictionaryImportRowOutcome::Updated { english_word_id }
+            | DictionaryImportRowOutcome::Unchanged { english_word_id } => {
+                entry_report.english_word_id = Some(english_word_id.to_string());
+                entry_report.status = TbxImportEntryStatus::Merged;
+
+                if !is_merged_into_row {
+                    entry_report
+                        .messages
+                        .push("Merged into the existing English word.".to_string());
+                }
+            }
+            DictionaryImportRowOutcome::Conflicting {
+                english_word_id, ..
+            } => {
+                entry_report.english_word_id = Some(english_word_id.to_string());
+                entry_report.status = TbxImportEntryStatus::Skipped;
+                entry_report
+                    .messages
+                    .push("The entry conflicts with an existing English word.".to_string());
+            }
+            DictionaryImportRowOutcome::Invalid { errors } => {
+                entry_report.status = TbxImportEntryStatus::Skipped;
+                entry_report.messages.extend(errors.iter().cloned());
+            }
+        }
+
+        match entry_report.status {
+            TbxImportEntryStatus::Created => summary.created += 1,
+            TbxImportEntryStatus::Merged => summary.merged += 1,
+            TbxImportEntryStatus::Skipped => summary.skipped += 1,
+        }
+    }
+
+
+    let (changed_english_word_ids, changed_slovene_word_ids) = match import_result.committed {
+        true => (
+            import_result.changed_english_word_ids,
+            import_result.changed_slovene_word_ids,
+        ),
+        false => (Vec::new(), Vec::new()),
+    };
+
+    Ok(CompletedTbxImport {
+        report: TbxImportReport {
+            preview,
+            committed: import_result.committed,
+            summary,
+            entries: entry_reports,
+        },
+        changed_english_word_ids,
+        changed_slovene_word_ids,
+    })
+}
@@ -45,6 +45,10 @@
 //! | |   > which you can then use when documenting endpoint functions with
 //! | |   > the `utoipa::path` macro.
 //! |
+//! |-> activity.rs
+//! |   > Keeps users' last activity times up to date: an Actix middleware that
+//! |   > records activity of authenticated requests and writes it to the database in batches.
+//! |
 //! |-> authentication.rs
 //! |   > Authentication-related code, namely an Actix extractor that
 //! |   > allows us to ergonomically check for roles and permissions.
@@ -52,12 +56,32 @@
 //! |-> cli.rs
 //! |   > Definition of the command-line interface.
 //! |
+//! |-> dictionary_import.rs
+//! |   > Parsing of CSV, TSV and JSON dictionary import files
+//! |   > (used by both the import endpoint and the import command).
+//! |
+//! |-| dictionary_import/
+//! | |
+//! | |-> tbx.rs
+//! | |   > Parsing and import of TBX glossaries.
+//! |
 //! |-> logging.rs
 //! |   > Sets up logging via the `tracing` crate.
 //! |
+//! |-> maintenance.rs
+//! |   > Periodic database maintenance running in the background
+//! |   > (e.g. removing expired role grants).
+//! |
 //! |-> state.rs
 //! |   > Houses the entire application state that is shared between workers.
 //! |   > It contains things like the current configuration and database connection.
+//! |
+//! |-> well_known.rs
+//! |   > Well-known URIs served outside of the API (e.g. `/.well-known/jwks.json`).
+//! |
+//! |-> word_references.rs
+//! |   > Wiki-style references to other words in word descriptions
+//! |   > (e.g. `[[en:adventurer]]`): parsing, validation and rendering to HTML.
 //! ```
 //!
 
@@ -70,11 +94,16 @@ use sea_orm_migration::MigrationStatus;
 use tracing::info;
 
 
+pub mod activity;
 pub mod api;
 pub mod authentication;
 pub mod cli;
+pub mod dictionary_import;
 pub mod logging;
+pub mod maintenance;
 pub mod state;
+pub mod well_known;
+pub mod word_references;
 
 #[cfg(feature = "with_test_facilities")]
 pub mod testing;
@@ -1,25 +1,39 @@
+use std::path::Path;
+
 use actix_web::error::JsonPayloadError;
 use actix_web::{web, HttpServer};
 use clap::Parser;
 use kolomoni::connect_and_set_up_database;
 use kolomoni_configuration::Configuration;
-use miette::{Context, IntoDiagnostic, Result};
+use miette::{miette, Context, IntoDiagnostic, Result};
 use tracing::info;
 
+mod activity;
 mod api;
 mod authentication;
 mod cli;
+mod dictionary_import;
 mod logging;
+mod maintenance;
 mod state;
+mod well_known;
 
 #[cfg(feature = "with_test_facilities")]
 mod testing;
 
+use crate::activity::UserActivityTracking;
 use crate::api::api_router;
 use crate::api::errors::APIError;
-use crate::cli::CLIArgs;
+use crate::cli::{CLIArgs, CLICommand};
+use crate::dictionary_import::{
+    import_parsed_rows,
+    parse_import_file,
+    DictionaryImportFormat,
+    DictionaryImportRowStatus,
+};
 use crate::logging::initialize_tracing;
 use crate::state::ApplicationStateInner;
+use crate::well_known::well_known_router;
 
 
 
@@ -72,6 +86,23 @@ async fn main() -> Result<()> {
     .wrap_err("Failed to initialize tracing.")?;
 
 
+    if let Some(command) = arguments.command {
+        match command {
+            CLICommand::ImportDictionary {
+                input_file_path,
+                format,
+                dry_run,
+            } => {
+                import_dictionary_from_file(&configuration, &input_file_path, format, dry_run)
+                    .await?;
+            }
+        }
+
+        drop(guard);
+        return Ok(());
+    }
+
+
     // TODO Introduce request rate-limiting.
 
     let mut state_inner = ApplicationStateInner::new(configuration.clone()).await?;
@@ -85,6 +116,9 @@ async fn main() -> Result<()> {
 
     let state = web::Data::new(state_inner);
 
+    // Kept outside of the server factory, so we can still access the state after the server stops.
+    let shutdown_state = state.clone();
+
 
     // Initialize and start the actix HTTP server.
     #[rustfmt::skip]
@@ -132,13 +166,15 @@ async fn main() -> Result<()> {
         ]);
 
         let mut app = actix_web::App::new()
+            .wrap(UserActivityTracking)
             .wrap(actix_web::middleware::Compress::default())
             .wrap(actix_web::middleware::NormalizePath::trim())
             .wrap(cors)
             .wrap(tracing_actix_web::TracingLogger::default())
             .app_data(json_extractor_config)
             .app_data(state.clone())
-            .service(api_router());
+            .service(api_router())
+            .service(well_known_router());
 
         #[cfg(feature = "with_test_facilities")]
         {
@@ -175,9 +211,13 @@ async fn main() -> Result<()> {
     );
 
     // Run HTTP server until stopped.
-    server
-        .run()
-        .await
+    let server_result = server.run().await;
+
+    // Write any activity that was recorded but not yet flushed,
+    // even if the server stopped because of an error.
+    shutdown_state.activity_tracker.flush().await;
+
+    server_result
         .into_diagnostic()
         .wrap_err("Errored while running actix HTTP server.")?;
 
@@ -186,3 +226,86 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+
+
+/// Runs the `import-dictionary` command: imports the given file into the dictionary
+/// and prints out a summary, along with any conflicting or invalid rows.
+async fn import_dictionary_from_file(
+    configuration: &Configuration,
+    input_file_path: &Path,
+    format: Option<DictionaryImportFormat>,
+    dry_run: bool,
+) -> Result<()> {
+    let Some(format) = format.or_else(|| DictionaryImportFormat::from_file_path(input_file_path))
+    else {
+        return Err(miette!(
+            "Could not infer the format of the import file from its extension, \
+            please specify it with --format."
+        ));
+    };
+
+    let file_contents = std::fs::read_to_string(input_file_path)
+        .into_diagnostic()
+        .wrap_err("Failed to read the import file.")?;
+
+    let parsed_rows = parse_import_file(format, &file_contents)
+        .into_diagnostic()
+        .wrap_err("Failed to parse the import file.")?;
+
+
+    let database = connect_and_set_up_database(configuration).await?;
+
+    // Words created from the command line have no author.
+    let report = import_parsed_rows(&database, parsed_rows, None, dry_run)
+        .await?
+        .report;
+
+
+    for row in &report.rows {
+        let english_lemma = row.english_lemma.as_deref().unwrap_or("(unknown)");
+
+        match row.status {
+            DictionaryImportRowStatus::Conflicting => println!(
+                "Row {} ({}): conflicts with the existing word, differing fields: {}.",
+                row.row,
+                english_lemma,
+                row.conflicting_fields.join(", ")
+            ),
+            DictionaryImportRowStatus::Invalid => println!(
+                "Row {} ({}): {}",
+                row.row,
+                english_lemma,
+                row.errors.join(" ")
+            ),
+            _ => {}
+        }
+    }
+
+    let summary = &report.summary;
+    println!(
+        "New: {}, updated: {}, unchanged: {}, conflicting: {}, invalid: {}.",
+        summary.new, summary.updated, summary.unchanged, summary.conflicting, summary.invalid
+    );
+
+
+    if report.committed {
+        info!(
+            new = summary.new,
+            updated = summary.updated,
+            unchanged = summary.unchanged,
+            conflicting = summary.conflicting,
+            "Imported dictionary entries from file."
+        );
+
+        println!("Import committed.");
+        Ok(())
+    } else if dry_run {
+        println!("Dry run, nothing has been imported.");
+        Ok(())
+    } else {
+        Err(miette!(
+            "Some rows are invalid, nothing has been imported."
+        ))
+    }
+}
@@ -0,0 +1,64 @@
+//! Periodic database maintenance performed in the background.
+//!
+//! Expired time-limited role grants already stop applying the moment they expire
+//! (see [`UserRoleQuery`][kolomoni_database::query::UserRoleQuery]),
+//! so pruning them is not time-critical and can run at a relaxed interval.
+
+use std::time::Duration;
+
+use kolomoni_database::mutation::UserRoleMutation;
+use sea_orm::DatabaseConnection;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+
+/// How often expired role grants are removed from the database.
+const EXPIRED_ROLE_GRANT_PRUNING_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+
+/// Handle to the background database maintenance task.
+///
+/// The task is aborted when this is dropped.
+pub struct DatabaseMaintenance {
+    task_handle: JoinHandle<()>,
+}
+
+impl DatabaseMaintenance {
+    /// Starts a background async task that periodically performs database maintenance.
+    pub fn start(database: DatabaseConnection) -> Self {
+        let task_handle = tokio::spawn(Self::maintenance_loop(database));
+
+        Self { task_handle }
+    }
+
+    /// The main maintenance loop. This task is spawned inside [`Self::start`].
+    async fn maintenance_loop(database: DatabaseConnection) {
+        let mut interval = tokio::time::interval(EXPIRED_ROLE_GRANT_PRUNING_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            match UserRoleMutation::delete_expired_role_grants(&database).await {
+                Ok(0) => {}
+                Ok(num_deleted_grants) => {
+                    info!(
+                        num_deleted_grants = num_deleted_grants,
+                        "Removed expired role grants."
+                    );
+                }
+                Err(error) => {
+                    error!(
+                        error = error.to_string(),
+                        "Failed to remove expired role grants."
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Drop for DatabaseMaintenance {
+    fn drop(&mut self) {
+        self.task_handle.abort();
+    }
+}
@@ -1,7 +1,7 @@
 //! Application-wide state (shared between endpoint functions).
 
 use actix_web::web::Data;
-use kolomoni_auth::JsonWebTokenManager;
+use kolomoni_auth::{JsonWebTokenManager, OpenIdConnectClient};
 use kolomoni_configuration::Configuration;
 use kolomoni_database::mutation::ArgonHasher;
 use kolomoni_search::{ChangeEvent, KolomoniSearchEngine, SearchResults};
@@ -9,7 +9,9 @@ use miette::{Context, IntoDiagnostic, Result};
 use sea_orm::{prelude::Uuid, DatabaseConnection};
 use tokio::sync::mpsc;
 
+use crate::activity::UserActivityTracker;
 use crate::connect_and_set_up_database;
+use crate::maintenance::DatabaseMaintenance;
 
 
 /// A dictionary search engine.
@@ -142,14 +144,33 @@ pub struct ApplicationStateInner {
     /// Authentication token manager (JSON Web Token).
     pub jwt_manager: JsonWebTokenManager,
 
+    /// OpenID Connect client (`None` if OpenID Connect login is not configured).
+    pub oidc_client: Option<OpenIdConnectClient>,
+
     pub search: KolomoniSearch,
+
+    /// Tracker of user activity (keeps `last_active_at` up to date).
+    pub activity_tracker: UserActivityTracker,
+
+    /// Background database maintenance (e.g. pruning expired role grants).
+    pub database_maintenance: DatabaseMaintenance,
 }
 
 impl ApplicationStateInner {
     pub async fn new(configuration: Configuration) -> Result<Self> {
         let hasher = ArgonHasher::new(&configuration)?;
         let database = connect_and_set_up_database(&configuration).await?;
-        let jwt_manager = JsonWebTokenManager::new(&configuration.json_web_token.secret);
+        let jwt_manager = JsonWebTokenManager::new(&configuration.json_web_token)
+            .wrap_err("Failed to initialize JSON Web Token manager.")?;
+
+        // The identity provider is only contacted on first use, so it being unavailable
+        // does not prevent the server from starting.
+        let oidc_client = configuration
+            .oidc
+            .clone()
+            .map(OpenIdConnectClient::new)
+            .transpose()
+            .wrap_err("Failed to initialize OpenID Connect client.")?;
 
         let search = {
             let engine = KolomoniSearchEngine::new(&configuration).await?;
@@ -161,12 +182,20 @@ impl ApplicationStateInner {
             }
         };
 
+        let activity_tracker =
+            UserActivityTracker::start(&configuration.activity_tracking, database.clone());
+
+        let database_maintenance = DatabaseMaintenance::start(database.clone());
+
         Ok(Self {
             configuration,
             hasher,
             database,
             jwt_manager,
+            oidc_client,
             search,
+            activity_tracker,
+            database_maintenance,
         })
     }
 }
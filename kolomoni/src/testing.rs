@@ -2,8 +2,11 @@
 //! the `with_test_facilities` feature flag is enabled.
 
 use actix_web::{post, web, HttpResponse, Scope};
-use kolomoni_auth::{Role, DEFAULT_USER_ROLE};
-use kolomoni_database::{mutation, query};
+use kolomoni_auth::{BuiltinRole, DEFAULT_USER_ROLE};
+use kolomoni_database::{
+    mutation::{self, RoleGrantDetails},
+    query,
+};
 use kolomoni_migrations::Migrator;
 use miette::{Context, IntoDiagnostic, Result};
 use sea_orm::DatabaseConnection;
@@ -63,7 +66,8 @@ pub async fn give_full_permissions_to_user(
     mutation::UserRoleMutation::add_roles_to_user(
         &state.database,
         target_user_id,
-        &[Role::User, Role::Administrator],
+        &[BuiltinRole::User.id(), BuiltinRole::Administrator.id()],
+        RoleGrantDetails::default(),
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -96,10 +100,7 @@ pub async fn reset_user_roles_to_starting_user_roles(
     mutation::UserRoleMutation::remove_roles_from_user(
         &state.database,
         target_user_id,
-        &previous_role_set
-            .into_roles()
-            .into_iter()
-            .collect::<Vec<_>>(),
+        &previous_role_set.role_ids(),
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -107,7 +108,8 @@ pub async fn reset_user_roles_to_starting_user_roles(
     mutation::UserRoleMutation::add_roles_to_user(
         &state.database,
         target_user_id,
-        &[DEFAULT_USER_ROLE],
+        &[DEFAULT_USER_ROLE.id()],
+        RoleGrantDetails::default(),
     )
     .await
     .map_err(APIError::InternalError)?;
@@ -0,0 +1,29 @@
+//! Well-known URIs (see [RFC 8615](https://datatracker.ietf.org/doc/html/rfc8615)).
+//!
+//! These live under `/.well-known`, outside of the versioned API,
+//! and are meant primarily for other services, not API users.
+
+use actix_web::{get, web, HttpResponse, Scope};
+
+use crate::api::errors::EndpointResult;
+use crate::state::ApplicationState;
+
+
+/// Get the public keys authentication tokens are signed with, as a JSON Web Key Set
+/// (see [RFC 7517](https://datatracker.ietf.org/doc/html/rfc7517)).
+///
+/// Other services can use these to verify our tokens themselves. Each token's `kid` header
+/// identifies the key it was signed with; during key rotation, several keys are listed at once.
+#[get("/jwks.json")]
+pub async fn get_json_web_key_set(state: ApplicationState) -> EndpointResult {
+    Ok(HttpResponse::Ok().json(state.jwt_manager.json_web_key_set()))
+}
+
+
+/// Router for well-known URIs.
+/// Lives under `/.well-known`.
+#[rustfmt::skip]
+pub fn well_known_router() -> Scope {
+    web::scope("/.well-known")
+        .service(get_json_web_key_set)
+}
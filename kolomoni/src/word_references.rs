@@ -0,0 +1,356 @@
+//! Wiki-style references to other dictionary words inside word descriptions.
+//!
+//! A reference is written as `[[en:lemma]]` (an english word) or `[[sl:lemma]]`
+//! (a slovene word), optionally followed by the text to show instead of the lemma,
+//! e.g. `[[sl:pustolovec|character]]`. References can't span multiple lines.
+//!
+//! If there are several words with the same lemma (homonyms), a specific one can be referenced
+//! by appending its disambiguation, e.g. `[[en:spell#magic]]`. Without a disambiguation,
+//! the reference points to the homonym without a disambiguation (or the oldest one,
+//! if they all have one).
+//!
+//! References are checked when a description is written (see [`parse_word_references`]
+//! and [`resolve_word_reference`]). The words a description references are stored
+//! in the database (see [`update_word_references`]), which is how each word knows
+//! which other words reference it.
+
+use std::ops::Range;
+
+use kolomoni_database::{
+    mutation::WordReferenceMutation,
+    query::{EnglishWordQuery, SloveneWordQuery},
+    shared::WordLanguage,
+};
+use miette::Result;
+use sea_orm::{prelude::Uuid, ConnectionTrait, TransactionTrait};
+use thiserror::Error;
+
+
+const REFERENCE_START: &str = "[[";
+const REFERENCE_END: &str = "]]";
+const REFERENCE_LABEL_SEPARATOR: char = '|';
+const REFERENCE_LANGUAGE_SEPARATOR: char = ':';
+const REFERENCE_DISAMBIGUATION_SEPARATOR: char = '#';
+
+
+/// A single `[[...]]` reference in a description.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WordReference {
+    pub language: WordLanguage,
+    pub lemma: String,
+
+    /// Disambiguation of the referenced word, if a specific homonym is referenced.
+    pub disambiguation: Option<String>,
+
+    /// Text to show instead of the lemma, if any.
+    pub label: Option<String>,
+}
+
+impl WordReference {
+    /// Text that should be shown in place of the reference.
+    pub fn display_text(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.lemma)
+    }
+}
+
+
+/// A reference in a description is malformed (e.g. has an unknown language prefix).
+#[derive(Error, Clone, PartialEq, Eq, Debug)]
+#[error("invalid word reference {reference}: {reason}")]
+pub struct InvalidWordReferenceError {
+    /// The reference as written in the description.
+    pub reference: String,
+    pub reason: String,
+}
+
+
+/// A reference found in a description, which might be malformed.
+struct ScannedWordReference {
+    /// Byte range of the entire reference (including the brackets) in the description.
+    range: Range<usize>,
+
+    /// The parsed reference, or the reason it is malformed.
+    parsed: Result<WordReference, &'static str>,
+}
+
+/// Finds all references in the description, along with any malformed ones.
+fn scan_word_references(description: &str) -> Vec<ScannedWordReference> {
+    let mut references = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(relative_start) = description[search_from..].find(REFERENCE_START) {
+        let start = search_from + relative_start;
+        let contents_start = start + REFERENCE_START.len();
+
+        let Some(relative_end) = description[contents_start..].find(REFERENCE_END) else {
+            // An unclosed `[[` is just text.
+            break;
+        };
+
+        let contents_end = contents_start + relative_end;
+        let contents = &description[contents_start..contents_end];
+
+        if contents.contains('\n') {
+            // References can't span multiple lines: this `[[` is just text.
+            search_from = contents_start;
+            continue;
+        }
+
+        let end = contents_end + REFERENCE_END.len();
+
+        references.push(ScannedWordReference {
+            range: start..end,
+            parsed: parse_word_reference(contents),
+        });
+
+        search_from = end;
+    }
+
+    references
+}
+
+/// Parses the contents of a single reference (i.e. without the brackets).
+fn parse_word_reference(contents: &str) -> Result<WordReference, &'static str> {
+    let (target, label) = match contents.split_once(REFERENCE_LABEL_SEPARATOR) {
+        Some((target, label)) => {
+            let label = label.trim();
+            if label.is_empty() {
+                return Err("the text after | is empty");
+            }
+
+            (target, Some(label.to_string()))
+        }
+        None => (contents, None),
+    };
+
+    let Some((language_prefix, lemma)) = target.split_once(REFERENCE_LANGUAGE_SEPARATOR) else {
+        return Err("missing language prefix (en: or sl:)");
+    };
+
+    let language = match language_prefix.trim() {
+        "en" => WordLanguage::English,
+        "sl" => WordLanguage::Slovene,
+        _ => return Err("unknown language prefix (expected en: or sl:)"),
+    };
+
+    let (lemma, disambiguation) = match lemma.split_once(REFERENCE_DISAMBIGUATION_SEPARATOR) {
+        Some((lemma, disambiguation)) => {
+            let disambiguation = disambiguation.trim();
+            if disambiguation.is_empty() {
+                return Err("the text after # is empty");
+            }
+
+            (lemma, Some(disambiguation.to_string()))
+        }
+        None => (lemma, None),
+    };
+
+    let lemma = lemma.trim();
+    if lemma.is_empty() {
+        return Err("missing lemma");
+    }
+
+
+    Ok(WordReference {
+        language,
+        lemma: lemma.to_string(),
+        disambiguation,
+        label,
+    })
+}
+
+
+/// Returns all references in the description, or an error if any of them is malformed.
+pub fn parse_word_references(
+    description: &str,
+) -> Result<Vec<WordReference>, InvalidWordReferenceError> {
+    scan_word_references(description)
+        .into_iter()
+        .map(|scanned_reference| {
+            scanned_reference
+                .parsed
+                .map_err(|reason| InvalidWordReferenceError {
+                    reference: description[scanned_reference.range].to_string(),
+                    reason: reason.to_string(),
+                })
+        })
+        .collect()
+}
+
+
+/// Looks up the word a reference points to, returning its UUID (`None` if there is no such word).
+///
+/// See the module documentation for how references without a disambiguation are resolved.
+pub async fn resolve_word_reference<C: ConnectionTrait + TransactionTrait>(
+    database: &C,
+    reference: &WordReference,
+) -> Result<Option<Uuid>> {
+    let homonyms: Vec<(Uuid, Option<String>)> = match reference.language {
+        WordLanguage::English => {
+            EnglishWordQuery::words_by_lemma(database, reference.lemma.clone())
+                .await?
+                .into_iter()
+                .map(|word| (word.word_id, word.disambiguation))
+                .collect()
+        }
+        WordLanguage::Slovene => {
+            SloveneWordQuery::words_by_lemma(database, reference.lemma.clone())
+                .await?
+                .into_iter()
+                .map(|word| (word.word_id, word.disambiguation))
+                .collect()
+        }
+    };
+
+    let referenced_homonym = match &reference.disambiguation {
+        Some(_) => homonyms
+            .iter()
+            .find(|(_, disambiguation)| disambiguation == &reference.disambiguation),
+        None => homonyms
+            .iter()
+            .find(|(_, disambiguation)| disambiguation.is_none())
+            .or_else(|| homonyms.first()),
+    };
+
+    Ok(referenced_homonym.map(|(word_uuid, _)| *word_uuid))
+}
+
+
+/// Stores the words referenced from the given description as the references of the word
+/// (replacing any previous ones). This should be called whenever the description of a word changes.
+///
+/// Malformed references and references to words that don't exist are skipped.
+pub async fn update_word_references<C: ConnectionTrait + TransactionTrait>(
+    database: &C,
+    word_uuid: Uuid,
+    description: Option<&str>,
+) -> Result<()> {
+    let mut referenced_word_uuids = Vec::new();
+
+    for reference in scan_word_references(description.unwrap_or_default())
+        .into_iter()
+        .filter_map(|scanned_reference| scanned_reference.parsed.ok())
+    {
+        if let Some(referenced_word_uuid) = resolve_word_reference(database, &reference).await? {
+            referenced_word_uuids.push(referenced_word_uuid);
+        }
+    }
+
+    WordReferenceMutation::replace_references_from_word(
+        database,
+        word_uuid,
+        referenced_word_uuids,
+    )
+    .await
+}
+
+
+/// Renders a Markdown description into HTML.
+///
+/// References are rendered as `<a class="word-reference" data-word-id="..." data-language="en">`
+/// elements (`data-language` is `en` or `sl`). References to words that don't exist
+/// (or malformed ones) are rendered as `<span class="word-reference word-reference-broken">`.
+///
+/// The resulting HTML is sanitised, so it is safe to embed into a page.
+pub async fn render_description_html<C: ConnectionTrait + TransactionTrait>(
+    database: &C,
+    description: &str,
+) -> Result<String> {
+    // References are replaced with inline HTML before rendering the Markdown.
+    let mut markdown = String::with_capacity(description.len());
+    let mut copied_until = 0;
+
+    for scanned_reference in scan_word_references(description) {
+        let range = scanned_reference.range;
+
+        let reference_html = match scanned_reference.parsed {
+            Ok(reference) => {
+                let referenced_word_uuid = resolve_word_reference(database, &reference).await?;
+
+                match referenced_word_uuid {
+                    Some(referenced_word_uuid) => format!(
+                        "<a class=\"word-reference\" data-word-id=\"{}\" data-language=\"{}\">{}</a>",
+                        referenced_word_uuid,
+                        reference_language_prefix(reference.language),
+                        escape_html(reference.display_text())
+                    ),
+                    None => broken_reference_html(reference.display_text()),
+                }
+            }
+            Err(_) => broken_reference_html(&description[range.clone()]),
+        };
+
+        markdown.push_str(&description[copied_until..range.start]);
+        markdown.push_str(&reference_html);
+        copied_until = range.end;
+    }
+
+    markdown.push_str(&description[copied_until..]);
+
+
+    let mut unsanitised_html = String::with_capacity(markdown.len() * 2);
+    pulldown_cmark::html::push_html(
+        &mut unsanitised_html,
+        pulldown_cmark::Parser::new(&markdown),
+    );
+
+    let sanitised_html = ammonia::Builder::default()
+        .add_tag_attributes("a", &["data-word-id", "data-language"])
+        .add_allowed_classes("a", &["word-reference"])
+        .add_allowed_classes("span", &["word-reference", "word-reference-broken"])
+        .clean(&unsanitised_html)
+        .to_string();
+
+    Ok(sanitised_html)
+}
+
+fn broken_reference_html(text: &str) -> String {
+    format!(
+        "<span class=\"word-reference word-reference-broken\">{}</span>",
+        escape_html(text)
+    )
+}
+
+/// Returns the reference as it would be written in a description, without the label
+/// (e.g. `[[en:spell#magic]]`).
+pub fn format_word_reference(reference: &WordReference) -> String {
+    match &reference.disambiguation {
+        Some(disambiguation) => format!(
+            "[[{}:{}{}{}]]",
+            reference_language_prefix(reference.language),
+            reference.lemma,
+            REFERENCE_DISAMBIGUATION_SEPARATOR,
+            disambiguation
+        ),
+        None => format!(
+            "[[{}:{}]]",
+            reference_language_prefix(reference.language),
+            reference.lemma
+        ),
+    }
+}
+
+/// Returns the language prefix used in references (`en` or `sl`).
+pub fn reference_language_prefix(language: WordLanguage) -> &'static str {
+    match language {
+        WordLanguage::English => "en",
+        WordLanguage::Slovene => "sl",
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
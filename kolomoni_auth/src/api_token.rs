@@ -0,0 +1,79 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use miette::{miette, Result};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+
+
+/// Prefix of all personal API tokens.
+///
+/// This allows us to tell API tokens and JSON Web Tokens apart
+/// when they are provided in the `Authorization` header.
+pub const API_TOKEN_PREFIX: &str = "kolomoni_pat_";
+
+/// Number of random bytes in an API token (before encoding).
+const API_TOKEN_RANDOM_BYTES: usize = 32;
+
+
+/// Generates a new random personal API token.
+///
+/// The token consists of [`API_TOKEN_PREFIX`] and 32 random bytes encoded as URL-safe base64.
+/// Only its hash (see [`hash_api_token`]) should ever be stored.
+pub fn generate_api_token() -> Result<String> {
+    let mut token_bytes = [0u8; API_TOKEN_RANDOM_BYTES];
+
+    SystemRandom::new()
+        .fill(&mut token_bytes)
+        .map_err(|_| miette!("Failed to generate random bytes for an API token."))?;
+
+    Ok(format!(
+        "{}{}",
+        API_TOKEN_PREFIX,
+        URL_SAFE_NO_PAD.encode(token_bytes)
+    ))
+}
+
+/// Returns `true` if the provided string looks like a personal API token
+/// (as opposed to e.g. a JSON Web Token).
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// Hashes a personal API token for storage and lookup (lower-case hexadecimal SHA-256).
+///
+/// Because the tokens are long and random, a fast hash is sufficient here
+/// (unlike with passwords, there is nothing to brute-force).
+pub fn hash_api_token(token: &str) -> String {
+    digest(&SHA256, token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generates_and_hashes_tokens() {
+        let first_token = generate_api_token().unwrap();
+        let second_token = generate_api_token().unwrap();
+
+        assert!(is_api_token(&first_token));
+        assert!(is_api_token(&second_token));
+        assert_ne!(first_token, second_token);
+
+        assert_eq!(
+            hash_api_token(&first_token),
+            hash_api_token(&first_token)
+        );
+        assert_ne!(
+            hash_api_token(&first_token),
+            hash_api_token(&second_token)
+        );
+        assert_eq!(hash_api_token(&first_token).len(), 64);
+    }
+}
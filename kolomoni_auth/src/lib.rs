@@ -1,7 +1,13 @@
+mod api_token;
+mod oidc;
 mod permissions;
 mod roles;
 mod token;
+mod totp;
 
+pub use api_token::*;
+pub use oidc::*;
 pub use permissions::*;
 pub use roles::*;
 pub use token::*;
+pub use totp::*;
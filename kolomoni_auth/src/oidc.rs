@@ -0,0 +1,470 @@
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use kolomoni_configuration::OpenIdConnectConfiguration;
+use miette::{miette, Context, IntoDiagnostic, Result};
+use reqwest::{Client, StatusCode, Url};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+
+/// Number of random bytes in PKCE code verifiers, states and nonces (before encoding).
+///
+/// 32 bytes encode into a 43-character code verifier, the minimum allowed by RFC 7636.
+const OIDC_RANDOM_VALUE_BYTES: usize = 32;
+
+/// How long to wait for the identity provider to respond.
+const OIDC_PROVIDER_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+
+/// Generates a random URL-safe value, usable as a PKCE code verifier,
+/// an OAuth 2.0 `state` or an OpenID Connect `nonce`.
+pub fn generate_oidc_random_value() -> Result<String> {
+    let mut random_bytes = [0u8; OIDC_RANDOM_VALUE_BYTES];
+
+    SystemRandom::new()
+        .fill(&mut random_bytes)
+        .map_err(|_| miette!("Failed to generate random bytes for an OpenID Connect login."))?;
+
+    Ok(URL_SAFE_NO_PAD.encode(random_bytes))
+}
+
+/// Computes the PKCE code challenge for the given code verifier
+/// using the `S256` method (see RFC 7636, section 4.2).
+pub fn pkce_code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, code_verifier.as_bytes()))
+}
+
+
+
+/// OpenID Connect login error type.
+#[derive(Error, Debug)]
+pub enum OpenIdConnectError {
+    /// The identity provider refused to exchange the authorization code
+    /// or returned an ID token that did not pass validation.
+    #[error("login was rejected: {0}")]
+    LoginRejected(String),
+
+    /// The identity provider could not be reached or responded unexpectedly.
+    #[error("failed to communicate with the identity provider: {0}")]
+    ProviderError(miette::Report),
+}
+
+
+/// The parts of the identity provider's metadata
+/// (`/.well-known/openid-configuration`) we need.
+#[derive(Deserialize, Clone, Debug)]
+pub struct OpenIdConnectProviderMetadata {
+    pub issuer: String,
+
+    pub authorization_endpoint: String,
+
+    pub token_endpoint: String,
+
+    pub jwks_uri: String,
+}
+
+/// An identity provider's metadata along with its current signing keys.
+#[derive(Clone)]
+struct DiscoveredProvider {
+    metadata: OpenIdConnectProviderMetadata,
+    key_set: JwkSet,
+}
+
+
+/// A successful token endpoint response (only the fields we need).
+#[derive(Deserialize)]
+struct TokenEndpointResponse {
+    id_token: String,
+}
+
+/// An unsuccessful token endpoint response (see RFC 6749, section 5.2).
+#[derive(Deserialize)]
+struct TokenEndpointErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// ID token claims we need (the audience and expiration time are validated separately).
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    iss: String,
+    sub: String,
+    nonce: Option<String>,
+    preferred_username: Option<String>,
+    name: Option<String>,
+}
+
+
+/// A user identity confirmed by the identity provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpenIdConnectIdentity {
+    /// The identity provider's issuer identifier (`iss` claim).
+    pub issuer: String,
+
+    /// The identifier of the user at the identity provider (`sub` claim).
+    /// Only the issuer and subject together uniquely identify a user.
+    pub subject: String,
+
+    /// The username the user prefers, if the identity provider shared it.
+    pub preferred_username: Option<String>,
+
+    /// The user's full name, if the identity provider shared it.
+    pub name: Option<String>,
+}
+
+
+
+/// An OpenID Connect relying party, performing the authorization code flow with PKCE
+/// against a single identity provider.
+///
+/// The identity provider's metadata and signing keys are discovered on first use and cached.
+/// The signing keys are re-fetched when an ID token is signed with an unknown key,
+/// so keys can be rotated at the identity provider without restarting the server.
+pub struct OpenIdConnectClient {
+    configuration: OpenIdConnectConfiguration,
+
+    http_client: Client,
+
+    discovered_provider: RwLock<Option<DiscoveredProvider>>,
+}
+
+impl OpenIdConnectClient {
+    pub fn new(configuration: OpenIdConnectConfiguration) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(OIDC_PROVIDER_REQUEST_TIMEOUT)
+            .build()
+            .into_diagnostic()
+            .wrap_err("Failed to build HTTP client for OpenID Connect.")?;
+
+        Ok(Self {
+            configuration,
+            http_client,
+            discovered_provider: RwLock::new(None),
+        })
+    }
+
+    /// Returns the configuration of this client.
+    #[inline]
+    pub fn configuration(&self) -> &OpenIdConnectConfiguration {
+        &self.configuration
+    }
+
+    /// Builds the URL of the identity provider's authorization endpoint
+    /// the user should be redirected to in order to log in.
+    pub async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String> {
+        let provider = self.discovered_provider(false).await?;
+
+        let scope = self.configuration.scopes.join(" ");
+
+        let authorization_url = Url::parse_with_params(
+            &provider.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", &self.configuration.client_id),
+                ("redirect_uri", &self.configuration.redirect_url),
+                ("scope", &scope),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .into_diagnostic()
+        .wrap_err("Identity provider has an invalid authorization endpoint URL.")?;
+
+        Ok(authorization_url.to_string())
+    }
+
+    /// Exchanges an authorization code (received by the user after logging in at the identity
+    /// provider) for an ID token, validates it and returns the identity it confirms.
+    ///
+    /// `code_verifier` and `expected_nonce` must be the values the authorization URL
+    /// was built with.
+    pub async fn exchange_authorization_code(
+        &self,
+        authorization_code: &str,
+        code_verifier: &str,
+        expected_nonce: &str,
+    ) -> Result<OpenIdConnectIdentity, OpenIdConnectError> {
+        let provider = self
+            .discovered_provider(false)
+            .await
+            .map_err(OpenIdConnectError::ProviderError)?;
+
+
+        let mut form_parameters = vec![
+            ("grant_type", "authorization_code"),
+            ("code", authorization_code),
+            ("redirect_uri", &self.configuration.redirect_url),
+            ("client_id", &self.configuration.client_id),
+            ("code_verifier", code_verifier),
+        ];
+
+        if let Some(client_secret) = &self.configuration.client_secret {
+            form_parameters.push(("client_secret", client_secret));
+        }
+
+        let response = self
+            .http_client
+            .post(&provider.metadata.token_endpoint)
+            .form(&form_parameters)
+            .send()
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed to send token request to the identity provider.")
+            .map_err(OpenIdConnectError::ProviderError)?;
+
+        let response_status = response.status();
+        let response_body = response
+            .text()
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed to read token response from the identity provider.")
+            .map_err(OpenIdConnectError::ProviderError)?;
+
+        if response_status == StatusCode::BAD_REQUEST || response_status == StatusCode::UNAUTHORIZED
+        {
+            let error_response =
+                serde_json::from_str::<TokenEndpointErrorResponse>(&response_body).ok();
+
+            let reason = match error_response {
+                Some(TokenEndpointErrorResponse {
+                    error,
+                    error_description: Some(description),
+                }) => format!("{error} ({description})"),
+                Some(TokenEndpointErrorResponse { error, .. }) => error,
+                None => format!("status code {response_status}"),
+            };
+
+            return Err(OpenIdConnectError::LoginRejected(format!(
+                "identity provider refused the authorization code: {reason}"
+            )));
+        }
+
+        if !response_status.is_success() {
+            return Err(OpenIdConnectError::ProviderError(miette!(
+                "Identity provider responded to token request with status code {}.",
+                response_status
+            )));
+        }
+
+        let token_response = serde_json::from_str::<TokenEndpointResponse>(&response_body)
+            .into_diagnostic()
+            .wrap_err("Identity provider returned an invalid token response.")
+            .map_err(OpenIdConnectError::ProviderError)?;
+
+
+        let claims = self
+            .validate_id_token(&token_response.id_token, expected_nonce)
+            .await?;
+
+        Ok(OpenIdConnectIdentity {
+            issuer: claims.iss,
+            subject: claims.sub,
+            preferred_username: claims.preferred_username,
+            name: claims.name,
+        })
+    }
+
+    /// Validates the signature, issuer, audience, expiration time and nonce of an ID token.
+    async fn validate_id_token(
+        &self,
+        id_token: &str,
+        expected_nonce: &str,
+    ) -> Result<IdTokenClaims, OpenIdConnectError> {
+        let token_header = jsonwebtoken::decode_header(id_token).map_err(|error| {
+            OpenIdConnectError::LoginRejected(format!("invalid ID token header: {error}"))
+        })?;
+
+        // Symmetric algorithms would require the client secret as the key,
+        // which a public client does not have; we only accept signatures by the provider's keys.
+        if matches!(
+            token_header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(OpenIdConnectError::LoginRejected(
+                "ID token is signed with an unsupported (symmetric) algorithm".to_string(),
+            ));
+        }
+
+
+        let mut provider = self
+            .discovered_provider(false)
+            .await
+            .map_err(OpenIdConnectError::ProviderError)?;
+
+        let mut decoding_key = find_decoding_key(&provider.key_set, token_header.kid.as_deref());
+
+        if decoding_key.is_none() {
+            // The identity provider might have rotated its keys since we last fetched them.
+            provider = self
+                .discovered_provider(true)
+                .await
+                .map_err(OpenIdConnectError::ProviderError)?;
+
+            decoding_key = find_decoding_key(&provider.key_set, token_header.kid.as_deref());
+        }
+
+        let Some(decoding_key) = decoding_key else {
+            return Err(OpenIdConnectError::LoginRejected(
+                "ID token is signed with an unknown key".to_string(),
+            ));
+        };
+
+
+        let mut validation = Validation::new(token_header.alg);
+        validation.set_issuer(&[&provider.metadata.issuer]);
+        validation.set_audience(&[&self.configuration.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let token_data = jsonwebtoken::decode::<IdTokenClaims>(id_token, &decoding_key, &validation)
+            .map_err(|error| {
+                OpenIdConnectError::LoginRejected(match error.kind() {
+                    ErrorKind::ExpiredSignature => "ID token has expired".to_string(),
+                    ErrorKind::InvalidIssuer => "ID token has an invalid issuer".to_string(),
+                    ErrorKind::InvalidAudience => "ID token has an invalid audience".to_string(),
+                    _ => format!("invalid ID token: {error}"),
+                })
+            })?;
+
+        if token_data.claims.nonce.as_deref() != Some(expected_nonce) {
+            return Err(OpenIdConnectError::LoginRejected(
+                "ID token has an invalid nonce".to_string(),
+            ));
+        }
+
+        Ok(token_data.claims)
+    }
+
+    /// Returns the identity provider's metadata and signing keys,
+    /// discovering them first if they have not been yet (or if `refresh` is `true`).
+    async fn discovered_provider(&self, refresh: bool) -> Result<DiscoveredProvider> {
+        if !refresh {
+            if let Some(provider) = self.discovered_provider.read().await.as_ref() {
+                return Ok(provider.clone());
+            }
+        }
+
+        let mut discovered_provider = self.discovered_provider.write().await;
+
+
+        let metadata_url = format!(
+            "{}/.well-known/openid-configuration",
+            self.configuration.issuer_url
+        );
+
+        debug!(
+            metadata_url = metadata_url,
+            "Discovering OpenID Connect provider."
+        );
+
+        let metadata = self
+            .fetch_json::<OpenIdConnectProviderMetadata>(&metadata_url)
+            .await
+            .wrap_err("Failed to discover OpenID Connect provider metadata.")?;
+
+        // See OpenID Connect Discovery 1.0, section 4.3.
+        if metadata.issuer.trim_end_matches('/') != self.configuration.issuer_url {
+            return Err(miette!(
+                "OpenID Connect provider metadata has a mismatched issuer: expected {}, got {}.",
+                self.configuration.issuer_url,
+                metadata.issuer
+            ));
+        }
+
+        let key_set = self
+            .fetch_json::<JwkSet>(&metadata.jwks_uri)
+            .await
+            .wrap_err("Failed to fetch OpenID Connect provider signing keys.")?;
+
+
+        info!(
+            issuer = metadata.issuer,
+            key_count = key_set.keys.len(),
+            "Discovered OpenID Connect provider."
+        );
+
+        let provider = DiscoveredProvider { metadata, key_set };
+        *discovered_provider = Some(provider.clone());
+
+        Ok(provider)
+    }
+
+    async fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self
+            .http_client
+            .get(url)
+            .send()
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to send request to {url}."))?
+            .error_for_status()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Request to {url} was unsuccessful."))?;
+
+        let response_body = response
+            .text()
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read response from {url}."))?;
+
+        serde_json::from_str(&response_body)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Invalid JSON response from {url}."))
+    }
+}
+
+
+/// Finds the key with the given ID in the key set. If the token has no key ID,
+/// the key set must contain exactly one key.
+fn find_decoding_key(key_set: &JwkSet, key_id: Option<&str>) -> Option<DecodingKey> {
+    let key = match key_id {
+        Some(key_id) => key_set.find(key_id)?,
+        None => match key_set.keys.as_slice() {
+            [only_key] => only_key,
+            _ => return None,
+        },
+    };
+
+    DecodingKey::from_jwk(key).ok()
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn computes_pkce_code_challenge() {
+        // Example from RFC 7636, appendix B.
+        assert_eq!(
+            pkce_code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn generates_random_values() {
+        let first_value = generate_oidc_random_value().unwrap();
+        let second_value = generate_oidc_random_value().unwrap();
+
+        assert_eq!(first_value.len(), 43);
+        assert_ne!(first_value, second_value);
+    }
+}
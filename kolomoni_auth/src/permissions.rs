@@ -70,6 +70,27 @@ pub enum Permission {
 
 
 impl Permission {
+    /// Returns a list of all permissions, ordered by their internal ID.
+    pub fn all() -> Vec<Self> {
+        vec![
+            Permission::UserSelfRead,
+            Permission::UserSelfWrite,
+            Permission::UserAnyRead,
+            Permission::UserAnyWrite,
+            Permission::WordCreate,
+            Permission::WordRead,
+            Permission::WordUpdate,
+            Permission::WordDelete,
+            Permission::SuggestionCreate,
+            Permission::SuggestionDelete,
+            Permission::TranslationCreate,
+            Permission::TranslationDelete,
+            Permission::CategoryCreate,
+            Permission::CategoryUpdate,
+            Permission::CategoryDelete,
+        ]
+    }
+
     pub fn from_id(internal_permission_id: i32) -> Option<Self> {
         match internal_permission_id {
             1 => Some(Permission::UserSelfRead),
@@ -203,6 +224,7 @@ pub const BLANKET_PERMISSION_GRANT: [Permission; 2] =
 
 
 /// Set of permissions, usually associated with some user.
+#[derive(Clone, Debug)]
 pub struct PermissionSet {
     /// Set of permissions.
     permissions: HashSet<Permission>,
@@ -261,6 +283,18 @@ impl PermissionSet {
         false
     }
 
+    /// Returns a new [`PermissionSet`] containing only the permissions
+    /// that are present in both `self` and `other`.
+    pub fn intersection(&self, other: &PermissionSet) -> Self {
+        Self {
+            permissions: self
+                .permissions
+                .intersection(&other.permissions)
+                .copied()
+                .collect(),
+        }
+    }
+
     /// Consumes the [`PermissionSet`] and returns a raw [`HashSet`] of [`Permission`]s.
     pub fn into_permissions(self) -> HashSet<Permission> {
         self.permissions
@@ -301,6 +335,43 @@ mod test {
         assert!(permissions.has_permission(Permission::UserAnyWrite));
     }
 
+    #[test]
+    fn intersects_permission_sets() {
+        let user_permissions = PermissionSet::from_permission_set(HashSet::from([
+            Permission::UserSelfRead,
+            Permission::WordCreate,
+            Permission::WordUpdate,
+        ]));
+
+        let token_scope = PermissionSet::from_permission_set(HashSet::from([
+            Permission::WordCreate,
+            Permission::WordUpdate,
+            Permission::WordDelete,
+        ]));
+
+        let effective_permissions = user_permissions.intersection(&token_scope);
+
+        assert_eq!(
+            effective_permissions.into_permissions(),
+            HashSet::from([Permission::WordCreate, Permission::WordUpdate])
+        );
+    }
+
+    #[test]
+    fn lists_all_permissions() {
+        let all_permissions = Permission::all();
+
+        for (index, permission) in all_permissions.iter().enumerate() {
+            assert_eq!(permission.id(), index as i32 + 1);
+            assert_eq!(Permission::from_id(permission.id()), Some(*permission));
+        }
+
+        assert_eq!(
+            Permission::from_id(all_permissions.len() as i32 + 1),
+            None
+        );
+    }
+
     #[test]
     fn converts_to_name() {
         assert_eq!(Permission::UserSelfRead.name(), "user.self:read");
@@ -2,19 +2,33 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
-use crate::Permission;
 
-
-/// User roles that we have.
+/// A user role, as stored in the `role` table.
 ///
 /// Roles can be assigned to users, granting them
-/// all permissions associated with the role.
+/// all permissions associated with the role (see `role_permission`).
+/// Apart from the [built-in ones][BuiltinRole], roles are
+/// created and managed by administrators at runtime.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+pub struct Role {
+    /// Internal database ID of the role.
+    pub id: i32,
+
+    /// Unique lower-case name of the role (e.g. "user").
+    pub name: String,
+}
+
+
+/// Roles that are always present.
+///
+/// These can not be deleted or renamed, but their permissions
+/// can be changed just like with any other role.
 ///
 /// # Maintenance
 /// **The defined roles must match with the `*_seed_roles.rs` file
 /// in `kolomoni_migrations`!**
 #[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
-pub enum Role {
+pub enum BuiltinRole {
     /// A normal Kolomoni user. Grants access to their own account
     /// and most read permissions.
     #[serde(rename = "user")]
@@ -24,13 +38,13 @@ pub enum Role {
     Administrator,
 }
 
-impl Role {
-    /// Attempts to deserialize a [`Role`] from its internal database ID
+impl BuiltinRole {
+    /// Attempts to deserialize a [`BuiltinRole`] from its internal database ID
     /// (e.g. 1).
     pub fn from_id(role_id: i32) -> Option<Self> {
         match role_id {
-            1 => Some(Role::User),
-            2 => Some(Role::Administrator),
+            1 => Some(BuiltinRole::User),
+            2 => Some(BuiltinRole::Administrator),
             _ => None,
         }
     }
@@ -38,12 +52,12 @@ impl Role {
     /// Returns an internal database ID associated with the role.
     pub fn id(&self) -> i32 {
         match self {
-            Role::User => 1,
-            Role::Administrator => 2,
+            BuiltinRole::User => 1,
+            BuiltinRole::Administrator => 2,
         }
     }
 
-    /// Attempt to deserialize a [`Role`] from its lower-case name
+    /// Attempt to deserialize a [`BuiltinRole`] from its lower-case name
     /// (e.g. "user").
     pub fn from_name(name: &str) -> Option<Self> {
         match name {
@@ -56,50 +70,14 @@ impl Role {
     /// Returns the lower-case name associated with the role.
     pub fn name(&self) -> &'static str {
         match self {
-            Role::User => "user",
-            Role::Administrator => "administrator",
-        }
-    }
-
-    /// Returns a description of the role.
-    #[rustfmt::skip]
-    pub fn description(&self) -> &'static str {
-        match self {
-            Role::User =>
-                "Normal user with most read permissions.",
-            Role::Administrator =>
-                "Administrator with almost all permission, including deletions.",
-        }
-    }
-
-    /// Returns a list of permissions that the role grants.
-    pub fn permissions_granted(&self) -> Vec<Permission> {
-        match self {
-            Role::User => vec![
-                Permission::UserSelfRead,
-                Permission::UserSelfWrite,
-                Permission::UserAnyRead,
-                Permission::WordRead,
-                Permission::SuggestionCreate,
-            ],
-            Role::Administrator => vec![
-                Permission::UserAnyWrite,
-                Permission::WordCreate,
-                Permission::WordUpdate,
-                Permission::WordDelete,
-                Permission::SuggestionDelete,
-                Permission::TranslationCreate,
-                Permission::TranslationDelete,
-                Permission::CategoryCreate,
-                Permission::CategoryUpdate,
-                Permission::CategoryDelete,
-            ],
+            BuiltinRole::User => "user",
+            BuiltinRole::Administrator => "administrator",
         }
     }
 }
 
 /// The default role given to newly-registered users.
-pub const DEFAULT_USER_ROLE: Role = Role::User;
+pub const DEFAULT_USER_ROLE: BuiltinRole = BuiltinRole::User;
 
 
 /// Set of roles, usually associated with some user.
@@ -117,7 +95,12 @@ impl RoleSet {
 
     /// Checks whether the role set contains a specific role.
     pub fn has_role(&self, role: &Role) -> bool {
-        self.roles.contains(role)
+        self.has_role_by_id(role.id)
+    }
+
+    /// Checks whether the role set contains a role with the given internal ID.
+    pub fn has_role_by_id(&self, role_id: i32) -> bool {
+        self.roles.iter().any(|role| role.id == role_id)
     }
 
     /// Consumes the [`RoleSet`] and returns a raw [`HashSet`] of [`Role`]s.
@@ -132,9 +115,11 @@ impl RoleSet {
 
     /// Returns a `Vec` of role names.
     pub fn role_names(&self) -> Vec<String> {
-        self.roles
-            .iter()
-            .map(|role| role.name().to_string())
-            .collect()
+        self.roles.iter().map(|role| role.name.clone()).collect()
+    }
+
+    /// Returns a `Vec` of internal role IDs.
+    pub fn role_ids(&self) -> Vec<i32> {
+        self.roles.iter().map(|role| role.id).collect()
     }
 }
@@ -1,20 +1,37 @@
+use std::collections::HashMap;
+use std::fs;
 use std::ops::Add;
+use std::path::Path;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Duration, SubsecRound, Utc};
 use jsonwebtoken::errors::ErrorKind;
+pub use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters,
+    CommonParameters,
+    EllipticCurve,
+    Jwk,
+    KeyAlgorithm,
+    OctetKeyPairParameters,
+    OctetKeyPairType,
+    PublicKeyUse,
+    RSAKeyParameters,
+    RSAKeyType,
+};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use miette::{Context, IntoDiagnostic, Result};
+use kolomoni_configuration::JsonWebTokenConfiguration;
+use miette::{miette, Context, IntoDiagnostic, Result};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair, RsaPublicKeyComponents};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::TimestampSeconds;
 use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
 
 
-// TODO Consider making this dynamic (for example through an environment variable).
-
-/// JSON Web Token issuer.
-const JWT_ISSUER: &str = "Stari Kolomoni";
-
 /// JSON Web Token subject.
 const JWT_SUBJECT: &str = "API token";
 
@@ -59,7 +76,7 @@ pub enum JWTTokenType {
 pub struct JWTClaims {
     /// JWT registered claim: Issuer
     ///
-    /// Should always be the same as `JWT_ISSUER`.
+    /// Should always be the same as the issuer in the `json_web_token` configuration table.
     pub iss: String,
 
     /// JWT registered claim: Subject
@@ -75,6 +92,13 @@ pub struct JWTClaims {
     #[serde_as(as = "TimestampSeconds<i64>")]
     pub exp: DateTime<Utc>,
 
+    /// JWT registered claim: JWT ID
+    ///
+    /// The ID of the login session this token belongs to.
+    /// Both the access and the refresh token issued on login share the same session ID,
+    /// which allows the session (and with it, both tokens) to be revoked.
+    pub jti: Uuid,
+
     /// JWT private claim: Internal user ID
     ///
     /// Internal ID the user was given upon registration.
@@ -88,57 +112,286 @@ pub struct JWTClaims {
     pub token_type: JWTTokenType,
 }
 
-impl JWTClaims {
-    /// Create a new JSON Web Token.
-    ///
-    /// Note that the `issued_at` timestamp will have its sub-second content truncated
-    /// (see [`trunc_subsecs`][chrono::round::SubsecRound::trunc_subsecs]).
-    pub fn create(
-        user_id: i32,
-        issued_at: DateTime<Utc>,
-        valid_for: Duration,
-        token_type: JWTTokenType,
-    ) -> Self {
-        let issued_at = issued_at.trunc_subsecs(0);
-        let expires_on = issued_at.add(valid_for);
+/// A single JSON Web Token signing key, identified by its key ID (`kid`).
+///
+/// Both Ed25519 (signing with `EdDSA`) and RSA (signing with `RS256`) private keys
+/// are supported, PEM-encoded either as PKCS#8 (`BEGIN PRIVATE KEY`) or, for RSA keys,
+/// also as PKCS#1 (`BEGIN RSA PRIVATE KEY`).
+pub struct JsonWebTokenSigningKey {
+    /// Key ID, included in the `kid` header of tokens signed with this key.
+    key_id: String,
 
-        Self {
-            iss: JWT_ISSUER.to_string(),
-            sub: JWT_SUBJECT.to_string(),
-            iat: issued_at,
-            exp: expires_on,
-            user_id,
-            token_type,
+    /// Signing algorithm, derived from the key type.
+    algorithm: Algorithm,
+
+    /// Private key, used to sign new tokens.
+    encoding_key: EncodingKey,
+
+    /// Public key, used to verify tokens.
+    decoding_key: DecodingKey,
+
+    /// Public key in the JSON Web Key format.
+    public_key_jwk: Jwk,
+}
+
+impl JsonWebTokenSigningKey {
+    /// Parse a PEM-encoded private key.
+    pub fn from_pem<S: Into<String>>(key_id: S, pem_contents: &[u8]) -> Result<Self> {
+        let key_id: String = key_id.into();
+
+        let parsed_pem = pem::parse(pem_contents)
+            .into_diagnostic()
+            .wrap_err("Failed to parse PEM-encoded private key.")?;
+
+        let (algorithm, algorithm_parameters) = match parsed_pem.tag() {
+            "PRIVATE KEY" => {
+                if let Ok(key_pair) =
+                    Ed25519KeyPair::from_pkcs8_maybe_unchecked(parsed_pem.contents())
+                {
+                    (
+                        Algorithm::EdDSA,
+                        ed25519_algorithm_parameters(&key_pair),
+                    )
+                } else {
+                    let key_pair =
+                        RsaKeyPair::from_pkcs8(parsed_pem.contents()).map_err(|error| {
+                            miette!(
+                                "Unsupported private key, expected an Ed25519 or RSA key: {error}."
+                            )
+                        })?;
+
+                    (
+                        Algorithm::RS256,
+                        rsa_algorithm_parameters(&key_pair),
+                    )
+                }
+            }
+            "RSA PRIVATE KEY" => {
+                let key_pair = RsaKeyPair::from_der(parsed_pem.contents())
+                    .map_err(|error| miette!("Invalid RSA private key: {error}."))?;
+
+                (
+                    Algorithm::RS256,
+                    rsa_algorithm_parameters(&key_pair),
+                )
+            }
+            other_tag => {
+                return Err(miette!(
+                    "Unsupported PEM block \"{other_tag}\", expected a private key."
+                ));
+            }
+        };
+
+
+        let encoding_key = match algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(pem_contents),
+            _ => EncodingKey::from_rsa_pem(pem_contents),
         }
+        .into_diagnostic()
+        .wrap_err("Failed to load private key.")?;
+
+        let public_key_jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(match algorithm {
+                    Algorithm::EdDSA => KeyAlgorithm::EdDSA,
+                    _ => KeyAlgorithm::RS256,
+                }),
+                key_id: Some(key_id.clone()),
+                ..Default::default()
+            },
+            algorithm: algorithm_parameters,
+        };
+
+        let decoding_key = DecodingKey::from_jwk(&public_key_jwk)
+            .into_diagnostic()
+            .wrap_err("Failed to load public key.")?;
+
+
+        Ok(Self {
+            key_id,
+            algorithm,
+            encoding_key,
+            decoding_key,
+            public_key_jwk,
+        })
     }
+
+    /// Load a PEM-encoded private key from a file.
+    /// The key ID is the file name without its extension.
+    pub fn load_from_file<P: AsRef<Path>>(key_file_path: P) -> Result<Self> {
+        let key_file_path = key_file_path.as_ref();
+
+        let key_id = key_file_path
+            .file_stem()
+            .and_then(|file_stem| file_stem.to_str())
+            .ok_or_else(|| miette!("Invalid signing key file name."))?;
+
+        let pem_contents = fs::read(key_file_path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("Failed to read signing key file {key_file_path:?}."))?;
+
+        Self::from_pem(key_id, &pem_contents)
+            .wrap_err_with(|| format!("Failed to load signing key file {key_file_path:?}."))
+    }
+
+    /// Returns the key ID (`kid`).
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns the algorithm tokens are signed with when using this key.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+}
+
+fn ed25519_algorithm_parameters(key_pair: &Ed25519KeyPair) -> AlgorithmParameters {
+    AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+        key_type: OctetKeyPairType::OctetKeyPair,
+        curve: EllipticCurve::Ed25519,
+        x: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+    })
+}
+
+fn rsa_algorithm_parameters(key_pair: &RsaKeyPair) -> AlgorithmParameters {
+    let public_key_components = RsaPublicKeyComponents::<Vec<u8>>::from(key_pair.public());
+
+    AlgorithmParameters::RSA(RSAKeyParameters {
+        key_type: RSAKeyType::RSA,
+        n: URL_SAFE_NO_PAD.encode(public_key_components.n),
+        e: URL_SAFE_NO_PAD.encode(public_key_components.e),
+    })
 }
 
 
+
 /// JSON Web Token manager --- encoder and decoder.
+///
+/// Multiple signing keys can be active at once: new tokens are always signed with
+/// a single (configured) key, but tokens signed with any of the known keys are accepted.
+/// This allows keys to be rotated without invalidating already-issued tokens.
 pub struct JsonWebTokenManager {
-    /// Token header.
+    /// Token issuer.
+    issuer: String,
+
+    /// How long access tokens are valid for.
+    access_token_lifetime: Duration,
+
+    /// How long refresh tokens are valid for.
+    refresh_token_lifetime: Duration,
+
+    /// Token header for newly-signed tokens (contains the algorithm and key ID).
     header: Header,
 
-    /// JSON Web Token encoding key, derived from the provided secret.
-    encoding_key: EncodingKey,
+    /// ID of the key new tokens are signed with.
+    signing_key_id: String,
 
-    /// JSON Web Token decoding key, derived from the provided secret.
-    decoding_key: DecodingKey,
+    /// All known signing keys, keyed by their key ID.
+    signing_keys: HashMap<String, JsonWebTokenSigningKey>,
+
+    /// Public keys of all known signing keys in the JSON Web Key Set format.
+    json_web_key_set: JwkSet,
 
     /// A token subject and issuer validator.
     validation: Validation,
 }
 
 impl JsonWebTokenManager {
-    pub fn new(json_web_token_secret: &str) -> Self {
-        let header = Header::new(Algorithm::HS256);
-        let encoding_key = EncodingKey::from_secret(json_web_token_secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(json_web_token_secret.as_bytes());
+    /// Initialize the manager, loading all signing keys (`*.pem` files)
+    /// from the configured signing keys directory.
+    pub fn new(configuration: &JsonWebTokenConfiguration) -> Result<Self> {
+        let directory_entries = fs::read_dir(&configuration.signing_keys_directory_path)
+            .into_diagnostic()
+            .wrap_err("Failed to read signing keys directory.")?;
+
+        let mut signing_keys = Vec::new();
+
+        for directory_entry in directory_entries {
+            let key_file_path = directory_entry
+                .into_diagnostic()
+                .wrap_err("Failed to read signing keys directory entry.")?
+                .path();
+
+            let is_pem_file = key_file_path
+                .extension()
+                .map(|extension| extension == "pem")
+                .unwrap_or(false);
+
+            if !key_file_path.is_file() || !is_pem_file {
+                continue;
+            }
+
+            signing_keys.push(JsonWebTokenSigningKey::load_from_file(
+                &key_file_path,
+            )?);
+        }
+
+        info!(
+            num_keys = signing_keys.len(),
+            signing_key_id = configuration.signing_key_id,
+            "Loaded JSON Web Token signing keys."
+        );
+
+        Self::with_signing_keys(configuration, signing_keys)
+    }
+
+    /// Initialize the manager with the provided signing keys
+    /// (the configured signing keys directory is ignored).
+    pub fn with_signing_keys(
+        configuration: &JsonWebTokenConfiguration,
+        signing_keys: Vec<JsonWebTokenSigningKey>,
+    ) -> Result<Self> {
+        let access_token_lifetime = Duration::from_std(configuration.access_token_lifetime)
+            .into_diagnostic()
+            .wrap_err("Access token lifetime is out of range.")?;
+
+        let refresh_token_lifetime = Duration::from_std(configuration.refresh_token_lifetime)
+            .into_diagnostic()
+            .wrap_err("Refresh token lifetime is out of range.")?;
+
+
+        let mut signing_keys_by_id = HashMap::with_capacity(signing_keys.len());
+
+        for signing_key in signing_keys {
+            if signing_keys_by_id.contains_key(signing_key.key_id()) {
+                return Err(miette!(
+                    "Duplicate JSON Web Token signing key ID: {}.",
+                    signing_key.key_id()
+                ));
+            }
+
+            signing_keys_by_id.insert(signing_key.key_id.clone(), signing_key);
+        }
+
+        let Some(signing_key) = signing_keys_by_id.get(&configuration.signing_key_id) else {
+            return Err(miette!(
+                "Missing JSON Web Token signing key \"{}\" (expected a \"{}.pem\" file \
+                in the signing keys directory). An Ed25519 key can be generated with \
+                `openssl genpkey -algorithm ed25519 -out {}.pem`.",
+                configuration.signing_key_id,
+                configuration.signing_key_id,
+                configuration.signing_key_id,
+            ));
+        };
+
+        let mut header = Header::new(signing_key.algorithm());
+        header.kid = Some(signing_key.key_id().to_string());
+
+
+        let mut public_keys = signing_keys_by_id
+            .values()
+            .map(|signing_key| signing_key.public_key_jwk.clone())
+            .collect::<Vec<_>>();
+
+        // Makes the key set output stable.
+        public_keys.sort_unstable_by(|first, second| first.common.key_id.cmp(&second.common.key_id));
 
-        let mut validation = Validation::new(Algorithm::HS256);
+
+        let mut validation = Validation::default();
 
         // Validate issuer and subject automatically when decoding.
-        validation.set_issuer(&[JWT_ISSUER]);
+        validation.set_issuer(&[&configuration.issuer]);
         validation.sub = Some(JWT_SUBJECT.to_string());
 
         // Disable "expiry" and "not before" validation, we'll do it ourselves
@@ -146,25 +399,103 @@ impl JsonWebTokenManager {
         validation.validate_exp = false;
         validation.validate_nbf = false;
 
-        Self {
+        Ok(Self {
+            issuer: configuration.issuer.clone(),
+            access_token_lifetime,
+            refresh_token_lifetime,
             header,
-            encoding_key,
-            decoding_key,
+            signing_key_id: configuration.signing_key_id.clone(),
+            signing_keys: signing_keys_by_id,
+            json_web_key_set: JwkSet { keys: public_keys },
             validation,
+        })
+    }
+
+    /// Returns how long access tokens are valid for.
+    pub fn access_token_lifetime(&self) -> Duration {
+        self.access_token_lifetime
+    }
+
+    /// Returns how long refresh tokens are valid for.
+    pub fn refresh_token_lifetime(&self) -> Duration {
+        self.refresh_token_lifetime
+    }
+
+    /// Returns the public keys of all known signing keys as a JSON Web Key Set.
+    ///
+    /// This can be published so that other services can verify our tokens.
+    pub fn json_web_key_set(&self) -> &JwkSet {
+        &self.json_web_key_set
+    }
+
+    /// Create new JSON Web Token claims.
+    ///
+    /// The expiration time depends on the token type (see [`Self::access_token_lifetime`]
+    /// and [`Self::refresh_token_lifetime`]).
+    ///
+    /// Note that the `issued_at` timestamp will have its sub-second content truncated
+    /// (see [`trunc_subsecs`][chrono::round::SubsecRound::trunc_subsecs]).
+    pub fn create_claims(
+        &self,
+        user_id: i32,
+        session_id: Uuid,
+        issued_at: DateTime<Utc>,
+        token_type: JWTTokenType,
+    ) -> JWTClaims {
+        let issued_at = issued_at.trunc_subsecs(0);
+        let expires_on = issued_at.add(match token_type {
+            JWTTokenType::Access => self.access_token_lifetime,
+            JWTTokenType::Refresh => self.refresh_token_lifetime,
+        });
+
+        JWTClaims {
+            iss: self.issuer.clone(),
+            sub: JWT_SUBJECT.to_string(),
+            iat: issued_at,
+            exp: expires_on,
+            jti: session_id,
+            user_id,
+            token_type,
         }
     }
 
     /// Create (encode) a new token. Returns a string with the encoded content.
     pub fn create_token(&self, claims: JWTClaims) -> Result<String> {
-        jsonwebtoken::encode(&self.header, &claims, &self.encoding_key)
+        let signing_key = self
+            .signing_keys
+            .get(&self.signing_key_id)
+            .expect("BUG: Signing key has been validated on initialization, but is now missing.");
+
+        jsonwebtoken::encode(&self.header, &claims, &signing_key.encoding_key)
             .into_diagnostic()
             .wrap_err("Failed to create JWT token.")
     }
 
     /// Decode a JSON Web Token from a string.
+    ///
+    /// The token can be signed with any of the known signing keys.
     pub fn decode_token(&self, token: &str) -> Result<JWTClaims, JWTValidationError> {
+        let token_header = jsonwebtoken::decode_header(token)
+            .map_err(|err| format!("Errored while decoding token header: {err}."))
+            .map_err(JWTValidationError::InvalidToken)?;
+
+        let Some(key_id) = token_header.kid else {
+            return Err(JWTValidationError::InvalidToken(
+                "Invalid token: missing key ID.".to_string(),
+            ));
+        };
+
+        let Some(signing_key) = self.signing_keys.get(&key_id) else {
+            return Err(JWTValidationError::InvalidToken(
+                "Invalid token: unknown key ID.".to_string(),
+            ));
+        };
+
+        let mut validation = self.validation.clone();
+        validation.algorithms = vec![signing_key.algorithm()];
+
         let token_data =
-            jsonwebtoken::decode::<JWTClaims>(token, &self.decoding_key, &self.validation)
+            jsonwebtoken::decode::<JWTClaims>(token, &signing_key.decoding_key, &validation)
                 .map_err(|err| match err.kind() {
                     ErrorKind::InvalidIssuer => "Invalid token: invalid issuer.".to_string(),
                     ErrorKind::InvalidSubject => "Invalid token: invalid subject.".to_string(),
@@ -193,29 +524,126 @@ impl JsonWebTokenManager {
 
 #[cfg(test)]
 mod test {
+    use std::path::PathBuf;
+
     use chrono::SubsecRound;
+    use ring::rand::SystemRandom;
 
     use super::*;
 
+    fn generate_ed25519_signing_key(key_id: &str) -> JsonWebTokenSigningKey {
+        let pkcs8_document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem_contents = pem::encode(&pem::Pem::new(
+            "PRIVATE KEY",
+            pkcs8_document.as_ref(),
+        ));
+
+        JsonWebTokenSigningKey::from_pem(key_id, pem_contents.as_bytes()).unwrap()
+    }
+
+    fn testing_configuration(signing_key_id: &str) -> JsonWebTokenConfiguration {
+        JsonWebTokenConfiguration {
+            issuer: "Stari Kolomoni".to_string(),
+            signing_keys_directory_path: PathBuf::new(),
+            signing_key_id: signing_key_id.to_string(),
+            access_token_lifetime: std::time::Duration::from_secs(60),
+            refresh_token_lifetime: std::time::Duration::from_secs(120),
+        }
+    }
+
     #[test]
     fn create_and_validate_token() {
-        let manager = JsonWebTokenManager::new("secret");
+        let manager = JsonWebTokenManager::with_signing_keys(
+            &testing_configuration("primary"),
+            vec![generate_ed25519_signing_key("primary")],
+        )
+        .unwrap();
 
         let issued_at = Utc::now().trunc_subsecs(0);
-        let valid_for = chrono::Duration::from_std(std::time::Duration::from_secs(60)).unwrap();
+        let session_id = Uuid::now_v7();
 
-        let claims = JWTClaims::create(1, issued_at, valid_for, JWTTokenType::Access);
+        let claims = manager.create_claims(1, session_id, issued_at, JWTTokenType::Access);
 
         let encoded_token = manager.create_token(claims).unwrap();
 
+        let token_header = jsonwebtoken::decode_header(&encoded_token).unwrap();
+        assert_eq!(token_header.alg, Algorithm::EdDSA);
+        assert_eq!(token_header.kid.as_deref(), Some("primary"));
+
 
         let decoded_claims = manager.decode_token(&encoded_token).unwrap();
 
-        assert_eq!(decoded_claims.iss, JWT_ISSUER);
+        assert_eq!(decoded_claims.iss, "Stari Kolomoni");
         assert_eq!(decoded_claims.sub, JWT_SUBJECT);
         assert_eq!(decoded_claims.iat, issued_at);
-        assert_eq!(decoded_claims.exp, issued_at + valid_for);
+        assert_eq!(
+            decoded_claims.exp,
+            issued_at + Duration::seconds(60)
+        );
+        assert_eq!(decoded_claims.jti, session_id);
         assert_eq!(decoded_claims.user_id, 1);
         assert_eq!(decoded_claims.token_type, JWTTokenType::Access);
     }
+
+    #[test]
+    fn tokens_signed_with_rotated_out_key_remain_valid() {
+        let mut old_manager = JsonWebTokenManager::with_signing_keys(
+            &testing_configuration("old"),
+            vec![generate_ed25519_signing_key("old")],
+        )
+        .unwrap();
+
+        let old_token = old_manager
+            .create_token(old_manager.create_claims(
+                1,
+                Uuid::now_v7(),
+                Utc::now(),
+                JWTTokenType::Refresh,
+            ))
+            .unwrap();
+
+
+        // The new manager signs with a new key, but still knows the old one.
+        let rotated_manager = JsonWebTokenManager::with_signing_keys(
+            &testing_configuration("new"),
+            vec![
+                old_manager.signing_keys.remove_entry("old").unwrap().1,
+                generate_ed25519_signing_key("new"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(rotated_manager.json_web_key_set().keys.len(), 2);
+
+        let decoded_old_claims = rotated_manager.decode_token(&old_token).unwrap();
+        assert_eq!(
+            decoded_old_claims.token_type,
+            JWTTokenType::Refresh
+        );
+
+        let new_token = rotated_manager
+            .create_token(rotated_manager.create_claims(
+                1,
+                Uuid::now_v7(),
+                Utc::now(),
+                JWTTokenType::Access,
+            ))
+            .unwrap();
+
+        let new_token_header = jsonwebtoken::decode_header(&new_token).unwrap();
+        assert_eq!(new_token_header.kid.as_deref(), Some("new"));
+
+
+        // Once the old key is removed, tokens signed with it are no longer accepted.
+        let manager_without_old_key = JsonWebTokenManager::with_signing_keys(
+            &testing_configuration("new"),
+            vec![generate_ed25519_signing_key("new")],
+        )
+        .unwrap();
+
+        assert!(matches!(
+            manager_without_old_key.decode_token(&old_token),
+            Err(JWTValidationError::InvalidToken(_))
+        ));
+    }
 }
@@ -0,0 +1,350 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use miette::{miette, Result};
+use ring::digest::{digest, SHA256};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+
+
+/// Number of random bytes in a TOTP secret (160 bits, as recommended by RFC 4226).
+const TOTP_SECRET_BYTES: usize = 20;
+
+/// Length of a TOTP time step, in seconds.
+pub const TOTP_STEP_SECONDS: i64 = 30;
+
+/// Number of digits in a TOTP code.
+pub const TOTP_DIGITS: u32 = 6;
+
+/// How many time steps before and after the current one to accept codes from,
+/// to account for clock drift and slow typists.
+const TOTP_ALLOWED_STEP_DRIFT: i64 = 1;
+
+/// Number of random bytes in a recovery code (before encoding).
+const RECOVERY_CODE_BYTES: usize = 5;
+
+/// Number of random bytes in a two-factor login challenge token (before encoding).
+const LOGIN_CHALLENGE_TOKEN_BYTES: usize = 32;
+
+/// The RFC 4648 base32 alphabet.
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+
+/// Encodes bytes as unpadded RFC 4648 base32 (the format authenticator apps expect secrets in).
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 8 / 5 + 1);
+
+    let mut buffer: u32 = 0;
+    let mut buffered_bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        buffered_bits += 8;
+
+        while buffered_bits >= 5 {
+            buffered_bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> buffered_bits) & 0b11111) as usize] as char);
+        }
+    }
+
+    if buffered_bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - buffered_bits)) & 0b11111) as usize] as char);
+    }
+
+    encoded
+}
+
+/// Decodes (optionally padded, case-insensitive) RFC 4648 base32.
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 5 / 8);
+
+    let mut buffer: u32 = 0;
+    let mut buffered_bits = 0;
+
+    for character in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|alphabet_character| *alphabet_character == character.to_ascii_uppercase())?;
+
+        buffer = (buffer << 5) | value as u32;
+        buffered_bits += 5;
+
+        if buffered_bits >= 8 {
+            buffered_bits -= 8;
+            decoded.push((buffer >> buffered_bits) as u8);
+        }
+    }
+
+    Some(decoded)
+}
+
+/// Percent-encodes everything but the unreserved characters (RFC 3986, section 2.3).
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+
+/// Generates a new random TOTP secret, encoded as base32.
+pub fn generate_totp_secret() -> Result<String> {
+    let mut secret_bytes = [0u8; TOTP_SECRET_BYTES];
+
+    SystemRandom::new()
+        .fill(&mut secret_bytes)
+        .map_err(|_| miette!("Failed to generate random bytes for a TOTP secret."))?;
+
+    Ok(base32_encode(&secret_bytes))
+}
+
+/// Builds the `otpauth://` provisioning URI for a TOTP secret.
+///
+/// Authenticator apps can import the secret by scanning a QR code containing this URI.
+pub fn totp_provisioning_uri(issuer: &str, account_name: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS
+    )
+}
+
+/// Returns the TOTP time step the given time falls into.
+pub fn totp_time_step(time: DateTime<Utc>) -> i64 {
+    time.timestamp().div_euclid(TOTP_STEP_SECONDS)
+}
+
+/// Computes the TOTP code for the given time step (RFC 6238 with HMAC-SHA1).
+fn totp_code_for_step(secret: &[u8], time_step: i64, digits: u32) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let hmac_value = hmac::sign(&key, &time_step.to_be_bytes());
+    let hmac_bytes = hmac_value.as_ref();
+
+    // Dynamic truncation, see RFC 4226, section 5.3.
+    let offset = (hmac_bytes[hmac_bytes.len() - 1] & 0x0f) as usize;
+    let binary_code = u32::from_be_bytes([
+        hmac_bytes[offset] & 0x7f,
+        hmac_bytes[offset + 1],
+        hmac_bytes[offset + 2],
+        hmac_bytes[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary_code % 10u32.pow(digits),
+        width = digits as usize
+    )
+}
+
+/// Computes the TOTP code a (base32-encoded) secret produces at the given time,
+/// i.e. the code an authenticator app would show. Returns `None` if the secret is not valid base32.
+pub fn totp_code_at(secret: &str, time: DateTime<Utc>) -> Option<String> {
+    let secret = base32_decode(secret)?;
+
+    Some(totp_code_for_step(
+        &secret,
+        totp_time_step(time),
+        TOTP_DIGITS,
+    ))
+}
+
+/// Checks a TOTP code against a (base32-encoded) secret at the given time.
+///
+/// Codes from the neighbouring time steps are also accepted. To prevent a code from being
+/// used twice, codes from `last_used_time_step` or earlier are rejected.
+///
+/// Returns the time step the code belongs to (to be stored as the new last used step),
+/// or `None` if the code is not valid.
+pub fn verify_totp_code(
+    secret: &str,
+    code: &str,
+    time: DateTime<Utc>,
+    last_used_time_step: Option<i64>,
+) -> Option<i64> {
+    let secret = base32_decode(secret)?;
+
+    let code = code.trim().replace(' ', "");
+    if code.len() != TOTP_DIGITS as usize {
+        return None;
+    }
+
+    let current_time_step = totp_time_step(time);
+
+    (current_time_step - TOTP_ALLOWED_STEP_DRIFT..=current_time_step + TOTP_ALLOWED_STEP_DRIFT)
+        .filter(|time_step| {
+            last_used_time_step
+                .map(|last_used_time_step| *time_step > last_used_time_step)
+                .unwrap_or(true)
+        })
+        .find(|time_step| {
+            // Comparing the strings in constant time is not necessary here: the expected
+            // code changes every time step and the number of attempts is limited.
+            totp_code_for_step(&secret, *time_step, TOTP_DIGITS) == code
+        })
+}
+
+
+/// Generates a single-use recovery code (e.g. `k3v9-x2qa`).
+pub fn generate_recovery_code() -> Result<String> {
+    let mut code_bytes = [0u8; RECOVERY_CODE_BYTES];
+
+    SystemRandom::new()
+        .fill(&mut code_bytes)
+        .map_err(|_| miette!("Failed to generate random bytes for a recovery code."))?;
+
+    let encoded = base32_encode(&code_bytes).to_ascii_lowercase();
+    let (first_half, second_half) = encoded.split_at(encoded.len() / 2);
+
+    Ok(format!("{first_half}-{second_half}"))
+}
+
+/// Hashes a recovery code for storage and lookup (lower-case hexadecimal SHA-256).
+///
+/// The code is normalized first, so it can be entered with or without the dash
+/// and in any letter case.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized_code = code
+        .chars()
+        .filter(|character| character.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    sha256_hex(&normalized_code)
+}
+
+
+/// Generates a new random token identifying a login that is waiting for its second factor.
+///
+/// Only its hash (see [`hash_login_challenge_token`]) should ever be stored.
+pub fn generate_login_challenge_token() -> Result<String> {
+    let mut token_bytes = [0u8; LOGIN_CHALLENGE_TOKEN_BYTES];
+
+    SystemRandom::new()
+        .fill(&mut token_bytes)
+        .map_err(|_| miette!("Failed to generate random bytes for a login challenge token."))?;
+
+    Ok(URL_SAFE_NO_PAD.encode(token_bytes))
+}
+
+/// Hashes a two-factor login challenge token for storage and lookup
+/// (lower-case hexadecimal SHA-256).
+pub fn hash_login_challenge_token(token: &str) -> String {
+    sha256_hex(token)
+}
+
+
+fn sha256_hex(value: &str) -> String {
+    digest(&SHA256, value.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn encodes_and_decodes_base32() {
+        // Test vectors from RFC 4648, section 10.
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_encode(b"fooba"), "MZXW6YTB");
+        assert_eq!(
+            base32_decode("MZXW6YTBOI======").unwrap(),
+            b"foobar"
+        );
+        assert_eq!(base32_decode("mzxw6ytb").unwrap(), b"fooba");
+        assert!(base32_decode("not base32!").is_none());
+
+        let secret = generate_totp_secret().unwrap();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), 20);
+    }
+
+    #[test]
+    fn verifies_totp_codes() {
+        // Test vectors from RFC 6238, appendix B (the SHA-1 ones, truncated to 6 digits).
+        let secret = base32_encode(b"12345678901234567890");
+
+        let time = Utc.timestamp_opt(59, 0).unwrap();
+        assert_eq!(
+            verify_totp_code(&secret, "287082", time, None),
+            Some(totp_time_step(time))
+        );
+
+        let time = Utc.timestamp_opt(1111111109, 0).unwrap();
+        assert_eq!(totp_code_at(&secret, time).unwrap(), "081804");
+
+        let time_step = totp_time_step(time);
+        assert_eq!(
+            verify_totp_code(&secret, "081804", time, None),
+            Some(time_step)
+        );
+
+        // Codes from neighbouring time steps are accepted, but not older ones.
+        let next_time = time + chrono::Duration::seconds(TOTP_STEP_SECONDS);
+        assert_eq!(
+            verify_totp_code(&secret, "081804", next_time, None),
+            Some(time_step)
+        );
+        assert_eq!(
+            verify_totp_code(
+                &secret,
+                "081804",
+                next_time + chrono::Duration::seconds(TOTP_STEP_SECONDS),
+                None
+            ),
+            None
+        );
+
+        // Codes can not be reused.
+        assert_eq!(
+            verify_totp_code(&secret, "081804", time, Some(time_step)),
+            None
+        );
+
+        assert_eq!(
+            verify_totp_code(&secret, "000000", time, None),
+            None
+        );
+    }
+
+    #[test]
+    fn builds_provisioning_uri() {
+        assert_eq!(
+            totp_provisioning_uri("Stari Kolomoni", "janez", "JBSWY3DPEHPK3PXP"),
+            "otpauth://totp/Stari%20Kolomoni:janez?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Stari%20Kolomoni&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn generates_and_hashes_recovery_codes() {
+        let recovery_code = generate_recovery_code().unwrap();
+        assert_eq!(recovery_code.len(), 9);
+        assert_eq!(recovery_code.chars().nth(4), Some('-'));
+
+        assert_eq!(
+            hash_recovery_code(&recovery_code),
+            hash_recovery_code(&recovery_code.replace('-', "").to_uppercase())
+        );
+        assert_ne!(
+            hash_recovery_code(&recovery_code),
+            hash_recovery_code(&generate_recovery_code().unwrap())
+        );
+    }
+}
@@ -4,28 +4,46 @@ use std::path::{Path, PathBuf};
 use miette::{Context, IntoDiagnostic, Result};
 use serde::Deserialize;
 
+mod activity_tracking;
 mod base_paths;
 mod database;
+mod dictionary;
 mod http;
 mod json_web_token;
 mod logging;
+mod login_throttling;
+mod openid_connect;
+mod registration;
 mod search;
 mod secrets;
+mod two_factor_authentication;
 
+pub use activity_tracking::ActivityTrackingConfiguration;
+use activity_tracking::UnresolvedActivityTrackingConfiguration;
 pub use base_paths::BasePathsConfiguration;
 use base_paths::UnresolvedBasePathsConfiguration;
 pub use database::DatabaseConfiguration;
 use database::UnresolvedDatabaseConfiguration;
+pub use dictionary::DictionaryConfiguration;
+use dictionary::UnresolvedDictionaryConfiguration;
 pub use http::HttpConfiguration;
 use http::UnresolvedHttpConfiguration;
 pub use json_web_token::JsonWebTokenConfiguration;
 use json_web_token::UnresolvedJsonWebTokenConfiguration;
 pub use logging::LoggingConfiguration;
 use logging::UnresolvedLoggingConfiguration;
+pub use login_throttling::LoginThrottlingConfiguration;
+use login_throttling::UnresolvedLoginThrottlingConfiguration;
+pub use openid_connect::OpenIdConnectConfiguration;
+use openid_connect::UnresolvedOpenIdConnectConfiguration;
+use registration::UnresolvedRegistrationConfiguration;
+pub use registration::{RegistrationConfiguration, RegistrationMode};
 pub use search::SearchConfiguration;
 use search::UnresolvedSearchConfiguration;
 pub use secrets::SecretsConfiguration;
 use secrets::UnresolvedSecretsConfiguration;
+pub use two_factor_authentication::TwoFactorAuthenticationConfiguration;
+use two_factor_authentication::UnresolvedTwoFactorAuthenticationConfiguration;
 
 use crate::traits::{ResolvableConfiguration, ResolvableConfigurationWithContext};
 use crate::utilities::get_default_configuration_file_path;
@@ -50,6 +68,24 @@ pub(crate) struct UnresolvedConfiguration {
     /// Json Web Token-related configuration.
     json_web_token: UnresolvedJsonWebTokenConfiguration,
 
+    /// Login throttling-related configuration.
+    login_throttling: UnresolvedLoginThrottlingConfiguration,
+
+    /// User activity tracking-related configuration.
+    activity_tracking: UnresolvedActivityTrackingConfiguration,
+
+    /// Two-factor authentication-related configuration.
+    two_factor_authentication: UnresolvedTwoFactorAuthenticationConfiguration,
+
+    /// User registration-related configuration.
+    registration: UnresolvedRegistrationConfiguration,
+
+    /// Dictionary-related configuration.
+    dictionary: UnresolvedDictionaryConfiguration,
+
+    /// OpenID Connect login-related configuration (optional).
+    oidc: Option<UnresolvedOpenIdConnectConfiguration>,
+
     /// Search-related configuration.
     search: UnresolvedSearchConfiguration,
 }
@@ -79,6 +115,25 @@ pub struct Configuration {
     /// Json Web Token-related configuration.
     pub json_web_token: JsonWebTokenConfiguration,
 
+    /// Login throttling-related configuration.
+    pub login_throttling: LoginThrottlingConfiguration,
+
+    /// User activity tracking-related configuration.
+    pub activity_tracking: ActivityTrackingConfiguration,
+
+    /// Two-factor authentication-related configuration.
+    pub two_factor_authentication: TwoFactorAuthenticationConfiguration,
+
+    /// User registration-related configuration.
+    pub registration: RegistrationConfiguration,
+
+    /// Dictionary-related configuration.
+    pub dictionary: DictionaryConfiguration,
+
+    /// OpenID Connect login-related configuration.
+    /// If `None`, users can only log in with their username and password.
+    pub oidc: Option<OpenIdConnectConfiguration>,
+
     /// Search-related configuration.
     pub search: SearchConfiguration,
 }
@@ -116,9 +171,40 @@ impl ResolvableConfigurationWithContext for UnresolvedConfiguration {
 
         let json_web_token = self
             .json_web_token
-            .resolve()
+            .resolve(base_paths.clone())
             .wrap_err("Failed to resolve json_web_token table.")?;
 
+        let login_throttling = self
+            .login_throttling
+            .resolve()
+            .wrap_err("Failed to resolve login_throttling table.")?;
+
+        let activity_tracking = self
+            .activity_tracking
+            .resolve()
+            .wrap_err("Failed to resolve activity_tracking table.")?;
+
+        let two_factor_authentication = self
+            .two_factor_authentication
+            .resolve()
+            .wrap_err("Failed to resolve two_factor_authentication table.")?;
+
+        let registration = self
+            .registration
+            .resolve()
+            .wrap_err("Failed to resolve registration table.")?;
+
+        let dictionary = self
+            .dictionary
+            .resolve()
+            .wrap_err("Failed to resolve dictionary table.")?;
+
+        let oidc = self
+            .oidc
+            .map(|oidc| oidc.resolve())
+            .transpose()
+            .wrap_err("Failed to resolve oidc table.")?;
+
         let search = self
             .search
             .resolve(base_paths.clone())
@@ -133,6 +219,12 @@ impl ResolvableConfigurationWithContext for UnresolvedConfiguration {
             database,
             secrets,
             json_web_token,
+            login_throttling,
+            activity_tracking,
+            two_factor_authentication,
+            registration,
+            dictionary,
+            oidc,
             search,
         })
     }
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use miette::{miette, Result};
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedActivityTrackingConfiguration {
+    pub(super) update_interval_seconds: u64,
+
+    pub(super) flush_interval_seconds: u64,
+}
+
+
+/// User activity tracking-related configuration.
+#[derive(Debug, Clone)]
+pub struct ActivityTrackingConfiguration {
+    /// How often a single user's last activity time can be updated.
+    /// Any activity between two updates is not recorded.
+    pub update_interval: Duration,
+
+    /// How often the recorded activity times are written to the database (in a single batch).
+    pub flush_interval: Duration,
+}
+
+impl ResolvableConfiguration for UnresolvedActivityTrackingConfiguration {
+    type Resolved = ActivityTrackingConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        if self.flush_interval_seconds == 0 {
+            return Err(miette!(
+                "The activity flush interval must be greater than zero."
+            ));
+        }
+
+
+        Ok(ActivityTrackingConfiguration {
+            update_interval: Duration::from_secs(self.update_interval_seconds),
+            flush_interval: Duration::from_secs(self.flush_interval_seconds),
+        })
+    }
+}
@@ -0,0 +1,45 @@
+use miette::{miette, Result};
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedDictionaryConfiguration {
+    pub(super) default_page_size: u64,
+
+    pub(super) maximum_page_size: u64,
+}
+
+
+/// Dictionary-related configuration.
+#[derive(Debug, Clone)]
+pub struct DictionaryConfiguration {
+    /// How many words a page of a word listing contains
+    /// if the caller does not ask for a specific page size.
+    pub default_page_size: u64,
+
+    /// The largest page size a caller can ask for when listing words.
+    pub maximum_page_size: u64,
+}
+
+impl ResolvableConfiguration for UnresolvedDictionaryConfiguration {
+    type Resolved = DictionaryConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        if self.default_page_size == 0 || self.maximum_page_size == 0 {
+            return Err(miette!("Page sizes must be greater than zero."));
+        }
+
+        if self.default_page_size > self.maximum_page_size {
+            return Err(miette!(
+                "The default page size must not be larger than the maximum page size."
+            ));
+        }
+
+
+        Ok(DictionaryConfiguration {
+            default_page_size: self.default_page_size,
+            maximum_page_size: self.maximum_page_size,
+        })
+    }
+}
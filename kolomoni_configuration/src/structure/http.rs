@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use serde::Deserialize;
 
 use crate::traits::ResolvableConfiguration;
@@ -12,6 +14,21 @@ pub struct HttpConfiguration {
 
     /// Port to bind the HTTP server to.
     pub port: usize,
+
+    /// IP addresses of reverse proxies in front of the server.
+    ///
+    /// The `X-Forwarded-For` header is only trusted on connections coming directly
+    /// from one of these addresses. Otherwise the client IP address is
+    /// the address of the connection's peer.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl HttpConfiguration {
+    /// Returns `true` if `ip_address` belongs to one of the trusted reverse proxies.
+    pub fn is_trusted_proxy(&self, ip_address: &IpAddr) -> bool {
+        self.trusted_proxies.contains(ip_address)
+    }
 }
 
 impl ResolvableConfiguration for UnresolvedHttpConfiguration {
@@ -1,20 +1,90 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use miette::{miette, Context, IntoDiagnostic, Result};
 use serde::Deserialize;
 
-use crate::traits::ResolvableConfiguration;
+use super::base_paths::BasePathsConfiguration;
+use crate::{traits::ResolvableConfigurationWithContext, utilities::replace_placeholders_in_path};
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedJsonWebTokenConfiguration {
+    pub(super) issuer: String,
+
+    pub(super) signing_keys_directory_path: String,
 
-pub(crate) type UnresolvedJsonWebTokenConfiguration = JsonWebTokenConfiguration;
+    pub(super) signing_key_id: String,
+
+    pub(super) access_token_lifetime_seconds: u64,
+
+    pub(super) refresh_token_lifetime_seconds: u64,
+}
 
 
 /// JSON Web Token-related configuration.
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct JsonWebTokenConfiguration {
-    pub secret: String,
+    /// Value of the `iss` claim in issued tokens (also required when validating tokens).
+    pub issuer: String,
+
+    /// Directory containing the PEM-encoded private signing keys (Ed25519 or RSA).
+    ///
+    /// Each `*.pem` file is a separate key; the file name (without the extension)
+    /// is the key ID (`kid`). All keys in this directory are accepted when validating tokens.
+    pub signing_keys_directory_path: PathBuf,
+
+    /// ID of the key that new tokens are signed with.
+    pub signing_key_id: String,
+
+    /// How long newly-issued access tokens are valid for.
+    pub access_token_lifetime: Duration,
+
+    /// How long newly-issued refresh tokens (and with them, login sessions) are valid for.
+    pub refresh_token_lifetime: Duration,
 }
 
-impl ResolvableConfiguration for UnresolvedJsonWebTokenConfiguration {
+impl ResolvableConfigurationWithContext for UnresolvedJsonWebTokenConfiguration {
     type Resolved = JsonWebTokenConfiguration;
+    type Context = BasePathsConfiguration;
+
+    fn resolve(self, context: Self::Context) -> Result<Self::Resolved> {
+        let signing_keys_directory_path = replace_placeholders_in_path(
+            Path::new(&self.signing_keys_directory_path),
+            context.placeholders(),
+        );
+
+        if signing_keys_directory_path.exists() && !signing_keys_directory_path.is_dir() {
+            return Err(miette!(
+                "Signing keys directory path exists, but is not a directory!"
+            ));
+        }
+
+        if !signing_keys_directory_path.is_dir() {
+            std::fs::create_dir_all(&signing_keys_directory_path)
+                .into_diagnostic()
+                .wrap_err("Failed to create missing signing keys directory.")?;
+        }
+
+
+        if self.access_token_lifetime_seconds == 0 || self.refresh_token_lifetime_seconds == 0 {
+            return Err(miette!(
+                "Token lifetimes must be greater than zero."
+            ));
+        }
+
+        if self.refresh_token_lifetime_seconds < self.access_token_lifetime_seconds {
+            return Err(miette!(
+                "Refresh token lifetime must not be shorter than the access token lifetime."
+            ));
+        }
+
 
-    fn resolve(self) -> miette::Result<Self::Resolved> {
-        Ok(self)
+        Ok(JsonWebTokenConfiguration {
+            issuer: self.issuer,
+            signing_keys_directory_path,
+            signing_key_id: self.signing_key_id,
+            access_token_lifetime: Duration::from_secs(self.access_token_lifetime_seconds),
+            refresh_token_lifetime: Duration::from_secs(self.refresh_token_lifetime_seconds),
+        })
     }
 }
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use miette::{miette, Result};
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedLoginThrottlingConfiguration {
+    pub(super) failed_attempt_window_seconds: u64,
+
+    pub(super) max_failed_attempts_per_username: u32,
+
+    pub(super) max_failed_attempts_per_ip_address: u32,
+
+    pub(super) initial_lockout_duration_seconds: u64,
+
+    pub(super) max_lockout_duration_seconds: u64,
+}
+
+
+/// Login throttling-related configuration.
+#[derive(Debug, Clone)]
+pub struct LoginThrottlingConfiguration {
+    /// How long failed login attempts are remembered for. Once this much time passes
+    /// since the last failed attempt (and any lockout has ended), the count starts over.
+    pub failed_attempt_window: Duration,
+
+    /// How many failed login attempts for a single username are allowed before it is locked out.
+    pub max_failed_attempts_per_username: u32,
+
+    /// How many failed login attempts from a single IP address are allowed before it is locked out.
+    pub max_failed_attempts_per_ip_address: u32,
+
+    /// How long the first lockout lasts. Each further failed attempt doubles the duration.
+    pub initial_lockout_duration: Duration,
+
+    /// The upper limit for the lockout duration.
+    pub max_lockout_duration: Duration,
+}
+
+impl LoginThrottlingConfiguration {
+    /// Returns how long to lock out a username or IP address after its `failed_attempt_count`-th
+    /// failed login attempt, or `None` if `max_failed_attempts` has not been exceeded yet.
+    ///
+    /// The first attempt over the limit results in the initial lockout duration,
+    /// and the duration doubles with each following attempt (up to the configured maximum).
+    pub fn lockout_duration(
+        &self,
+        failed_attempt_count: u32,
+        max_failed_attempts: u32,
+    ) -> Option<Duration> {
+        if failed_attempt_count < max_failed_attempts {
+            return None;
+        }
+
+        let doublings = failed_attempt_count - max_failed_attempts;
+
+        let lockout_duration = 2u32
+            .checked_pow(doublings)
+            .and_then(|multiplier| self.initial_lockout_duration.checked_mul(multiplier))
+            .unwrap_or(self.max_lockout_duration);
+
+        Some(lockout_duration.min(self.max_lockout_duration))
+    }
+}
+
+impl ResolvableConfiguration for UnresolvedLoginThrottlingConfiguration {
+    type Resolved = LoginThrottlingConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        if self.max_failed_attempts_per_username == 0 || self.max_failed_attempts_per_ip_address == 0
+        {
+            return Err(miette!(
+                "The maximum numbers of failed login attempts must be greater than zero."
+            ));
+        }
+
+        if self.max_lockout_duration_seconds < self.initial_lockout_duration_seconds {
+            return Err(miette!(
+                "Maximum lockout duration must not be shorter than the initial lockout duration."
+            ));
+        }
+
+
+        Ok(LoginThrottlingConfiguration {
+            failed_attempt_window: Duration::from_secs(self.failed_attempt_window_seconds),
+            max_failed_attempts_per_username: self.max_failed_attempts_per_username,
+            max_failed_attempts_per_ip_address: self.max_failed_attempts_per_ip_address,
+            initial_lockout_duration: Duration::from_secs(self.initial_lockout_duration_seconds),
+            max_lockout_duration: Duration::from_secs(self.max_lockout_duration_seconds),
+        })
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use miette::{miette, Result};
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+/// Scope that is always requested, since it is what makes a request an OpenID Connect one.
+const OPENID_SCOPE: &str = "openid";
+
+fn default_scopes() -> Vec<String> {
+    vec![OPENID_SCOPE.to_string(), "profile".to_string()]
+}
+
+fn default_authorization_request_lifetime_seconds() -> u64 {
+    600
+}
+
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedOpenIdConnectConfiguration {
+    pub(super) issuer_url: String,
+
+    pub(super) client_id: String,
+
+    pub(super) client_secret: Option<String>,
+
+    pub(super) redirect_url: String,
+
+    #[serde(default = "default_scopes")]
+    pub(super) scopes: Vec<String>,
+
+    #[serde(default = "default_authorization_request_lifetime_seconds")]
+    pub(super) authorization_request_lifetime_seconds: u64,
+}
+
+
+/// OpenID Connect (single sign-on) login-related configuration.
+#[derive(Debug, Clone)]
+pub struct OpenIdConnectConfiguration {
+    /// Issuer URL of the identity provider (without a trailing slash).
+    /// The provider metadata is discovered at `{issuer_url}/.well-known/openid-configuration`.
+    pub issuer_url: String,
+
+    /// Client ID this server is registered with at the identity provider.
+    pub client_id: String,
+
+    /// Client secret, if the identity provider treats this server as a confidential client.
+    pub client_secret: Option<String>,
+
+    /// URL the identity provider redirects the user back to after they log in
+    /// (usually a page in the frontend that passes the received code on to the API).
+    pub redirect_url: String,
+
+    /// Scopes to request (always includes `openid`).
+    pub scopes: Vec<String>,
+
+    /// How long a started login is valid for, i.e. how much time the user has
+    /// to log in at the identity provider.
+    pub authorization_request_lifetime: Duration,
+}
+
+impl ResolvableConfiguration for UnresolvedOpenIdConnectConfiguration {
+    type Resolved = OpenIdConnectConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        let issuer_url = self.issuer_url.trim_end_matches('/').to_string();
+
+        if !issuer_url.starts_with("https://") && !issuer_url.starts_with("http://") {
+            return Err(miette!(
+                "Issuer URL must be an http:// or https:// URL."
+            ));
+        }
+
+        if self.client_id.is_empty() {
+            return Err(miette!("Client ID must not be empty."));
+        }
+
+        if self.authorization_request_lifetime_seconds == 0 {
+            return Err(miette!(
+                "Authorization request lifetime must be greater than zero."
+            ));
+        }
+
+
+        let mut scopes = self.scopes;
+        if !scopes.iter().any(|scope| scope == OPENID_SCOPE) {
+            scopes.insert(0, OPENID_SCOPE.to_string());
+        }
+
+
+        Ok(OpenIdConnectConfiguration {
+            issuer_url,
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            redirect_url: self.redirect_url,
+            scopes,
+            authorization_request_lifetime: Duration::from_secs(
+                self.authorization_request_lifetime_seconds,
+            ),
+        })
+    }
+}
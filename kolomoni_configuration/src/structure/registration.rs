@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+pub(crate) type UnresolvedRegistrationConfiguration = RegistrationConfiguration;
+
+/// Who is allowed to register a new account.
+#[derive(Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RegistrationMode {
+    /// Anyone can register.
+    #[serde(rename = "open")]
+    Open,
+
+    /// Only callers with a valid invite code can register.
+    #[serde(rename = "invite_only")]
+    InviteOnly,
+
+    /// Nobody can register.
+    #[serde(rename = "closed")]
+    Closed,
+}
+
+/// User registration-related configuration.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RegistrationConfiguration {
+    /// Registration mode (`open`, `invite_only` or `closed`).
+    pub mode: RegistrationMode,
+}
+
+impl ResolvableConfiguration for UnresolvedRegistrationConfiguration {
+    type Resolved = RegistrationConfiguration;
+
+    fn resolve(self) -> miette::Result<Self::Resolved> {
+        Ok(self)
+    }
+}
@@ -4,10 +4,43 @@ use crate::traits::ResolvableConfiguration;
 
 pub(super) type UnresolvedSecretsConfiguration = SecretsConfiguration;
 
+// The defaults are the same as `argon2::Params::DEFAULT_M_COST`, `DEFAULT_T_COST`
+// and `DEFAULT_P_COST` (the parameters recommended by OWASP).
+
+fn default_argon2_memory_cost() -> u32 {
+    19456
+}
+
+fn default_argon2_time_cost() -> u32 {
+    2
+}
+
+fn default_argon2_parallelism() -> u32 {
+    1
+}
+
+
 /// Password hashing-related configuration.
 #[derive(Deserialize, Debug, Clone)]
 pub struct SecretsConfiguration {
-    pub hash_salt: String,
+    /// Salt that was previously shared by all password hashes.
+    ///
+    /// Every new hash now receives its own random salt, so this is only used to recognize
+    /// old hashes: any password still hashed with this salt is rehashed on the user's next login.
+    /// Once all users have logged in at least once, this can safely be removed.
+    pub hash_salt: Option<String>,
+
+    /// Argon2id memory cost, in KiB.
+    #[serde(default = "default_argon2_memory_cost")]
+    pub argon2_memory_cost: u32,
+
+    /// Argon2id time cost (number of iterations).
+    #[serde(default = "default_argon2_time_cost")]
+    pub argon2_time_cost: u32,
+
+    /// Argon2id degree of parallelism (number of lanes).
+    #[serde(default = "default_argon2_parallelism")]
+    pub argon2_parallelism: u32,
 }
 
 impl ResolvableConfiguration for UnresolvedSecretsConfiguration {
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use miette::{miette, Result};
+use serde::Deserialize;
+
+use crate::traits::ResolvableConfiguration;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct UnresolvedTwoFactorAuthenticationConfiguration {
+    pub(super) issuer_name: String,
+
+    pub(super) required_for_privileged_users: bool,
+
+    pub(super) login_challenge_lifetime_seconds: u64,
+
+    pub(super) max_failed_attempts_per_login_challenge: u32,
+}
+
+
+/// Two-factor authentication-related configuration.
+#[derive(Debug, Clone)]
+pub struct TwoFactorAuthenticationConfiguration {
+    /// Name of the service as shown in authenticator apps (the `issuer` of the provisioning URI).
+    pub issuer_name: String,
+
+    /// Whether two-factor authentication is mandatory for privileged users, i.e. users with
+    /// the `administrator` role or any permission to delete content (`*:delete`).
+    ///
+    /// Such users that have not enrolled yet must do so before they can finish logging in.
+    pub required_for_privileged_users: bool,
+
+    /// How long the user has to complete the second login step after entering their password.
+    pub login_challenge_lifetime: Duration,
+
+    /// How many invalid codes can be entered for a single login before it must be started over.
+    pub max_failed_attempts_per_login_challenge: u32,
+}
+
+impl ResolvableConfiguration for UnresolvedTwoFactorAuthenticationConfiguration {
+    type Resolved = TwoFactorAuthenticationConfiguration;
+
+    fn resolve(self) -> Result<Self::Resolved> {
+        let issuer_name = self.issuer_name.trim().to_string();
+        if issuer_name.is_empty() {
+            return Err(miette!("Issuer name must not be empty."));
+        }
+
+        if self.login_challenge_lifetime_seconds == 0 {
+            return Err(miette!(
+                "Login challenge lifetime must be greater than zero."
+            ));
+        }
+
+        if self.max_failed_attempts_per_login_challenge == 0 {
+            return Err(miette!(
+                "The maximum number of failed attempts per login challenge must be greater than zero."
+            ));
+        }
+
+
+        Ok(TwoFactorAuthenticationConfiguration {
+            issuer_name,
+            required_for_privileged_users: self.required_for_privileged_users,
+            login_challenge_lifetime: Duration::from_secs(self.login_challenge_lifetime_seconds),
+            max_failed_attempts_per_login_challenge: self.max_failed_attempts_per_login_challenge,
+        })
+    }
+}
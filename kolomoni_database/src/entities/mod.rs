@@ -3,14 +3,29 @@
 pub mod prelude;
 
 pub mod category;
+pub mod login_throttle;
+pub mod oidc_authorization_request;
 pub mod permission;
 pub mod role;
 pub mod role_permission;
+pub mod two_factor_login_challenge;
 pub mod user;
+pub mod user_api_token;
+pub mod user_api_token_permission;
+pub mod user_invite;
+pub mod user_invite_role;
+pub mod user_oidc_identity;
 pub mod user_role;
+pub mod user_session;
+pub mod user_suspension;
+pub mod user_two_factor;
+pub mod user_two_factor_recovery_code;
 pub mod word;
 pub mod word_category;
 pub mod word_english;
+pub mod word_link;
+pub mod word_reference;
+pub mod word_revision;
 pub mod word_slovene;
 pub mod word_translation;
 pub mod word_translation_suggestion;
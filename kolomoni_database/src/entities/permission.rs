@@ -16,6 +16,7 @@ pub struct Model {
     pub id: i32,
     pub name: String,
     pub description: String,
+    pub last_modified_at: DateTimeWithTimeZone,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -23,6 +24,7 @@ pub enum Column {
     Id,
     Name,
     Description,
+    LastModifiedAt,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -40,6 +42,7 @@ impl PrimaryKeyTrait for PrimaryKey {
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
     RolePermission,
+    UserApiTokenPermission,
 }
 
 impl ColumnTrait for Column {
@@ -49,6 +52,7 @@ impl ColumnTrait for Column {
             Self::Id => ColumnType::Integer.def(),
             Self::Name => ColumnType::String(None).def().unique(),
             Self::Description => ColumnType::String(None).def(),
+            Self::LastModifiedAt => ColumnType::TimestampWithTimeZone.def(),
         }
     }
 }
@@ -57,6 +61,9 @@ impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
             Self::RolePermission => Entity::has_many(super::role_permission::Entity).into(),
+            Self::UserApiTokenPermission => {
+                Entity::has_many(super::user_api_token_permission::Entity).into()
+            }
         }
     }
 }
@@ -67,6 +74,12 @@ impl Related<super::role_permission::Entity> for Entity {
     }
 }
 
+impl Related<super::user_api_token_permission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserApiTokenPermission.def()
+    }
+}
+
 impl Related<super::role::Entity> for Entity {
     fn to() -> RelationDef {
         super::role_permission::Relation::Role.def()
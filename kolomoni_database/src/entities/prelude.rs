@@ -1,14 +1,29 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.12
 
 pub use super::category::Entity as Category;
+pub use super::login_throttle::Entity as LoginThrottle;
+pub use super::oidc_authorization_request::Entity as OidcAuthorizationRequest;
 pub use super::permission::Entity as Permission;
 pub use super::role::Entity as Role;
 pub use super::role_permission::Entity as RolePermission;
+pub use super::two_factor_login_challenge::Entity as TwoFactorLoginChallenge;
 pub use super::user::Entity as User;
+pub use super::user_api_token::Entity as UserApiToken;
+pub use super::user_api_token_permission::Entity as UserApiTokenPermission;
+pub use super::user_invite::Entity as UserInvite;
+pub use super::user_invite_role::Entity as UserInviteRole;
+pub use super::user_oidc_identity::Entity as UserOidcIdentity;
 pub use super::user_role::Entity as UserRole;
+pub use super::user_session::Entity as UserSession;
+pub use super::user_suspension::Entity as UserSuspension;
+pub use super::user_two_factor::Entity as UserTwoFactor;
+pub use super::user_two_factor_recovery_code::Entity as UserTwoFactorRecoveryCode;
 pub use super::word::Entity as Word;
 pub use super::word_category::Entity as WordCategory;
 pub use super::word_english::Entity as WordEnglish;
+pub use super::word_link::Entity as WordLink;
+pub use super::word_reference::Entity as WordReference;
+pub use super::word_revision::Entity as WordRevision;
 pub use super::word_slovene::Entity as WordSlovene;
 pub use super::word_translation::Entity as WordTranslation;
 pub use super::word_translation_suggestion::Entity as WordTranslationSuggestion;
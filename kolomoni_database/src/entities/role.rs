@@ -16,6 +16,7 @@ pub struct Model {
     pub id: i32,
     pub name: String,
     pub description: String,
+    pub last_modified_at: DateTimeWithTimeZone,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -23,6 +24,7 @@ pub enum Column {
     Id,
     Name,
     Description,
+    LastModifiedAt,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -40,6 +42,7 @@ impl PrimaryKeyTrait for PrimaryKey {
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
     RolePermission,
+    UserInviteRole,
     UserRole,
 }
 
@@ -50,6 +53,7 @@ impl ColumnTrait for Column {
             Self::Id => ColumnType::Integer.def(),
             Self::Name => ColumnType::String(None).def(),
             Self::Description => ColumnType::String(None).def(),
+            Self::LastModifiedAt => ColumnType::TimestampWithTimeZone.def(),
         }
     }
 }
@@ -58,6 +62,7 @@ impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
             Self::RolePermission => Entity::has_many(super::role_permission::Entity).into(),
+            Self::UserInviteRole => Entity::has_many(super::user_invite_role::Entity).into(),
             Self::UserRole => Entity::has_many(super::user_role::Entity).into(),
         }
     }
@@ -69,6 +74,12 @@ impl Related<super::role_permission::Entity> for Entity {
     }
 }
 
+impl Related<super::user_invite_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserInviteRole.def()
+    }
+}
+
 impl Related<super::user_role::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserRole.def()
@@ -93,4 +104,13 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::user_invite::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::user_invite_role::Relation::UserInvite.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::user_invite_role::Relation::Role.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
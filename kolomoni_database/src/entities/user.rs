@@ -20,6 +20,8 @@ pub struct Model {
     pub joined_at: DateTimeWithTimeZone,
     pub last_modified_at: DateTimeWithTimeZone,
     pub last_active_at: DateTimeWithTimeZone,
+    pub password_last_changed_at: Option<DateTimeWithTimeZone>,
+    pub deactivated_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -31,6 +33,8 @@ pub enum Column {
     JoinedAt,
     LastModifiedAt,
     LastActiveAt,
+    PasswordLastChangedAt,
+    DeactivatedAt,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -47,7 +51,16 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    OidcAuthorizationRequest,
+    TwoFactorLoginChallenge,
+    UserApiToken,
+    UserInvite,
+    UserOidcIdentity,
     UserRole,
+    UserSession,
+    UserSuspension,
+    UserTwoFactor,
+    UserTwoFactorRecoveryCode,
 }
 
 impl ColumnTrait for Column {
@@ -61,6 +74,8 @@ impl ColumnTrait for Column {
             Self::JoinedAt => ColumnType::TimestampWithTimeZone.def(),
             Self::LastModifiedAt => ColumnType::TimestampWithTimeZone.def(),
             Self::LastActiveAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::PasswordLastChangedAt => ColumnType::TimestampWithTimeZone.def().null(),
+            Self::DeactivatedAt => ColumnType::TimestampWithTimeZone.def().null(),
         }
     }
 }
@@ -68,17 +83,86 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::OidcAuthorizationRequest => {
+                Entity::has_many(super::oidc_authorization_request::Entity).into()
+            }
+            Self::TwoFactorLoginChallenge => {
+                Entity::has_many(super::two_factor_login_challenge::Entity).into()
+            }
+            Self::UserApiToken => Entity::has_many(super::user_api_token::Entity).into(),
+            Self::UserInvite => Entity::has_many(super::user_invite::Entity).into(),
+            Self::UserOidcIdentity => Entity::has_many(super::user_oidc_identity::Entity).into(),
             Self::UserRole => Entity::has_many(super::user_role::Entity).into(),
+            Self::UserSession => Entity::has_many(super::user_session::Entity).into(),
+            Self::UserSuspension => Entity::has_many(super::user_suspension::Entity).into(),
+            Self::UserTwoFactor => Entity::has_one(super::user_two_factor::Entity).into(),
+            Self::UserTwoFactorRecoveryCode => {
+                Entity::has_many(super::user_two_factor_recovery_code::Entity).into()
+            }
         }
     }
 }
 
+impl Related<super::oidc_authorization_request::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OidcAuthorizationRequest.def()
+    }
+}
+
+impl Related<super::two_factor_login_challenge::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TwoFactorLoginChallenge.def()
+    }
+}
+
+impl Related<super::user_api_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserApiToken.def()
+    }
+}
+
+impl Related<super::user_invite::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserInvite.def()
+    }
+}
+
+impl Related<super::user_oidc_identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserOidcIdentity.def()
+    }
+}
+
 impl Related<super::user_role::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserRole.def()
     }
 }
 
+impl Related<super::user_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserSession.def()
+    }
+}
+
+impl Related<super::user_suspension::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserSuspension.def()
+    }
+}
+
+impl Related<super::user_two_factor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTwoFactor.def()
+    }
+}
+
+impl Related<super::user_two_factor_recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTwoFactorRecoveryCode.def()
+    }
+}
+
 impl Related<super::role::Entity> for Entity {
     fn to() -> RelationDef {
         super::user_role::Relation::Role.def()
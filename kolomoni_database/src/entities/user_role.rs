@@ -15,12 +15,18 @@ impl EntityName for Entity {
 pub struct Model {
     pub user_id: i32,
     pub role_id: i32,
+    pub granted_by: Option<i32>,
+    pub granted_at: Option<DateTimeWithTimeZone>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
 pub enum Column {
     UserId,
     RoleId,
+    GrantedBy,
+    GrantedAt,
+    ExpiresAt,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -38,6 +44,7 @@ impl PrimaryKeyTrait for PrimaryKey {
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
+    GrantedByUser,
     Role,
     User,
 }
@@ -48,6 +55,9 @@ impl ColumnTrait for Column {
         match self {
             Self::UserId => ColumnType::Integer.def(),
             Self::RoleId => ColumnType::Integer.def(),
+            Self::GrantedBy => ColumnType::Integer.def().null(),
+            Self::GrantedAt => ColumnType::TimestampWithTimeZone.def().null(),
+            Self::ExpiresAt => ColumnType::TimestampWithTimeZone.def().null(),
         }
     }
 }
@@ -55,6 +65,10 @@ impl ColumnTrait for Column {
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
+            Self::GrantedByUser => Entity::belongs_to(super::user::Entity)
+                .from(Column::GrantedBy)
+                .to(super::user::Column::Id)
+                .into(),
             Self::Role => Entity::belongs_to(super::role::Entity)
                 .from(Column::RoleId)
                 .to(super::role::Column::Id)
@@ -19,6 +19,9 @@ pub struct Model {
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub last_modified_at: DateTimeWithTimeZone,
+    pub part_of_speech: Option<String>,
+    pub number_restriction: Option<String>,
+    pub plural_form: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -29,6 +32,9 @@ pub enum Column {
     Description,
     CreatedAt,
     LastModifiedAt,
+    PartOfSpeech,
+    NumberRestriction,
+    PluralForm,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -60,6 +66,9 @@ impl ColumnTrait for Column {
             Self::Description => ColumnType::String(None).def().null(),
             Self::CreatedAt => ColumnType::TimestampWithTimeZone.def(),
             Self::LastModifiedAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::PartOfSpeech => ColumnType::String(None).def().null(),
+            Self::NumberRestriction => ColumnType::String(None).def().null(),
+            Self::PluralForm => ColumnType::String(None).def().null(),
         }
     }
 }
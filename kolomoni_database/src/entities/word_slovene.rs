@@ -19,6 +19,27 @@ pub struct Model {
     pub description: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub last_modified_at: DateTimeWithTimeZone,
+    pub part_of_speech: Option<String>,
+    pub grammatical_gender: Option<String>,
+    pub number_restriction: Option<String>,
+    pub nominative_singular: Option<String>,
+    pub nominative_dual: Option<String>,
+    pub nominative_plural: Option<String>,
+    pub genitive_singular: Option<String>,
+    pub genitive_dual: Option<String>,
+    pub genitive_plural: Option<String>,
+    pub dative_singular: Option<String>,
+    pub dative_dual: Option<String>,
+    pub dative_plural: Option<String>,
+    pub accusative_singular: Option<String>,
+    pub accusative_dual: Option<String>,
+    pub accusative_plural: Option<String>,
+    pub locative_singular: Option<String>,
+    pub locative_dual: Option<String>,
+    pub locative_plural: Option<String>,
+    pub instrumental_singular: Option<String>,
+    pub instrumental_dual: Option<String>,
+    pub instrumental_plural: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
@@ -29,6 +50,27 @@ pub enum Column {
     Description,
     CreatedAt,
     LastModifiedAt,
+    PartOfSpeech,
+    GrammaticalGender,
+    NumberRestriction,
+    NominativeSingular,
+    NominativeDual,
+    NominativePlural,
+    GenitiveSingular,
+    GenitiveDual,
+    GenitivePlural,
+    DativeSingular,
+    DativeDual,
+    DativePlural,
+    AccusativeSingular,
+    AccusativeDual,
+    AccusativePlural,
+    LocativeSingular,
+    LocativeDual,
+    LocativePlural,
+    InstrumentalSingular,
+    InstrumentalDual,
+    InstrumentalPlural,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
@@ -60,6 +102,27 @@ impl ColumnTrait for Column {
             Self::Description => ColumnType::String(None).def().null(),
             Self::CreatedAt => ColumnType::TimestampWithTimeZone.def(),
             Self::LastModifiedAt => ColumnType::TimestampWithTimeZone.def(),
+            Self::PartOfSpeech => ColumnType::String(None).def().null(),
+            Self::GrammaticalGender => ColumnType::String(None).def().null(),
+            Self::NumberRestriction => ColumnType::String(None).def().null(),
+            Self::NominativeSingular => ColumnType::String(None).def().null(),
+            Self::NominativeDual => ColumnType::String(None).def().null(),
+            Self::NominativePlural => ColumnType::String(None).def().null(),
+            Self::GenitiveSingular => ColumnType::String(None).def().null(),
+            Self::GenitiveDual => ColumnType::String(None).def().null(),
+            Self::GenitivePlural => ColumnType::String(None).def().null(),
+            Self::DativeSingular => ColumnType::String(None).def().null(),
+            Self::DativeDual => ColumnType::String(None).def().null(),
+            Self::DativePlural => ColumnType::String(None).def().null(),
+            Self::AccusativeSingular => ColumnType::String(None).def().null(),
+            Self::AccusativeDual => ColumnType::String(None).def().null(),
+            Self::AccusativePlural => ColumnType::String(None).def().null(),
+            Self::LocativeSingular => ColumnType::String(None).def().null(),
+            Self::LocativeDual => ColumnType::String(None).def().null(),
+            Self::LocativePlural => ColumnType::String(None).def().null(),
+            Self::InstrumentalSingular => ColumnType::String(None).def().null(),
+            Self::InstrumentalDual => ColumnType::String(None).def().null(),
+            Self::InstrumentalPlural => ColumnType::String(None).def().null(),
         }
     }
 }
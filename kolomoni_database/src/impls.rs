@@ -1 +1,4 @@
 mod word;
+mod word_english;
+mod word_link;
+mod word_slovene;
@@ -0,0 +1,43 @@
+use sea_orm::ActiveValue;
+
+use crate::{
+    entities,
+    shared::{EnglishWordGrammar, NumberRestriction, PartOfSpeech},
+};
+
+impl entities::word_english::Model {
+    /// Returns the grammatical information about the word.
+    ///
+    /// Values that aren't recognized (which are never written by
+    /// [`EnglishWordMutation`][crate::mutation::EnglishWordMutation]) are treated as missing.
+    pub fn grammar(&self) -> EnglishWordGrammar {
+        EnglishWordGrammar {
+            part_of_speech: self
+                .part_of_speech
+                .as_deref()
+                .and_then(PartOfSpeech::from_name),
+            number_restriction: self
+                .number_restriction
+                .as_deref()
+                .and_then(NumberRestriction::from_name),
+            plural_form: self.plural_form.clone(),
+        }
+    }
+}
+
+impl entities::word_english::ActiveModel {
+    /// Sets all grammatical fields of the word, including the ones missing from `grammar`.
+    pub fn set_grammar(&mut self, grammar: EnglishWordGrammar) {
+        self.part_of_speech = ActiveValue::Set(
+            grammar
+                .part_of_speech
+                .map(|part_of_speech| part_of_speech.name().to_string()),
+        );
+        self.number_restriction = ActiveValue::Set(
+            grammar
+                .number_restriction
+                .map(|restriction| restriction.name().to_string()),
+        );
+        self.plural_form = ActiveValue::Set(grammar.plural_form);
+    }
+}
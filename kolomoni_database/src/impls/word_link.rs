@@ -0,0 +1,10 @@
+use miette::{miette, Result};
+
+use crate::{entities, shared::WordLinkType};
+
+impl entities::word_link::Model {
+    pub fn link_type(&self) -> Result<WordLinkType> {
+        WordLinkType::from_name(&self.link_type)
+            .ok_or_else(|| miette!("Unrecognized word link type: {}", self.link_type))
+    }
+}
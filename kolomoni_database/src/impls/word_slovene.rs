@@ -0,0 +1,130 @@
+use sea_orm::ActiveValue;
+
+use crate::{
+    entities,
+    shared::{
+        GrammaticalCase,
+        GrammaticalGender,
+        GrammaticalNumber,
+        NumberRestriction,
+        PartOfSpeech,
+        SloveneInflectionTable,
+        SloveneWordGrammar,
+    },
+};
+
+impl entities::word_slovene::Model {
+    /// Returns the grammatical information about the word.
+    ///
+    /// Values that aren't recognized (which are never written by
+    /// [`SloveneWordMutation`][crate::mutation::SloveneWordMutation]) are treated as missing.
+    pub fn grammar(&self) -> SloveneWordGrammar {
+        let mut inflection = SloveneInflectionTable::default();
+        for case in GrammaticalCase::ALL {
+            for number in GrammaticalNumber::ALL {
+                inflection.set_form(
+                    case,
+                    number,
+                    self.inflected_form(case, number).clone(),
+                );
+            }
+        }
+
+        SloveneWordGrammar {
+            part_of_speech: self
+                .part_of_speech
+                .as_deref()
+                .and_then(PartOfSpeech::from_name),
+            gender: self
+                .grammatical_gender
+                .as_deref()
+                .and_then(GrammaticalGender::from_name),
+            number_restriction: self
+                .number_restriction
+                .as_deref()
+                .and_then(NumberRestriction::from_name),
+            inflection,
+        }
+    }
+
+    fn inflected_form(&self, case: GrammaticalCase, number: GrammaticalNumber) -> &Option<String> {
+        use GrammaticalCase::*;
+        use GrammaticalNumber::*;
+
+        match (case, number) {
+            (Nominative, Singular) => &self.nominative_singular,
+            (Nominative, Dual) => &self.nominative_dual,
+            (Nominative, Plural) => &self.nominative_plural,
+            (Genitive, Singular) => &self.genitive_singular,
+            (Genitive, Dual) => &self.genitive_dual,
+            (Genitive, Plural) => &self.genitive_plural,
+            (Dative, Singular) => &self.dative_singular,
+            (Dative, Dual) => &self.dative_dual,
+            (Dative, Plural) => &self.dative_plural,
+            (Accusative, Singular) => &self.accusative_singular,
+            (Accusative, Dual) => &self.accusative_dual,
+            (Accusative, Plural) => &self.accusative_plural,
+            (Locative, Singular) => &self.locative_singular,
+            (Locative, Dual) => &self.locative_dual,
+            (Locative, Plural) => &self.locative_plural,
+            (Instrumental, Singular) => &self.instrumental_singular,
+            (Instrumental, Dual) => &self.instrumental_dual,
+            (Instrumental, Plural) => &self.instrumental_plural,
+        }
+    }
+}
+
+impl entities::word_slovene::ActiveModel {
+    /// Sets all grammatical fields of the word, including the ones missing from `grammar`.
+    pub fn set_grammar(&mut self, grammar: SloveneWordGrammar) {
+        self.part_of_speech = ActiveValue::Set(
+            grammar
+                .part_of_speech
+                .map(|part_of_speech| part_of_speech.name().to_string()),
+        );
+        self.grammatical_gender =
+            ActiveValue::Set(grammar.gender.map(|gender| gender.name().to_string()));
+        self.number_restriction = ActiveValue::Set(
+            grammar
+                .number_restriction
+                .map(|restriction| restriction.name().to_string()),
+        );
+
+        for case in GrammaticalCase::ALL {
+            for number in GrammaticalNumber::ALL {
+                *self.inflected_form_mut(case, number) =
+                    ActiveValue::Set(grammar.inflection.form(case, number).map(str::to_string));
+            }
+        }
+    }
+
+    fn inflected_form_mut(
+        &mut self,
+        case: GrammaticalCase,
+        number: GrammaticalNumber,
+    ) -> &mut ActiveValue<Option<String>> {
+        use GrammaticalCase::*;
+        use GrammaticalNumber::*;
+
+        match (case, number) {
+            (Nominative, Singular) => &mut self.nominative_singular,
+            (Nominative, Dual) => &mut self.nominative_dual,
+            (Nominative, Plural) => &mut self.nominative_plural,
+            (Genitive, Singular) => &mut self.genitive_singular,
+            (Genitive, Dual) => &mut self.genitive_dual,
+            (Genitive, Plural) => &mut self.genitive_plural,
+            (Dative, Singular) => &mut self.dative_singular,
+            (Dative, Dual) => &mut self.dative_dual,
+            (Dative, Plural) => &mut self.dative_plural,
+            (Accusative, Singular) => &mut self.accusative_singular,
+            (Accusative, Dual) => &mut self.accusative_dual,
+            (Accusative, Plural) => &mut self.accusative_plural,
+            (Locative, Singular) => &mut self.locative_singular,
+            (Locative, Dual) => &mut self.locative_dual,
+            (Locative, Plural) => &mut self.locative_plural,
+            (Instrumental, Singular) => &mut self.instrumental_singular,
+            (Instrumental, Dual) => &mut self.instrumental_dual,
+            (Instrumental, Plural) => &mut self.instrumental_plural,
+        }
+    }
+}
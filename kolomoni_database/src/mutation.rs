@@ -1,19 +1,47 @@
 mod category;
+mod dictionary_import;
+mod login_throttle;
+mod oidc_authorization_request;
+mod role;
+mod two_factor_login_challenge;
 mod user;
+mod user_api_token;
+mod user_invite;
+mod user_oidc_identity;
 mod user_role;
+mod user_session;
+mod user_suspension;
+mod user_two_factor;
 mod word;
 mod word_category;
 mod word_english;
+mod word_link;
+mod word_reference;
+mod word_revision;
 mod word_slovene;
 mod word_translation;
 mod word_translation_suggestion;
 
 pub use category::*;
+pub use dictionary_import::*;
+pub use login_throttle::*;
+pub use oidc_authorization_request::*;
+pub use role::*;
+pub use two_factor_login_challenge::*;
 pub use user::*;
+pub use user_api_token::*;
+pub use user_invite::*;
+pub use user_oidc_identity::*;
 pub use user_role::*;
+pub use user_session::*;
+pub use user_suspension::*;
+pub use user_two_factor::*;
 pub use word::*;
 pub use word_category::*;
 pub use word_english::*;
+pub use word_link::*;
+pub use word_reference::*;
+pub use word_revision::*;
 pub use word_slovene::*;
 pub use word_translation::*;
 pub use word_translation_suggestion::*;
@@ -0,0 +1,427 @@
+use std::collections::{HashMap, HashSet};
+
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ConnectionTrait, TransactionTrait};
+use uuid::Uuid;
+
+use super::{
+    EnglishWordMutation,
+    NewEnglishWord,
+    NewSloveneWord,
+    NewTranslation,
+    SloveneWordMutation,
+    TranslationMutation,
+    WordCategoryMutation,
+};
+use crate::{
+    begin_transaction,
+    commit_transaction,
+    entities::word_english,
+    query::{
+        CategoriesQueryOptions,
+        CategoryQuery,
+        EnglishWordQuery,
+        SloveneWordQuery,
+        TranslationQuery,
+        WordCategoryQuery,
+    },
+};
+
+
+/// A single dictionary entry to import: an english word
+/// along with its slovene translations and categories.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DictionaryImportRow {
+    pub english_lemma: String,
+    pub english_disambiguation: Option<String>,
+    pub english_description: Option<String>,
+
+    /// Lemmas of the slovene translations. Slovene words that
+    /// don't exist yet are created (with only a lemma).
+    ///
+    /// If there are several slovene words with the lemma (homonyms), the one without
+    /// a disambiguation is used, or the oldest one if they all have a disambiguation.
+    pub slovene_lemmas: Vec<String>,
+
+    /// Categories (by either their english or slovene name) to add to the english word.
+    /// Categories are never created by an import, they must already exist.
+    pub category_names: Vec<String>,
+}
+
+
+/// How imported rows are matched to existing english words.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DictionaryImportMatching {
+    /// Rows are matched by lemma. If the disambiguation or description of a row
+    /// differ from the matched word, the row is conflicting.
+    ///
+    /// If there are several words with the lemma (homonyms), the row is matched to the one
+    /// with the same disambiguation. A row without a disambiguation can't be matched
+    /// to one of several homonyms, so it is conflicting as well.
+    Lemma,
+
+    /// Rows are matched by lemma and disambiguation (descriptions are not compared).
+    /// A row whose lemma only belongs to words with a different disambiguation
+    /// creates a new homonym.
+    LemmaAndDisambiguation,
+}
+
+
+/// What happened (or, on a dry run, would have happened) to a single imported row.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DictionaryImportRowOutcome {
+    /// The english word did not exist yet and was created.
+    New { english_word_id: Uuid },
+
+    /// The english word already existed and matched the row,
+    /// but some translations or categories were missing and have been added.
+    Updated { english_word_id: Uuid },
+
+    /// The english word already existed, matched the row
+    /// and already had all of its translations and categories.
+    Unchanged { english_word_id: Uuid },
+
+    /// The english word already existed, but does not match the row
+    /// (see [`DictionaryImportMatching`]). Such rows are skipped (the existing word is left as is).
+    Conflicting {
+        english_word_id: Uuid,
+        conflicting_fields: Vec<&'static str>,
+    },
+
+    /// The row can not be imported. Any invalid row prevents the import from being committed.
+    Invalid { errors: Vec<String> },
+}
+
+
+/// Result of a dictionary import.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DictionaryImportResult {
+    /// Outcomes of each row, in the same order as the rows were given.
+    pub row_outcomes: Vec<DictionaryImportRowOutcome>,
+
+    /// Whether the import has actually been written to the database
+    /// (it isn't on dry runs or when some rows are invalid).
+    pub committed: bool,
+
+    /// English words that have been created or changed by the import.
+    pub changed_english_word_ids: Vec<Uuid>,
+
+    /// Slovene words that have been created or changed by the import.
+    pub changed_slovene_word_ids: Vec<Uuid>,
+}
+
+
+
+pub struct DictionaryImportMutation;
+
+impl DictionaryImportMutation {
+    /// Import dictionary entries, creating english and slovene words, translations
+    /// and word categories as needed.
+    ///
+    /// Rows are matched to existing english words as specified by `matching`.
+    /// With [`DictionaryImportMatching::Lemma`], fields left empty in a row
+    /// are not compared against the existing word, which means rows can be used
+    /// to simply add translations or categories to existing words.
+    ///
+    /// Words created by the import are recorded as authored by `author_user_id`, if known.
+    ///
+    /// The entire import runs in a single transaction. It is only committed if
+    /// `dry_run` is `false` and no row is invalid, otherwise it is rolled back
+    /// (the outcomes are still reported as if it had been committed).
+    pub async fn import<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        rows: Vec<DictionaryImportRow>,
+        matching: DictionaryImportMatching,
+        author_user_id: Option<i32>,
+        dry_run: bool,
+    ) -> Result<DictionaryImportResult> {
+        let transaction = begin_transaction!(database)?;
+
+        let category_ids_by_name = Self::category_ids_by_name(&transaction).await?;
+
+
+        let mut row_outcomes = Vec::with_capacity(rows.len());
+        let mut changed_english_word_ids = HashSet::new();
+        let mut changed_slovene_word_ids = HashSet::new();
+
+        let mut seen_english_words = HashSet::new();
+
+        for row in rows {
+            let mut errors = Vec::new();
+
+            if !seen_english_words.insert((
+                row.english_lemma.clone(),
+                row.english_disambiguation.clone(),
+            )) {
+                errors.push(match &row.english_disambiguation {
+                    Some(disambiguation) => format!(
+                        "The english lemma \"{}\" with the disambiguation \"{}\" \
+                        appears more than once in the import.",
+                        row.english_lemma, disambiguation
+                    ),
+                    None => format!(
+                        "The english lemma \"{}\" appears more than once in the import.",
+                        row.english_lemma
+                    ),
+                });
+            }
+
+            let mut category_ids = Vec::with_capacity(row.category_names.len());
+            for category_name in &row.category_names {
+                match category_ids_by_name.get(category_name) {
+                    Some(category_id) => category_ids.push(*category_id),
+                    None => errors.push(format!("Unknown category: \"{}\".", category_name)),
+                }
+            }
+
+            if !errors.is_empty() {
+                row_outcomes.push(DictionaryImportRowOutcome::Invalid { errors });
+                continue;
+            }
+
+
+            let outcome = Self::import_row(
+                &transaction,
+                row,
+                category_ids,
+                matching,
+                author_user_id,
+                &mut changed_slovene_word_ids,
+            )
+            .await?;
+
+            if let DictionaryImportRowOutcome::New { english_word_id }
+            | DictionaryImportRowOutcome::Updated { english_word_id } = &outcome
+            {
+                changed_english_word_ids.insert(*english_word_id);
+            }
+
+            row_outcomes.push(outcome);
+        }
+
+
+        let has_invalid_rows = row_outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, DictionaryImportRowOutcome::Invalid { .. }));
+
+        let committed = !dry_run && !has_invalid_rows;
+
+        if committed {
+            commit_transaction!(transaction)?;
+        } else {
+            transaction
+                .rollback()
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed to roll back dictionary import transaction.")?;
+        }
+
+
+        Ok(DictionaryImportResult {
+            row_outcomes,
+            committed,
+            changed_english_word_ids: changed_english_word_ids.into_iter().collect(),
+            changed_slovene_word_ids: changed_slovene_word_ids.into_iter().collect(),
+        })
+    }
+
+    /// Maps both english and slovene category names to their category IDs.
+    async fn category_ids_by_name<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+    ) -> Result<HashMap<String, i32>> {
+        let categories = CategoryQuery::all(database, CategoriesQueryOptions::default())
+            .await
+            .wrap_err("Failed while loading categories for dictionary import.")?;
+
+        let mut category_ids_by_name = HashMap::with_capacity(categories.len() * 2);
+        for category in categories {
+            category_ids_by_name.insert(category.english_name, category.id);
+            category_ids_by_name.insert(category.slovene_name, category.id);
+        }
+
+        Ok(category_ids_by_name)
+    }
+
+    async fn import_row<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        row: DictionaryImportRow,
+        category_ids: Vec<i32>,
+        matching: DictionaryImportMatching,
+        author_user_id: Option<i32>,
+        changed_slovene_word_ids: &mut HashSet<Uuid>,
+    ) -> Result<DictionaryImportRowOutcome> {
+        let (existing_english_word, homonym_count) =
+            Self::matching_english_word(database, &row, matching).await?;
+
+        let (english_word_id, is_new_word) = match existing_english_word {
+            Some(existing_word) => {
+                let conflicting_fields =
+                    Self::conflicting_fields(&existing_word, homonym_count, &row, matching);
+                if !conflicting_fields.is_empty() {
+                    return Ok(DictionaryImportRowOutcome::Conflicting {
+                        english_word_id: existing_word.word_id,
+                        conflicting_fields,
+                    });
+                }
+
+                (existing_word.word_id, false)
+            }
+            None => {
+                let new_word = EnglishWordMutation::create(
+                    database,
+                    NewEnglishWord {
+                        lemma: row.english_lemma,
+                        disambiguation: row.english_disambiguation,
+                        description: row.english_description,
+                        grammar: Default::default(),
+                    },
+                    author_user_id,
+                )
+                .await
+                .wrap_err("Failed while creating english word during dictionary import.")?
+                .ok_or_else(|| {
+                    miette!("English word was created concurrently during dictionary import.")
+                })?;
+
+                (new_word.word_id, true)
+            }
+        };
+
+
+        let mut has_added_anything = false;
+
+        for slovene_lemma in row.slovene_lemmas {
+            let slovene_homonyms =
+                SloveneWordQuery::words_by_lemma(database, slovene_lemma.clone()).await?;
+
+            let existing_slovene_word = slovene_homonyms
+                .iter()
+                .find(|word| word.disambiguation.is_none())
+                .or_else(|| slovene_homonyms.first());
+
+            let slovene_word_id = match existing_slovene_word {
+                Some(existing_slovene_word) => existing_slovene_word.word_id,
+                None => {
+                    let new_slovene_word = SloveneWordMutation::create(
+                        database,
+                        NewSloveneWord {
+                            lemma: slovene_lemma,
+                            disambiguation: None,
+                            description: None,
+                            grammar: Default::default(),
+                        },
+                        author_user_id,
+                    )
+                    .await
+                    .wrap_err("Failed while creating slovene word during dictionary import.")?
+                    .ok_or_else(|| {
+                        miette!("Slovene word was created concurrently during dictionary import.")
+                    })?;
+
+                    new_slovene_word.word_id
+                }
+            };
+
+            let translation_exists =
+                TranslationQuery::exists(database, english_word_id, slovene_word_id).await?;
+
+            if !translation_exists {
+                TranslationMutation::create(
+                    database,
+                    NewTranslation {
+                        english_word_id,
+                        slovene_word_id,
+                    },
+                )
+                .await
+                .wrap_err("Failed while creating translation during dictionary import.")?;
+
+                changed_slovene_word_ids.insert(slovene_word_id);
+                has_added_anything = true;
+            }
+        }
+
+        for category_id in category_ids {
+            let already_has_category =
+                WordCategoryQuery::word_has_category(database, english_word_id, category_id)
+                    .await?;
+
+            if !already_has_category {
+                WordCategoryMutation::add_category_to_word(database, english_word_id, category_id)
+                    .await
+                    .wrap_err("Failed while adding word category during dictionary import.")?;
+
+                has_added_anything = true;
+            }
+        }
+
+
+        Ok(match (is_new_word, has_added_anything) {
+            (true, _) => DictionaryImportRowOutcome::New { english_word_id },
+            (false, true) => DictionaryImportRowOutcome::Updated { english_word_id },
+            (false, false) => DictionaryImportRowOutcome::Unchanged { english_word_id },
+        })
+    }
+
+    /// Finds the existing english word the `row` should be matched to
+    /// (see [`DictionaryImportMatching`]).
+    ///
+    /// Also returns the number of existing english words with the lemma of the row.
+    /// If no word is returned, a new english word should be created for the row.
+    async fn matching_english_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        row: &DictionaryImportRow,
+        matching: DictionaryImportMatching,
+    ) -> Result<(Option<word_english::Model>, usize)> {
+        let mut homonyms =
+            EnglishWordQuery::words_by_lemma(database, row.english_lemma.clone()).await?;
+        let homonym_count = homonyms.len();
+
+        let matching_homonym_index = homonyms
+            .iter()
+            .position(|word| word.disambiguation == row.english_disambiguation);
+
+        let matched_word = match (matching, matching_homonym_index) {
+            (_, Some(index)) => Some(homonyms.swap_remove(index)),
+            (DictionaryImportMatching::LemmaAndDisambiguation, None) => None,
+            // If there is no homonym with the same disambiguation,
+            // the row is compared to (and conflicts with) the oldest one.
+            (DictionaryImportMatching::Lemma, None) => homonyms.into_iter().next(),
+        };
+
+        Ok((matched_word, homonym_count))
+    }
+
+    /// Returns the names of fields in which the `row` doesn't match
+    /// the existing english word with the same lemma.
+    fn conflicting_fields(
+        existing_word: &word_english::Model,
+        homonym_count: usize,
+        row: &DictionaryImportRow,
+        matching: DictionaryImportMatching,
+    ) -> Vec<&'static str> {
+        if matching == DictionaryImportMatching::LemmaAndDisambiguation {
+            // The word has been matched by both, so there is nothing else to compare.
+            return Vec::new();
+        }
+
+
+        let mut conflicting_fields = Vec::new();
+
+        // A row without a disambiguation only matches a word with a disambiguation
+        // if there are no other homonyms (see `matching_english_word`).
+        if row.english_disambiguation != existing_word.disambiguation
+            && (row.english_disambiguation.is_some() || homonym_count > 1)
+        {
+            conflicting_fields.push("disambiguation");
+        }
+
+        if row.english_description.is_some()
+            && row.english_description != existing_word.description
+        {
+            conflicting_fields.push("description");
+        }
+
+        conflicting_fields
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{Duration, Utc};
+use kolomoni_configuration::LoginThrottlingConfiguration;
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::{Condition, OnConflict};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    QuerySelect,
+    TransactionTrait,
+};
+
+use crate::{
+    begin_transaction,
+    commit_transaction,
+    entities::login_throttle,
+    shared::LoginThrottleSubjectType,
+};
+
+
+/// Mutations for the [`crate::entities::login_throttle::Entity`] entity.
+pub struct LoginThrottleMutation;
+
+impl LoginThrottleMutation {
+    /// Record a failed login attempt for the given username or IP address,
+    /// locking it out if it has exceeded `max_failed_attempts`.
+    ///
+    /// Failed attempts are forgotten once `failed_attempt_window` passes since the last one
+    /// (or since the end of the lockout, if that is later).
+    ///
+    /// As a form of housekeeping, this also removes any throttles that have been forgotten.
+    pub async fn record_failed_attempt<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        configuration: &LoginThrottlingConfiguration,
+        subject_type: LoginThrottleSubjectType,
+        subject: &str,
+        max_failed_attempts: u32,
+    ) -> Result<login_throttle::Model> {
+        let current_time = Utc::now();
+        let failed_attempt_window = Duration::from_std(configuration.failed_attempt_window)
+            .into_diagnostic()
+            .wrap_err("Failed attempt window is out of range.")?;
+
+        let transaction = begin_transaction!(database)?;
+
+        Self::delete_stale_throttles(&transaction, failed_attempt_window)
+            .await
+            .wrap_err("Failed to remove stale login throttles before recording a failed attempt.")?;
+
+
+        // Ensure the row exists and lock it, so concurrent failed attempts are all counted.
+        login_throttle::Entity::insert(login_throttle::ActiveModel {
+            subject_type: ActiveValue::Set(subject_type.name().to_string()),
+            subject: ActiveValue::Set(subject.to_string()),
+            failed_attempt_count: ActiveValue::Set(0),
+            last_failed_attempt_at: ActiveValue::Set(current_time.fixed_offset()),
+            locked_until: ActiveValue::Set(None),
+        })
+        .on_conflict(OnConflict::new().do_nothing().to_owned())
+        .exec_without_returning(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting login throttle into the database.")?;
+
+        let throttle = login_throttle::Entity::find_by_id((
+            subject_type.name().to_string(),
+            subject.to_string(),
+        ))
+        .lock_exclusive()
+        .one(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while searching database for login throttle.")?
+        .ok_or_else(|| miette!("Login throttle disappeared while recording a failed attempt."))?;
+
+
+        let forget_failed_attempts_after = throttle
+            .locked_until
+            .map(|locked_until| locked_until.max(throttle.last_failed_attempt_at))
+            .unwrap_or(throttle.last_failed_attempt_at)
+            + failed_attempt_window;
+
+        let failed_attempt_count = if forget_failed_attempts_after < current_time {
+            1
+        } else {
+            u32::try_from(throttle.failed_attempt_count).unwrap_or_default() + 1
+        };
+
+        let locked_until = configuration
+            .lockout_duration(failed_attempt_count, max_failed_attempts)
+            .map(Duration::from_std)
+            .transpose()
+            .into_diagnostic()
+            .wrap_err("Lockout duration is out of range.")?
+            .map(|lockout_duration| (current_time + lockout_duration).fixed_offset());
+
+
+        let updated_throttle = login_throttle::ActiveModel {
+            subject_type: ActiveValue::Unchanged(throttle.subject_type),
+            subject: ActiveValue::Unchanged(throttle.subject),
+            failed_attempt_count: ActiveValue::Set(
+                i32::try_from(failed_attempt_count).unwrap_or(i32::MAX),
+            ),
+            last_failed_attempt_at: ActiveValue::Set(current_time.fixed_offset()),
+            locked_until: ActiveValue::Set(locked_until),
+        }
+        .update(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while updating login throttle.")?;
+
+
+        commit_transaction!(transaction)?;
+        Ok(updated_throttle)
+    }
+
+    /// Forget all failed login attempts for the given username or IP address,
+    /// lifting any lockout.
+    ///
+    /// Returns `true` if there were any.
+    pub async fn clear<C: ConnectionTrait>(
+        database: &C,
+        subject_type: LoginThrottleSubjectType,
+        subject: &str,
+    ) -> Result<bool> {
+        let delete_result = login_throttle::Entity::delete_by_id((
+            subject_type.name().to_string(),
+            subject.to_string(),
+        ))
+        .exec(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while deleting login throttle.")?;
+
+        Ok(delete_result.rows_affected == 1)
+    }
+
+    /// Delete all throttles whose failed attempts have been forgotten,
+    /// i.e. those without failed attempts or lockouts within the last `failed_attempt_window`.
+    async fn delete_stale_throttles<C: ConnectionTrait>(
+        database: &C,
+        failed_attempt_window: Duration,
+    ) -> Result<()> {
+        let cutoff_time = (Utc::now() - failed_attempt_window).fixed_offset();
+
+        login_throttle::Entity::delete_many()
+            .filter(login_throttle::Column::LastFailedAttemptAt.lt(cutoff_time))
+            .filter(
+                Condition::any()
+                    .add(login_throttle::Column::LockedUntil.is_null())
+                    .add(login_throttle::Column::LockedUntil.lt(cutoff_time)),
+            )
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting stale login throttles.")?;
+
+        Ok(())
+    }
+}
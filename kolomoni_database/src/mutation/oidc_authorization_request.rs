@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+
+use crate::entities::oidc_authorization_request;
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Information about a newly-started OpenID Connect login.
+pub struct NewOidcAuthorizationRequest {
+    /// The OAuth 2.0 `state` value the identity provider will pass back to us.
+    pub state: String,
+
+    /// The PKCE code verifier (the identity provider only gets its challenge).
+    pub code_verifier: String,
+
+    /// The nonce the ID token must contain.
+    pub nonce: String,
+
+    /// ID of the user the identity should be linked to, if this is not a plain login.
+    pub linking_user_id: Option<i32>,
+
+    /// When the login must be completed by.
+    pub expires_at: DateTime<Utc>,
+}
+
+
+/// Mutations for the [`crate::entities::oidc_authorization_request::Entity`] entity.
+pub struct OidcAuthorizationRequestMutation;
+
+impl OidcAuthorizationRequestMutation {
+    /// Record a newly-started OpenID Connect login.
+    ///
+    /// As a form of housekeeping, this also removes any expired (abandoned) logins.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        new_request: NewOidcAuthorizationRequest,
+    ) -> Result<oidc_authorization_request::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        oidc_authorization_request::Entity::delete_many()
+            .filter(oidc_authorization_request::Column::ExpiresAt.lte(Utc::now().fixed_offset()))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting expired OpenID Connect authorization requests.")?;
+
+        let authorization_request = oidc_authorization_request::ActiveModel {
+            state: ActiveValue::Set(new_request.state),
+            code_verifier: ActiveValue::Set(new_request.code_verifier),
+            nonce: ActiveValue::Set(new_request.nonce),
+            linking_user_id: ActiveValue::Set(new_request.linking_user_id),
+            created_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            expires_at: ActiveValue::Set(new_request.expires_at.fixed_offset()),
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err(
+            "Failed while inserting OpenID Connect authorization request into the database.",
+        )?;
+
+        commit_transaction!(transaction)?;
+        Ok(authorization_request)
+    }
+
+    /// Remove and return the OpenID Connect login with the given `state`.
+    ///
+    /// Returns `None` if there is no such login or if it has expired.
+    /// Each login can only be consumed once, even if this is called concurrently.
+    pub async fn consume<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        state: &str,
+    ) -> Result<Option<oidc_authorization_request::Model>> {
+        let transaction = begin_transaction!(database)?;
+
+        let authorization_request =
+            oidc_authorization_request::Entity::find_by_id(state.to_string())
+                .one(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while looking up OpenID Connect authorization request.")?;
+
+        let Some(authorization_request) = authorization_request else {
+            return Ok(None);
+        };
+
+        let delete_result = oidc_authorization_request::Entity::delete_by_id(state.to_string())
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting OpenID Connect authorization request.")?;
+
+        commit_transaction!(transaction)?;
+
+
+        if delete_result.rows_affected != 1
+            || authorization_request.expires_at <= Utc::now().fixed_offset()
+        {
+            return Ok(None);
+        }
+
+        Ok(Some(authorization_request))
+    }
+}
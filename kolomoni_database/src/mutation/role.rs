@@ -0,0 +1,175 @@
+use chrono::Utc;
+use kolomoni_auth::Permission;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    sea_query::Expr,
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+
+use crate::entities::{role, role_permission};
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Information about a new role.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NewRole {
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<Permission>,
+}
+
+
+/// Changes to an existing role. Fields that are `None` are left unchanged.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UpdatedRole {
+    pub name: Option<String>,
+    pub description: Option<String>,
+
+    /// If set, replaces the entire list of permissions the role grants.
+    pub permissions: Option<Vec<Permission>>,
+}
+
+
+/// Mutations for the [`crate::entities::role::Entity`] entity.
+pub struct RoleMutation;
+
+impl RoleMutation {
+    /// Create a new role that grants the given permissions.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        new_role: NewRole,
+    ) -> Result<role::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        let role = role::ActiveModel {
+            name: ActiveValue::Set(new_role.name),
+            description: ActiveValue::Set(new_role.description),
+            last_modified_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            ..Default::default()
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting new role into the database.")?;
+
+        Self::insert_role_permissions(&transaction, role.id, &new_role.permissions).await?;
+
+        commit_transaction!(transaction)?;
+        Ok(role)
+    }
+
+    /// Update the name, description and/or permissions of an existing role.
+    pub async fn update<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        role_id: i32,
+        update: UpdatedRole,
+    ) -> Result<role::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        let mut active_role = role::ActiveModel {
+            id: ActiveValue::Unchanged(role_id),
+            last_modified_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            ..Default::default()
+        };
+
+        if let Some(updated_name) = update.name {
+            active_role.name = ActiveValue::Set(updated_name);
+        }
+
+        if let Some(updated_description) = update.description {
+            active_role.description = ActiveValue::Set(updated_description);
+        }
+
+        let updated_role = active_role
+            .update(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while updating role in database.")?;
+
+
+        if let Some(updated_permissions) = update.permissions {
+            role_permission::Entity::delete_many()
+                .filter(role_permission::Column::RoleId.eq(role_id))
+                .exec(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while removing previous permissions from role.")?;
+
+            Self::insert_role_permissions(&transaction, role_id, &updated_permissions).await?;
+        }
+
+
+        commit_transaction!(transaction)?;
+        Ok(updated_role)
+    }
+
+    /// Delete a role. Users and invites that had the role lose it.
+    ///
+    /// A deleted role can't record when it was deleted, so the modification time
+    /// of all remaining roles is updated instead (see [`RoleQuery::roles_last_modified_at`]).
+    ///
+    /// Returns `false` if no such role existed.
+    ///
+    /// [`RoleQuery::roles_last_modified_at`]: crate::query::RoleQuery::roles_last_modified_at
+    pub async fn delete<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        role_id: i32,
+    ) -> Result<bool> {
+        let transaction = begin_transaction!(database)?;
+
+        let deletion_result = role::Entity::delete_by_id(role_id)
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed to delete role from the database.")?;
+
+        if deletion_result.rows_affected == 0 {
+            return Ok(false);
+        }
+
+        role::Entity::update_many()
+            .col_expr(
+                role::Column::LastModifiedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed to update the modification time of the remaining roles.")?;
+
+        commit_transaction!(transaction)?;
+        Ok(true)
+    }
+
+    async fn insert_role_permissions<C: ConnectionTrait>(
+        database: &C,
+        role_id: i32,
+        permissions: &[Permission],
+    ) -> Result<()> {
+        if permissions.is_empty() {
+            return Ok(());
+        }
+
+        let role_permission_models = permissions
+            .iter()
+            .map(|permission| role_permission::ActiveModel {
+                role_id: ActiveValue::Set(role_id),
+                permission_id: ActiveValue::Set(permission.id()),
+            })
+            .collect::<Vec<_>>();
+
+        role_permission::Entity::insert_many(role_permission_models)
+            .exec_without_returning(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while adding permissions to role.")?;
+
+        Ok(())
+    }
+}
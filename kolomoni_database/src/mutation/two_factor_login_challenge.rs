@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+
+use crate::entities::two_factor_login_challenge;
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Mutations for the [`crate::entities::two_factor_login_challenge::Entity`] entity.
+pub struct TwoFactorLoginChallengeMutation;
+
+impl TwoFactorLoginChallengeMutation {
+    /// Record a login that has passed the first step and is waiting for the second factor.
+    /// The token itself is not stored, only its hash
+    /// (see [`kolomoni_auth::hash_login_challenge_token`]).
+    ///
+    /// As a form of housekeeping, this also removes any expired (abandoned) challenges.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        user_id: i32,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> Result<two_factor_login_challenge::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        two_factor_login_challenge::Entity::delete_many()
+            .filter(two_factor_login_challenge::Column::ExpiresAt.lte(Utc::now().fixed_offset()))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting expired two-factor login challenges.")?;
+
+        let challenge = two_factor_login_challenge::ActiveModel {
+            token_hash: ActiveValue::Set(token_hash),
+            user_id: ActiveValue::Set(user_id),
+            created_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            expires_at: ActiveValue::Set(expires_at.fixed_offset()),
+            failed_attempt_count: ActiveValue::Set(0),
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting two-factor login challenge into the database.")?;
+
+        commit_transaction!(transaction)?;
+        Ok(challenge)
+    }
+
+    /// Count an invalid code entered for the login challenge. Once `max_failed_attempts`
+    /// is reached, the challenge is removed and the login must be started over.
+    ///
+    /// Returns `true` if the challenge was removed.
+    pub async fn record_failed_attempt<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        token_hash: &str,
+        max_failed_attempts: u32,
+    ) -> Result<bool> {
+        let transaction = begin_transaction!(database)?;
+
+        two_factor_login_challenge::Entity::update_many()
+            .col_expr(
+                two_factor_login_challenge::Column::FailedAttemptCount,
+                Expr::col(two_factor_login_challenge::Column::FailedAttemptCount).add(1),
+            )
+            .filter(two_factor_login_challenge::Column::TokenHash.eq(token_hash))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while recording failed two-factor login attempt.")?;
+
+        let delete_result = two_factor_login_challenge::Entity::delete_many()
+            .filter(two_factor_login_challenge::Column::TokenHash.eq(token_hash))
+            .filter(
+                two_factor_login_challenge::Column::FailedAttemptCount
+                    .gte(i32::try_from(max_failed_attempts).unwrap_or(i32::MAX)),
+            )
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting exhausted two-factor login challenge.")?;
+
+        commit_transaction!(transaction)?;
+        Ok(delete_result.rows_affected == 1)
+    }
+
+    /// Remove the login challenge once it has been completed.
+    ///
+    /// Returns `false` if it had already been removed, e.g. by a concurrent request,
+    /// in which case the login must not be completed again.
+    pub async fn consume<C: ConnectionTrait>(database: &C, token_hash: &str) -> Result<bool> {
+        let delete_result = two_factor_login_challenge::Entity::delete_by_id(token_hash.to_string())
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting completed two-factor login challenge.")?;
+
+        Ok(delete_result.rows_affected == 1)
+    }
+}
@@ -1,9 +1,11 @@
+use argon2::password_hash::rand_core::OsRng;
 use argon2::password_hash::SaltString;
 use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
 use chrono::{DateTime, Utc};
 use kolomoni_auth::DEFAULT_USER_ROLE;
 use kolomoni_configuration::Configuration;
 use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::Expr;
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue,
@@ -15,36 +17,63 @@ use sea_orm::{
 };
 
 use super::super::entities::user;
-use crate::entities::user_role;
+use super::UserSessionMutation;
+use crate::entities::{user_invite, user_role, user_suspension, word_revision};
+use crate::shared::DELETED_USER_ID;
 use crate::{begin_transaction, commit_transaction, query};
 
 
+/// Argon2id password hasher.
+///
+/// Each new password hash receives its own randomly-generated salt, and the
+/// memory, time and parallelism costs are taken from the `secrets` configuration table.
 pub struct ArgonHasher {
-    salt_string: SaltString,
     argon_hasher: Argon2<'static>,
+
+    /// The salt that was (in the past) shared by all password hashes, if configured.
+    /// Hashes using this salt are considered outdated, see [`Self::needs_rehash`].
+    legacy_salt_string: Option<SaltString>,
 }
 
 impl ArgonHasher {
     pub fn new(config: &Configuration) -> Result<Self> {
-        let salt_string = SaltString::from_b64(&config.secrets.hash_salt)
-            .map_err(|error| miette!("Failed to initialize SaltString: {error}."))?;
-
-        let argon_hasher = Argon2::new(
-            Algorithm::Argon2id,
-            Version::V0x13,
-            Params::default(),
-        );
+        let legacy_salt_string = config
+            .secrets
+            .hash_salt
+            .as_ref()
+            .map(|hash_salt| SaltString::from_b64(hash_salt))
+            .transpose()
+            .map_err(|error| miette!("Failed to initialize legacy SaltString: {error}."))?;
+
+        let params = Params::new(
+            config.secrets.argon2_memory_cost,
+            config.secrets.argon2_time_cost,
+            config.secrets.argon2_parallelism,
+            None,
+        )
+        .map_err(|error| miette!("Invalid Argon2 parameters: {error}."))?;
+
+        let argon_hasher = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
 
         Ok(Self {
-            salt_string,
             argon_hasher,
+            legacy_salt_string,
         })
     }
 
-    pub fn hash_password(&self, password: &str) -> Result<PasswordHash> {
-        self.argon_hasher
-            .hash_password(password.as_bytes(), &self.salt_string)
-            .map_err(|error| miette!("Errored while hashing password: {error}"))
+    /// Hash the given password with a freshly-generated salt.
+    ///
+    /// Returns the hash in its PHC string format, which also contains the salt and the
+    /// parameters used, meaning it can be verified even after the configured parameters change.
+    pub fn hash_password(&self, password: &str) -> Result<String> {
+        let salt_string = SaltString::generate(&mut OsRng);
+
+        let password_hash = self
+            .argon_hasher
+            .hash_password(password.as_bytes(), &salt_string)
+            .map_err(|error| miette!("Errored while hashing password: {error}"))?;
+
+        Ok(password_hash.to_string())
     }
 
     pub fn verify_password_against_hash(
@@ -60,6 +89,38 @@ impl ArgonHasher {
             .verify_password(password.as_bytes(), &hashed_password)
             .is_ok())
     }
+
+    /// Returns `true` if the given password hash should be replaced with a new one,
+    /// i.e. when it wasn't produced by Argon2id with the currently configured parameters,
+    /// or when it still uses the legacy shared salt.
+    pub fn needs_rehash(&self, hashed_password: &str) -> Result<bool> {
+        let hashed_password = PasswordHash::new(hashed_password)
+            .map_err(|error| miette!("Errored while parsing hashed password: {error}"))?;
+
+        if hashed_password.algorithm != Algorithm::Argon2id.ident()
+            || hashed_password.version != Some(Version::V0x13.into())
+        {
+            return Ok(true);
+        }
+
+        if let (Some(legacy_salt_string), Some(salt)) =
+            (&self.legacy_salt_string, hashed_password.salt)
+        {
+            if legacy_salt_string.as_str() == salt.as_str() {
+                return Ok(true);
+            }
+        }
+
+        let Ok(hash_params) = Params::try_from(&hashed_password) else {
+            return Ok(true);
+        };
+
+        let current_params = self.argon_hasher.params();
+
+        Ok(hash_params.m_cost() != current_params.m_cost()
+            || hash_params.t_cost() != current_params.t_cost()
+            || hash_params.p_cost() != current_params.p_cost())
+    }
 }
 
 
@@ -95,7 +156,7 @@ impl UserMutation {
         let user = user::ActiveModel {
             username: ActiveValue::Set(registration_info.username),
             display_name: ActiveValue::Set(registration_info.display_name),
-            hashed_password: ActiveValue::Set(hashed_password.to_string()),
+            hashed_password: ActiveValue::Set(hashed_password),
             joined_at: ActiveValue::Set(registration_time.fixed_offset()),
             last_modified_at: ActiveValue::Set(registration_time.fixed_offset()),
             last_active_at: ActiveValue::Set(registration_time.fixed_offset()),
@@ -110,6 +171,8 @@ impl UserMutation {
         user_role::ActiveModel {
             role_id: ActiveValue::Set(DEFAULT_USER_ROLE.id()),
             user_id: ActiveValue::Set(user.id),
+            granted_at: ActiveValue::Set(Some(registration_time.fixed_offset())),
+            ..Default::default()
         }
         .insert(&transaction)
         .await
@@ -174,6 +237,213 @@ impl UserMutation {
         Ok(updated_user)
     }
 
+    /// Update last activity times for multiple users at once (in a single transaction).
+    ///
+    /// Activity times are never moved backwards: if a user's stored last activity time
+    /// is already newer than the provided one, it is left as is.
+    /// Users that no longer exist are silently skipped.
+    pub async fn update_last_active_at_in_bulk<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        last_activity_times: &[(i32, DateTime<Utc>)],
+    ) -> Result<()> {
+        if last_activity_times.is_empty() {
+            return Ok(());
+        }
+
+        let transaction = begin_transaction!(database)?;
+
+        for (user_id, last_active_at) in last_activity_times {
+            let last_active_at = last_active_at.fixed_offset();
+
+            user::Entity::update_many()
+                .col_expr(
+                    user::Column::LastActiveAt,
+                    Expr::value(last_active_at),
+                )
+                .filter(user::Column::Id.eq(*user_id))
+                .filter(user::Column::LastActiveAt.lt(last_active_at))
+                .exec(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while updating a user's last activity time (in bulk).")?;
+        }
+
+        commit_transaction!(transaction)?;
+
+        Ok(())
+    }
+
+    /// Replace a user's password hash with a new one. The user is looked up by their ID.
+    ///
+    /// This does not hash anything by itself, see [`ArgonHasher::hash_password`].
+    pub async fn update_password_hash_by_user_id<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        new_hashed_password: String,
+    ) -> Result<user::Model> {
+        let user_with_updated_password_hash = user::ActiveModel {
+            id: ActiveValue::Unchanged(user_id),
+            hashed_password: ActiveValue::Set(new_hashed_password),
+            ..Default::default()
+        };
+
+        let updated_user = user_with_updated_password_hash
+            .update(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while updating a user's password hash (by ID).")?;
+
+        Ok(updated_user)
+    }
+
+    /// Change a user's password. The user is looked up by their ID.
+    ///
+    /// The new password is hashed with a fresh salt, and the user's password change time is updated,
+    /// which makes all tokens issued before this point invalid.
+    pub async fn change_password_by_user_id<C: ConnectionTrait>(
+        database: &C,
+        hasher: &ArgonHasher,
+        user_id: i32,
+        new_password: &str,
+    ) -> Result<user::Model> {
+        let new_hashed_password = hasher
+            .hash_password(new_password)
+            .wrap_err("Failed to hash new password.")?;
+
+        let current_time = Utc::now().fixed_offset();
+
+        let user_with_updated_password = user::ActiveModel {
+            id: ActiveValue::Unchanged(user_id),
+            hashed_password: ActiveValue::Set(new_hashed_password),
+            password_last_changed_at: ActiveValue::Set(Some(current_time)),
+            last_modified_at: ActiveValue::Set(current_time),
+            ..Default::default()
+        };
+
+        let updated_user = user_with_updated_password
+            .update(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while updating a user's password (by ID).")?;
+
+        Ok(updated_user)
+    }
+
+    /// Deactivate a user account. The user is looked up by their ID.
+    ///
+    /// A deactivated user can no longer log in or use previously-issued tokens,
+    /// but their account and data are kept. This also ends all of the user's sessions.
+    pub async fn deactivate_by_user_id<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<user::Model> {
+        let current_time = Utc::now().fixed_offset();
+
+        let deactivated_user = user::ActiveModel {
+            id: ActiveValue::Unchanged(user_id),
+            deactivated_at: ActiveValue::Set(Some(current_time)),
+            last_modified_at: ActiveValue::Set(current_time),
+            ..Default::default()
+        };
+
+        let updated_user = deactivated_user
+            .update(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deactivating a user (by ID).")?;
+
+        UserSessionMutation::delete_all_sessions_for_user(database, user_id)
+            .await
+            .wrap_err("Failed to end the sessions of a deactivated user.")?;
+
+        Ok(updated_user)
+    }
+
+    /// Reactivate a previously-deactivated user account. The user is looked up by their ID.
+    pub async fn reactivate_by_user_id<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<user::Model> {
+        let reactivated_user = user::ActiveModel {
+            id: ActiveValue::Unchanged(user_id),
+            deactivated_at: ActiveValue::Set(None),
+            last_modified_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            ..Default::default()
+        };
+
+        let updated_user = reactivated_user
+            .update(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while reactivating a user (by ID).")?;
+
+        Ok(updated_user)
+    }
+
+    /// Permanently delete a user account. The user is looked up by their ID.
+    ///
+    /// The user's roles and sessions are removed along with the account.
+    /// Their contributions (word revisions, role grants, suspensions and invites they created)
+    /// are kept, but reassigned to the deleted user placeholder (see [`DELETED_USER_ID`]).
+    ///
+    /// Returns `true` if the user existed. The placeholder itself can't be deleted.
+    pub async fn delete_by_user_id<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<bool> {
+        if user_id == DELETED_USER_ID {
+            return Ok(false);
+        }
+
+        let transaction = begin_transaction!(database)?;
+
+
+        reassign_to_deleted_user::<_, word_revision::Entity>(
+            &transaction,
+            word_revision::Column::AuthorUserId,
+            user_id,
+        )
+        .await?;
+
+        reassign_to_deleted_user::<_, user_role::Entity>(
+            &transaction,
+            user_role::Column::GrantedBy,
+            user_id,
+        )
+        .await?;
+
+        reassign_to_deleted_user::<_, user_suspension::Entity>(
+            &transaction,
+            user_suspension::Column::SuspendedByUserId,
+            user_id,
+        )
+        .await?;
+
+        reassign_to_deleted_user::<_, user_suspension::Entity>(
+            &transaction,
+            user_suspension::Column::LiftedByUserId,
+            user_id,
+        )
+        .await?;
+
+        reassign_to_deleted_user::<_, user_invite::Entity>(
+            &transaction,
+            user_invite::Column::CreatedByUserId,
+            user_id,
+        )
+        .await?;
+
+
+        let delete_result = user::Entity::delete_by_id(user_id)
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting a user (by ID).")?;
+
+        commit_transaction!(transaction)?;
+        Ok(delete_result.rows_affected == 1)
+    }
+
     /// Update a user's display name. The user is looked up by their ID.
     pub async fn update_display_name_by_user_id<C: ConnectionTrait>(
         database: &C,
@@ -239,3 +509,21 @@ impl UserMutation {
         Ok(updated_user)
     }
 }
+
+
+/// Reassigns all rows whose `user_column` references the given user to the deleted user placeholder.
+async fn reassign_to_deleted_user<C: ConnectionTrait, E: EntityTrait>(
+    database: &C,
+    user_column: E::Column,
+    user_id: i32,
+) -> Result<()> {
+    E::update_many()
+        .col_expr(user_column, Expr::value(DELETED_USER_ID))
+        .filter(user_column.eq(user_id))
+        .exec(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while reassigning the contributions of a deleted user.")?;
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use kolomoni_auth::Permission;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::entities::{user_api_token, user_api_token_permission};
+use crate::shared::generate_random_api_token_uuid;
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Information about a new personal API token.
+pub struct NewUserApiToken {
+    /// ID of the user that owns the token.
+    pub user_id: i32,
+
+    /// User-provided name of the token (e.g. "import script").
+    pub name: String,
+
+    /// Hash of the token value (see [`kolomoni_auth::hash_api_token`]).
+    /// The token value itself is never stored.
+    pub token_hash: String,
+
+    /// When the token expires, if ever.
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Permissions the token is limited to.
+    pub permissions: Vec<Permission>,
+}
+
+
+/// Mutations for the [`crate::entities::user_api_token::Entity`] entity.
+pub struct UserApiTokenMutation;
+
+impl UserApiTokenMutation {
+    /// Create a new personal API token.
+    ///
+    /// As a form of housekeeping, this also removes any of the user's expired tokens.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        new_token: NewUserApiToken,
+    ) -> Result<user_api_token::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        Self::delete_expired_tokens_for_user(&transaction, new_token.user_id)
+            .await
+            .wrap_err("Failed to remove expired API tokens before creating a new one.")?;
+
+
+        let token = user_api_token::ActiveModel {
+            id: ActiveValue::Set(generate_random_api_token_uuid()),
+            user_id: ActiveValue::Set(new_token.user_id),
+            name: ActiveValue::Set(new_token.name),
+            token_hash: ActiveValue::Set(new_token.token_hash),
+            created_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            expires_at: ActiveValue::Set(
+                new_token
+                    .expires_at
+                    .map(|expires_at| expires_at.fixed_offset()),
+            ),
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting new API token into the database.")?;
+
+
+        if !new_token.permissions.is_empty() {
+            let token_permission_models = new_token
+                .permissions
+                .iter()
+                .map(
+                    |permission| user_api_token_permission::ActiveModel {
+                        token_id: ActiveValue::Set(token.id),
+                        permission_id: ActiveValue::Set(permission.id()),
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            user_api_token_permission::Entity::insert_many(token_permission_models)
+                .exec_without_returning(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while adding permissions to API token.")?;
+        }
+
+
+        commit_transaction!(transaction)?;
+        Ok(token)
+    }
+
+    /// Delete (i.e. revoke) a single personal API token.
+    ///
+    /// Returns `true` if the token existed.
+    pub async fn delete<C: ConnectionTrait>(database: &C, token_id: Uuid) -> Result<bool> {
+        let delete_result = user_api_token::Entity::delete_by_id(token_id)
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting an API token.")?;
+
+        Ok(delete_result.rows_affected == 1)
+    }
+
+    /// Delete all of the user's personal API tokens that have already expired.
+    pub async fn delete_expired_tokens_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<()> {
+        user_api_token::Entity::delete_many()
+            .filter(user_api_token::Column::UserId.eq(user_id))
+            .filter(user_api_token::Column::ExpiresAt.lte(Utc::now().fixed_offset()))
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting expired API tokens.")?;
+
+        Ok(())
+    }
+}
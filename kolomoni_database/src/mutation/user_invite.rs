@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::{Condition, Expr};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::entities::{user_invite, user_invite_role};
+use crate::shared::{generate_random_invite_code, generate_random_invite_uuid};
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Information about a new registration invite.
+pub struct NewUserInvite {
+    /// ID of the user that created the invite.
+    pub created_by_user_id: i32,
+
+    /// When the invite expires, if ever.
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// How many times the invite can be used, if limited.
+    pub max_uses: Option<i32>,
+
+    /// IDs of roles (in addition to the default one) to give to users that register with the invite.
+    pub role_ids: Vec<i32>,
+}
+
+
+/// Mutations for the [`crate::entities::user_invite::Entity`] entity.
+pub struct UserInviteMutation;
+
+impl UserInviteMutation {
+    /// Create a new registration invite with a randomly-generated code.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        new_invite: NewUserInvite,
+    ) -> Result<user_invite::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        let invite = user_invite::ActiveModel {
+            id: ActiveValue::Set(generate_random_invite_uuid()),
+            code: ActiveValue::Set(generate_random_invite_code()),
+            created_by_user_id: ActiveValue::Set(Some(new_invite.created_by_user_id)),
+            created_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            expires_at: ActiveValue::Set(
+                new_invite
+                    .expires_at
+                    .map(|expires_at| expires_at.fixed_offset()),
+            ),
+            max_uses: ActiveValue::Set(new_invite.max_uses),
+            use_count: ActiveValue::Set(0),
+            revoked_at: ActiveValue::Set(None),
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting new user invite into the database.")?;
+
+
+        if !new_invite.role_ids.is_empty() {
+            let invite_role_models = new_invite
+                .role_ids
+                .iter()
+                .map(|role_id| user_invite_role::ActiveModel {
+                    invite_id: ActiveValue::Set(invite.id),
+                    role_id: ActiveValue::Set(*role_id),
+                })
+                .collect::<Vec<_>>();
+
+            user_invite_role::Entity::insert_many(invite_role_models)
+                .exec_without_returning(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while adding roles to user invite.")?;
+        }
+
+
+        commit_transaction!(transaction)?;
+        Ok(invite)
+    }
+
+    /// Revoke an invite, meaning it can no longer be used to register.
+    ///
+    /// Revoking an already-revoked invite does nothing.
+    pub async fn revoke<C: ConnectionTrait>(database: &C, invite_id: Uuid) -> Result<()> {
+        user_invite::Entity::update_many()
+            .col_expr(
+                user_invite::Column::RevokedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .filter(user_invite::Column::Id.eq(invite_id))
+            .filter(user_invite::Column::RevokedAt.is_null())
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while revoking user invite.")?;
+
+        Ok(())
+    }
+
+    /// Use up one use of the invite with the given code.
+    ///
+    /// Returns `None` if no such invite exists or if it can no longer be used,
+    /// i.e. when it has been revoked, has expired, or has reached its maximum number of uses.
+    ///
+    /// The check and the increment of the use count happen in a single statement,
+    /// so concurrent registrations can not overuse an invite.
+    pub async fn redeem<C: ConnectionTrait>(
+        database: &C,
+        invite_code: &str,
+    ) -> Result<Option<user_invite::Model>> {
+        let current_time = Utc::now().fixed_offset();
+
+        let updated_invites = user_invite::Entity::update_many()
+            .col_expr(
+                user_invite::Column::UseCount,
+                Expr::col(user_invite::Column::UseCount).add(1),
+            )
+            .filter(user_invite::Column::Code.eq(invite_code))
+            .filter(user_invite::Column::RevokedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(user_invite::Column::ExpiresAt.is_null())
+                    .add(user_invite::Column::ExpiresAt.gt(current_time)),
+            )
+            .filter(
+                Condition::any()
+                    .add(user_invite::Column::MaxUses.is_null())
+                    .add(
+                        Expr::col(user_invite::Column::UseCount)
+                            .lt(Expr::col(user_invite::Column::MaxUses)),
+                    ),
+            )
+            .exec_with_returning(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while redeeming user invite.")?;
+
+        Ok(updated_invites.into_iter().next())
+    }
+}
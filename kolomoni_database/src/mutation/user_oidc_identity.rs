@@ -0,0 +1,30 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait};
+
+use crate::entities::user_oidc_identity;
+
+
+/// Mutations for the [`crate::entities::user_oidc_identity::Entity`] entity.
+pub struct UserOidcIdentityMutation;
+
+impl UserOidcIdentityMutation {
+    /// Link an OpenID Connect identity (an issuer and subject pair) to a user.
+    pub async fn link<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<user_oidc_identity::Model> {
+        user_oidc_identity::ActiveModel {
+            issuer: ActiveValue::Set(issuer.to_string()),
+            subject: ActiveValue::Set(subject.to_string()),
+            user_id: ActiveValue::Set(user_id),
+            linked_at: ActiveValue::Set(Utc::now().fixed_offset()),
+        }
+        .insert(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while linking OpenID Connect identity to user.")
+    }
+}
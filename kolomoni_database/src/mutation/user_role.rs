@@ -1,7 +1,7 @@
-use kolomoni_auth::Role;
+use chrono::{DateTime, Utc};
 use miette::{Context, IntoDiagnostic, Result};
 use sea_orm::{
-    sea_query::OnConflict,
+    sea_query::{Expr, OnConflict},
     ActiveValue,
     ColumnTrait,
     ConnectionTrait,
@@ -11,28 +11,71 @@ use sea_orm::{
 
 use crate::entities;
 
+
+/// Details of a role grant: who granted the role and until when.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct RoleGrantDetails {
+    /// ID of the user that granted the role, if any.
+    pub granted_by: Option<i32>,
+
+    /// When the granted role expires. If `None`, the role is granted permanently.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+
 pub struct UserRoleMutation;
 
 impl UserRoleMutation {
+    /// Grant roles to a user.
+    ///
+    /// If the user already has one of the roles, the existing grant is only replaced
+    /// if the new one lasts longer (i.e. a temporary grant never shortens an existing one,
+    /// but a permanent grant makes a temporary one permanent).
     pub async fn add_roles_to_user<C: ConnectionTrait>(
         database: &C,
         user_id: i32,
-        roles: &[Role],
+        role_ids: &[i32],
+        grant_details: RoleGrantDetails,
     ) -> Result<()> {
-        if roles.is_empty() {
+        if role_ids.is_empty() {
             return Ok(());
         }
 
-        let role_models = roles
+        let granted_at = Utc::now().fixed_offset();
+
+        let role_models = role_ids
             .iter()
-            .map(|role| entities::user_role::ActiveModel {
+            .map(|role_id| entities::user_role::ActiveModel {
                 user_id: ActiveValue::Set(user_id),
-                role_id: ActiveValue::Set(role.id()),
+                role_id: ActiveValue::Set(*role_id),
+                granted_by: ActiveValue::Set(grant_details.granted_by),
+                granted_at: ActiveValue::Set(Some(granted_at)),
+                expires_at: ActiveValue::Set(
+                    grant_details
+                        .expires_at
+                        .map(|expires_at| expires_at.fixed_offset()),
+                ),
             })
             .collect::<Vec<_>>();
 
         entities::user_role::Entity::insert_many(role_models)
-            .on_conflict(OnConflict::new().do_nothing().to_owned())
+            .on_conflict(
+                OnConflict::columns([
+                    entities::user_role::Column::UserId,
+                    entities::user_role::Column::RoleId,
+                ])
+                .update_columns([
+                    entities::user_role::Column::GrantedBy,
+                    entities::user_role::Column::GrantedAt,
+                    entities::user_role::Column::ExpiresAt,
+                ])
+                .action_and_where(Expr::cust(
+                    "\"user_role\".\"expires_at\" IS NOT NULL \
+                    AND (\"excluded\".\"expires_at\" IS NULL \
+                         OR \"excluded\".\"expires_at\" > \"user_role\".\"expires_at\")",
+                ))
+                .to_owned(),
+            )
             .exec_without_returning(database)
             .await
             .into_diagnostic()
@@ -44,9 +87,9 @@ impl UserRoleMutation {
     pub async fn remove_roles_from_user<C: ConnectionTrait>(
         database: &C,
         user_id: i32,
-        roles: &[Role],
+        role_ids: &[i32],
     ) -> Result<()> {
-        if roles.is_empty() {
+        if role_ids.is_empty() {
             return Ok(());
         }
 
@@ -57,14 +100,14 @@ impl UserRoleMutation {
 
         let base_removal_condition = entities::user_role::Column::UserId.eq(user_id);
 
-        let role_id_removal_conditions = roles
+        let role_id_removal_conditions = role_ids
             .iter()
-            .map(|role| entities::user_role::Column::RoleId.eq(role.id()))
+            .map(|role_id| entities::user_role::Column::RoleId.eq(*role_id))
             .collect::<Vec<_>>();
         let merged_role_id_conditions = {
             let mut condition_iterator = role_id_removal_conditions.into_iter();
 
-            // PANIC SAFETY: We checked that `roles` wasn't empty.
+            // PANIC SAFETY: We checked that `role_ids` wasn't empty.
             let mut current_condition = condition_iterator.next().unwrap();
 
             for next_condition in condition_iterator {
@@ -86,4 +129,19 @@ impl UserRoleMutation {
 
         Ok(())
     }
+
+    /// Delete all role grants that have expired.
+    ///
+    /// Expired grants are already ignored by [`UserRoleQuery`][crate::query::UserRoleQuery],
+    /// so this only cleans them up. Returns the number of deleted grants.
+    pub async fn delete_expired_role_grants<C: ConnectionTrait>(database: &C) -> Result<u64> {
+        let delete_result = entities::user_role::Entity::delete_many()
+            .filter(entities::user_role::Column::ExpiresAt.lte(Utc::now().fixed_offset()))
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting expired role grants.")?;
+
+        Ok(delete_result.rows_affected)
+    }
 }
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+};
+use uuid::Uuid;
+
+use crate::{entities::user_session, shared::generate_random_session_uuid};
+
+
+/// Information about a new login session.
+pub struct NewUserSession {
+    /// ID of the user that logged in.
+    pub user_id: i32,
+
+    /// When the session (i.e. its refresh token) expires.
+    pub expires_at: DateTime<Utc>,
+
+    /// User agent of the client that logged in, if known.
+    pub user_agent: Option<String>,
+
+    /// IP address of the client that logged in, if known.
+    pub ip_address: Option<String>,
+}
+
+
+/// Mutations for the [`crate::entities::user_session::Entity`] entity.
+pub struct UserSessionMutation;
+
+impl UserSessionMutation {
+    /// Create a new login session.
+    ///
+    /// As a form of housekeeping, this also removes any of the user's expired sessions.
+    pub async fn create<C: ConnectionTrait>(
+        database: &C,
+        new_session: NewUserSession,
+    ) -> Result<user_session::Model> {
+        Self::delete_expired_sessions_for_user(database, new_session.user_id)
+            .await
+            .wrap_err("Failed to remove expired sessions before creating a new one.")?;
+
+
+        let current_time = Utc::now().fixed_offset();
+
+        let session = user_session::ActiveModel {
+            id: ActiveValue::Set(generate_random_session_uuid()),
+            user_id: ActiveValue::Set(new_session.user_id),
+            created_at: ActiveValue::Set(current_time),
+            last_refreshed_at: ActiveValue::Set(current_time),
+            expires_at: ActiveValue::Set(new_session.expires_at.fixed_offset()),
+            user_agent: ActiveValue::Set(new_session.user_agent),
+            ip_address: ActiveValue::Set(new_session.ip_address),
+        };
+
+        session
+            .insert(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while inserting new user session into the database.")
+    }
+
+    /// Update the last refresh time of a session to the current time.
+    pub async fn mark_refreshed<C: ConnectionTrait>(database: &C, session_id: Uuid) -> Result<()> {
+        let session = user_session::ActiveModel {
+            id: ActiveValue::Unchanged(session_id),
+            last_refreshed_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            ..Default::default()
+        };
+
+        session
+            .update(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while updating the last refresh time of a user session.")?;
+
+        Ok(())
+    }
+
+    /// Delete (i.e. revoke) a single session.
+    ///
+    /// Returns `true` if the session existed.
+    pub async fn delete<C: ConnectionTrait>(database: &C, session_id: Uuid) -> Result<bool> {
+        let delete_result = user_session::Entity::delete_by_id(session_id)
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting a user session.")?;
+
+        Ok(delete_result.rows_affected == 1)
+    }
+
+    /// Delete (i.e. revoke) all of the user's sessions.
+    pub async fn delete_all_sessions_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<()> {
+        user_session::Entity::delete_many()
+            .filter(user_session::Column::UserId.eq(user_id))
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting all user sessions.")?;
+
+        Ok(())
+    }
+
+    /// Delete all of the user's sessions that have already expired.
+    pub async fn delete_expired_sessions_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<()> {
+        user_session::Entity::delete_many()
+            .filter(user_session::Column::UserId.eq(user_id))
+            .filter(user_session::Column::ExpiresAt.lte(Utc::now().fixed_offset()))
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting expired user sessions.")?;
+
+        Ok(())
+    }
+}
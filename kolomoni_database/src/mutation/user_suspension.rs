@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+use crate::entities::user_suspension;
+use crate::query::active_suspension_condition;
+use crate::shared::generate_random_suspension_uuid;
+
+
+/// Information about a new user suspension.
+pub struct NewUserSuspension {
+    /// ID of the user to suspend.
+    pub user_id: i32,
+
+    /// Why the user is being suspended.
+    pub reason: String,
+
+    /// ID of the user that issued the suspension.
+    pub suspended_by_user_id: i32,
+
+    /// When the suspension ends, if ever (until lifted).
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+
+/// Mutations for the [`crate::entities::user_suspension::Entity`] entity.
+pub struct UserSuspensionMutation;
+
+impl UserSuspensionMutation {
+    /// Suspend a user.
+    ///
+    /// Suspensions are never deleted (only lifted), which means they
+    /// also serve as a history of the user's suspensions.
+    pub async fn create<C: ConnectionTrait>(
+        database: &C,
+        new_suspension: NewUserSuspension,
+    ) -> Result<user_suspension::Model> {
+        user_suspension::ActiveModel {
+            id: ActiveValue::Set(generate_random_suspension_uuid()),
+            user_id: ActiveValue::Set(new_suspension.user_id),
+            reason: ActiveValue::Set(new_suspension.reason),
+            suspended_by_user_id: ActiveValue::Set(Some(new_suspension.suspended_by_user_id)),
+            suspended_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            ends_at: ActiveValue::Set(
+                new_suspension
+                    .ends_at
+                    .map(|ends_at| ends_at.fixed_offset()),
+            ),
+            lifted_by_user_id: ActiveValue::Set(None),
+            lifted_at: ActiveValue::Set(None),
+        }
+        .insert(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting new user suspension into the database.")
+    }
+
+    /// Lift all suspensions of the given user that are currently in effect.
+    ///
+    /// Returns `true` if any suspension was lifted.
+    pub async fn lift_active_suspensions<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        lifted_by_user_id: i32,
+    ) -> Result<bool> {
+        let update_result = user_suspension::Entity::update_many()
+            .col_expr(
+                user_suspension::Column::LiftedAt,
+                Expr::value(Utc::now().fixed_offset()),
+            )
+            .col_expr(
+                user_suspension::Column::LiftedByUserId,
+                Expr::value(lifted_by_user_id),
+            )
+            .filter(user_suspension::Column::UserId.eq(user_id))
+            .filter(active_suspension_condition())
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while lifting user suspensions.")?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+}
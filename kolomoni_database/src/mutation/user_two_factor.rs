@@ -0,0 +1,187 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::{Condition, Expr};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+
+use crate::entities::{user_two_factor, user_two_factor_recovery_code};
+use crate::{begin_transaction, commit_transaction};
+
+
+/// Mutations for the [`crate::entities::user_two_factor::Entity`]
+/// and [`crate::entities::user_two_factor_recovery_code::Entity`] entities.
+pub struct UserTwoFactorMutation;
+
+impl UserTwoFactorMutation {
+    /// Start (or restart) enrolling the user into two-factor authentication with a new secret
+    /// and set of recovery codes (provided as hashes, see [`kolomoni_auth::hash_recovery_code`]).
+    ///
+    /// Any previous secret and recovery codes are discarded. The new secret is *pending*
+    /// until it is confirmed with [`Self::enable`].
+    pub async fn begin_enrollment<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        user_id: i32,
+        secret: String,
+        recovery_code_hashes: Vec<String>,
+    ) -> Result<user_two_factor::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        user_two_factor::Entity::delete_by_id(user_id)
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting previous two-factor authentication secret.")?;
+
+        let two_factor = user_two_factor::ActiveModel {
+            user_id: ActiveValue::Set(user_id),
+            secret: ActiveValue::Set(secret),
+            created_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            enabled_at: ActiveValue::Set(None),
+            last_used_time_step: ActiveValue::Set(None),
+        }
+        .insert(&transaction)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while inserting two-factor authentication secret into the database.")?;
+
+        Self::replace_recovery_codes(&transaction, user_id, recovery_code_hashes).await?;
+
+        commit_transaction!(transaction)?;
+        Ok(two_factor)
+    }
+
+    /// Finish enrolling the user into two-factor authentication
+    /// after they have confirmed the secret with the code for `time_step`.
+    pub async fn enable<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        time_step: i64,
+    ) -> Result<user_two_factor::Model> {
+        user_two_factor::ActiveModel {
+            user_id: ActiveValue::Unchanged(user_id),
+            enabled_at: ActiveValue::Set(Some(Utc::now().fixed_offset())),
+            last_used_time_step: ActiveValue::Set(Some(time_step)),
+            ..Default::default()
+        }
+        .update(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while enabling two-factor authentication.")
+    }
+
+    /// Record that the code for `time_step` has been used, so it can not be used again.
+    ///
+    /// Returns `false` if the code for `time_step` (or a later one) has already been used,
+    /// e.g. by a concurrent login.
+    pub async fn record_used_time_step<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        time_step: i64,
+    ) -> Result<bool> {
+        let update_result = user_two_factor::Entity::update_many()
+            .col_expr(
+                user_two_factor::Column::LastUsedTimeStep,
+                Expr::value(time_step),
+            )
+            .filter(user_two_factor::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(user_two_factor::Column::LastUsedTimeStep.is_null())
+                    .add(user_two_factor::Column::LastUsedTimeStep.lt(time_step)),
+            )
+            .exec(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while recording used two-factor authentication code.")?;
+
+        Ok(update_result.rows_affected == 1)
+    }
+
+    /// Use up one of the user's recovery codes (provided as a hash).
+    ///
+    /// Returns `false` if the user has no such (unused) recovery code.
+    pub async fn consume_recovery_code<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        recovery_code_hash: &str,
+    ) -> Result<bool> {
+        let delete_result = user_two_factor_recovery_code::Entity::delete_by_id((
+            user_id,
+            recovery_code_hash.to_string(),
+        ))
+        .exec(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while deleting used recovery code.")?;
+
+        Ok(delete_result.rows_affected == 1)
+    }
+
+    /// Replace all of the user's recovery codes with new ones (provided as hashes).
+    pub async fn replace_recovery_codes<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        user_id: i32,
+        recovery_code_hashes: Vec<String>,
+    ) -> Result<()> {
+        let transaction = begin_transaction!(database)?;
+
+        user_two_factor_recovery_code::Entity::delete_many()
+            .filter(user_two_factor_recovery_code::Column::UserId.eq(user_id))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting previous recovery codes.")?;
+
+        if !recovery_code_hashes.is_empty() {
+            user_two_factor_recovery_code::Entity::insert_many(
+                recovery_code_hashes.into_iter().map(|code_hash| {
+                    user_two_factor_recovery_code::ActiveModel {
+                        user_id: ActiveValue::Set(user_id),
+                        code_hash: ActiveValue::Set(code_hash),
+                    }
+                }),
+            )
+            .exec_without_returning(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while inserting recovery codes into the database.")?;
+        }
+
+        commit_transaction!(transaction)?;
+        Ok(())
+    }
+
+    /// Turn off two-factor authentication for the user,
+    /// removing their secret and recovery codes.
+    ///
+    /// Returns `false` if the user had not started enrolling.
+    pub async fn disable<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<bool> {
+        let transaction = begin_transaction!(database)?;
+
+        user_two_factor_recovery_code::Entity::delete_many()
+            .filter(user_two_factor_recovery_code::Column::UserId.eq(user_id))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting recovery codes.")?;
+
+        let delete_result = user_two_factor::Entity::delete_by_id(user_id)
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting two-factor authentication secret.")?;
+
+        commit_transaction!(transaction)?;
+        Ok(delete_result.rows_affected == 1)
+    }
+}
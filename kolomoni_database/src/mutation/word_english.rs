@@ -1,12 +1,21 @@
 use chrono::{DateTime, Utc};
-use miette::{Context, IntoDiagnostic, Result};
-use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, TransactionTrait, TryIntoModel};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, TransactionTrait};
 use uuid::Uuid;
 
+use super::{WordRevisionContents, WordRevisionMutation};
 use crate::{
     begin_transaction,
-    entities::{word, word_english},
-    shared::{generate_random_word_uuid, WordLanguage},
+    commit_transaction,
+    entities::{word, word_english, word_revision},
+    shared::{
+        generate_random_word_uuid,
+        is_unique_constraint_violation,
+        EnglishWordGrammar,
+        InvalidWordGrammarError,
+        PartOfSpeech,
+        WordLanguage,
+    },
 };
 
 
@@ -15,13 +24,20 @@ pub struct NewEnglishWord {
     pub lemma: String,
     pub disambiguation: Option<String>,
     pub description: Option<String>,
+    pub grammar: EnglishWordGrammar,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct UpdatedEnglishWord {
     pub lemma: Option<String>,
-    pub disambiguation: Option<String>,
+
+    /// If set, replaces the disambiguation (`Some(None)` removes it).
+    pub disambiguation: Option<Option<String>>,
+
     pub description: Option<String>,
+
+    /// If set, replaces all grammatical information about the word.
+    pub grammar: Option<EnglishWordGrammar>,
 }
 
 
@@ -29,10 +45,20 @@ pub struct UpdatedEnglishWord {
 pub struct EnglishWordMutation;
 
 impl EnglishWordMutation {
+    /// Creates a new english word and records its first revision
+    /// (authored by `author_user_id`, if known).
+    ///
+    /// Returns `None` (and creates nothing) if another english word
+    /// with the same lemma and disambiguation already exists.
     pub async fn create<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         english_word: NewEnglishWord,
-    ) -> Result<word_english::Model> {
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_english::Model>> {
+        Self::validate_grammar(&english_word.grammar)
+            .into_diagnostic()
+            .wrap_err("Invalid grammatical information for new english word.")?;
+
         let transaction = begin_transaction!(database)?;
 
         let random_uuid = generate_random_word_uuid();
@@ -51,20 +77,37 @@ impl EnglishWordMutation {
             .wrap_err("Failed while inserting base word.")?;
 
 
-        let active_english_word = word_english::ActiveModel {
+        let mut active_english_word = word_english::ActiveModel {
             word_id: ActiveValue::Set(random_uuid),
             lemma: ActiveValue::Set(english_word.lemma),
             disambiguation: ActiveValue::Set(english_word.disambiguation),
             description: ActiveValue::Set(english_word.description),
             created_at: ActiveValue::Set(created_at.fixed_offset()),
             last_modified_at: ActiveValue::Set(created_at.fixed_offset()),
+            ..Default::default()
         };
 
-        let new_english_word = active_english_word
-            .insert(&transaction)
-            .await
-            .into_diagnostic()
-            .wrap_err("Failed while inserting english word.")?;
+        active_english_word.set_grammar(english_word.grammar);
+
+        let new_english_word = match active_english_word.insert(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed while inserting english word.");
+            }
+        };
+
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            random_uuid,
+            WordLanguage::English,
+            Self::revision_contents(&new_english_word),
+            author_user_id,
+            created_at,
+        )
+        .await?;
 
 
         transaction
@@ -74,17 +117,35 @@ impl EnglishWordMutation {
             .wrap_err("Failed to commit english word creation transaction.")?;
 
 
-        Ok(new_english_word)
+        Ok(Some(new_english_word))
     }
 
+    /// Updates the given fields of a english word and, if its lemma, disambiguation,
+    /// description or grammatical information changed, records a new revision
+    /// (authored by `author_user_id`, if known).
+    ///
+    /// Returns `None` (and changes nothing) if the update would give the word the same
+    /// lemma and disambiguation as another english word.
     pub async fn update<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         word_uuid: Uuid,
         update: UpdatedEnglishWord,
-    ) -> Result<word_english::Model> {
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_english::Model>> {
+        if let Some(updated_grammar) = &update.grammar {
+            Self::validate_grammar(updated_grammar)
+                .into_diagnostic()
+                .wrap_err("Invalid grammatical information for updated english word.")?;
+        }
+
+        let transaction = begin_transaction!(database)?;
+
+        let modified_at = Utc::now();
+
+
         let mut active_word_model = word_english::ActiveModel {
             word_id: ActiveValue::Unchanged(word_uuid),
-            last_modified_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            last_modified_at: ActiveValue::Set(modified_at.fixed_offset()),
             ..Default::default()
         };
 
@@ -93,26 +154,103 @@ impl EnglishWordMutation {
         };
 
         if let Some(updated_disambiguation) = update.disambiguation {
-            active_word_model.disambiguation = ActiveValue::Set(Some(updated_disambiguation));
+            active_word_model.disambiguation = ActiveValue::Set(updated_disambiguation);
         }
 
         if let Some(updated_description) = update.description {
             active_word_model.description = ActiveValue::Set(Some(updated_description));
         }
 
-        let updated_active_word = active_word_model
-            .save(database)
-            .await
-            .into_diagnostic()
-            .wrap_err("Failed to update english word.")?;
+        if let Some(updated_grammar) = update.grammar {
+            active_word_model.set_grammar(updated_grammar);
+        }
 
-        let updated_word = updated_active_word
-            .try_into_model()
-            .into_diagnostic()
-            .wrap_err("Failed to convert active english model to normal model.")?;
 
+        let updated_word = match active_word_model.update(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed to update english word.");
+            }
+        };
 
-        Ok(updated_word)
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            word_uuid,
+            WordLanguage::English,
+            Self::revision_contents(&updated_word),
+            author_user_id,
+            modified_at,
+        )
+        .await?;
+
+
+        commit_transaction!(transaction)?;
+
+        Ok(Some(updated_word))
+    }
+
+    /// Reverts the lemma, disambiguation, description and grammatical information
+    /// of a english word to those of the given (earlier) revision. The revert is recorded as a new revision
+    /// (authored by `author_user_id`, if known), so no history is lost.
+    ///
+    /// Returns `None` (and changes nothing) if another english word has since taken
+    /// the lemma and disambiguation of the revision.
+    pub async fn revert_to_revision<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        revision: &word_revision::Model,
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_english::Model>> {
+        let transaction = begin_transaction!(database)?;
+
+        let modified_at = Utc::now();
+
+
+        let mut active_word_model = word_english::ActiveModel {
+            word_id: ActiveValue::Unchanged(word_uuid),
+            lemma: ActiveValue::Set(revision.lemma.clone()),
+            disambiguation: ActiveValue::Set(revision.disambiguation.clone()),
+            description: ActiveValue::Set(revision.description.clone()),
+            last_modified_at: ActiveValue::Set(modified_at.fixed_offset()),
+            ..Default::default()
+        };
+
+        // Revisions recorded before grammatical information was tracked
+        // leave the current grammatical information as it is.
+        if let Some(revision_grammar) = &revision.grammar {
+            let grammar = EnglishWordGrammar::from_revision_json(revision_grammar)
+                .ok_or_else(|| miette!("Invalid grammatical information in word revision."))?;
+
+            active_word_model.set_grammar(grammar);
+        }
+
+        let reverted_word = match active_word_model.update(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed to revert english word.");
+            }
+        };
+
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            word_uuid,
+            WordLanguage::English,
+            Self::revision_contents(&reverted_word),
+            author_user_id,
+            modified_at,
+        )
+        .await?;
+
+
+        commit_transaction!(transaction)?;
+
+        Ok(Some(reverted_word))
     }
 
     pub async fn set_last_modified_at<C: ConnectionTrait + TransactionTrait>(
@@ -136,5 +274,55 @@ impl EnglishWordMutation {
         Ok(updated_word)
     }
 
+    /// Checks that the grammatical information about an english word is consistent:
+    /// only nouns can have a number restriction or a plural form, and the plural form
+    /// can't be empty or be given for a noun that is only used in one number.
+    pub fn validate_grammar(grammar: &EnglishWordGrammar) -> Result<(), InvalidWordGrammarError> {
+        let is_noun = grammar.part_of_speech == Some(PartOfSpeech::Noun);
+
+        if grammar.number_restriction.is_some() && !is_noun {
+            return Err(InvalidWordGrammarError::OnlyAllowedForNouns {
+                field: "number restriction",
+            });
+        }
+
+
+        let Some(plural_form) = &grammar.plural_form else {
+            return Ok(());
+        };
+
+        if !is_noun {
+            return Err(InvalidWordGrammarError::OnlyAllowedForNouns {
+                field: "plural form",
+            });
+        }
+
+        if plural_form.trim().is_empty() {
+            return Err(InvalidWordGrammarError::EmptyForm {
+                form: "plural form".to_string(),
+            });
+        }
+
+        if let Some(restriction) = grammar.number_restriction {
+            return Err(
+                InvalidWordGrammarError::FormExcludedByNumberRestriction {
+                    form: "plural form".to_string(),
+                    restriction,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn revision_contents(word: &word_english::Model) -> WordRevisionContents {
+        WordRevisionContents {
+            lemma: word.lemma.clone(),
+            disambiguation: word.disambiguation.clone(),
+            description: word.description.clone(),
+            grammar: Some(word.grammar().to_revision_json()),
+        }
+    }
+
     // For deletion, see [`WordMutation::delete`][super::word::WordMutation::delete].
 }
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, EntityTrait, TransactionTrait};
+use uuid::Uuid;
+
+use super::{EnglishWordMutation, SloveneWordMutation};
+use crate::{
+    begin_transaction,
+    commit_transaction,
+    entities::word_link,
+    shared::{WordLanguage, WordLinkType},
+};
+
+
+
+pub struct NewWordLink {
+    /// Language of both words (links can only be created between words of the same language).
+    pub language: WordLanguage,
+    pub word_id: Uuid,
+    pub linked_word_id: Uuid,
+    pub link_type: WordLinkType,
+}
+
+pub struct WordLinkToDelete {
+    /// Language of both words.
+    pub language: WordLanguage,
+    pub word_id: Uuid,
+    pub linked_word_id: Uuid,
+}
+
+
+pub struct WordLinkMutation;
+
+impl WordLinkMutation {
+    /// Links the two words together. The link is stored in both directions:
+    /// the linked word receives a link back with the inverse link type
+    /// (see [`WordLinkType::inverse`]).
+    ///
+    /// Returns the link from `word_id` to `linked_word_id`.
+    pub async fn create<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        new_link: NewWordLink,
+    ) -> Result<word_link::Model> {
+        let transaction = begin_transaction!(database)?;
+
+        let linked_at = Utc::now();
+
+
+        let active_link = word_link::ActiveModel {
+            word_id: ActiveValue::Set(new_link.word_id),
+            linked_word_id: ActiveValue::Set(new_link.linked_word_id),
+            link_type: ActiveValue::Set(new_link.link_type.name().to_string()),
+            linked_at: ActiveValue::Set(linked_at.fixed_offset()),
+        };
+
+        let new_link_model = active_link
+            .insert(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while inserting new word link into the database.")?;
+
+
+        let active_inverse_link = word_link::ActiveModel {
+            word_id: ActiveValue::Set(new_link.linked_word_id),
+            linked_word_id: ActiveValue::Set(new_link.word_id),
+            link_type: ActiveValue::Set(new_link.link_type.inverse().name().to_string()),
+            linked_at: ActiveValue::Set(linked_at.fixed_offset()),
+        };
+
+        active_inverse_link
+            .insert(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while inserting new inverse word link into the database.")?;
+
+
+        // Now update the `last_modified_at` values for both words as well.
+
+        Self::set_last_modified_at_for_both_words(
+            &transaction,
+            new_link.language,
+            new_link.word_id,
+            new_link.linked_word_id,
+            linked_at,
+        )
+        .await
+        .wrap_err("Failed to set last modified for words after creating a link.")?;
+
+
+        commit_transaction!(transaction)?;
+        Ok(new_link_model)
+    }
+
+    /// Removes the link between the two words (in both directions).
+    pub async fn delete<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        to_delete: WordLinkToDelete,
+    ) -> Result<()> {
+        let transaction = begin_transaction!(database)?;
+
+
+        for (word_id, linked_word_id) in [
+            (to_delete.word_id, to_delete.linked_word_id),
+            (to_delete.linked_word_id, to_delete.word_id),
+        ] {
+            word_link::Entity::delete_by_id((word_id, linked_word_id))
+                .exec(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while deleting word link from the database.")?;
+        }
+
+
+        // Now update the `last_modified_at` values for both words as well.
+
+        Self::set_last_modified_at_for_both_words(
+            &transaction,
+            to_delete.language,
+            to_delete.word_id,
+            to_delete.linked_word_id,
+            Utc::now(),
+        )
+        .await
+        .wrap_err("Failed to set last modified for words after deleting a link.")?;
+
+
+        commit_transaction!(transaction)?;
+        Ok(())
+    }
+
+    async fn set_last_modified_at_for_both_words<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        language: WordLanguage,
+        word_uuid: Uuid,
+        linked_word_uuid: Uuid,
+        new_last_modified_at: DateTime<Utc>,
+    ) -> Result<()> {
+        for word_uuid in [word_uuid, linked_word_uuid] {
+            match language {
+                WordLanguage::English => {
+                    EnglishWordMutation::set_last_modified_at(
+                        database,
+                        word_uuid,
+                        new_last_modified_at,
+                    )
+                    .await?;
+                }
+                WordLanguage::Slovene => {
+                    SloveneWordMutation::set_last_modified_at(
+                        database,
+                        word_uuid,
+                        new_last_modified_at,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{begin_transaction, commit_transaction, entities::word_reference};
+
+
+
+pub struct WordReferenceMutation;
+
+impl WordReferenceMutation {
+    /// Replaces the set of words referenced from the description of the given word.
+    ///
+    /// Parsing descriptions is up to the caller; this should be called
+    /// whenever the description of a word changes.
+    pub async fn replace_references_from_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        referenced_word_uuids: Vec<Uuid>,
+    ) -> Result<()> {
+        let transaction = begin_transaction!(database)?;
+
+
+        word_reference::Entity::delete_many()
+            .filter(word_reference::Column::WordId.eq(word_uuid))
+            .exec(&transaction)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while deleting previous word references from the database.")?;
+
+
+        let referenced_at = Utc::now();
+
+        let mut inserted_word_uuids = Vec::with_capacity(referenced_word_uuids.len());
+        for referenced_word_uuid in referenced_word_uuids {
+            // A description can reference the same word more than once.
+            if inserted_word_uuids.contains(&referenced_word_uuid) {
+                continue;
+            }
+
+            let active_reference = word_reference::ActiveModel {
+                word_id: ActiveValue::Set(word_uuid),
+                referenced_word_id: ActiveValue::Set(referenced_word_uuid),
+                referenced_at: ActiveValue::Set(referenced_at.fixed_offset()),
+            };
+
+            active_reference
+                .insert(&transaction)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while inserting word reference into the database.")?;
+
+            inserted_word_uuids.push(referenced_word_uuid);
+        }
+
+
+        commit_transaction!(transaction)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue,
+    ConnectionTrait,
+    EntityTrait,
+    QuerySelect,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    entities::{word_english, word_revision, word_slovene},
+    query::WordRevisionQuery,
+    shared::WordLanguage,
+};
+
+
+/// Lemma, disambiguation, description and grammatical information of a word,
+/// as stored in each of its revisions.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WordRevisionContents {
+    pub lemma: String,
+    pub disambiguation: Option<String>,
+    pub description: Option<String>,
+
+    /// Grammatical information, serialized with `to_revision_json` of
+    /// [`SloveneWordGrammar`][crate::shared::SloveneWordGrammar] or
+    /// [`EnglishWordGrammar`][crate::shared::EnglishWordGrammar]. Missing in revisions
+    /// that were recorded before grammatical information was tracked.
+    pub grammar: Option<String>,
+}
+
+impl WordRevisionContents {
+    pub fn from_revision(revision: &word_revision::Model) -> Self {
+        Self {
+            lemma: revision.lemma.clone(),
+            disambiguation: revision.disambiguation.clone(),
+            description: revision.description.clone(),
+            grammar: revision.grammar.clone(),
+        }
+    }
+}
+
+
+
+pub struct WordRevisionMutation;
+
+impl WordRevisionMutation {
+    /// Records the given contents as the newest revision of a word, unless they are
+    /// the same as in its current newest revision. Returns the new revision, if one was recorded.
+    ///
+    /// This is already done by the english and slovene word mutations whenever a word
+    /// is created or changed, and should be called in the same transaction as the change.
+    /// The word's row is locked until the end of that transaction, so concurrent changes
+    /// of the same word can't both be recorded under the same revision number.
+    pub async fn record_if_changed<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        language: WordLanguage,
+        contents: WordRevisionContents,
+        author_user_id: Option<i32>,
+        created_at: DateTime<Utc>,
+    ) -> Result<Option<word_revision::Model>> {
+        let word_exists = match language {
+            WordLanguage::English => word_english::Entity::find_by_id(word_uuid)
+                .lock_exclusive()
+                .one(database)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while locking english word before recording a revision.")?
+                .is_some(),
+            WordLanguage::Slovene => word_slovene::Entity::find_by_id(word_uuid)
+                .lock_exclusive()
+                .one(database)
+                .await
+                .into_diagnostic()
+                .wrap_err("Failed while locking slovene word before recording a revision.")?
+                .is_some(),
+        };
+
+        if !word_exists {
+            return Err(miette!("Word to record a revision for doesn't exist."));
+        }
+
+        let latest_revision = WordRevisionQuery::latest_revision_for_word(database, word_uuid)
+            .await
+            .wrap_err("Failed while looking up latest word revision before recording a new one.")?;
+
+        let next_revision_number = match &latest_revision {
+            Some(latest_revision) => {
+                if WordRevisionContents::from_revision(latest_revision) == contents {
+                    return Ok(None);
+                }
+
+                latest_revision.revision_number + 1
+            }
+            None => 1,
+        };
+
+
+        let active_revision = word_revision::ActiveModel {
+            word_id: ActiveValue::Set(word_uuid),
+            revision_number: ActiveValue::Set(next_revision_number),
+            lemma: ActiveValue::Set(contents.lemma),
+            disambiguation: ActiveValue::Set(contents.disambiguation),
+            description: ActiveValue::Set(contents.description),
+            grammar: ActiveValue::Set(contents.grammar),
+            author_user_id: ActiveValue::Set(author_user_id),
+            created_at: ActiveValue::Set(created_at.fixed_offset()),
+        };
+
+        let new_revision = active_revision
+            .insert(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while inserting word revision.")?;
+
+
+        Ok(Some(new_revision))
+    }
+}
@@ -1,12 +1,21 @@
 use chrono::{DateTime, Utc};
-use miette::{Context, IntoDiagnostic, Result};
-use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, TransactionTrait, TryIntoModel};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ActiveModelTrait, ActiveValue, ConnectionTrait, TransactionTrait};
 use uuid::Uuid;
 
+use super::{WordRevisionContents, WordRevisionMutation};
 use crate::{
     begin_transaction,
-    entities::{word, word_slovene},
-    shared::{generate_random_word_uuid, WordLanguage},
+    commit_transaction,
+    entities::{word, word_revision, word_slovene},
+    shared::{
+        generate_random_word_uuid,
+        is_unique_constraint_violation,
+        InvalidWordGrammarError,
+        PartOfSpeech,
+        SloveneWordGrammar,
+        WordLanguage,
+    },
 };
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -14,13 +23,20 @@ pub struct NewSloveneWord {
     pub lemma: String,
     pub disambiguation: Option<String>,
     pub description: Option<String>,
+    pub grammar: SloveneWordGrammar,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct UpdatedSloveneWord {
     pub lemma: Option<String>,
-    pub disambiguation: Option<String>,
+
+    /// If set, replaces the disambiguation (`Some(None)` removes it).
+    pub disambiguation: Option<Option<String>>,
+
     pub description: Option<String>,
+
+    /// If set, replaces all grammatical information about the word.
+    pub grammar: Option<SloveneWordGrammar>,
 }
 
 
@@ -28,10 +44,20 @@ pub struct UpdatedSloveneWord {
 pub struct SloveneWordMutation;
 
 impl SloveneWordMutation {
+    /// Creates a new slovene word and records its first revision
+    /// (authored by `author_user_id`, if known).
+    ///
+    /// Returns `None` (and creates nothing) if another slovene word
+    /// with the same lemma and disambiguation already exists.
     pub async fn create<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         slovene_word: NewSloveneWord,
-    ) -> Result<word_slovene::Model> {
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_slovene::Model>> {
+        Self::validate_grammar(&slovene_word.grammar)
+            .into_diagnostic()
+            .wrap_err("Invalid grammatical information for new slovene word.")?;
+
         let transaction = begin_transaction!(database)?;
 
         let random_uuid = generate_random_word_uuid();
@@ -50,20 +76,37 @@ impl SloveneWordMutation {
             .wrap_err("Failed while inserting base word.")?;
 
 
-        let active_slovene_word = word_slovene::ActiveModel {
+        let mut active_slovene_word = word_slovene::ActiveModel {
             word_id: ActiveValue::Set(random_uuid),
             lemma: ActiveValue::Set(slovene_word.lemma),
             disambiguation: ActiveValue::Set(slovene_word.disambiguation),
             description: ActiveValue::Set(slovene_word.description),
             created_at: ActiveValue::Set(created_at.fixed_offset()),
             last_modified_at: ActiveValue::Set(created_at.fixed_offset()),
+            ..Default::default()
         };
 
-        let new_slovene_word = active_slovene_word
-            .insert(&transaction)
-            .await
-            .into_diagnostic()
-            .wrap_err("Failed while inserting slovene word.")?;
+        active_slovene_word.set_grammar(slovene_word.grammar);
+
+        let new_slovene_word = match active_slovene_word.insert(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed while inserting slovene word.");
+            }
+        };
+
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            random_uuid,
+            WordLanguage::Slovene,
+            Self::revision_contents(&new_slovene_word),
+            author_user_id,
+            created_at,
+        )
+        .await?;
 
 
         transaction
@@ -72,17 +115,35 @@ impl SloveneWordMutation {
             .into_diagnostic()
             .wrap_err("Failed to commit english word creation transaction.")?;
 
-        Ok(new_slovene_word)
+        Ok(Some(new_slovene_word))
     }
 
+    /// Updates the given fields of a slovene word and, if its lemma, disambiguation,
+    /// description or grammatical information changed, records a new revision
+    /// (authored by `author_user_id`, if known).
+    ///
+    /// Returns `None` (and changes nothing) if the update would give the word the same
+    /// lemma and disambiguation as another slovene word.
     pub async fn update<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         word_uuid: Uuid,
         update: UpdatedSloveneWord,
-    ) -> Result<word_slovene::Model> {
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_slovene::Model>> {
+        if let Some(updated_grammar) = &update.grammar {
+            Self::validate_grammar(updated_grammar)
+                .into_diagnostic()
+                .wrap_err("Invalid grammatical information for updated slovene word.")?;
+        }
+
+        let transaction = begin_transaction!(database)?;
+
+        let modified_at = Utc::now();
+
+
         let mut active_word_model = word_slovene::ActiveModel {
             word_id: ActiveValue::Unchanged(word_uuid),
-            last_modified_at: ActiveValue::Set(Utc::now().fixed_offset()),
+            last_modified_at: ActiveValue::Set(modified_at.fixed_offset()),
             ..Default::default()
         };
 
@@ -91,27 +152,103 @@ impl SloveneWordMutation {
         };
 
         if let Some(updated_disambiguation) = update.disambiguation {
-            active_word_model.disambiguation = ActiveValue::Set(Some(updated_disambiguation));
+            active_word_model.disambiguation = ActiveValue::Set(updated_disambiguation);
         }
 
         if let Some(updated_description) = update.description {
             active_word_model.description = ActiveValue::Set(Some(updated_description));
         }
 
+        if let Some(updated_grammar) = update.grammar {
+            active_word_model.set_grammar(updated_grammar);
+        }
 
-        let updated_active_word = active_word_model
-            .save(database)
-            .await
-            .into_diagnostic()
-            .wrap_err("Failed to update slovene word.")?;
 
-        let updated_word = updated_active_word
-            .try_into_model()
-            .into_diagnostic()
-            .wrap_err("Failed to convert active slovene model to normal model.")?;
+        let updated_word = match active_word_model.update(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed to update slovene word.");
+            }
+        };
 
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            word_uuid,
+            WordLanguage::Slovene,
+            Self::revision_contents(&updated_word),
+            author_user_id,
+            modified_at,
+        )
+        .await?;
 
-        Ok(updated_word)
+
+        commit_transaction!(transaction)?;
+
+        Ok(Some(updated_word))
+    }
+
+    /// Reverts the lemma, disambiguation, description and grammatical information
+    /// of a slovene word to those of the given (earlier) revision. The revert is recorded as a new revision
+    /// (authored by `author_user_id`, if known), so no history is lost.
+    ///
+    /// Returns `None` (and changes nothing) if another slovene word has since taken
+    /// the lemma and disambiguation of the revision.
+    pub async fn revert_to_revision<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        revision: &word_revision::Model,
+        author_user_id: Option<i32>,
+    ) -> Result<Option<word_slovene::Model>> {
+        let transaction = begin_transaction!(database)?;
+
+        let modified_at = Utc::now();
+
+
+        let mut active_word_model = word_slovene::ActiveModel {
+            word_id: ActiveValue::Unchanged(word_uuid),
+            lemma: ActiveValue::Set(revision.lemma.clone()),
+            disambiguation: ActiveValue::Set(revision.disambiguation.clone()),
+            description: ActiveValue::Set(revision.description.clone()),
+            last_modified_at: ActiveValue::Set(modified_at.fixed_offset()),
+            ..Default::default()
+        };
+
+        // Revisions recorded before grammatical information was tracked
+        // leave the current grammatical information as it is.
+        if let Some(revision_grammar) = &revision.grammar {
+            let grammar = SloveneWordGrammar::from_revision_json(revision_grammar)
+                .ok_or_else(|| miette!("Invalid grammatical information in word revision."))?;
+
+            active_word_model.set_grammar(grammar);
+        }
+
+        let reverted_word = match active_word_model.update(&transaction).await {
+            Ok(word) => word,
+            Err(error) if is_unique_constraint_violation(&error) => return Ok(None),
+            Err(error) => {
+                return Err(error)
+                    .into_diagnostic()
+                    .wrap_err("Failed to revert slovene word.");
+            }
+        };
+
+        WordRevisionMutation::record_if_changed(
+            &transaction,
+            word_uuid,
+            WordLanguage::Slovene,
+            Self::revision_contents(&reverted_word),
+            author_user_id,
+            modified_at,
+        )
+        .await?;
+
+
+        commit_transaction!(transaction)?;
+
+        Ok(Some(reverted_word))
     }
 
     pub async fn set_last_modified_at<C: ConnectionTrait + TransactionTrait>(
@@ -135,5 +272,67 @@ impl SloveneWordMutation {
         Ok(updated_word)
     }
 
+    /// Checks that the grammatical information about a slovene word is consistent:
+    /// only nouns can have a gender or a number restriction, only declinable words
+    /// (see [`PartOfSpeech::is_declinable`]) can have inflected forms, and
+    /// the inflected forms can't be empty or be in a number the word is not used in.
+    pub fn validate_grammar(grammar: &SloveneWordGrammar) -> Result<(), InvalidWordGrammarError> {
+        let is_noun = grammar.part_of_speech == Some(PartOfSpeech::Noun);
+
+        if grammar.gender.is_some() && !is_noun {
+            return Err(InvalidWordGrammarError::OnlyAllowedForNouns {
+                field: "grammatical gender",
+            });
+        }
+
+        if grammar.number_restriction.is_some() && !is_noun {
+            return Err(InvalidWordGrammarError::OnlyAllowedForNouns {
+                field: "number restriction",
+            });
+        }
+
+
+        if grammar.inflection.is_empty() {
+            return Ok(());
+        }
+
+        if !grammar
+            .part_of_speech
+            .is_some_and(PartOfSpeech::is_declinable)
+        {
+            return Err(InvalidWordGrammarError::InflectionOfIndeclinableWord);
+        }
+
+        for (case, number, form) in grammar.inflection.present_forms() {
+            let form_name = || format!("{} {} form", case.name(), number.name());
+
+            if form.trim().is_empty() {
+                return Err(InvalidWordGrammarError::EmptyForm { form: form_name() });
+            }
+
+            if let Some(restriction) = grammar.number_restriction {
+                if !restriction.allows(number) {
+                    return Err(
+                        InvalidWordGrammarError::FormExcludedByNumberRestriction {
+                            form: form_name(),
+                            restriction,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn revision_contents(word: &word_slovene::Model) -> WordRevisionContents {
+        WordRevisionContents {
+            lemma: word.lemma.clone(),
+            disambiguation: word.disambiguation.clone(),
+            description: word.description.clone(),
+            grammar: Some(word.grammar().to_revision_json()),
+        }
+    }
+
     // For deletion, see [`WordMutation::delete`][super::word::WordMutation::delete].
 }
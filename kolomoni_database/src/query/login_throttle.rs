@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{entities::login_throttle, shared::LoginThrottleSubjectType};
+
+
+/// Queries related to the [`crate::entities::login_throttle::Entity`] entity.
+pub struct LoginThrottleQuery;
+
+impl LoginThrottleQuery {
+    /// Returns the time the given username or IP address is locked out until,
+    /// or `None` if it is not currently locked out.
+    pub async fn locked_until<C: ConnectionTrait>(
+        database: &C,
+        subject_type: LoginThrottleSubjectType,
+        subject: &str,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let throttle = login_throttle::Entity::find_by_id((
+            subject_type.name().to_string(),
+            subject.to_string(),
+        ))
+        .filter(login_throttle::Column::LockedUntil.gt(Utc::now().fixed_offset()))
+        .one(database)
+        .await
+        .into_diagnostic()
+        .wrap_err("Failed while searching database for login lockout.")?;
+
+        Ok(throttle
+            .and_then(|throttle| throttle.locked_until)
+            .map(|locked_until| locked_until.to_utc()))
+    }
+
+    /// Get all usernames and IP addresses that are currently locked out,
+    /// ordered by when their lockout ends.
+    pub async fn all_active_lockouts<C: ConnectionTrait>(
+        database: &C,
+    ) -> Result<Vec<login_throttle::Model>> {
+        login_throttle::Entity::find()
+            .filter(login_throttle::Column::LockedUntil.gt(Utc::now().fixed_offset()))
+            .order_by_asc(login_throttle::Column::LockedUntil)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying active login lockouts from database.")
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ConnectionTrait, EntityTrait, QueryOrder};
+
+use crate::entities::permission;
+
+
+/// Queries related to the [`crate::entities::permission::Entity`] entity.
+pub struct PermissionQuery;
+
+impl PermissionQuery {
+    /// Get the last time any permission was added or changed (permissions are only
+    /// ever modified by migrations).
+    pub async fn permissions_last_modified_at<C: ConnectionTrait>(
+        database: &C,
+    ) -> Result<DateTime<Utc>> {
+        let most_recently_modified_permission = permission::Entity::find()
+            .order_by_desc(permission::Column::LastModifiedAt)
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up the most recently modified permission.")?
+            .ok_or_else(|| miette!("No permissions exist in the database."))?;
+
+        Ok(most_recently_modified_permission.last_modified_at.to_utc())
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use kolomoni_auth::{Permission, PermissionSet, Role};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::entities::{role, role_permission};
+
+
+impl From<role::Model> for Role {
+    fn from(model: role::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+        }
+    }
+}
+
+
+/// A role along with the permissions it grants.
+pub struct RoleWithPermissions {
+    pub role: role::Model,
+    pub permissions: PermissionSet,
+}
+
+impl RoleWithPermissions {
+    fn from_models(
+        role: role::Model,
+        role_permissions: Vec<role_permission::Model>,
+    ) -> Result<Self> {
+        let permissions = role_permissions
+            .into_iter()
+            .map(|role_permission| {
+                Permission::from_id(role_permission.permission_id).ok_or_else(|| {
+                    miette!(
+                        "Failed to deserialize database response: unrecognized permission ID {}!",
+                        role_permission.permission_id
+                    )
+                })
+            })
+            .collect::<Result<HashSet<_>>>()?;
+
+        Ok(Self {
+            role,
+            permissions: PermissionSet::from_permission_set(permissions),
+        })
+    }
+}
+
+
+/// Queries related to the [`crate::entities::role::Entity`] entity.
+pub struct RoleQuery;
+
+impl RoleQuery {
+    /// Get all roles along with the permissions they grant, ordered by their internal ID.
+    pub async fn all_roles<C: ConnectionTrait>(database: &C) -> Result<Vec<RoleWithPermissions>> {
+        let roles_with_permissions = role::Entity::find()
+            .order_by_asc(role::Column::Id)
+            .find_with_related(role_permission::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while loading all roles from the database.")?;
+
+        roles_with_permissions
+            .into_iter()
+            .map(|(role, role_permissions)| RoleWithPermissions::from_models(role, role_permissions))
+            .collect()
+    }
+
+    /// Get a role by its internal ID.
+    pub async fn get_role_by_id<C: ConnectionTrait>(
+        database: &C,
+        role_id: i32,
+    ) -> Result<Option<RoleWithPermissions>> {
+        let role_with_permissions = role::Entity::find_by_id(role_id)
+            .find_with_related(role_permission::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for role by ID.")?;
+
+        role_with_permissions
+            .into_iter()
+            .next()
+            .map(|(role, role_permissions)| RoleWithPermissions::from_models(role, role_permissions))
+            .transpose()
+    }
+
+    /// Get a role by its (unique) name.
+    pub async fn get_role_by_name<C: ConnectionTrait>(
+        database: &C,
+        role_name: &str,
+    ) -> Result<Option<RoleWithPermissions>> {
+        let role_with_permissions = role::Entity::find()
+            .filter(role::Column::Name.eq(role_name))
+            .find_with_related(role_permission::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for role by name.")?;
+
+        role_with_permissions
+            .into_iter()
+            .next()
+            .map(|(role, role_permissions)| RoleWithPermissions::from_models(role, role_permissions))
+            .transpose()
+    }
+
+    /// Get the last time any role was created, updated or deleted.
+    ///
+    /// Deletions are recorded by [`crate::mutation::RoleMutation::delete`],
+    /// which updates the modification time of all remaining roles.
+    pub async fn roles_last_modified_at<C: ConnectionTrait>(database: &C) -> Result<DateTime<Utc>> {
+        let most_recently_modified_role = role::Entity::find()
+            .order_by_desc(role::Column::LastModifiedAt)
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up the most recently modified role.")?
+            .ok_or_else(|| miette!("No roles exist in the database."))?;
+
+        Ok(most_recently_modified_role.last_modified_at.to_utc())
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+use crate::entities::two_factor_login_challenge;
+
+
+/// Queries related to the [`crate::entities::two_factor_login_challenge::Entity`] entity.
+pub struct TwoFactorLoginChallengeQuery;
+
+impl TwoFactorLoginChallengeQuery {
+    /// Get an unexpired login challenge by the hash of its token
+    /// (see [`kolomoni_auth::hash_login_challenge_token`]).
+    pub async fn get_unexpired_by_token_hash<C: ConnectionTrait>(
+        database: &C,
+        token_hash: &str,
+    ) -> Result<Option<two_factor_login_challenge::Model>> {
+        two_factor_login_challenge::Entity::find_by_id(token_hash.to_string())
+            .filter(two_factor_login_challenge::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up two-factor login challenge.")
+    }
+}
@@ -1,17 +1,288 @@
+use chrono::{DateTime, SecondsFormat, Utc};
 use miette::{Context, IntoDiagnostic, Result};
-use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::{Expr, Func, Query};
 use sea_orm::{
     ColumnTrait,
+    Condition,
     ConnectionTrait,
     EntityTrait,
     FromQueryResult,
+    Order,
     QueryFilter,
+    QueryOrder,
     QuerySelect,
 };
 
 use super::super::entities::prelude::User;
-use super::super::entities::user;
-use crate::mutation::ArgonHasher;
+use super::super::entities::{role, user, user_role};
+use crate::mutation::{ArgonHasher, UserMutation};
+use crate::shared::DELETED_USER_ID;
+
+
+
+/// Field to sort the user list by.
+///
+/// Ties are always broken by the user ID, which makes the ordering stable
+/// (and thus usable for cursor pagination).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum UserListSortField {
+    #[default]
+    JoinedAt,
+    LastActiveAt,
+    Username,
+    DisplayName,
+}
+
+impl UserListSortField {
+    /// Returns the name of the sort field, as used in pagination cursors.
+    pub fn name(self) -> &'static str {
+        match self {
+            UserListSortField::JoinedAt => "joined_at",
+            UserListSortField::LastActiveAt => "last_active_at",
+            UserListSortField::Username => "username",
+            UserListSortField::DisplayName => "display_name",
+        }
+    }
+
+    /// Attempt to parse a [`UserListSortField`] from its name (e.g. "joined_at").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "joined_at" => Some(Self::JoinedAt),
+            "last_active_at" => Some(Self::LastActiveAt),
+            "username" => Some(Self::Username),
+            "display_name" => Some(Self::DisplayName),
+            _ => None,
+        }
+    }
+
+    fn column(self) -> user::Column {
+        match self {
+            UserListSortField::JoinedAt => user::Column::JoinedAt,
+            UserListSortField::LastActiveAt => user::Column::LastActiveAt,
+            UserListSortField::Username => user::Column::Username,
+            UserListSortField::DisplayName => user::Column::DisplayName,
+        }
+    }
+
+    /// Extracts the value of this sort field from the given user model,
+    /// serialized into the form used in pagination cursors.
+    fn cursor_value_of(self, user: &user::Model) -> String {
+        match self {
+            UserListSortField::JoinedAt => user
+                .joined_at
+                .to_utc()
+                .to_rfc3339_opts(SecondsFormat::Micros, true),
+            UserListSortField::LastActiveAt => user
+                .last_active_at
+                .to_utc()
+                .to_rfc3339_opts(SecondsFormat::Micros, true),
+            UserListSortField::Username => user.username.clone(),
+            UserListSortField::DisplayName => user.display_name.clone(),
+        }
+    }
+}
+
+
+/// Direction to sort the user list in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum UserListSortDirection {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl UserListSortDirection {
+    fn order(self) -> Order {
+        match self {
+            UserListSortDirection::Ascending => Order::Asc,
+            UserListSortDirection::Descending => Order::Desc,
+        }
+    }
+}
+
+
+/// Position in a sorted user list, pointing just after the last user of some page.
+///
+/// A cursor is only valid for the sort field and direction it was created with;
+/// the sort field is embedded in the cursor and checked when it is used.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UserListCursor {
+    sort_field: UserListSortField,
+    sort_direction: UserListSortDirection,
+    sort_value: String,
+    user_id: i32,
+}
+
+impl UserListCursor {
+    fn after_user(
+        user: &user::Model,
+        sort_field: UserListSortField,
+        sort_direction: UserListSortDirection,
+    ) -> Self {
+        Self {
+            sort_field,
+            sort_direction,
+            sort_value: sort_field.cursor_value_of(user),
+            user_id: user.id,
+        }
+    }
+
+    /// Encodes the cursor into an opaque, URL-safe string
+    /// (lower-case hexadecimal characters).
+    pub fn encode(&self) -> String {
+        let direction = match self.sort_direction {
+            UserListSortDirection::Ascending => "asc",
+            UserListSortDirection::Descending => "desc",
+        };
+
+        let raw_cursor = format!(
+            "{}:{}:{}:{}",
+            self.sort_field.name(),
+            direction,
+            self.user_id,
+            self.sort_value
+        );
+
+        raw_cursor
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Decodes a cursor previously produced by [`Self::encode`].
+    /// Returns `None` if the cursor is malformed.
+    pub fn decode(encoded_cursor: &str) -> Option<Self> {
+        if encoded_cursor.len() % 2 != 0 || !encoded_cursor.is_ascii() {
+            return None;
+        }
+
+        let raw_bytes = (0..encoded_cursor.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&encoded_cursor[index..index + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        let raw_cursor = String::from_utf8(raw_bytes).ok()?;
+
+        let mut cursor_parts = raw_cursor.splitn(4, ':');
+
+        let sort_field = UserListSortField::from_name(cursor_parts.next()?)?;
+        let sort_direction = match cursor_parts.next()? {
+            "asc" => UserListSortDirection::Ascending,
+            "desc" => UserListSortDirection::Descending,
+            _ => return None,
+        };
+        let user_id = cursor_parts.next()?.parse::<i32>().ok()?;
+        let sort_value = cursor_parts.next()?.to_string();
+
+        // Timestamp sort values must be parseable, otherwise the cursor is useless.
+        if matches!(
+            sort_field,
+            UserListSortField::JoinedAt | UserListSortField::LastActiveAt
+        ) && DateTime::parse_from_rfc3339(&sort_value).is_err()
+        {
+            return None;
+        }
+
+        Some(Self {
+            sort_field,
+            sort_direction,
+            sort_value,
+            user_id,
+        })
+    }
+
+    /// Returns `true` if this cursor was created for the given sort field and direction.
+    pub fn matches_ordering(
+        &self,
+        sort_field: UserListSortField,
+        sort_direction: UserListSortDirection,
+    ) -> bool {
+        self.sort_field == sort_field && self.sort_direction == sort_direction
+    }
+
+    /// Builds a condition matching only the users that come after this cursor.
+    fn into_condition(self) -> Condition {
+        let column = self.sort_field.column();
+
+        let sort_value_expression = match self.sort_field {
+            UserListSortField::JoinedAt | UserListSortField::LastActiveAt => {
+                // PANIC SAFETY: Timestamp values are validated when the cursor is decoded
+                // and are always valid when created by `after_user`.
+                let timestamp = DateTime::parse_from_rfc3339(&self.sort_value).unwrap();
+                Expr::value(timestamp)
+            }
+            UserListSortField::Username | UserListSortField::DisplayName => {
+                Expr::value(self.sort_value)
+            }
+        };
+
+        let (value_comparison, id_comparison) = match self.sort_direction {
+            UserListSortDirection::Ascending => (
+                Expr::col(column).gt(sort_value_expression.clone()),
+                user::Column::Id.gt(self.user_id),
+            ),
+            UserListSortDirection::Descending => (
+                Expr::col(column).lt(sort_value_expression.clone()),
+                user::Column::Id.lt(self.user_id),
+            ),
+        };
+
+        Condition::any().add(value_comparison).add(
+            Condition::all()
+                .add(Expr::col(column).eq(sort_value_expression))
+                .add(id_comparison),
+        )
+    }
+}
+
+
+/// Filtering, sorting and pagination options for [`UserQuery::users_paginated`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UsersQueryOptions {
+    /// If `Some`, only users whose username or display name contain
+    /// this string (case-insensitive) are returned.
+    pub search: Option<String>,
+
+    /// If `Some`, only users that have the role with this name are returned.
+    pub role_name: Option<String>,
+
+    pub joined_after: Option<DateTime<Utc>>,
+    pub joined_before: Option<DateTime<Utc>>,
+
+    pub last_active_after: Option<DateTime<Utc>>,
+    pub last_active_before: Option<DateTime<Utc>>,
+
+    pub sort_field: UserListSortField,
+    pub sort_direction: UserListSortDirection,
+
+    /// If `Some`, the page starts just after the position this cursor points to.
+    pub after_cursor: Option<UserListCursor>,
+
+    /// Maximum amount of users on a page.
+    pub page_size: u64,
+}
+
+
+/// A single page of users, as returned by [`UserQuery::users_paginated`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UsersPage {
+    pub users: Vec<user::Model>,
+
+    /// Cursor pointing to the next page, or `None` if this is the last page.
+    pub next_cursor: Option<UserListCursor>,
+}
+
+
+/// Escapes `LIKE` wildcard characters, meaning the resulting
+/// string will only match literally.
+fn escape_like_pattern(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
 
 
 /// Queries related to the [`crate::entities::user::Entity`] entity.
@@ -98,6 +369,10 @@ impl UserQuery {
 
     /// Validate a user's credentials (the username and password combination).
     /// This is basically the login verification method.
+    ///
+    /// If the credentials are valid, but the stored password hash is outdated
+    /// (see [`ArgonHasher::needs_rehash`]), the password is rehashed
+    /// and the new hash is saved to the database.
     pub async fn validate_user_credentials<C: ConnectionTrait>(
         database: &C,
         hasher: &ArgonHasher,
@@ -111,24 +386,51 @@ impl UserQuery {
             .into_diagnostic()
             .wrap_err("Failed while looking up user in database (by username).")?;
 
-        if let Some(user) = user {
-            let is_valid_password = hasher
-                .verify_password_against_hash(password, &user.hashed_password)
-                .wrap_err("Errored while validating password against hash.")?;
+        let Some(user) = user else {
+            return Ok(None);
+        };
 
-            if is_valid_password {
-                Ok(Some(user))
-            } else {
-                Ok(None)
-            }
-        } else {
-            Ok(None)
+        // The deleted user placeholder has no usable password hash.
+        if user.id == DELETED_USER_ID {
+            return Ok(None);
+        }
+
+
+        let is_valid_password = hasher
+            .verify_password_against_hash(password, &user.hashed_password)
+            .wrap_err("Errored while validating password against hash.")?;
+
+        if !is_valid_password {
+            return Ok(None);
+        }
+
+
+        let needs_rehash = hasher
+            .needs_rehash(&user.hashed_password)
+            .wrap_err("Errored while checking whether the password hash is outdated.")?;
+
+        if !needs_rehash {
+            return Ok(Some(user));
         }
+
+        // The password is valid, but its hash uses outdated parameters (or the legacy shared salt),
+        // so we take this opportunity to replace it (we have the plaintext password only at this point).
+        let new_hashed_password = hasher
+            .hash_password(password)
+            .wrap_err("Failed to rehash password.")?;
+
+        let updated_user =
+            UserMutation::update_password_hash_by_user_id(database, user.id, new_hashed_password)
+                .await
+                .wrap_err("Failed to save rehashed password.")?;
+
+        Ok(Some(updated_user))
     }
 
-    /// Get a list of all registered users.
+    /// Get a list of all registered users (excluding the deleted user placeholder).
     pub async fn get_all_users<C: ConnectionTrait>(database: &C) -> Result<Vec<user::Model>> {
         let users = User::find()
+            .filter(user::Column::Id.ne(DELETED_USER_ID))
             .all(database)
             .await
             .into_diagnostic()
@@ -136,4 +438,114 @@ impl UserQuery {
 
         Ok(users)
     }
+
+    /// Get a single page of registered users, filtered and sorted
+    /// as described by the provided [`UsersQueryOptions`].
+    pub async fn users_paginated<C: ConnectionTrait>(
+        database: &C,
+        options: UsersQueryOptions,
+    ) -> Result<UsersPage> {
+        let mut users_query = User::find().filter(user::Column::Id.ne(DELETED_USER_ID));
+
+
+        if let Some(search) = options.search {
+            let search_pattern = format!(
+                "%{}%",
+                escape_like_pattern(&search.to_lowercase())
+            );
+
+            users_query = users_query.filter(
+                Condition::any()
+                    .add(
+                        Expr::expr(Func::lower(Expr::col(user::Column::Username)))
+                            .like(search_pattern.clone()),
+                    )
+                    .add(
+                        Expr::expr(Func::lower(Expr::col(user::Column::DisplayName)))
+                            .like(search_pattern),
+                    ),
+            );
+        }
+
+        if let Some(role_name) = options.role_name {
+            // Functionally equivalent to the following SQL condition:
+            //      "user"."id" IN (
+            //        SELECT "user_role"."user_id" FROM "user_role"
+            //          INNER JOIN "role" ON "role"."id" = "user_role"."role_id"
+            //          WHERE "role"."name" = <role name>
+            //            AND ("user_role"."expires_at" IS NULL OR "user_role"."expires_at" > NOW())
+            //      )
+            users_query = users_query.filter(
+                user::Column::Id.in_subquery(
+                    Query::select()
+                        .column((user_role::Entity, user_role::Column::UserId))
+                        .from(user_role::Entity)
+                        .inner_join(
+                            role::Entity,
+                            Expr::col((role::Entity, role::Column::Id))
+                                .equals((user_role::Entity, user_role::Column::RoleId)),
+                        )
+                        .cond_where(
+                            Condition::all()
+                                .add(Expr::col((role::Entity, role::Column::Name)).eq(role_name))
+                                .add(super::user_role::unexpired_user_role_condition()),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+
+        if let Some(joined_after) = options.joined_after {
+            users_query = users_query.filter(user::Column::JoinedAt.gte(joined_after));
+        }
+
+        if let Some(joined_before) = options.joined_before {
+            users_query = users_query.filter(user::Column::JoinedAt.lte(joined_before));
+        }
+
+        if let Some(last_active_after) = options.last_active_after {
+            users_query = users_query.filter(user::Column::LastActiveAt.gte(last_active_after));
+        }
+
+        if let Some(last_active_before) = options.last_active_before {
+            users_query = users_query.filter(user::Column::LastActiveAt.lte(last_active_before));
+        }
+
+        if let Some(cursor) = options.after_cursor {
+            users_query = users_query.filter(cursor.into_condition());
+        }
+
+
+        // We request one user more than the page size to find out whether there is a next page.
+        let mut users = users_query
+            .order_by(
+                options.sort_field.column(),
+                options.sort_direction.order(),
+            )
+            .order_by(user::Column::Id, options.sort_direction.order())
+            .limit(options.page_size + 1)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying a page of users from database.")?;
+
+
+        let has_next_page = users.len() as u64 > options.page_size;
+
+        let next_cursor = if has_next_page {
+            users.truncate(options.page_size as usize);
+
+            users.last().map(|last_user| {
+                UserListCursor::after_user(
+                    last_user,
+                    options.sort_field,
+                    options.sort_direction,
+                )
+            })
+        } else {
+            None
+        };
+
+        Ok(UsersPage { users, next_cursor })
+    }
 }
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use kolomoni_auth::{Permission, PermissionSet};
+use miette::{miette, Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::entities::{user_api_token, user_api_token_permission};
+
+
+/// A personal API token along with the permissions it is limited to.
+pub struct UserApiTokenWithPermissions {
+    pub token: user_api_token::Model,
+    pub permissions: PermissionSet,
+}
+
+impl UserApiTokenWithPermissions {
+    fn from_models(
+        token: user_api_token::Model,
+        token_permissions: Vec<user_api_token_permission::Model>,
+    ) -> Result<Self> {
+        let permissions = token_permissions
+            .into_iter()
+            .map(|token_permission| {
+                Permission::from_id(token_permission.permission_id).ok_or_else(|| {
+                    miette!(
+                        "Failed to deserialize database response: unrecognized permission ID {}!",
+                        token_permission.permission_id
+                    )
+                })
+            })
+            .collect::<Result<HashSet<_>>>()?;
+
+        Ok(Self {
+            token,
+            permissions: PermissionSet::from_permission_set(permissions),
+        })
+    }
+}
+
+
+/// Queries related to the [`crate::entities::user_api_token::Entity`] entity.
+pub struct UserApiTokenQuery;
+
+impl UserApiTokenQuery {
+    /// Get a personal API token by the hash of its value
+    /// (see [`kolomoni_auth::hash_api_token`]).
+    ///
+    /// Expired tokens are returned as well; it is up to the caller to check the expiry.
+    pub async fn get_token_by_hash<C: ConnectionTrait>(
+        database: &C,
+        token_hash: &str,
+    ) -> Result<Option<UserApiTokenWithPermissions>> {
+        let token_with_permissions = user_api_token::Entity::find()
+            .filter(user_api_token::Column::TokenHash.eq(token_hash))
+            .find_with_related(user_api_token_permission::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for API token by hash.")?;
+
+        token_with_permissions
+            .into_iter()
+            .next()
+            .map(|(token, token_permissions)| {
+                UserApiTokenWithPermissions::from_models(token, token_permissions)
+            })
+            .transpose()
+    }
+
+    /// Get a personal API token by its ID, but only if it belongs to the given user.
+    pub async fn get_token_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        token_id: Uuid,
+    ) -> Result<Option<user_api_token::Model>> {
+        user_api_token::Entity::find_by_id(token_id)
+            .filter(user_api_token::Column::UserId.eq(user_id))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for API token.")
+    }
+
+    /// Get all of the user's personal API tokens (including expired ones), oldest first.
+    pub async fn tokens_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<Vec<UserApiTokenWithPermissions>> {
+        let tokens_with_permissions = user_api_token::Entity::find()
+            .filter(user_api_token::Column::UserId.eq(user_id))
+            .order_by_asc(user_api_token::Column::CreatedAt)
+            .find_with_related(user_api_token_permission::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying API tokens from database.")?;
+
+        tokens_with_permissions
+            .into_iter()
+            .map(|(token, token_permissions)| {
+                UserApiTokenWithPermissions::from_models(token, token_permissions)
+            })
+            .collect()
+    }
+}
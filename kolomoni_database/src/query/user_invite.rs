@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use kolomoni_auth::{Role, RoleSet};
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ConnectionTrait, EntityTrait, QueryOrder};
+use uuid::Uuid;
+
+use crate::entities::{role, user_invite};
+
+
+/// A registration invite along with the roles it grants to users that register with it.
+pub struct UserInviteWithRoles {
+    pub invite: user_invite::Model,
+    pub roles: RoleSet,
+}
+
+impl UserInviteWithRoles {
+    fn from_models(invite: user_invite::Model, invite_roles: Vec<role::Model>) -> Self {
+        let roles = invite_roles
+            .into_iter()
+            .map(Role::from)
+            .collect::<HashSet<_>>();
+
+        Self {
+            invite,
+            roles: RoleSet::from_role_set(roles),
+        }
+    }
+}
+
+
+/// Queries related to the [`crate::entities::user_invite::Entity`] entity.
+pub struct UserInviteQuery;
+
+impl UserInviteQuery {
+    /// Get an invite (including revoked, expired or used up ones) by its ID.
+    pub async fn get_invite_by_id<C: ConnectionTrait>(
+        database: &C,
+        invite_id: Uuid,
+    ) -> Result<Option<UserInviteWithRoles>> {
+        let invite_with_roles = user_invite::Entity::find_by_id(invite_id)
+            .find_with_related(role::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for user invite.")?;
+
+        Ok(invite_with_roles
+            .into_iter()
+            .next()
+            .map(|(invite, invite_roles)| UserInviteWithRoles::from_models(invite, invite_roles)))
+    }
+
+    /// Get all invites (including revoked, expired or used up ones), oldest first.
+    pub async fn all_invites<C: ConnectionTrait>(database: &C) -> Result<Vec<UserInviteWithRoles>> {
+        let invites_with_roles = user_invite::Entity::find()
+            .order_by_asc(user_invite::Column::CreatedAt)
+            .find_with_related(role::Entity)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying user invites from database.")?;
+
+        Ok(invites_with_roles
+            .into_iter()
+            .map(|(invite, invite_roles)| UserInviteWithRoles::from_models(invite, invite_roles))
+            .collect())
+    }
+}
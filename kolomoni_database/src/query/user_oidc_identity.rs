@@ -0,0 +1,23 @@
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ConnectionTrait, EntityTrait};
+
+use crate::entities::user_oidc_identity;
+
+
+/// Queries related to the [`crate::entities::user_oidc_identity::Entity`] entity.
+pub struct UserOidcIdentityQuery;
+
+impl UserOidcIdentityQuery {
+    /// Find the link of the OpenID Connect identity with the given issuer and subject, if any.
+    pub async fn get_identity<C: ConnectionTrait>(
+        database: &C,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<Option<user_oidc_identity::Model>> {
+        user_oidc_identity::Entity::find_by_id((issuer.to_string(), subject.to_string()))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up OpenID Connect identity.")
+    }
+}
@@ -1,11 +1,13 @@
 use std::collections::HashSet;
 
+use chrono::Utc;
 use kolomoni_auth::{Permission, PermissionSet, Role, RoleSet};
 use miette::{miette, Result};
 use miette::{Context, IntoDiagnostic};
 use sea_orm::sea_query::Expr;
 use sea_orm::{
     ColumnTrait,
+    Condition,
     ConnectionTrait,
     EntityTrait,
     FromQueryResult,
@@ -17,6 +19,18 @@ use sea_orm::{
 use crate::entities;
 
 
+/// Returns a condition that matches only role grants that have not expired yet.
+pub(crate) fn unexpired_user_role_condition() -> Condition {
+    Condition::any()
+        .add(entities::user_role::Column::ExpiresAt.is_null())
+        .add(entities::user_role::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+}
+
+
+/// Queries related to user roles.
+///
+/// Role grants that have expired are ignored by all queries here, even if they
+/// have not been pruned from the database yet.
 pub struct UserRoleQuery;
 
 impl UserRoleQuery {
@@ -32,7 +46,8 @@ impl UserRoleQuery {
         // Functionally equivalent to the following SQL query:
         //      SELECT DISTINCT role_permission.permission_id FROM role_permission
         //        INNER JOIN user_role ON user_role.role_id = role_permission.role_id
-        //        WHERE user_role.user_id = <some user id>;
+        //        WHERE user_role.user_id = <some user id>
+        //          AND (user_role.expires_at IS NULL OR user_role.expires_at > NOW());
 
         let distinct_permission_ids = entities::role_permission::Entity::find()
             .select_only()
@@ -46,6 +61,7 @@ impl UserRoleQuery {
                     .into(),
             )
             .filter(entities::user_role::Column::UserId.eq(user_id))
+            .filter(unexpired_user_role_condition())
             .into_model::<PermissionIdSelect>()
             .all(database)
             .await
@@ -77,16 +93,10 @@ impl UserRoleQuery {
     }
 
     pub async fn user_roles<C: ConnectionTrait>(database: &C, user_id: i32) -> Result<RoleSet> {
-        #[derive(Debug, FromQueryResult, PartialEq, Eq, Hash)]
-        struct RoleIdSelect {
-            role_id: i32,
-        }
-
-        let user_roles = entities::user_role::Entity::find()
-            .select_only()
-            .column(entities::user_role::Column::RoleId)
+        let user_roles = entities::role::Entity::find()
+            .inner_join(entities::user_role::Entity)
             .filter(entities::user_role::Column::UserId.eq(user_id))
-            .into_model::<RoleIdSelect>()
+            .filter(unexpired_user_role_condition())
             .all(database)
             .await
             .into_diagnostic()
@@ -95,15 +105,8 @@ impl UserRoleQuery {
 
         let role_set = user_roles
             .into_iter()
-            .map(|select_result| select_result.role_id)
-            .map(|role_id| {
-                Role::from_id(role_id).ok_or_else(|| {
-                    miette!(
-                        "Failed to deserialize database response: unrecognized role ID {role_id}!"
-                    )
-                })
-            })
-            .collect::<Result<HashSet<_>>>()?;
+            .map(Role::from)
+            .collect::<HashSet<_>>();
 
         Ok(RoleSet::from_role_set(role_set))
     }
@@ -121,7 +124,8 @@ impl UserRoleQuery {
         // Functionally equivalent to the following SQL query:
         //      SELECT COUNT(1) AS "count" FROM "role_permission"
         //        INNER JOIN "user_role" ON "role_permission"."role_id" = "user_role"."role_id"
-        //        WHERE "user_role"."user_id" = <user id> AND "role_permission"."permission_id" = <permission id>;
+        //        WHERE "user_role"."user_id" = <user id> AND "role_permission"."permission_id" = <permission id>
+        //          AND ("user_role"."expires_at" IS NULL OR "user_role"."expires_at" > NOW());
 
         let mut count_query = entities::role_permission::Entity::find().select_only();
 
@@ -139,6 +143,7 @@ impl UserRoleQuery {
             )
             .filter(entities::user_role::Column::UserId.eq(user_id))
             .filter(entities::role_permission::Column::PermissionId.eq(permission.id()))
+            .filter(unexpired_user_role_condition())
             .into_model::<PermissionCheckCountResult>()
             .one(database)
             .await
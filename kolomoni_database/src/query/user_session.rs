@@ -0,0 +1,42 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::entities::user_session;
+
+
+/// Queries related to the [`crate::entities::user_session::Entity`] entity.
+pub struct UserSessionQuery;
+
+impl UserSessionQuery {
+    /// Get a session by its ID, but only if it belongs to the given user and has not expired yet.
+    pub async fn get_active_session<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+        session_id: Uuid,
+    ) -> Result<Option<user_session::Model>> {
+        user_session::Entity::find_by_id(session_id)
+            .filter(user_session::Column::UserId.eq(user_id))
+            .filter(user_session::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for active user session.")
+    }
+
+    /// Get all of the user's sessions that have not expired yet, oldest first.
+    pub async fn active_sessions_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<Vec<user_session::Model>> {
+        user_session::Entity::find()
+            .filter(user_session::Column::UserId.eq(user_id))
+            .filter(user_session::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+            .order_by_asc(user_session::Column::CreatedAt)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying active user sessions from database.")
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::Utc;
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::sea_query::Condition;
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::entities::user_suspension;
+
+
+/// Returns a condition matching suspensions that are currently in effect,
+/// i.e. those that have neither been lifted nor have run out.
+pub(crate) fn active_suspension_condition() -> Condition {
+    Condition::all()
+        .add(user_suspension::Column::LiftedAt.is_null())
+        .add(
+            Condition::any()
+                .add(user_suspension::Column::EndsAt.is_null())
+                .add(user_suspension::Column::EndsAt.gt(Utc::now().fixed_offset())),
+        )
+}
+
+
+/// Queries related to the [`crate::entities::user_suspension::Entity`] entity.
+pub struct UserSuspensionQuery;
+
+impl UserSuspensionQuery {
+    /// Get the suspension of the given user that is currently in effect, if any.
+    ///
+    /// If (unusually) more than one suspension is in effect,
+    /// the one that was issued last is returned.
+    pub async fn active_suspension<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<Option<user_suspension::Model>> {
+        user_suspension::Entity::find()
+            .filter(user_suspension::Column::UserId.eq(user_id))
+            .filter(active_suspension_condition())
+            .order_by_desc(user_suspension::Column::SuspendedAt)
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up active user suspension.")
+    }
+
+    /// Get all suspensions of the given user (including lifted and ended ones), newest first.
+    pub async fn all_suspensions_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<Vec<user_suspension::Model>> {
+        user_suspension::Entity::find()
+            .filter(user_suspension::Column::UserId.eq(user_id))
+            .order_by_desc(user_suspension::Column::SuspendedAt)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying user suspensions from database.")
+    }
+}
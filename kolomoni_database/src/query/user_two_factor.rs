@@ -0,0 +1,55 @@
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::entities::{user_two_factor, user_two_factor_recovery_code};
+
+
+/// Queries related to the [`crate::entities::user_two_factor::Entity`] entity.
+pub struct UserTwoFactorQuery;
+
+impl UserTwoFactorQuery {
+    /// Get the user's two-factor authentication secret, if they have started enrolling.
+    ///
+    /// Enrollment is only complete (and two-factor authentication required when logging in)
+    /// once `enabled_at` is set.
+    pub async fn get_by_user_id<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<Option<user_two_factor::Model>> {
+        user_two_factor::Entity::find_by_id(user_id)
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up two-factor authentication secret.")
+    }
+
+    /// Returns whether the user has enabled (i.e. finished enrolling into)
+    /// two-factor authentication.
+    pub async fn is_enabled_for_user<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<bool> {
+        let two_factor = Self::get_by_user_id(database, user_id).await?;
+
+        Ok(matches!(
+            two_factor,
+            Some(user_two_factor::Model {
+                enabled_at: Some(_),
+                ..
+            })
+        ))
+    }
+
+    /// Count the recovery codes the user has not used yet.
+    pub async fn remaining_recovery_code_count<C: ConnectionTrait>(
+        database: &C,
+        user_id: i32,
+    ) -> Result<u64> {
+        user_two_factor_recovery_code::Entity::find()
+            .filter(user_two_factor_recovery_code::Column::UserId.eq(user_id))
+            .count(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while counting remaining recovery codes.")
+    }
+}
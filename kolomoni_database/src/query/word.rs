@@ -13,6 +13,60 @@ use uuid::Uuid;
 
 use crate::entities::{self, word};
 
+
+/// Options for fetching a single page of words.
+///
+/// Pages are ordered by word ID. Because word IDs are UUIDv7, this is
+/// (roughly) the order in which the words were created, and it stays
+/// stable when words are added or removed in-between fetching pages.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WordPageOptions {
+    /// Only return words whose ID comes after this one
+    /// (i.e. the `next_cursor` of the previous page).
+    pub after_word_id: Option<Uuid>,
+
+    /// Maximum number of words to return.
+    pub page_size: u64,
+}
+
+
+/// A single page of words.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WordsPage<W> {
+    pub words: Vec<W>,
+
+    /// ID of the last word on this page, if there are more words after it.
+    /// Pass it as [`WordPageOptions::after_word_id`] to get the next page.
+    pub next_cursor: Option<Uuid>,
+}
+
+impl<W> WordsPage<W> {
+    /// Builds a page out of up to `page_size + 1` words that were fetched
+    /// in word ID order. The extra word only signals that there is a next page.
+    pub(crate) fn from_overfetched_words<F>(
+        mut words: Vec<W>,
+        page_size: u64,
+        word_id_of: F,
+    ) -> Self
+    where
+        F: Fn(&W) -> Uuid,
+    {
+        let has_next_page = words.len() as u64 > page_size;
+        if has_next_page {
+            words.truncate(page_size as usize);
+        }
+
+        let next_cursor = match has_next_page {
+            true => words.last().map(word_id_of),
+            false => None,
+        };
+
+        Self { words, next_cursor }
+    }
+}
+
+
+
 pub struct WordQuery;
 
 impl WordQuery {
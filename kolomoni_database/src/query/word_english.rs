@@ -1,13 +1,14 @@
 use chrono::{DateTime, Utc};
 use miette::Result;
 use miette::{Context, IntoDiagnostic};
-use sea_orm::sea_query::Expr;
+use sea_orm::sea_query::{Expr, SimpleExpr};
 use sea_orm::{
     ColumnTrait,
     ConnectionTrait,
     EntityTrait,
     FromQueryResult,
     QueryFilter,
+    QueryOrder,
     QuerySelect,
     TransactionTrait,
 };
@@ -19,6 +20,10 @@ use super::{
     TranslationQuery,
     TranslationSuggestionQuery,
     WordCategoryQuery,
+    EnglishWordLink,
+    WordLinkQuery,
+    WordPageOptions,
+    WordsPage,
 };
 use crate::entities::{category, word_english};
 
@@ -33,6 +38,7 @@ pub struct RelatedEnglishWordInfo {
     pub categories: Vec<category::Model>,
     pub suggested_translations: Vec<ExpandedSloveneWordInfo>,
     pub translations: Vec<ExpandedSloveneWordInfo>,
+    pub links: Vec<EnglishWordLink>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -41,6 +47,7 @@ pub struct ExpandedEnglishWordInfo {
     pub categories: Vec<category::Model>,
     pub suggested_translations: Vec<ExpandedSloveneWordInfo>,
     pub translations: Vec<ExpandedSloveneWordInfo>,
+    pub links: Vec<EnglishWordLink>,
 }
 
 
@@ -78,9 +85,14 @@ impl EnglishWordQuery {
         }
     }
 
-    pub async fn word_exists_by_lemma<C: ConnectionTrait>(
+    /// Returns whether an english word with the given lemma and disambiguation exists.
+    ///
+    /// Words with the same lemma (homonyms) are distinguished by their disambiguation,
+    /// which means this is the pair that needs to be unique.
+    pub async fn word_exists_by_lemma_and_disambiguation<C: ConnectionTrait>(
         database: &C,
         lemma: String,
+        disambiguation: Option<String>,
     ) -> Result<bool> {
         #[derive(Debug, FromQueryResult, PartialEq, Eq, Hash)]
         struct WordCount {
@@ -93,11 +105,14 @@ impl EnglishWordQuery {
 
         let count_result = word_exists_query
             .filter(word_english::Column::Lemma.eq(lemma))
+            .filter(disambiguation_condition(disambiguation))
             .into_model::<WordCount>()
             .one(database)
             .await
             .into_diagnostic()
-            .wrap_err("Failed while looking up whether the english word exists by lemma.")?;
+            .wrap_err(
+                "Failed while looking up whether the english word exists by lemma and disambiguation.",
+            )?;
 
         match count_result {
             Some(word_count) => {
@@ -119,16 +134,35 @@ impl EnglishWordQuery {
             .wrap_err("Failed while searching database for english word by UUID.")
     }
 
-    pub async fn word_by_lemma<C: ConnectionTrait>(
+    /// Returns all english words with the given lemma (i.e. the word and its homonyms),
+    /// ordered by their creation.
+    pub async fn words_by_lemma<C: ConnectionTrait>(
         database: &C,
         word_lemma: String,
+    ) -> Result<Vec<word_english::Model>> {
+        WordEnglish::find()
+            .filter(word_english::Column::Lemma.eq(word_lemma))
+            .order_by_asc(word_english::Column::WordId)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for english words by lemma.")
+    }
+
+    pub async fn word_by_lemma_and_disambiguation<C: ConnectionTrait>(
+        database: &C,
+        word_lemma: String,
+        disambiguation: Option<String>,
     ) -> Result<Option<word_english::Model>> {
         WordEnglish::find()
             .filter(word_english::Column::Lemma.eq(word_lemma))
+            .filter(disambiguation_condition(disambiguation))
             .one(database)
             .await
             .into_diagnostic()
-            .wrap_err("Failed while searching database for english word by lemma.")
+            .wrap_err(
+                "Failed while searching database for english word by lemma and disambiguation.",
+            )
     }
 
     pub async fn all_words<C: ConnectionTrait>(
@@ -170,7 +204,54 @@ impl EnglishWordQuery {
             .into_diagnostic()
             .wrap_err("Failed while querying all english words from the database.")?;
 
+        Self::expand_words(database, base_words).await
+    }
+
+    /// Like [`Self::all_words_expanded`], but only returns a single page of words,
+    /// ordered by word ID (see [`WordPageOptions`]).
+    pub async fn all_words_expanded_paginated<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        options: EnglishWordsQueryOptions,
+        page_options: WordPageOptions,
+    ) -> Result<WordsPage<ExpandedEnglishWordInfo>> {
+        let mut query = WordEnglish::find();
+
+
+        // Add modifiers onto the query based on `options`.
+        if let Some(only_words_modified_after) = options.only_words_modified_after {
+            query = query.filter(word_english::Column::LastModifiedAt.gt(only_words_modified_after));
+        }
+
+        if let Some(after_word_id) = page_options.after_word_id {
+            query = query.filter(word_english::Column::WordId.gt(after_word_id));
+        }
+
+
+        // One word more than requested is fetched to find out whether there is a next page.
+        let base_words = query
+            .order_by_asc(word_english::Column::WordId)
+            .limit(page_options.page_size + 1)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying a page of english words from the database.")?;
+
+        let base_words_page = WordsPage::from_overfetched_words(
+            base_words,
+            page_options.page_size,
+            |word| word.word_id,
+        );
+
+        Ok(WordsPage {
+            words: Self::expand_words(database, base_words_page.words).await?,
+            next_cursor: base_words_page.next_cursor,
+        })
+    }
 
+    async fn expand_words<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        base_words: Vec<word_english::Model>,
+    ) -> Result<Vec<ExpandedEnglishWordInfo>> {
         // PERF This could be improved.
 
         let mut expanded_english_words = Vec::with_capacity(base_words.len());
@@ -184,6 +265,7 @@ impl EnglishWordQuery {
                 categories: related_info.categories,
                 suggested_translations: related_info.suggested_translations,
                 translations: related_info.translations,
+                links: related_info.links,
             });
         }
 
@@ -205,25 +287,33 @@ impl EnglishWordQuery {
             categories: related_info.categories,
             suggested_translations: related_info.suggested_translations,
             translations: related_info.translations,
+            links: related_info.links,
         }))
     }
 
-    pub async fn expanded_word_by_lemma<C: ConnectionTrait + TransactionTrait>(
+    /// Returns all english words with the given lemma (i.e. the word and its homonyms),
+    /// along with their related information, ordered by their creation.
+    pub async fn expanded_words_by_lemma<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         word_lemma: String,
-    ) -> Result<Option<ExpandedEnglishWordInfo>> {
-        let Some(word_model) = Self::word_by_lemma(database, word_lemma).await? else {
-            return Ok(None);
-        };
+    ) -> Result<Vec<ExpandedEnglishWordInfo>> {
+        let base_words = Self::words_by_lemma(database, word_lemma).await?;
 
-        let related_info = Self::related_word_information_only(database, word_model.word_id).await?;
+        let mut expanded_words = Vec::with_capacity(base_words.len());
+        for base_word in base_words {
+            let related_info =
+                Self::related_word_information_only(database, base_word.word_id).await?;
 
-        Ok(Some(ExpandedEnglishWordInfo {
-            word: word_model,
-            categories: related_info.categories,
-            suggested_translations: related_info.suggested_translations,
-            translations: related_info.translations,
-        }))
+            expanded_words.push(ExpandedEnglishWordInfo {
+                word: base_word,
+                categories: related_info.categories,
+                suggested_translations: related_info.suggested_translations,
+                translations: related_info.translations,
+                links: related_info.links,
+            });
+        }
+
+        Ok(expanded_words)
     }
 
     /// PERF: This might be a good candidate for optimization, probably with caching.
@@ -250,9 +340,16 @@ impl EnglishWordQuery {
                     )
                     .await?;
 
+                let suggested_translation_word_links = WordLinkQuery::links_for_slovene_word(
+                    database,
+                    suggested_translation_model.word_id,
+                )
+                .await?;
+
                 suggested_translations.push(ExpandedSloveneWordInfo {
                     word: suggested_translation_model,
                     categories: suggested_translation_word_categories,
+                    links: suggested_translation_word_links,
                 });
             }
 
@@ -273,9 +370,14 @@ impl EnglishWordQuery {
                 )
                 .await?;
 
+                let translated_word_links =
+                    WordLinkQuery::links_for_slovene_word(database, translation_model.word_id)
+                        .await?;
+
                 translations.push(ExpandedSloveneWordInfo {
                     word: translation_model,
                     categories: translated_word_categories,
+                    links: translated_word_links,
                 });
             }
 
@@ -283,10 +385,24 @@ impl EnglishWordQuery {
         };
 
 
+        let links = WordLinkQuery::links_for_english_word(database, word_uuid).await?;
+
+
         Ok(RelatedEnglishWordInfo {
             categories,
             suggested_translations,
             translations,
+            links,
         })
     }
 }
+
+
+
+/// Matches english words with the given disambiguation (or without one, if `None`).
+fn disambiguation_condition(disambiguation: Option<String>) -> SimpleExpr {
+    match disambiguation {
+        Some(disambiguation) => word_english::Column::Disambiguation.eq(disambiguation),
+        None => word_english::Column::Disambiguation.is_null(),
+    }
+}
@@ -0,0 +1,146 @@
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    entities::{word_english, word_link, word_slovene},
+    shared::WordLinkType,
+};
+
+
+/// A link from an english word to another english word.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EnglishWordLink {
+    pub link_type: WordLinkType,
+    pub linked_word: word_english::Model,
+}
+
+/// A link from a slovene word to another slovene word.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SloveneWordLink {
+    pub link_type: WordLinkType,
+    pub linked_word: word_slovene::Model,
+}
+
+
+
+pub struct WordLinkQuery;
+
+impl WordLinkQuery {
+    /// Returns the link from `word_uuid` to `linked_word_uuid`, if it exists.
+    pub async fn link_between<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        linked_word_uuid: Uuid,
+    ) -> Result<Option<word_link::Model>> {
+        word_link::Entity::find_by_id((word_uuid, linked_word_uuid))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up word link.")
+    }
+
+    /// Returns the UUIDs of all words the given word is linked to.
+    pub async fn linked_word_uuids<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Vec<Uuid>> {
+        let links = Self::links_from_word(database, word_uuid).await?;
+
+        Ok(links.into_iter().map(|link| link.linked_word_id).collect())
+    }
+
+    pub async fn links_for_english_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        english_word_uuid: Uuid,
+    ) -> Result<Vec<EnglishWordLink>> {
+        let links = Self::links_from_word(database, english_word_uuid).await?;
+        if links.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let linked_words = word_english::Entity::find()
+            .filter(word_english::Column::WordId.is_in(links.iter().map(|link| link.linked_word_id)))
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving linked english words from database.")?;
+
+
+        let mut english_word_links = Vec::with_capacity(links.len());
+        for link in links {
+            let Some(linked_word) = linked_words
+                .iter()
+                .find(|word| word.word_id == link.linked_word_id)
+            else {
+                // Links are only ever created between words of the same language,
+                // so this can only be a link to a word of some other language.
+                continue;
+            };
+
+            english_word_links.push(EnglishWordLink {
+                link_type: link.link_type()?,
+                linked_word: linked_word.clone(),
+            });
+        }
+
+        Ok(english_word_links)
+    }
+
+    pub async fn links_for_slovene_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        slovene_word_uuid: Uuid,
+    ) -> Result<Vec<SloveneWordLink>> {
+        let links = Self::links_from_word(database, slovene_word_uuid).await?;
+        if links.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let linked_words = word_slovene::Entity::find()
+            .filter(word_slovene::Column::WordId.is_in(links.iter().map(|link| link.linked_word_id)))
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving linked slovene words from database.")?;
+
+
+        let mut slovene_word_links = Vec::with_capacity(links.len());
+        for link in links {
+            let Some(linked_word) = linked_words
+                .iter()
+                .find(|word| word.word_id == link.linked_word_id)
+            else {
+                // Links are only ever created between words of the same language,
+                // so this can only be a link to a word of some other language.
+                continue;
+            };
+
+            slovene_word_links.push(SloveneWordLink {
+                link_type: link.link_type()?,
+                linked_word: linked_word.clone(),
+            });
+        }
+
+        Ok(slovene_word_links)
+    }
+
+    async fn links_from_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Vec<word_link::Model>> {
+        word_link::Entity::find()
+            .filter(word_link::Column::WordId.eq(word_uuid))
+            .order_by_asc(word_link::Column::LinkedAt)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving word links from database.")
+    }
+}
@@ -0,0 +1,125 @@
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{
+    ColumnTrait,
+    ConnectionTrait,
+    EntityTrait,
+    QueryFilter,
+    QueryOrder,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use crate::{
+    entities::{word_english, word_reference, word_slovene},
+    shared::WordLanguage,
+};
+
+
+/// Basic information about a word on either end of a description reference.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WordSummary {
+    pub word_id: Uuid,
+    pub language: WordLanguage,
+    pub lemma: String,
+    pub disambiguation: Option<String>,
+}
+
+
+
+pub struct WordReferenceQuery;
+
+impl WordReferenceQuery {
+    /// Returns the words referenced from the description of the given word.
+    pub async fn words_referenced_by_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Vec<WordSummary>> {
+        let references = word_reference::Entity::find()
+            .filter(word_reference::Column::WordId.eq(word_uuid))
+            .order_by_asc(word_reference::Column::ReferencedAt)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving references from word from database.")?;
+
+        Self::word_summaries(
+            database,
+            references
+                .into_iter()
+                .map(|reference| reference.referenced_word_id)
+                .collect(),
+        )
+        .await
+    }
+
+    /// Returns the words whose descriptions reference the given word (i.e. its backlinks).
+    pub async fn words_referencing_word<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Vec<WordSummary>> {
+        let references = word_reference::Entity::find()
+            .filter(word_reference::Column::ReferencedWordId.eq(word_uuid))
+            .order_by_asc(word_reference::Column::ReferencedAt)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving references to word from database.")?;
+
+        Self::word_summaries(
+            database,
+            references
+                .into_iter()
+                .map(|reference| reference.word_id)
+                .collect(),
+        )
+        .await
+    }
+
+    /// Looks up the english or slovene word behind each UUID, keeping the order of `word_uuids`.
+    async fn word_summaries<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_uuids: Vec<Uuid>,
+    ) -> Result<Vec<WordSummary>> {
+        if word_uuids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let english_words = word_english::Entity::find()
+            .filter(word_english::Column::WordId.is_in(word_uuids.clone()))
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving referenced english words from database.")?;
+
+        let slovene_words = word_slovene::Entity::find()
+            .filter(word_slovene::Column::WordId.is_in(word_uuids.clone()))
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while retrieving referenced slovene words from database.")?;
+
+
+        let mut summaries = Vec::with_capacity(word_uuids.len());
+        for word_uuid in word_uuids {
+            if let Some(english_word) = english_words.iter().find(|word| word.word_id == word_uuid) {
+                summaries.push(WordSummary {
+                    word_id: word_uuid,
+                    language: WordLanguage::English,
+                    lemma: english_word.lemma.clone(),
+                    disambiguation: english_word.disambiguation.clone(),
+                });
+            } else if let Some(slovene_word) =
+                slovene_words.iter().find(|word| word.word_id == word_uuid)
+            {
+                summaries.push(WordSummary {
+                    word_id: word_uuid,
+                    language: WordLanguage::Slovene,
+                    lemma: slovene_word.lemma.clone(),
+                    disambiguation: slovene_word.disambiguation.clone(),
+                });
+            }
+        }
+
+        Ok(summaries)
+    }
+}
@@ -0,0 +1,52 @@
+use miette::{Context, IntoDiagnostic, Result};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::entities::word_revision;
+
+
+/// Queries related to the [`crate::entities::word_revision::Entity`] entity.
+pub struct WordRevisionQuery;
+
+impl WordRevisionQuery {
+    /// Get all revisions of the given word, oldest first.
+    pub async fn all_revisions_for_word<C: ConnectionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Vec<word_revision::Model>> {
+        word_revision::Entity::find()
+            .filter(word_revision::Column::WordId.eq(word_uuid))
+            .order_by_asc(word_revision::Column::RevisionNumber)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying word revisions from database.")
+    }
+
+    /// Get a single revision of the given word by its revision number.
+    pub async fn revision_by_number<C: ConnectionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+        revision_number: i32,
+    ) -> Result<Option<word_revision::Model>> {
+        word_revision::Entity::find_by_id((word_uuid, revision_number))
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up word revision by number.")
+    }
+
+    /// Get the newest revision of the given word, if it has any.
+    pub async fn latest_revision_for_word<C: ConnectionTrait>(
+        database: &C,
+        word_uuid: Uuid,
+    ) -> Result<Option<word_revision::Model>> {
+        word_revision::Entity::find()
+            .filter(word_revision::Column::WordId.eq(word_uuid))
+            .order_by_desc(word_revision::Column::RevisionNumber)
+            .one(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while looking up latest word revision.")
+    }
+}
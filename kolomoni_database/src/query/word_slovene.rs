@@ -1,18 +1,26 @@
 use chrono::{DateTime, Utc};
 use miette::{Context, IntoDiagnostic, Result};
 use sea_orm::{
-    sea_query::Expr,
+    sea_query::{Expr, SimpleExpr},
     ColumnTrait,
     ConnectionTrait,
     EntityTrait,
     FromQueryResult,
     QueryFilter,
+    QueryOrder,
     QuerySelect,
     TransactionTrait,
 };
 use uuid::Uuid;
 
-use super::{super::entities::prelude::WordSlovene, WordCategoryQuery};
+use super::{
+    super::entities::prelude::WordSlovene,
+    SloveneWordLink,
+    WordCategoryQuery,
+    WordLinkQuery,
+    WordPageOptions,
+    WordsPage,
+};
 use crate::entities::{category, word_slovene};
 
 
@@ -25,6 +33,7 @@ pub struct SloveneWordsQueryOptions {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct RelatedSloveneWordInfo {
     pub categories: Vec<category::Model>,
+    pub links: Vec<SloveneWordLink>,
 }
 
 
@@ -32,6 +41,7 @@ pub struct RelatedSloveneWordInfo {
 pub struct ExpandedSloveneWordInfo {
     pub word: word_slovene::Model,
     pub categories: Vec<category::Model>,
+    pub links: Vec<SloveneWordLink>,
 }
 
 
@@ -69,9 +79,14 @@ impl SloveneWordQuery {
         }
     }
 
-    pub async fn word_exists_by_lemma<C: ConnectionTrait + TransactionTrait>(
+    /// Returns whether a slovene word with the given lemma and disambiguation exists.
+    ///
+    /// Words with the same lemma (homonyms) are distinguished by their disambiguation,
+    /// which means this is the pair that needs to be unique.
+    pub async fn word_exists_by_lemma_and_disambiguation<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         lemma: String,
+        disambiguation: Option<String>,
     ) -> Result<bool> {
         #[derive(Debug, FromQueryResult, PartialEq, Eq, Hash)]
         struct WordCount {
@@ -84,11 +99,14 @@ impl SloveneWordQuery {
 
         let count_result = word_exists_query
             .filter(word_slovene::Column::Lemma.eq(lemma))
+            .filter(disambiguation_condition(disambiguation))
             .into_model::<WordCount>()
             .one(database)
             .await
             .into_diagnostic()
-            .wrap_err("Failed while looking up whether the slovene word exists by lemma.")?;
+            .wrap_err(
+                "Failed while looking up whether the slovene word exists by lemma and disambiguation.",
+            )?;
 
         match count_result {
             Some(word_count) => {
@@ -110,16 +128,35 @@ impl SloveneWordQuery {
             .wrap_err("Failed while searching database for slovene word by UUID.")
     }
 
-    pub async fn word_by_lemma<C: ConnectionTrait + TransactionTrait>(
+    /// Returns all slovene words with the given lemma (i.e. the word and its homonyms),
+    /// ordered by their creation.
+    pub async fn words_by_lemma<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        word_lemma: String,
+    ) -> Result<Vec<word_slovene::Model>> {
+        WordSlovene::find()
+            .filter(word_slovene::Column::Lemma.eq(word_lemma))
+            .order_by_asc(word_slovene::Column::WordId)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while searching database for slovene words by lemma.")
+    }
+
+    pub async fn word_by_lemma_and_disambiguation<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         word_lemma: String,
+        disambiguation: Option<String>,
     ) -> Result<Option<word_slovene::Model>> {
         WordSlovene::find()
             .filter(word_slovene::Column::Lemma.eq(word_lemma))
+            .filter(disambiguation_condition(disambiguation))
             .one(database)
             .await
             .into_diagnostic()
-            .wrap_err("Failed while seaching database for slovene word by lemma.")
+            .wrap_err(
+                "Failed while searching database for slovene word by lemma and disambiguation.",
+            )
     }
 
     pub async fn expanded_word_by_uuid<C: ConnectionTrait + TransactionTrait>(
@@ -143,32 +180,31 @@ impl SloveneWordQuery {
         Ok(Some(ExpandedSloveneWordInfo {
             word: base_word,
             categories: related_info.categories,
+            links: related_info.links,
         }))
     }
 
-    pub async fn expanded_word_by_lemma<C: ConnectionTrait + TransactionTrait>(
+    /// Returns all slovene words with the given lemma (i.e. the word and its homonyms),
+    /// along with their related information, ordered by their creation.
+    pub async fn expanded_words_by_lemma<C: ConnectionTrait + TransactionTrait>(
         database: &C,
         word_lemma: String,
-    ) -> Result<Option<ExpandedSloveneWordInfo>> {
-        let optional_base_word = WordSlovene::find()
-            .filter(word_slovene::Column::Lemma.eq(word_lemma))
-            .one(database)
-            .await
-            .into_diagnostic()
-            .wrap_err("Failed while seaching database for slovene word by lemma.")?;
-
-        let Some(base_word) = optional_base_word else {
-            return Ok(None);
-        };
-
+    ) -> Result<Vec<ExpandedSloveneWordInfo>> {
+        let base_words = Self::words_by_lemma(database, word_lemma).await?;
 
-        let related_info = Self::related_word_information_only(database, base_word.word_id).await?;
+        let mut expanded_words = Vec::with_capacity(base_words.len());
+        for base_word in base_words {
+            let related_info =
+                Self::related_word_information_only(database, base_word.word_id).await?;
 
+            expanded_words.push(ExpandedSloveneWordInfo {
+                word: base_word,
+                categories: related_info.categories,
+                links: related_info.links,
+            });
+        }
 
-        Ok(Some(ExpandedSloveneWordInfo {
-            word: base_word,
-            categories: related_info.categories,
-        }))
+        Ok(expanded_words)
     }
 
     pub async fn all_words<C: ConnectionTrait + TransactionTrait>(
@@ -206,7 +242,52 @@ impl SloveneWordQuery {
             .into_diagnostic()
             .wrap_err("Failed while querying all slovene words from the database.")?;
 
+        Self::expand_words(database, base_words).await
+    }
+
+    /// Like [`Self::all_words_expanded`], but only returns a single page of words,
+    /// ordered by word ID (see [`WordPageOptions`]).
+    pub async fn all_words_expanded_paginated<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        options: SloveneWordsQueryOptions,
+        page_options: WordPageOptions,
+    ) -> Result<WordsPage<ExpandedSloveneWordInfo>> {
+        let mut query = WordSlovene::find();
+
+        // Add modifiers onto the query based on `options`.
+        if let Some(only_words_modified_after) = options.only_words_modified_after {
+            query = query.filter(word_slovene::Column::LastModifiedAt.gt(only_words_modified_after));
+        }
+
+        if let Some(after_word_id) = page_options.after_word_id {
+            query = query.filter(word_slovene::Column::WordId.gt(after_word_id));
+        }
+
+        // One word more than requested is fetched to find out whether there is a next page.
+        let base_words = query
+            .order_by_asc(word_slovene::Column::WordId)
+            .limit(page_options.page_size + 1)
+            .all(database)
+            .await
+            .into_diagnostic()
+            .wrap_err("Failed while querying a page of slovene words from the database.")?;
+
+        let base_words_page = WordsPage::from_overfetched_words(
+            base_words,
+            page_options.page_size,
+            |word| word.word_id,
+        );
+
+        Ok(WordsPage {
+            words: Self::expand_words(database, base_words_page.words).await?,
+            next_cursor: base_words_page.next_cursor,
+        })
+    }
 
+    async fn expand_words<C: ConnectionTrait + TransactionTrait>(
+        database: &C,
+        base_words: Vec<word_slovene::Model>,
+    ) -> Result<Vec<ExpandedSloveneWordInfo>> {
         // PERF This could be improved (but the expanded english word has bigger issues).
 
         let mut expanded_slovene_words = Vec::with_capacity(base_words.len());
@@ -218,6 +299,7 @@ impl SloveneWordQuery {
             expanded_slovene_words.push(ExpandedSloveneWordInfo {
                 word: base_slovene_word,
                 categories: related_info.categories,
+                links: related_info.links,
             });
         }
 
@@ -233,6 +315,18 @@ impl SloveneWordQuery {
         let categories =
             WordCategoryQuery::word_categories_by_word_uuid(database, word_uuid).await?;
 
-        Ok(RelatedSloveneWordInfo { categories })
+        let links = WordLinkQuery::links_for_slovene_word(database, word_uuid).await?;
+
+        Ok(RelatedSloveneWordInfo { categories, links })
+    }
+}
+
+
+
+/// Matches slovene words with the given disambiguation (or without one, if `None`).
+fn disambiguation_condition(disambiguation: Option<String>) -> SimpleExpr {
+    match disambiguation {
+        Some(disambiguation) => word_slovene::Column::Disambiguation.eq(disambiguation),
+        None => word_slovene::Column::Disambiguation.is_null(),
     }
 }
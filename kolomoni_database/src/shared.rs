@@ -1,6 +1,15 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sea_orm::{DbErr, SqlErr};
+use serde_json::{json, Map, Value};
 use thiserror::Error;
 use uuid::{NoContext, Timestamp, Uuid};
 
+/// ID of the placeholder user that the contributions of deleted users
+/// (e.g. word revisions or role grants) are reassigned to.
+///
+/// The placeholder is seeded by the migrations. It can't log in and is not shown in user lists.
+pub const DELETED_USER_ID: i32 = 0;
+
 #[derive(Error, Debug)]
 pub enum WordLanguageError {
     #[error("unrecognized language: {language}")]
@@ -33,7 +42,492 @@ impl WordLanguage {
     }
 }
 
+/// Type of a link between two words of the same language.
+///
+/// Links are stored in both directions, each direction with its own type
+/// (see [`Self::inverse`]).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WordLinkType {
+    /// The words have the same meaning.
+    Synonym,
+
+    /// The words have opposite meanings.
+    Antonym,
+
+    /// The linked word has a broader meaning that includes this word
+    /// (e.g. "weapon" is a hypernym of "sword").
+    Hypernym,
+
+    /// The linked word has a narrower meaning that is included in this word
+    /// (e.g. "sword" is a hyponym of "weapon").
+    Hyponym,
+
+    /// The linked word is related in some other way.
+    SeeAlso,
+}
+
+impl WordLinkType {
+    /// Attempt to parse a [`WordLinkType`] from its name (e.g. "see_also").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "synonym" => Some(Self::Synonym),
+            "antonym" => Some(Self::Antonym),
+            "hypernym" => Some(Self::Hypernym),
+            "hyponym" => Some(Self::Hyponym),
+            "see_also" => Some(Self::SeeAlso),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the link type, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            WordLinkType::Synonym => "synonym",
+            WordLinkType::Antonym => "antonym",
+            WordLinkType::Hypernym => "hypernym",
+            WordLinkType::Hyponym => "hyponym",
+            WordLinkType::SeeAlso => "see_also",
+        }
+    }
+
+    /// Returns the type of the link in the opposite direction,
+    /// i.e. from the linked word back to the original one.
+    pub fn inverse(self) -> Self {
+        match self {
+            WordLinkType::Synonym => WordLinkType::Synonym,
+            WordLinkType::Antonym => WordLinkType::Antonym,
+            WordLinkType::Hypernym => WordLinkType::Hyponym,
+            WordLinkType::Hyponym => WordLinkType::Hypernym,
+            WordLinkType::SeeAlso => WordLinkType::SeeAlso,
+        }
+    }
+}
+
+/// Part of speech of a dictionary word.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    Pronoun,
+    Numeral,
+    Preposition,
+    Conjunction,
+    Particle,
+    Interjection,
+}
+
+impl PartOfSpeech {
+    /// Attempt to parse a [`PartOfSpeech`] from its name (e.g. "noun").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "noun" => Some(Self::Noun),
+            "verb" => Some(Self::Verb),
+            "adjective" => Some(Self::Adjective),
+            "adverb" => Some(Self::Adverb),
+            "pronoun" => Some(Self::Pronoun),
+            "numeral" => Some(Self::Numeral),
+            "preposition" => Some(Self::Preposition),
+            "conjunction" => Some(Self::Conjunction),
+            "particle" => Some(Self::Particle),
+            "interjection" => Some(Self::Interjection),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the part of speech, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            PartOfSpeech::Noun => "noun",
+            PartOfSpeech::Verb => "verb",
+            PartOfSpeech::Adjective => "adjective",
+            PartOfSpeech::Adverb => "adverb",
+            PartOfSpeech::Pronoun => "pronoun",
+            PartOfSpeech::Numeral => "numeral",
+            PartOfSpeech::Preposition => "preposition",
+            PartOfSpeech::Conjunction => "conjunction",
+            PartOfSpeech::Particle => "particle",
+            PartOfSpeech::Interjection => "interjection",
+        }
+    }
+
+    /// Whether slovene words of this part of speech are declined,
+    /// i.e. have a form for each case and number.
+    pub fn is_declinable(self) -> bool {
+        matches!(
+            self,
+            PartOfSpeech::Noun
+                | PartOfSpeech::Adjective
+                | PartOfSpeech::Pronoun
+                | PartOfSpeech::Numeral
+        )
+    }
+}
+
+/// Grammatical gender of a (slovene) noun.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrammaticalGender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+impl GrammaticalGender {
+    /// Attempt to parse a [`GrammaticalGender`] from its name (e.g. "feminine").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "masculine" => Some(Self::Masculine),
+            "feminine" => Some(Self::Feminine),
+            "neuter" => Some(Self::Neuter),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the gender, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            GrammaticalGender::Masculine => "masculine",
+            GrammaticalGender::Feminine => "feminine",
+            GrammaticalGender::Neuter => "neuter",
+        }
+    }
+}
+
+/// Restricts a noun to only some of the grammatical numbers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NumberRestriction {
+    /// The noun is only used in the singular (e.g. "mleko" or "information").
+    SingularOnly,
+
+    /// The noun is only used in the plural (e.g. "vrata" or "scissors").
+    PluralOnly,
+}
+
+impl NumberRestriction {
+    /// Attempt to parse a [`NumberRestriction`] from its name (e.g. "plural_only").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "singular_only" => Some(Self::SingularOnly),
+            "plural_only" => Some(Self::PluralOnly),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the number restriction, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberRestriction::SingularOnly => "singular_only",
+            NumberRestriction::PluralOnly => "plural_only",
+        }
+    }
+
+    /// Returns whether a noun with this restriction has forms in the given number.
+    pub fn allows(self, number: GrammaticalNumber) -> bool {
+        match self {
+            NumberRestriction::SingularOnly => number == GrammaticalNumber::Singular,
+            NumberRestriction::PluralOnly => number == GrammaticalNumber::Plural,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            NumberRestriction::SingularOnly => "only used in the singular",
+            NumberRestriction::PluralOnly => "only used in the plural",
+        }
+    }
+}
+
+/// Grammatical case of a slovene inflected form.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrammaticalCase {
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Locative,
+    Instrumental,
+}
+
+impl GrammaticalCase {
+    pub const ALL: [Self; 6] = [
+        Self::Nominative,
+        Self::Genitive,
+        Self::Dative,
+        Self::Accusative,
+        Self::Locative,
+        Self::Instrumental,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GrammaticalCase::Nominative => "nominative",
+            GrammaticalCase::Genitive => "genitive",
+            GrammaticalCase::Dative => "dative",
+            GrammaticalCase::Accusative => "accusative",
+            GrammaticalCase::Locative => "locative",
+            GrammaticalCase::Instrumental => "instrumental",
+        }
+    }
+}
+
+/// Grammatical number of an inflected form (slovene has a dual as well).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GrammaticalNumber {
+    Singular,
+    Dual,
+    Plural,
+}
+
+impl GrammaticalNumber {
+    pub const ALL: [Self; 3] = [Self::Singular, Self::Dual, Self::Plural];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GrammaticalNumber::Singular => "singular",
+            GrammaticalNumber::Dual => "dual",
+            GrammaticalNumber::Plural => "plural",
+        }
+    }
+}
+
+/// Inflected forms of a slovene word, one (optional) form for each case and number.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SloveneInflectionTable {
+    forms: [[Option<String>; 3]; 6],
+}
+
+impl SloveneInflectionTable {
+    pub fn form(&self, case: GrammaticalCase, number: GrammaticalNumber) -> Option<&str> {
+        self.forms[case as usize][number as usize].as_deref()
+    }
+
+    pub fn set_form(
+        &mut self,
+        case: GrammaticalCase,
+        number: GrammaticalNumber,
+        form: Option<String>,
+    ) {
+        self.forms[case as usize][number as usize] = form;
+    }
+
+    /// Returns all forms that are present, along with their case and number.
+    pub fn present_forms(
+        &self,
+    ) -> impl Iterator<Item = (GrammaticalCase, GrammaticalNumber, &str)> + '_ {
+        GrammaticalCase::ALL.into_iter().flat_map(move |case| {
+            GrammaticalNumber::ALL
+                .into_iter()
+                .filter_map(move |number| Some((case, number, self.form(case, number)?)))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.present_forms().next().is_none()
+    }
+}
+
+/// Grammatical information about a slovene word. All of it is optional.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SloveneWordGrammar {
+    pub part_of_speech: Option<PartOfSpeech>,
+
+    /// Only nouns have a gender.
+    pub gender: Option<GrammaticalGender>,
+
+    /// Only nouns can have a number restriction.
+    pub number_restriction: Option<NumberRestriction>,
+
+    /// Only declinable words (see [`PartOfSpeech::is_declinable`]) have inflected forms.
+    pub inflection: SloveneInflectionTable,
+}
+
+/// Grammatical information about an english word. All of it is optional.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct EnglishWordGrammar {
+    pub part_of_speech: Option<PartOfSpeech>,
+
+    /// Only nouns can have a number restriction.
+    pub number_restriction: Option<NumberRestriction>,
+
+    /// Plural form of a noun. Only nouns that are used in both numbers have one.
+    pub plural_form: Option<String>,
+}
+
+impl SloveneWordGrammar {
+    /// Serializes the grammatical information into the JSON object
+    /// that is stored in word revisions (see [`Self::from_revision_json`]).
+    pub fn to_revision_json(&self) -> String {
+        let inflection = self
+            .inflection
+            .present_forms()
+            .map(|(case, number, form)| {
+                (
+                    format!("{}_{}", case.name(), number.name()),
+                    Value::String(form.to_string()),
+                )
+            })
+            .collect::<Map<String, Value>>();
+
+        json!({
+            "part_of_speech": self.part_of_speech.map(PartOfSpeech::name),
+            "gender": self.gender.map(GrammaticalGender::name),
+            "number_restriction": self.number_restriction.map(NumberRestriction::name),
+            "inflection": inflection,
+        })
+        .to_string()
+    }
+
+    /// Parses grammatical information stored in a word revision
+    /// (see [`Self::to_revision_json`]). Returns `None` if it is malformed.
+    pub fn from_revision_json(revision_json: &str) -> Option<Self> {
+        let value = serde_json::from_str::<Value>(revision_json).ok()?;
+
+        let mut inflection = SloveneInflectionTable::default();
+        for case in GrammaticalCase::ALL {
+            for number in GrammaticalNumber::ALL {
+                let form = value["inflection"][format!("{}_{}", case.name(), number.name())]
+                    .as_str()
+                    .map(str::to_string);
+
+                inflection.set_form(case, number, form);
+            }
+        }
+
+        Some(Self {
+            part_of_speech: value["part_of_speech"]
+                .as_str()
+                .and_then(PartOfSpeech::from_name),
+            gender: value["gender"]
+                .as_str()
+                .and_then(GrammaticalGender::from_name),
+            number_restriction: value["number_restriction"]
+                .as_str()
+                .and_then(NumberRestriction::from_name),
+            inflection,
+        })
+    }
+}
+
+impl EnglishWordGrammar {
+    /// Serializes the grammatical information into the JSON object
+    /// that is stored in word revisions (see [`Self::from_revision_json`]).
+    pub fn to_revision_json(&self) -> String {
+        json!({
+            "part_of_speech": self.part_of_speech.map(PartOfSpeech::name),
+            "number_restriction": self.number_restriction.map(NumberRestriction::name),
+            "plural_form": self.plural_form,
+        })
+        .to_string()
+    }
+
+    /// Parses grammatical information stored in a word revision
+    /// (see [`Self::to_revision_json`]). Returns `None` if it is malformed.
+    pub fn from_revision_json(revision_json: &str) -> Option<Self> {
+        let value = serde_json::from_str::<Value>(revision_json).ok()?;
+
+        Some(Self {
+            part_of_speech: value["part_of_speech"]
+                .as_str()
+                .and_then(PartOfSpeech::from_name),
+            number_restriction: value["number_restriction"]
+                .as_str()
+                .and_then(NumberRestriction::from_name),
+            plural_form: value["plural_form"].as_str().map(str::to_string),
+        })
+    }
+}
+
+/// The grammatical information about a word is inconsistent (see
+/// `SloveneWordMutation::validate_grammar` and `EnglishWordMutation::validate_grammar`).
+#[derive(Error, Clone, PartialEq, Eq, Debug)]
+pub enum InvalidWordGrammarError {
+    #[error("only nouns can have a {field}")]
+    OnlyAllowedForNouns { field: &'static str },
+
+    #[error("only nouns, adjectives, pronouns and numerals can have inflected forms")]
+    InflectionOfIndeclinableWord,
+
+    #[error("the {form} is empty")]
+    EmptyForm { form: String },
+
+    #[error("a word that is {} can't have the {form}", .restriction.description())]
+    FormExcludedByNumberRestriction {
+        form: String,
+        restriction: NumberRestriction,
+    },
+}
+
+/// What a login throttle (i.e. a count of recent failed login attempts) applies to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoginThrottleSubjectType {
+    /// Failed login attempts for a single username.
+    Username,
+
+    /// Failed login attempts from a single IP address.
+    IpAddress,
+}
+
+impl LoginThrottleSubjectType {
+    /// Attempt to parse a [`LoginThrottleSubjectType`] from its name (e.g. "username").
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "username" => Some(Self::Username),
+            "ip_address" => Some(Self::IpAddress),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the subject type, as stored in the database.
+    pub fn name(self) -> &'static str {
+        match self {
+            LoginThrottleSubjectType::Username => "username",
+            LoginThrottleSubjectType::IpAddress => "ip_address",
+        }
+    }
+}
+
 #[inline]
 pub fn generate_random_word_uuid() -> Uuid {
     Uuid::new_v7(Timestamp::now(NoContext))
 }
+
+#[inline]
+pub fn generate_random_session_uuid() -> Uuid {
+    Uuid::new_v7(Timestamp::now(NoContext))
+}
+
+#[inline]
+pub fn generate_random_invite_uuid() -> Uuid {
+    Uuid::new_v7(Timestamp::now(NoContext))
+}
+
+#[inline]
+pub fn generate_random_api_token_uuid() -> Uuid {
+    Uuid::new_v7(Timestamp::now(NoContext))
+}
+
+#[inline]
+pub fn generate_random_suspension_uuid() -> Uuid {
+    Uuid::new_v7(Timestamp::now(NoContext))
+}
+
+/// Generates a random, URL-safe invite code (32 lower-case hexadecimal characters).
+pub fn generate_random_invite_code() -> String {
+    let mut code_bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut code_bytes);
+
+    code_bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Returns `true` if the database error was caused by a violated unique constraint or index
+/// (e.g. inserting a word with the same lemma and disambiguation as an existing one).
+pub(crate) fn is_unique_constraint_violation(error: &DbErr) -> bool {
+    matches!(
+        error.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(_))
+    )
+}
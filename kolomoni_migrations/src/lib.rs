@@ -8,6 +8,25 @@ mod m20230624_177050_seed_roles;
 mod m20240206_234618_create_word_tables;
 mod m20240219_161147_create_word_suggestion_and_translation_tables;
 mod m20240222_185323_create_category_related_tables;
+mod m20261016_101500_add_password_change_time_to_user;
+mod m20261016_103000_create_user_session_table;
+mod m20261016_104500_add_deactivation_time_to_user;
+mod m20261016_105000_seed_deleted_user_placeholder;
+mod m20261016_110000_create_user_invite_tables;
+mod m20261016_111500_create_login_throttle_table;
+mod m20261016_113000_sync_role_id_sequence;
+mod m20261016_114500_create_user_api_token_tables;
+mod m20261016_120000_create_openid_connect_tables;
+mod m20261016_121500_create_two_factor_authentication_tables;
+mod m20261016_123000_add_grant_details_to_user_role;
+mod m20261016_124500_create_user_suspension_table;
+mod m20261016_130000_create_word_revision_table;
+mod m20261016_131500_create_word_link_table;
+mod m20261016_133000_create_word_reference_table;
+mod m20261016_134000_add_unique_lemma_index_to_words;
+mod m20261016_134500_add_grammar_to_words;
+mod m20261016_143000_add_grammar_to_word_revision;
+mod m20261016_144500_add_last_modification_time_to_role_and_permission;
 
 pub struct Migrator;
 
@@ -26,6 +45,25 @@ impl MigratorTrait for Migrator {
             Box::new(m20240206_234618_create_word_tables::Migration),
             Box::new(m20240219_161147_create_word_suggestion_and_translation_tables::Migration),
             Box::new(m20240222_185323_create_category_related_tables::Migration),
+            Box::new(m20261016_101500_add_password_change_time_to_user::Migration),
+            Box::new(m20261016_103000_create_user_session_table::Migration),
+            Box::new(m20261016_104500_add_deactivation_time_to_user::Migration),
+            Box::new(m20261016_105000_seed_deleted_user_placeholder::Migration),
+            Box::new(m20261016_110000_create_user_invite_tables::Migration),
+            Box::new(m20261016_111500_create_login_throttle_table::Migration),
+            Box::new(m20261016_113000_sync_role_id_sequence::Migration),
+            Box::new(m20261016_114500_create_user_api_token_tables::Migration),
+            Box::new(m20261016_120000_create_openid_connect_tables::Migration),
+            Box::new(m20261016_121500_create_two_factor_authentication_tables::Migration),
+            Box::new(m20261016_123000_add_grant_details_to_user_role::Migration),
+            Box::new(m20261016_124500_create_user_suspension_table::Migration),
+            Box::new(m20261016_130000_create_word_revision_table::Migration),
+            Box::new(m20261016_131500_create_word_link_table::Migration),
+            Box::new(m20261016_133000_create_word_reference_table::Migration),
+            Box::new(m20261016_134000_add_unique_lemma_index_to_words::Migration),
+            Box::new(m20261016_134500_add_grammar_to_words::Migration),
+            Box::new(m20261016_143000_add_grammar_to_word_revision::Migration),
+            Box::new(m20261016_144500_add_last_modification_time_to_role_and_permission::Migration),
         ]
     }
 }
@@ -5,10 +5,10 @@ use crate::{
     m20230624_177000_initialize_role_related_tables::{Role, RolePermission},
 };
 
-/// This is the list of available roles.
+/// This is the list of built-in roles (further roles can be created at runtime).
 ///
-/// **IMPORTANT: This role list (or roles on related migrations) should be kept in sync
-/// with `./kolomoni_auth/src/roles.rs`.**
+/// **IMPORTANT: The IDs and names in this role list should be kept in sync
+/// with `BuiltinRole` in `./kolomoni_auth/src/roles.rs`.**
 ///
 /// We don't keep them in sync automatically because that would mean a migration would
 /// not stay the same. We can modify the migration sanely if any only if we're still in
@@ -0,0 +1,49 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveIden)]
+enum User {
+    #[sea_orm(iden = "user")]
+    Table,
+
+    #[sea_orm(iden = "password_last_changed_at")]
+    PasswordLastChangedAt,
+}
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(
+                            User::PasswordLastChangedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::PasswordLastChangedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
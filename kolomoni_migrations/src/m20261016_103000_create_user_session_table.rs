@@ -0,0 +1,119 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserSession {
+    #[sea_orm(iden = "user_session")]
+    Table,
+
+    #[sea_orm(iden = "id")]
+    Id,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "last_refreshed_at")]
+    LastRefreshedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+
+    #[sea_orm(iden = "user_agent")]
+    UserAgent,
+
+    #[sea_orm(iden = "ip_address")]
+    IpAddress,
+}
+
+const USER_SESSION_PK_CONSTRAINT_NAME: &str = "pk__user_session";
+const USER_SESSION_FK_USER_ID_CONSTRAINT_NAME: &str = "fk__user_session__user_id__user";
+const USER_SESSION_IDX_ON_USER_ID_INDEX_NAME: &str = "index__user_session__on__user_id";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSession::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(UserSession::Id, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(UserSession::UserId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSession::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSession::LastRefreshedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSession::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserSession::UserAgent, ColumnType::String(None))
+                            .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserSession::IpAddress, ColumnType::String(None))
+                            .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_SESSION_PK_CONSTRAINT_NAME)
+                            .col(UserSession::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_SESSION_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(UserSession::Table, UserSession::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_SESSION_IDX_ON_USER_ID_INDEX_NAME)
+                    .table(UserSession::Table)
+                    .col(UserSession::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserSession::Table).to_owned())
+            .await
+    }
+}
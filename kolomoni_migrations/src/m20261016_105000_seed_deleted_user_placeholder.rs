@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserDeactivation {
+    #[sea_orm(iden = "deactivated_at")]
+    DeactivatedAt,
+}
+
+
+/// ID of the placeholder user that contributions of deleted users are reassigned to.
+///
+/// **IMPORTANT: This should be kept in sync with `DELETED_USER_ID`
+/// in `./kolomoni_database/src/shared.rs`.**
+///
+/// The `user.id` sequence starts at 1, so this can never collide with a registered user.
+const DELETED_USER_ID: i32 = 0;
+
+const DELETED_USER_USERNAME: &str = "[deleted]";
+const DELETED_USER_DISPLAY_NAME: &str = "[deleted user]";
+
+/// Not a valid Argon2 hash, so no password will ever match it.
+const DELETED_USER_HASHED_PASSWORD: &str = "!";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The placeholder is deactivated right away, so it can't be used to log in.
+        let insert = Query::insert()
+            .into_table(User::Table)
+            .columns([
+                User::Id.into_iden(),
+                User::Username.into_iden(),
+                User::DisplayName.into_iden(),
+                User::HashedPassword.into_iden(),
+                User::JoinedAt.into_iden(),
+                User::LastModifiedAt.into_iden(),
+                User::LastActiveAt.into_iden(),
+                UserDeactivation::DeactivatedAt.into_iden(),
+            ])
+            .values_panic([
+                DELETED_USER_ID.into(),
+                DELETED_USER_USERNAME.into(),
+                DELETED_USER_DISPLAY_NAME.into(),
+                DELETED_USER_HASHED_PASSWORD.into(),
+                Expr::current_timestamp().into(),
+                Expr::current_timestamp().into(),
+                Expr::current_timestamp().into(),
+                Expr::current_timestamp().into(),
+            ])
+            .to_owned();
+
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Authorship columns reference the user with `ON DELETE SET NULL`,
+        // so contributions reassigned to the placeholder lose their author.
+        let delete = Query::delete()
+            .from_table(User::Table)
+            .cond_where(Expr::col(User::Id).eq(DELETED_USER_ID))
+            .to_owned();
+
+        manager.exec_stmt(delete).await
+    }
+}
@@ -0,0 +1,196 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20230624_133941_create_users_table::User,
+    m20230624_177000_initialize_role_related_tables::Role,
+};
+
+
+#[derive(DeriveIden)]
+enum UserInvite {
+    #[sea_orm(iden = "user_invite")]
+    Table,
+
+    #[sea_orm(iden = "id")]
+    Id,
+
+    #[sea_orm(iden = "code")]
+    Code,
+
+    #[sea_orm(iden = "created_by_user_id")]
+    CreatedByUserId,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+
+    #[sea_orm(iden = "max_uses")]
+    MaxUses,
+
+    #[sea_orm(iden = "use_count")]
+    UseCount,
+
+    #[sea_orm(iden = "revoked_at")]
+    RevokedAt,
+}
+
+const USER_INVITE_PK_CONSTRAINT_NAME: &str = "pk__user_invite";
+const USER_INVITE_UNIQUE_ON_CODE_CONSTRAINT_NAME: &str = "unique__user_invite__code";
+const USER_INVITE_FK_CREATED_BY_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__user_invite__created_by_user_id__user";
+
+
+
+#[derive(DeriveIden)]
+enum UserInviteRole {
+    #[sea_orm(iden = "user_invite_role")]
+    Table,
+
+    #[sea_orm(iden = "invite_id")]
+    InviteId,
+
+    #[sea_orm(iden = "role_id")]
+    RoleId,
+}
+
+const USER_INVITE_ROLE_PK_CONSTRAINT_NAME: &str = "pk__user_invite_role";
+const USER_INVITE_ROLE_FK_INVITE_ID_CONSTRAINT_NAME: &str =
+    "fk__user_invite_role__invite_id__user_invite";
+const USER_INVITE_ROLE_FK_ROLE_ID_CONSTRAINT_NAME: &str = "fk__user_invite_role__role_id__role";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserInvite::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(UserInvite::Id, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(UserInvite::Code, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserInvite::CreatedByUserId, ColumnType::Integer)
+                            .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserInvite::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserInvite::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserInvite::MaxUses, ColumnType::Integer)
+                            .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserInvite::UseCount, ColumnType::Integer)
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserInvite::RevokedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_INVITE_PK_CONSTRAINT_NAME)
+                            .col(UserInvite::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_INVITE_FK_CREATED_BY_USER_ID_CONSTRAINT_NAME)
+                            .from(UserInvite::Table, UserInvite::CreatedByUserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_INVITE_UNIQUE_ON_CODE_CONSTRAINT_NAME)
+                    .table(UserInvite::Table)
+                    .col(UserInvite::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserInviteRole::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(UserInviteRole::InviteId, ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserInviteRole::RoleId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_INVITE_ROLE_PK_CONSTRAINT_NAME)
+                            .col(UserInviteRole::InviteId)
+                            .col(UserInviteRole::RoleId)
+                            .primary(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_INVITE_ROLE_FK_INVITE_ID_CONSTRAINT_NAME)
+                            .from(UserInviteRole::Table, UserInviteRole::InviteId)
+                            .to(UserInvite::Table, UserInvite::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_INVITE_ROLE_FK_ROLE_ID_CONSTRAINT_NAME)
+                            .from(UserInviteRole::Table, UserInviteRole::RoleId)
+                            .to(Role::Table, Role::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserInviteRole::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserInvite::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,91 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveIden)]
+enum LoginThrottle {
+    #[sea_orm(iden = "login_throttle")]
+    Table,
+
+    #[sea_orm(iden = "subject_type")]
+    SubjectType,
+
+    #[sea_orm(iden = "subject")]
+    Subject,
+
+    #[sea_orm(iden = "failed_attempt_count")]
+    FailedAttemptCount,
+
+    #[sea_orm(iden = "last_failed_attempt_at")]
+    LastFailedAttemptAt,
+
+    #[sea_orm(iden = "locked_until")]
+    LockedUntil,
+}
+
+const LOGIN_THROTTLE_PK_CONSTRAINT_NAME: &str = "pk__login_throttle";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginThrottle::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(
+                            LoginThrottle::SubjectType,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(LoginThrottle::Subject, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            LoginThrottle::FailedAttemptCount,
+                            ColumnType::Integer,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            LoginThrottle::LastFailedAttemptAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            LoginThrottle::LockedUntil,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(LOGIN_THROTTLE_PK_CONSTRAINT_NAME)
+                            .col(LoginThrottle::SubjectType)
+                            .col(LoginThrottle::Subject)
+                            .primary(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginThrottle::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,28 @@
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The standard roles were seeded with explicit IDs, which leaves the sequence
+        // backing `role.id` at its start. Roles created at runtime would otherwise
+        // collide with the seeded ones, so we move the sequence past the largest existing ID.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "SELECT setval(pg_get_serial_sequence('role', 'id'), \
+                    (SELECT COALESCE(MAX(id), 0) + 1 FROM role), false)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Nothing to undo: the sequence being ahead of the seeded IDs is harmless.
+        Ok(())
+    }
+}
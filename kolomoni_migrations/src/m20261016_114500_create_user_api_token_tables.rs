@@ -0,0 +1,203 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20230624_133941_create_users_table::User,
+    m20230624_170512_initialize_permission_related_tables::Permission,
+};
+
+
+#[derive(DeriveIden)]
+enum UserApiToken {
+    #[sea_orm(iden = "user_api_token")]
+    Table,
+
+    #[sea_orm(iden = "id")]
+    Id,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "name")]
+    Name,
+
+    #[sea_orm(iden = "token_hash")]
+    TokenHash,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+}
+
+const USER_API_TOKEN_PK_CONSTRAINT_NAME: &str = "pk__user_api_token";
+const USER_API_TOKEN_UNIQUE_ON_TOKEN_HASH_CONSTRAINT_NAME: &str =
+    "unique__user_api_token__token_hash";
+const USER_API_TOKEN_FK_USER_ID_CONSTRAINT_NAME: &str = "fk__user_api_token__user_id__user";
+const USER_API_TOKEN_IDX_ON_USER_ID_INDEX_NAME: &str = "index__user_api_token__on__user_id";
+
+
+
+#[derive(DeriveIden)]
+enum UserApiTokenPermission {
+    #[sea_orm(iden = "user_api_token_permission")]
+    Table,
+
+    #[sea_orm(iden = "token_id")]
+    TokenId,
+
+    #[sea_orm(iden = "permission_id")]
+    PermissionId,
+}
+
+const USER_API_TOKEN_PERMISSION_PK_CONSTRAINT_NAME: &str = "pk__user_api_token_permission";
+const USER_API_TOKEN_PERMISSION_FK_TOKEN_ID_CONSTRAINT_NAME: &str =
+    "fk__user_api_token_permission__token_id__user_api_token";
+const USER_API_TOKEN_PERMISSION_FK_PERMISSION_ID_CONSTRAINT_NAME: &str =
+    "fk__user_api_token_permission__permission_id__permission";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserApiToken::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(UserApiToken::Id, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(UserApiToken::UserId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserApiToken::Name, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserApiToken::TokenHash, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserApiToken::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserApiToken::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_API_TOKEN_PK_CONSTRAINT_NAME)
+                            .col(UserApiToken::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_API_TOKEN_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(UserApiToken::Table, UserApiToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_API_TOKEN_UNIQUE_ON_TOKEN_HASH_CONSTRAINT_NAME)
+                    .table(UserApiToken::Table)
+                    .col(UserApiToken::TokenHash)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_API_TOKEN_IDX_ON_USER_ID_INDEX_NAME)
+                    .table(UserApiToken::Table)
+                    .col(UserApiToken::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserApiTokenPermission::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(UserApiTokenPermission::TokenId, ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserApiTokenPermission::PermissionId,
+                            ColumnType::Integer,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_API_TOKEN_PERMISSION_PK_CONSTRAINT_NAME)
+                            .col(UserApiTokenPermission::TokenId)
+                            .col(UserApiTokenPermission::PermissionId)
+                            .primary(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_API_TOKEN_PERMISSION_FK_TOKEN_ID_CONSTRAINT_NAME)
+                            .from(
+                                UserApiTokenPermission::Table,
+                                UserApiTokenPermission::TokenId,
+                            )
+                            .to(UserApiToken::Table, UserApiToken::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_API_TOKEN_PERMISSION_FK_PERMISSION_ID_CONSTRAINT_NAME)
+                            .from(
+                                UserApiTokenPermission::Table,
+                                UserApiTokenPermission::PermissionId,
+                            )
+                            .to(Permission::Table, Permission::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserApiTokenPermission::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserApiToken::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,206 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserOidcIdentity {
+    #[sea_orm(iden = "user_oidc_identity")]
+    Table,
+
+    #[sea_orm(iden = "issuer")]
+    Issuer,
+
+    #[sea_orm(iden = "subject")]
+    Subject,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "linked_at")]
+    LinkedAt,
+}
+
+const USER_OIDC_IDENTITY_PK_CONSTRAINT_NAME: &str = "pk__user_oidc_identity";
+const USER_OIDC_IDENTITY_FK_USER_ID_CONSTRAINT_NAME: &str = "fk__user_oidc_identity__user_id__user";
+const USER_OIDC_IDENTITY_IDX_ON_USER_ID_INDEX_NAME: &str = "index__user_oidc_identity__on__user_id";
+
+
+
+#[derive(DeriveIden)]
+enum OidcAuthorizationRequest {
+    #[sea_orm(iden = "oidc_authorization_request")]
+    Table,
+
+    #[sea_orm(iden = "state")]
+    State,
+
+    #[sea_orm(iden = "code_verifier")]
+    CodeVerifier,
+
+    #[sea_orm(iden = "nonce")]
+    Nonce,
+
+    #[sea_orm(iden = "linking_user_id")]
+    LinkingUserId,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+}
+
+const OIDC_AUTHORIZATION_REQUEST_PK_CONSTRAINT_NAME: &str = "pk__oidc_authorization_request";
+const OIDC_AUTHORIZATION_REQUEST_FK_LINKING_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__oidc_authorization_request__linking_user_id__user";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserOidcIdentity::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(UserOidcIdentity::Issuer, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserOidcIdentity::Subject,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserOidcIdentity::UserId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserOidcIdentity::LinkedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_OIDC_IDENTITY_PK_CONSTRAINT_NAME)
+                            .col(UserOidcIdentity::Issuer)
+                            .col(UserOidcIdentity::Subject)
+                            .primary(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_OIDC_IDENTITY_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(UserOidcIdentity::Table, UserOidcIdentity::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_OIDC_IDENTITY_IDX_ON_USER_ID_INDEX_NAME)
+                    .table(UserOidcIdentity::Table)
+                    .col(UserOidcIdentity::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OidcAuthorizationRequest::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::State,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::CodeVerifier,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::Nonce,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::LinkingUserId,
+                            ColumnType::Integer,
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            OidcAuthorizationRequest::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(OIDC_AUTHORIZATION_REQUEST_PK_CONSTRAINT_NAME)
+                            .col(OidcAuthorizationRequest::State),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(OIDC_AUTHORIZATION_REQUEST_FK_LINKING_USER_ID_CONSTRAINT_NAME)
+                            .from(
+                                OidcAuthorizationRequest::Table,
+                                OidcAuthorizationRequest::LinkingUserId,
+                            )
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(OidcAuthorizationRequest::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserOidcIdentity::Table).to_owned())
+            .await
+    }
+}
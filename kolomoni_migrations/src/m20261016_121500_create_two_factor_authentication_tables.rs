@@ -0,0 +1,262 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserTwoFactor {
+    #[sea_orm(iden = "user_two_factor")]
+    Table,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "secret")]
+    Secret,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "enabled_at")]
+    EnabledAt,
+
+    #[sea_orm(iden = "last_used_time_step")]
+    LastUsedTimeStep,
+}
+
+const USER_TWO_FACTOR_PK_CONSTRAINT_NAME: &str = "pk__user_two_factor";
+const USER_TWO_FACTOR_FK_USER_ID_CONSTRAINT_NAME: &str = "fk__user_two_factor__user_id__user";
+
+
+
+#[derive(DeriveIden)]
+enum UserTwoFactorRecoveryCode {
+    #[sea_orm(iden = "user_two_factor_recovery_code")]
+    Table,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "code_hash")]
+    CodeHash,
+}
+
+const USER_TWO_FACTOR_RECOVERY_CODE_PK_CONSTRAINT_NAME: &str = "pk__user_two_factor_recovery_code";
+const USER_TWO_FACTOR_RECOVERY_CODE_FK_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__user_two_factor_recovery_code__user_id__user";
+
+
+
+#[derive(DeriveIden)]
+enum TwoFactorLoginChallenge {
+    #[sea_orm(iden = "two_factor_login_challenge")]
+    Table,
+
+    #[sea_orm(iden = "token_hash")]
+    TokenHash,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+
+    #[sea_orm(iden = "failed_attempt_count")]
+    FailedAttemptCount,
+}
+
+const TWO_FACTOR_LOGIN_CHALLENGE_PK_CONSTRAINT_NAME: &str = "pk__two_factor_login_challenge";
+const TWO_FACTOR_LOGIN_CHALLENGE_FK_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__two_factor_login_challenge__user_id__user";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTwoFactor::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(UserTwoFactor::UserId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserTwoFactor::Secret, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserTwoFactor::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserTwoFactor::EnabledAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserTwoFactor::LastUsedTimeStep,
+                            ColumnType::BigInteger,
+                        )
+                        .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_TWO_FACTOR_PK_CONSTRAINT_NAME)
+                            .col(UserTwoFactor::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_TWO_FACTOR_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(UserTwoFactor::Table, UserTwoFactor::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTwoFactorRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserTwoFactorRecoveryCode::UserId,
+                            ColumnType::Integer,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserTwoFactorRecoveryCode::CodeHash,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_TWO_FACTOR_RECOVERY_CODE_PK_CONSTRAINT_NAME)
+                            .col(UserTwoFactorRecoveryCode::UserId)
+                            .col(UserTwoFactorRecoveryCode::CodeHash)
+                            .primary(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_TWO_FACTOR_RECOVERY_CODE_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(
+                                UserTwoFactorRecoveryCode::Table,
+                                UserTwoFactorRecoveryCode::UserId,
+                            )
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TwoFactorLoginChallenge::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(
+                            TwoFactorLoginChallenge::TokenHash,
+                            ColumnType::String(None),
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            TwoFactorLoginChallenge::UserId,
+                            ColumnType::Integer,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            TwoFactorLoginChallenge::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            TwoFactorLoginChallenge::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            TwoFactorLoginChallenge::FailedAttemptCount,
+                            ColumnType::Integer,
+                        )
+                        .not_null()
+                        .default(0),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(TWO_FACTOR_LOGIN_CHALLENGE_PK_CONSTRAINT_NAME)
+                            .col(TwoFactorLoginChallenge::TokenHash),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(TWO_FACTOR_LOGIN_CHALLENGE_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(
+                                TwoFactorLoginChallenge::Table,
+                                TwoFactorLoginChallenge::UserId,
+                            )
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(TwoFactorLoginChallenge::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(UserTwoFactorRecoveryCode::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserTwoFactor::Table).to_owned())
+            .await
+    }
+}
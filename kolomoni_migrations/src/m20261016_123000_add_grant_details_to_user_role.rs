@@ -0,0 +1,115 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserRole {
+    #[sea_orm(iden = "user_role")]
+    Table,
+
+    #[sea_orm(iden = "granted_by")]
+    GrantedBy,
+
+    #[sea_orm(iden = "granted_at")]
+    GrantedAt,
+
+    #[sea_orm(iden = "expires_at")]
+    ExpiresAt,
+}
+
+const USER_ROLE_FK_GRANTED_BY_CONSTRAINT_NAME: &str = "fk__user_role__granted_by__user";
+const USER_ROLE_IDX_ON_EXPIRES_AT_INDEX_NAME: &str = "index__user_role__on__expires_at";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // All three columns are optional: role grants that existed before this migration
+        // (and the default role given on registration) have no grant details and never expire.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserRole::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(UserRole::GrantedBy, ColumnType::Integer)
+                            .borrow_mut(),
+                    )
+                    .add_column(
+                        ColumnDef::new_with_type(
+                            UserRole::GrantedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .add_column(
+                        ColumnDef::new_with_type(
+                            UserRole::ExpiresAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name(USER_ROLE_FK_GRANTED_BY_CONSTRAINT_NAME)
+                    .from(UserRole::Table, UserRole::GrantedBy)
+                    .to(User::Table, User::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_ROLE_IDX_ON_EXPIRES_AT_INDEX_NAME)
+                    .table(UserRole::Table)
+                    .col(UserRole::ExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(USER_ROLE_IDX_ON_EXPIRES_AT_INDEX_NAME)
+                    .table(UserRole::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name(USER_ROLE_FK_GRANTED_BY_CONSTRAINT_NAME)
+                    .table(UserRole::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserRole::Table)
+                    .drop_column(UserRole::ExpiresAt)
+                    .drop_column(UserRole::GrantedAt)
+                    .drop_column(UserRole::GrantedBy)
+                    .to_owned(),
+            )
+            .await
+    }
+}
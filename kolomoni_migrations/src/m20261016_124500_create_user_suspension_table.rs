@@ -0,0 +1,152 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::m20230624_133941_create_users_table::User;
+
+
+#[derive(DeriveIden)]
+enum UserSuspension {
+    #[sea_orm(iden = "user_suspension")]
+    Table,
+
+    #[sea_orm(iden = "id")]
+    Id,
+
+    #[sea_orm(iden = "user_id")]
+    UserId,
+
+    #[sea_orm(iden = "reason")]
+    Reason,
+
+    #[sea_orm(iden = "suspended_by_user_id")]
+    SuspendedByUserId,
+
+    #[sea_orm(iden = "suspended_at")]
+    SuspendedAt,
+
+    #[sea_orm(iden = "ends_at")]
+    EndsAt,
+
+    #[sea_orm(iden = "lifted_by_user_id")]
+    LiftedByUserId,
+
+    #[sea_orm(iden = "lifted_at")]
+    LiftedAt,
+}
+
+const USER_SUSPENSION_PK_CONSTRAINT_NAME: &str = "pk__user_suspension";
+const USER_SUSPENSION_FK_USER_ID_CONSTRAINT_NAME: &str = "fk__user_suspension__user_id__user";
+const USER_SUSPENSION_FK_SUSPENDED_BY_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__user_suspension__suspended_by_user_id__user";
+const USER_SUSPENSION_FK_LIFTED_BY_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__user_suspension__lifted_by_user_id__user";
+const USER_SUSPENSION_IDX_ON_USER_ID_INDEX_NAME: &str = "index__user_suspension__on__user_id";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserSuspension::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(UserSuspension::Id, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(UserSuspension::UserId, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserSuspension::Reason, ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSuspension::SuspendedByUserId,
+                            ColumnType::Integer,
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSuspension::SuspendedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSuspension::EndsAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(UserSuspension::LiftedByUserId, ColumnType::Integer)
+                            .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            UserSuspension::LiftedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .borrow_mut(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(USER_SUSPENSION_PK_CONSTRAINT_NAME)
+                            .col(UserSuspension::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_SUSPENSION_FK_USER_ID_CONSTRAINT_NAME)
+                            .from(UserSuspension::Table, UserSuspension::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_SUSPENSION_FK_SUSPENDED_BY_USER_ID_CONSTRAINT_NAME)
+                            .from(
+                                UserSuspension::Table,
+                                UserSuspension::SuspendedByUserId,
+                            )
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(USER_SUSPENSION_FK_LIFTED_BY_USER_ID_CONSTRAINT_NAME)
+                            .from(UserSuspension::Table, UserSuspension::LiftedByUserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(USER_SUSPENSION_IDX_ON_USER_ID_INDEX_NAME)
+                    .table(UserSuspension::Table)
+                    .col(UserSuspension::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserSuspension::Table).to_owned())
+            .await
+    }
+}
@@ -0,0 +1,140 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    m20230624_133941_create_users_table::User,
+    m20240206_234618_create_word_tables::Word,
+};
+
+
+#[derive(DeriveIden)]
+enum WordRevision {
+    #[sea_orm(iden = "word_revision")]
+    Table,
+
+    #[sea_orm(iden = "word_id")]
+    WordId,
+
+    #[sea_orm(iden = "revision_number")]
+    RevisionNumber,
+
+    #[sea_orm(iden = "lemma")]
+    Lemma,
+
+    #[sea_orm(iden = "disambiguation")]
+    Disambiguation,
+
+    #[sea_orm(iden = "description")]
+    Description,
+
+    #[sea_orm(iden = "author_user_id")]
+    AuthorUserId,
+
+    #[sea_orm(iden = "created_at")]
+    CreatedAt,
+}
+
+const WORD_REVISION_PK_CONSTRAINT_NAME: &str = "pk__word_revision";
+const WORD_REVISION_FK_WORD_ID_CONSTRAINT_NAME: &str = "fk__word_revision__word_id__word";
+const WORD_REVISION_FK_AUTHOR_USER_ID_CONSTRAINT_NAME: &str =
+    "fk__word_revision__author_user_id__user";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordRevision::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(WordRevision::WordId, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(WordRevision::RevisionNumber, ColumnType::Integer)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(WordRevision::Lemma, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordRevision::Disambiguation,
+                            ColumnType::String(None),
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordRevision::Description,
+                            ColumnType::String(None),
+                        )
+                        .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(WordRevision::AuthorUserId, ColumnType::Integer)
+                            .borrow_mut(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordRevision::CreatedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(WORD_REVISION_PK_CONSTRAINT_NAME)
+                            .col(WordRevision::WordId)
+                            .col(WordRevision::RevisionNumber),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_REVISION_FK_WORD_ID_CONSTRAINT_NAME)
+                            .from(WordRevision::Table, WordRevision::WordId)
+                            .to(Word::Table, Word::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_REVISION_FK_AUTHOR_USER_ID_CONSTRAINT_NAME)
+                            .from(WordRevision::Table, WordRevision::AuthorUserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+
+        // Existing words get their current state as the first revision
+        // (without an author, as we don't know who created them).
+        for word_table in ["word_english", "word_slovene"] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "INSERT INTO word_revision \
+                        (word_id, revision_number, lemma, disambiguation, description, created_at) \
+                        SELECT word_id, 1, lemma, disambiguation, description, last_modified_at \
+                        FROM {}",
+                    word_table
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WordRevision::Table).to_owned())
+            .await
+    }
+}
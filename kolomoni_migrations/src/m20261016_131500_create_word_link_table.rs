@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240206_234618_create_word_tables::Word;
+
+
+#[derive(DeriveIden)]
+enum WordLink {
+    #[sea_orm(iden = "word_link")]
+    Table,
+
+    #[sea_orm(iden = "word_id")]
+    WordId,
+
+    #[sea_orm(iden = "linked_word_id")]
+    LinkedWordId,
+
+    #[sea_orm(iden = "link_type")]
+    LinkType,
+
+    #[sea_orm(iden = "linked_at")]
+    LinkedAt,
+}
+
+const WORD_LINK_PK_CONSTRAINT_NAME: &str = "pk__word_link";
+const WORD_LINK_FK_WORD_ID_CONSTRAINT_NAME: &str = "fk__word_link__word_id__word";
+const WORD_LINK_FK_LINKED_WORD_ID_CONSTRAINT_NAME: &str = "fk__word_link__linked_word_id__word";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordLink::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new_with_type(WordLink::WordId, ColumnType::Uuid).not_null())
+                    .col(
+                        ColumnDef::new_with_type(WordLink::LinkedWordId, ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(WordLink::LinkType, ColumnType::String(None))
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordLink::LinkedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(WORD_LINK_PK_CONSTRAINT_NAME)
+                            .col(WordLink::WordId)
+                            .col(WordLink::LinkedWordId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_LINK_FK_WORD_ID_CONSTRAINT_NAME)
+                            .from(WordLink::Table, WordLink::WordId)
+                            .to(Word::Table, Word::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_LINK_FK_LINKED_WORD_ID_CONSTRAINT_NAME)
+                            .from(WordLink::Table, WordLink::LinkedWordId)
+                            .to(Word::Table, Word::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WordLink::Table).to_owned())
+            .await
+    }
+}
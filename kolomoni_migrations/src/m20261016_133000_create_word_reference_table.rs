@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20240206_234618_create_word_tables::Word;
+
+
+#[derive(DeriveIden)]
+enum WordReference {
+    #[sea_orm(iden = "word_reference")]
+    Table,
+
+    #[sea_orm(iden = "word_id")]
+    WordId,
+
+    #[sea_orm(iden = "referenced_word_id")]
+    ReferencedWordId,
+
+    #[sea_orm(iden = "referenced_at")]
+    ReferencedAt,
+}
+
+const WORD_REFERENCE_PK_CONSTRAINT_NAME: &str = "pk__word_reference";
+const WORD_REFERENCE_FK_WORD_ID_CONSTRAINT_NAME: &str = "fk__word_reference__word_id__word";
+const WORD_REFERENCE_FK_REFERENCED_WORD_ID_CONSTRAINT_NAME: &str =
+    "fk__word_reference__referenced_word_id__word";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WordReference::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new_with_type(WordReference::WordId, ColumnType::Uuid)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordReference::ReferencedWordId,
+                            ColumnType::Uuid,
+                        )
+                        .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new_with_type(
+                            WordReference::ReferencedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .name(WORD_REFERENCE_PK_CONSTRAINT_NAME)
+                            .col(WordReference::WordId)
+                            .col(WordReference::ReferencedWordId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_REFERENCE_FK_WORD_ID_CONSTRAINT_NAME)
+                            .from(WordReference::Table, WordReference::WordId)
+                            .to(Word::Table, Word::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name(WORD_REFERENCE_FK_REFERENCED_WORD_ID_CONSTRAINT_NAME)
+                            .from(WordReference::Table, WordReference::ReferencedWordId)
+                            .to(Word::Table, Word::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WordReference::Table).to_owned())
+            .await
+    }
+}
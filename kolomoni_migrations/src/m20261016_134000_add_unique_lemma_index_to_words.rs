@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+
+const WORD_SLOVENE_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME: &str =
+    "unique__word_slovene__lemma__disambiguation";
+
+const WORD_ENGLISH_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME: &str =
+    "unique__word_english__lemma__disambiguation";
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Homonyms must differ in their disambiguation. A missing disambiguation
+        // is treated as an empty one, otherwise two words with the same lemma
+        // and no disambiguation would not be considered equal (`NULL <> NULL`).
+        // The query builder can't index expressions, hence the raw SQL.
+        for (index_name, table_name) in [
+            (
+                WORD_SLOVENE_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME,
+                "word_slovene",
+            ),
+            (
+                WORD_ENGLISH_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME,
+                "word_english",
+            ),
+        ] {
+            manager
+                .get_connection()
+                .execute_unprepared(&format!(
+                    "CREATE UNIQUE INDEX {} ON {} (lemma, COALESCE(disambiguation, ''))",
+                    index_name, table_name
+                ))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for index_name in [
+            WORD_ENGLISH_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME,
+            WORD_SLOVENE_UNIQUE_ON_LEMMA_AND_DISAMBIGUATION_INDEX_NAME,
+        ] {
+            manager
+                .drop_index(Index::drop().name(index_name).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveIden, Clone, Copy)]
+enum WordSlovene {
+    #[sea_orm(iden = "word_slovene")]
+    Table,
+
+    #[sea_orm(iden = "part_of_speech")]
+    PartOfSpeech,
+
+    #[sea_orm(iden = "grammatical_gender")]
+    GrammaticalGender,
+
+    #[sea_orm(iden = "number_restriction")]
+    NumberRestriction,
+
+    #[sea_orm(iden = "nominative_singular")]
+    NominativeSingular,
+
+    #[sea_orm(iden = "nominative_dual")]
+    NominativeDual,
+
+    #[sea_orm(iden = "nominative_plural")]
+    NominativePlural,
+
+    #[sea_orm(iden = "genitive_singular")]
+    GenitiveSingular,
+
+    #[sea_orm(iden = "genitive_dual")]
+    GenitiveDual,
+
+    #[sea_orm(iden = "genitive_plural")]
+    GenitivePlural,
+
+    #[sea_orm(iden = "dative_singular")]
+    DativeSingular,
+
+    #[sea_orm(iden = "dative_dual")]
+    DativeDual,
+
+    #[sea_orm(iden = "dative_plural")]
+    DativePlural,
+
+    #[sea_orm(iden = "accusative_singular")]
+    AccusativeSingular,
+
+    #[sea_orm(iden = "accusative_dual")]
+    AccusativeDual,
+
+    #[sea_orm(iden = "accusative_plural")]
+    AccusativePlural,
+
+    #[sea_orm(iden = "locative_singular")]
+    LocativeSingular,
+
+    #[sea_orm(iden = "locative_dual")]
+    LocativeDual,
+
+    #[sea_orm(iden = "locative_plural")]
+    LocativePlural,
+
+    #[sea_orm(iden = "instrumental_singular")]
+    InstrumentalSingular,
+
+    #[sea_orm(iden = "instrumental_dual")]
+    InstrumentalDual,
+
+    #[sea_orm(iden = "instrumental_plural")]
+    InstrumentalPlural,
+}
+
+/// All new slovene word columns (all of them are optional text).
+const WORD_SLOVENE_GRAMMAR_COLUMNS: [WordSlovene; 21] = [
+    WordSlovene::PartOfSpeech,
+    WordSlovene::GrammaticalGender,
+    WordSlovene::NumberRestriction,
+    WordSlovene::NominativeSingular,
+    WordSlovene::NominativeDual,
+    WordSlovene::NominativePlural,
+    WordSlovene::GenitiveSingular,
+    WordSlovene::GenitiveDual,
+    WordSlovene::GenitivePlural,
+    WordSlovene::DativeSingular,
+    WordSlovene::DativeDual,
+    WordSlovene::DativePlural,
+    WordSlovene::AccusativeSingular,
+    WordSlovene::AccusativeDual,
+    WordSlovene::AccusativePlural,
+    WordSlovene::LocativeSingular,
+    WordSlovene::LocativeDual,
+    WordSlovene::LocativePlural,
+    WordSlovene::InstrumentalSingular,
+    WordSlovene::InstrumentalDual,
+    WordSlovene::InstrumentalPlural,
+];
+
+
+#[derive(DeriveIden, Clone, Copy)]
+enum WordEnglish {
+    #[sea_orm(iden = "word_english")]
+    Table,
+
+    #[sea_orm(iden = "part_of_speech")]
+    PartOfSpeech,
+
+    #[sea_orm(iden = "number_restriction")]
+    NumberRestriction,
+
+    #[sea_orm(iden = "plural_form")]
+    PluralForm,
+}
+
+/// All new english word columns (all of them are optional text).
+const WORD_ENGLISH_GRAMMAR_COLUMNS: [WordEnglish; 3] = [
+    WordEnglish::PartOfSpeech,
+    WordEnglish::NumberRestriction,
+    WordEnglish::PluralForm,
+];
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut word_slovene_alteration = Table::alter().table(WordSlovene::Table).to_owned();
+        for column in WORD_SLOVENE_GRAMMAR_COLUMNS {
+            word_slovene_alteration
+                .add_column(ColumnDef::new_with_type(column, ColumnType::String(None)).borrow_mut());
+        }
+
+        manager.alter_table(word_slovene_alteration).await?;
+
+
+        let mut word_english_alteration = Table::alter().table(WordEnglish::Table).to_owned();
+        for column in WORD_ENGLISH_GRAMMAR_COLUMNS {
+            word_english_alteration
+                .add_column(ColumnDef::new_with_type(column, ColumnType::String(None)).borrow_mut());
+        }
+
+        manager.alter_table(word_english_alteration).await?;
+
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let mut word_english_alteration = Table::alter().table(WordEnglish::Table).to_owned();
+        for column in WORD_ENGLISH_GRAMMAR_COLUMNS {
+            word_english_alteration.drop_column(column);
+        }
+
+        manager.alter_table(word_english_alteration).await?;
+
+
+        let mut word_slovene_alteration = Table::alter().table(WordSlovene::Table).to_owned();
+        for column in WORD_SLOVENE_GRAMMAR_COLUMNS {
+            word_slovene_alteration.drop_column(column);
+        }
+
+        manager.alter_table(word_slovene_alteration).await?;
+
+
+        Ok(())
+    }
+}
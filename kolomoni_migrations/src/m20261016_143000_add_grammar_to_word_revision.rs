@@ -0,0 +1,48 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveIden)]
+enum WordRevision {
+    #[sea_orm(iden = "word_revision")]
+    Table,
+
+    #[sea_orm(iden = "grammar")]
+    Grammar,
+}
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Grammatical information about the word, as a JSON object. It stays empty
+        // for revisions that were recorded before grammar was tracked.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordRevision::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(WordRevision::Grammar, ColumnType::String(None))
+                            .borrow_mut(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WordRevision::Table)
+                    .drop_column(WordRevision::Grammar)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,88 @@
+use std::borrow::BorrowMut;
+
+use sea_orm_migration::prelude::*;
+
+
+#[derive(DeriveIden)]
+enum Role {
+    #[sea_orm(iden = "role")]
+    Table,
+
+    #[sea_orm(iden = "last_modified_at")]
+    LastModifiedAt,
+}
+
+#[derive(DeriveIden)]
+enum Permission {
+    #[sea_orm(iden = "permission")]
+    Table,
+
+    #[sea_orm(iden = "last_modified_at")]
+    LastModifiedAt,
+}
+
+
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Existing roles and permissions (and permissions seeded by later migrations)
+        // are considered to have been modified when they were inserted.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Role::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(
+                            Role::LastModifiedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                        .borrow_mut(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permission::Table)
+                    .add_column(
+                        ColumnDef::new_with_type(
+                            Permission::LastModifiedAt,
+                            ColumnType::TimestampWithTimeZone,
+                        )
+                        .not_null()
+                        .default(Expr::current_timestamp())
+                        .borrow_mut(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permission::Table)
+                    .drop_column(Permission::LastModifiedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Role::Table)
+                    .drop_column(Role::LastModifiedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -4,7 +4,9 @@ use actix_web::{App, HttpServer};
 use kolomoni::api::errors;
 use kolomoni::api::v1::dictionary;
 use kolomoni::api::v1::login;
+use kolomoni::api::v1::permissions;
 use kolomoni::api::v1::ping;
+use kolomoni::api::v1::roles;
 use kolomoni::api::v1::users;
 use kolomoni::logging::initialize_tracing;
 use miette::Context;
@@ -31,15 +33,49 @@ use utoipa_rapidoc::RapiDoc;
         // login.rs
         login::login,
         login::refresh_login,
+        login::logout,
+        login::get_all_login_lockouts,
+        login::clear_login_lockout,
+
+        // login/oidc.rs
+        login::oidc::start_oidc_login,
+        login::oidc::finish_oidc_login,
+
+        // login/two_factor.rs
+        login::two_factor::enroll_during_login,
+        login::two_factor::complete_two_factor_login,
+
+        // permissions.rs
+        permissions::get_all_permissions,
+
+        // roles.rs
+        roles::get_all_roles,
+        roles::create_role,
+        roles::update_role,
+        roles::delete_role,
 
         // users/all.rs
         users::all::get_all_registered_users,
 
+        // users/api_tokens.rs
+        users::api_tokens::get_current_user_api_tokens,
+        users::api_tokens::create_current_user_api_token,
+        users::api_tokens::revoke_current_user_api_token,
+
         // users/current.rs
         users::current::get_current_user_info,
         users::current::get_current_user_roles,
         users::current::get_current_user_effective_permissions,
         users::current::update_current_user_display_name,
+        users::current::update_current_user_password,
+        users::current::get_current_user_sessions,
+        users::current::revoke_current_user_session,
+        users::current::delete_current_user,
+
+        // users/invites.rs
+        users::invites::get_all_invites,
+        users::invites::create_invite,
+        users::invites::revoke_invite,
 
         // users/registration.rs
         users::registration::register_user,
@@ -51,6 +87,21 @@ use utoipa_rapidoc::RapiDoc;
         users::specific::update_specific_user_display_name,
         users::specific::add_roles_to_specific_user,
         users::specific::remove_roles_from_specific_user,
+        users::specific::deactivate_specific_user,
+        users::specific::reactivate_specific_user,
+        users::specific::delete_specific_user,
+
+        // users/suspensions.rs
+        users::suspensions::get_specific_user_suspensions,
+        users::suspensions::suspend_specific_user,
+        users::suspensions::lift_specific_user_suspension,
+
+        // users/two_factor.rs
+        users::two_factor::get_current_user_two_factor_status,
+        users::two_factor::begin_current_user_two_factor_enrollment,
+        users::two_factor::confirm_current_user_two_factor_enrollment,
+        users::two_factor::disable_current_user_two_factor,
+        users::two_factor::regenerate_current_user_recovery_codes,
 
         // dictionary/slovene_word.rs
         dictionary::slovene_word::get_all_slovene_words,
@@ -76,8 +127,27 @@ use utoipa_rapidoc::RapiDoc;
         dictionary::translations::create_translation,
         dictionary::translations::delete_translation,
 
+        // dictionary/links.rs
+        dictionary::links::create_word_link,
+        dictionary::links::delete_word_link,
+
+        // dictionary/references.rs
+        dictionary::references::get_word_references,
+
         // dictionary/search.rs
         dictionary::search::perform_search,
+
+        // dictionary/import.rs
+        dictionary::import::import_dictionary,
+        dictionary::import::import_tbx_glossary,
+
+        // dictionary/export.rs
+        dictionary::export::export_dictionary,
+
+        // dictionary/revisions.rs
+        dictionary::revisions::get_word_revisions,
+        dictionary::revisions::diff_word_revisions,
+        dictionary::revisions::revert_word_to_revision,
     ),
     components(
         schemas(
@@ -93,20 +163,63 @@ use utoipa_rapidoc::RapiDoc;
             login::UserLoginResponse,
             login::UserLoginRefreshRequest,
             login::UserLoginRefreshResponse,
+            login::LoginLockout,
+            login::LoginLockoutsResponse,
+
+            // login/oidc.rs
+            login::oidc::OidcAuthorizationResponse,
+            login::oidc::OidcCallbackRequest,
+
+            // login/two_factor.rs
+            login::two_factor::TwoFactorChallengeResponse,
+            login::two_factor::TwoFactorLoginEnrollmentRequest,
+            login::two_factor::TwoFactorLoginRequest,
+
+            // permissions.rs
+            permissions::PermissionInformation,
+            permissions::PermissionsResponse,
+
+            // roles.rs
+            roles::RoleInformation,
+            roles::RoleInfoResponse,
+            roles::RolesResponse,
+            roles::RoleCreationRequest,
+            roles::RoleUpdateRequest,
 
             // users.rs
             users::UserInformation,
             users::UserInfoResponse,
             users::UserDisplayNameChangeRequest,
             users::UserDisplayNameChangeResponse,
+            users::UserPasswordChangeRequest,
+            users::UserAccountDeletionRequest,
+            users::UserSession,
+            users::UserSessionsResponse,
             users::UserRolesResponse,
             users::UserPermissionsResponse,
 
             // users/all.rs
             users::all::RegisteredUsersListResponse,
+            users::all::RegisteredUsersSortField,
+            users::all::RegisteredUsersSortDirection,
+            users::all::RegisteredUsersFilters,
+            users::all::RegisteredUsersListRequest,
+
+            // users/api_tokens.rs
+            users::api_tokens::UserApiToken,
+            users::api_tokens::UserApiTokensResponse,
+            users::api_tokens::UserApiTokenCreationRequest,
+            users::api_tokens::UserApiTokenCreationResponse,
 
             // users/current.rs
             // (none)
+
+            // users/invites.rs
+            users::invites::UserInvite,
+            users::invites::UserInviteResponse,
+            users::invites::UserInvitesResponse,
+            users::invites::UserInviteCreationRequest,
+
             // users/registration.rs
             users::registration::UserRegistrationRequest,
             users::registration::UserRegistrationResponse,
@@ -115,6 +228,18 @@ use utoipa_rapidoc::RapiDoc;
             users::specific::UserRoleAddRequest,
             users::specific::UserRoleRemoveRequest,
 
+            // users/suspensions.rs
+            users::suspensions::UserSuspension,
+            users::suspensions::UserSuspensionResponse,
+            users::suspensions::UserSuspensionsResponse,
+            users::suspensions::UserSuspensionCreationRequest,
+
+            // users/two_factor.rs
+            users::two_factor::TwoFactorStatusResponse,
+            users::two_factor::TwoFactorEnrollmentResponse,
+            users::two_factor::TwoFactorConfirmationRequest,
+            users::two_factor::TwoFactorRecoveryCodesResponse,
+
             // ../errors.rs
             errors::ErrorReasonResponse,
 
@@ -129,7 +254,10 @@ use utoipa_rapidoc::RapiDoc;
             dictionary::slovene_word::SloveneWordCreationRequest,
             dictionary::slovene_word::SloveneWordCreationResponse,
             dictionary::slovene_word::SloveneWordInfoResponse,
+            dictionary::slovene_word::SloveneWordsByLemmaResponse,
             dictionary::slovene_word::SloveneWordUpdateRequest,
+            dictionary::slovene_word::SloveneWordUpdateResponse,
+            dictionary::slovene_word::SloveneWordDeletionResponse,
 
             // dictionary/english_word.rs
             dictionary::english_word::EnglishWord,
@@ -139,7 +267,19 @@ use utoipa_rapidoc::RapiDoc;
             dictionary::english_word::EnglishWordCreationRequest,
             dictionary::english_word::EnglishWordCreationResponse,
             dictionary::english_word::EnglishWordInfoResponse,
+            dictionary::english_word::EnglishWordsByLemmaResponse,
             dictionary::english_word::EnglishWordUpdateRequest,
+            dictionary::english_word::EnglishWordUpdateResponse,
+            dictionary::english_word::EnglishWordDeletionResponse,
+
+            // dictionary/grammar.rs
+            dictionary::grammar::PartOfSpeech,
+            dictionary::grammar::GrammaticalGender,
+            dictionary::grammar::NumberRestriction,
+            dictionary::grammar::SloveneCaseForms,
+            dictionary::grammar::SloveneInflectionTable,
+            dictionary::grammar::SloveneWordGrammar,
+            dictionary::grammar::EnglishWordGrammar,
 
             // dictionary/suggestions.rs
             dictionary::suggestions::TranslationSuggestionRequest,
@@ -149,10 +289,48 @@ use utoipa_rapidoc::RapiDoc;
             dictionary::translations::TranslationRequest,
             dictionary::translations::TranslationDeletionRequest,
 
+            // dictionary/links.rs
+            dictionary::links::WordLinkType,
+            dictionary::links::WordLink,
+            dictionary::links::WordLinkRequest,
+            dictionary::links::WordLinkDeletionRequest,
+
+            // dictionary/references.rs
+            dictionary::references::ReferencedWordLanguage,
+            dictionary::references::ReferencedWord,
+            dictionary::references::WordReferencesResponse,
+
             // dictionary/search.rs
             dictionary::search::SearchRequest,
             dictionary::search::SearchResults,
             dictionary::search::SearchResponse,
+
+            // dictionary/import.rs
+            dictionary::import::DictionaryImportRequest,
+            dictionary::import::DictionaryImportResponse,
+            kolomoni::dictionary_import::DictionaryImportFormat,
+            kolomoni::dictionary_import::DictionaryImportReport,
+            kolomoni::dictionary_import::DictionaryImportSummary,
+            kolomoni::dictionary_import::DictionaryImportRowReport,
+            kolomoni::dictionary_import::DictionaryImportRowStatus,
+            dictionary::import::TbxImportRequest,
+            dictionary::import::TbxImportResponse,
+            kolomoni::dictionary_import::tbx::TbxImportReport,
+            kolomoni::dictionary_import::tbx::TbxImportSummary,
+            kolomoni::dictionary_import::tbx::TbxImportEntryReport,
+            kolomoni::dictionary_import::tbx::TbxImportEntryStatus,
+
+            // dictionary/export.rs
+            dictionary::export::DictionaryExportFormat,
+            dictionary::export::DictionaryJsonExport,
+
+            // dictionary/revisions.rs
+            dictionary::revisions::WordRevision,
+            dictionary::revisions::WordRevisionsResponse,
+            dictionary::revisions::WordRevisionField,
+            dictionary::revisions::WordRevisionFieldChange,
+            dictionary::revisions::WordRevisionDiffResponse,
+            dictionary::revisions::WordRevisionRevertResponse,
         ),
     ),
     info(
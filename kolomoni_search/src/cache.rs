@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use kolomoni_database::{
     entities,
-    query::{ExpandedEnglishWordInfo, ExpandedSloveneWordInfo},
+    query::{EnglishWordLink, ExpandedEnglishWordInfo, ExpandedSloveneWordInfo, SloveneWordLink},
+    shared::WordLinkType,
 };
 use slotmap::{new_key_type, SlotMap};
 use uuid::Uuid;
@@ -28,13 +29,16 @@ pub struct CachedEnglishWord {
 
     /// The translations linked to this word.
     translations: Vec<SloveneWordSlotMapKey>,
+
+    /// Other english words this word is linked to (e.g. synonyms).
+    links: Vec<(WordLinkType, EnglishWordSlotMapKey)>,
 }
 
 impl CachedEnglishWord {
     /// Obtain a [`CachedEnglishWord`] given an [`ExpandedEnglishWordInfo`]
     /// (which you can get by fetching info from the database) and access to the indexer cache.
     ///
-    /// `None` can be returned if the word has invalid links to slovene words or categories
+    /// `None` can be returned if the word has invalid links to english words, slovene words or categories
     /// (i.e. when the linked words/categories don't exist in the cache yet).
     pub fn from_expanded_database_info(
         expanded_info: ExpandedEnglishWordInfo,
@@ -73,12 +77,23 @@ impl CachedEnglishWord {
             translation_keys.push(translation_slot_map_key);
         }
 
+        let mut link_keys = Vec::with_capacity(expanded_info.links.len());
+        for link in expanded_info.links {
+            let Some(linked_word_slot_map_key) = cache.english_word_key(link.linked_word.word_id)
+            else {
+                return None;
+            };
+
+            link_keys.push((link.link_type, linked_word_slot_map_key));
+        }
+
 
         Some(Self {
             word: expanded_info.word,
             categories: category_keys,
             suggested_translations: suggested_translation_keys,
             translations: translation_keys,
+            links: link_keys,
         })
     }
 
@@ -90,7 +105,7 @@ impl CachedEnglishWord {
     /// Convert this [`CachedEnglishWord`] back into an [`ExpandedEnglishWordInfo`].
     ///
     /// This will require access to the slot maps of the indexer cache
-    /// (which we need to convert weak slovene word / category references back into their explicit form).
+    /// (which we need to convert weak english word / slovene word / category references back into their explicit form).
     fn into_expanded_word_info(
         self,
         slot_context: &EntitySlotMapContext,
@@ -143,11 +158,28 @@ impl CachedEnglishWord {
         }
 
 
+        // Links to words that have since been removed from the cache are skipped
+        // (the word itself will be refreshed once its links are changed in the database).
+        let links = self
+            .links
+            .into_iter()
+            .filter_map(|(link_type, linked_word_key)| {
+                let linked_word = slot_context.english_word_slot_map.get(linked_word_key)?;
+
+                Some(EnglishWordLink {
+                    link_type,
+                    linked_word: linked_word.word.clone(),
+                })
+            })
+            .collect();
+
+
         Some(ExpandedEnglishWordInfo {
             word: self.word,
             categories,
             suggested_translations,
             translations,
+            links,
         })
     }
 }
@@ -160,14 +192,17 @@ pub struct CachedSloveneWord {
     pub word: entities::word_slovene::Model,
 
     categories: Vec<CategorySlotMapKey>,
+
+    /// Other slovene words this word is linked to (e.g. synonyms).
+    links: Vec<(WordLinkType, SloveneWordSlotMapKey)>,
 }
 
 impl CachedSloveneWord {
     /// Obtain a [`CachedSloveneWord`] given an [`ExpandedSloveneWordInfo`]
     /// (which you can get by fetching info from the database) and access to the indexer cache.
     ///
-    /// `None` can be returned if the word has invalid links to categories
-    /// (i.e. when the categories don't exist in the cache yet).
+    /// `None` can be returned if the word has invalid links to slovene words or categories
+    /// (i.e. when the linked words/categories don't exist in the cache yet).
     pub fn from_expanded_database_info(
         expanded_info: ExpandedSloveneWordInfo,
         cache: &KolomoniEntityCache,
@@ -182,10 +217,21 @@ impl CachedSloveneWord {
             category_keys.push(category_slot_map_key);
         }
 
+        let mut link_keys = Vec::with_capacity(expanded_info.links.len());
+        for link in expanded_info.links {
+            let Some(linked_word_slot_map_key) = cache.slovene_word_key(link.linked_word.word_id)
+            else {
+                return None;
+            };
+
+            link_keys.push((link.link_type, linked_word_slot_map_key));
+        }
+
 
         Some(Self {
             word: expanded_info.word,
             categories: category_keys,
+            links: link_keys,
         })
     }
 
@@ -197,7 +243,7 @@ impl CachedSloveneWord {
     /// Convert this [`CachedSloveneWord`] back into an [`ExpandedSloveneWordInfo`].
     ///
     /// This will require access to the slot maps of the indexer cache
-    /// (which we need to convert weak category references back into their explicit form).
+    /// (which we need to convert weak slovene word / category references back into their explicit form).
     fn into_expanded_word_info(
         self,
         slot_context: &EntitySlotMapContext,
@@ -212,9 +258,26 @@ impl CachedSloveneWord {
         }
 
 
+        // Links to words that have since been removed from the cache are skipped
+        // (the word itself will be refreshed once its links are changed in the database).
+        let links = self
+            .links
+            .into_iter()
+            .filter_map(|(link_type, linked_word_key)| {
+                let linked_word = slot_context.slovene_word_slot_map.get(linked_word_key)?;
+
+                Some(SloveneWordLink {
+                    link_type,
+                    linked_word: linked_word.word.clone(),
+                })
+            })
+            .collect();
+
+
         Some(ExpandedSloveneWordInfo {
             word: self.word,
             categories,
+            links,
         })
     }
 }
@@ -247,7 +310,6 @@ impl CachedCategory {
 ///
 /// This is an implementation detail and not visible externally.
 struct EntitySlotMapContext<'e, 's, 'c> {
-    #[allow(dead_code)]
     english_word_slot_map: &'e SlotMap<EnglishWordSlotMapKey, CachedEnglishWord>,
 
     slovene_word_slot_map: &'s SlotMap<SloveneWordSlotMapKey, CachedSloveneWord>,
@@ -266,12 +328,21 @@ struct EntitySlotMapContext<'e, 's, 'c> {
 /// that are linked as translations instead of holding their entire information in themselves, improving memory usage.
 /// Another upside to this approach is that modifying a slovene word is immediately reflected in all english words
 /// it is a translation of, simplifying word updates.
+///
+/// Links between words of the same language (e.g. synonyms) are held the same way. Because words
+/// can link to each other, a linked word may not be present in the cache yet when a word is inserted;
+/// in that case, insert the words with empty `links` first and then insert them again with their links.
+///
+/// Several words of the same language can share a lemma (homonyms, distinguished by their disambiguation),
+/// which is why words are also tracked by lemma (see e.g. [`Self::english_words_by_lemma`]).
 pub struct KolomoniEntityCache {
     english_word_slot_map: SlotMap<EnglishWordSlotMapKey, CachedEnglishWord>,
     english_word_uuid_to_key_map: HashMap<Uuid, EnglishWordSlotMapKey>,
+    english_word_lemma_to_keys_map: HashMap<String, Vec<EnglishWordSlotMapKey>>,
 
     slovene_word_slot_map: SlotMap<SloveneWordSlotMapKey, CachedSloveneWord>,
     slovene_word_uuid_to_key_map: HashMap<Uuid, SloveneWordSlotMapKey>,
+    slovene_word_lemma_to_keys_map: HashMap<String, Vec<SloveneWordSlotMapKey>>,
 
     category_slot_map: SlotMap<CategorySlotMapKey, CachedCategory>,
     category_id_to_key_map: HashMap<i32, CategorySlotMapKey>,
@@ -282,9 +353,11 @@ impl KolomoniEntityCache {
     pub fn new() -> Self {
         let english_word_slot_map = SlotMap::<EnglishWordSlotMapKey, CachedEnglishWord>::with_key();
         let english_word_uuid_to_key_map = HashMap::new();
+        let english_word_lemma_to_keys_map = HashMap::new();
 
         let slovene_word_slot_map = SlotMap::<SloveneWordSlotMapKey, CachedSloveneWord>::with_key();
         let slovene_word_uuid_to_key_map = HashMap::new();
+        let slovene_word_lemma_to_keys_map = HashMap::new();
 
         let category_slot_map = SlotMap::<CategorySlotMapKey, CachedCategory>::with_key();
         let category_id_to_key_map = HashMap::new();
@@ -293,8 +366,10 @@ impl KolomoniEntityCache {
         Self {
             english_word_slot_map,
             english_word_uuid_to_key_map,
+            english_word_lemma_to_keys_map,
             slovene_word_slot_map,
             slovene_word_uuid_to_key_map,
+            slovene_word_lemma_to_keys_map,
             category_slot_map,
             category_id_to_key_map,
         }
@@ -304,9 +379,11 @@ impl KolomoniEntityCache {
     pub fn clear(&mut self) {
         self.english_word_slot_map.clear();
         self.english_word_uuid_to_key_map.clear();
+        self.english_word_lemma_to_keys_map.clear();
 
         self.slovene_word_slot_map.clear();
         self.slovene_word_uuid_to_key_map.clear();
+        self.slovene_word_lemma_to_keys_map.clear();
 
         self.category_slot_map.clear();
         self.category_id_to_key_map.clear();
@@ -343,8 +420,25 @@ impl KolomoniEntityCache {
             .into_expanded_word_info(&self.slot_context())
     }
 
+    /// Obtain all english words with the given lemma (i.e. the word and its homonyms)
+    /// that are present in the cache and contain valid connections.
+    pub fn english_words_by_lemma(&self, lemma: &str) -> Vec<ExpandedEnglishWordInfo> {
+        let Some(english_word_slot_map_keys) = self.english_word_lemma_to_keys_map.get(lemma) else {
+            return Vec::new();
+        };
+
+        english_word_slot_map_keys
+            .iter()
+            .filter_map(|key| self.english_word_slot_map.get(*key))
+            .filter_map(|cached_english_word| {
+                cached_english_word
+                    .clone()
+                    .into_expanded_word_info(&self.slot_context())
+            })
+            .collect()
+    }
+
     /// Obtain an [`EnglishWordSlotMapKey`] slot map key given its [`Uuid`], if present in the cache.
-    #[allow(dead_code)]
     fn english_word_key(&self, word_uuid: Uuid) -> Option<EnglishWordSlotMapKey> {
         self.english_word_uuid_to_key_map.get(&word_uuid).copied()
     }
@@ -371,15 +465,35 @@ impl KolomoniEntityCache {
                 );
             };
 
+            if existing_word_entry.word.lemma != english_word.word.lemma {
+                remove_key_from_lemma_map(
+                    &mut self.english_word_lemma_to_keys_map,
+                    &existing_word_entry.word.lemma,
+                    *existing_slot_map_key,
+                );
+
+                self.english_word_lemma_to_keys_map
+                    .entry(english_word.word.lemma.clone())
+                    .or_default()
+                    .push(*existing_slot_map_key);
+            }
+
             *existing_word_entry = english_word;
             return;
         }
 
 
-        // Insert a fresh word (don't forget to update `english_word_uuid_to_key_map`)!
+        // Insert a fresh word (don't forget to update `english_word_uuid_to_key_map`
+        // and `english_word_lemma_to_keys_map`)!
+
+        let lemma = english_word.word.lemma.clone();
 
         let new_key = self.english_word_slot_map.insert(english_word);
         self.english_word_uuid_to_key_map.insert(word_uuid, new_key);
+        self.english_word_lemma_to_keys_map
+            .entry(lemma)
+            .or_default()
+            .push(new_key);
     }
 
     /// Remove an english word from the entity cache.
@@ -391,7 +505,7 @@ impl KolomoniEntityCache {
             return Err(());
         };
 
-        let Some(_) = self
+        let Some(removed_english_word) = self
             .english_word_slot_map
             .remove(*english_word_slot_map_key)
         else {
@@ -401,6 +515,12 @@ impl KolomoniEntityCache {
             )
         };
 
+        remove_key_from_lemma_map(
+            &mut self.english_word_lemma_to_keys_map,
+            &removed_english_word.word.lemma,
+            *english_word_slot_map_key,
+        );
+
         self.english_word_uuid_to_key_map.remove(&word_uuid);
 
         Ok(())
@@ -428,6 +548,24 @@ impl KolomoniEntityCache {
             .into_expanded_word_info(&self.slot_context())
     }
 
+    /// Obtain all slovene words with the given lemma (i.e. the word and its homonyms)
+    /// that are present in the cache and contain valid connections.
+    pub fn slovene_words_by_lemma(&self, lemma: &str) -> Vec<ExpandedSloveneWordInfo> {
+        let Some(slovene_word_slot_map_keys) = self.slovene_word_lemma_to_keys_map.get(lemma) else {
+            return Vec::new();
+        };
+
+        slovene_word_slot_map_keys
+            .iter()
+            .filter_map(|key| self.slovene_word_slot_map.get(*key))
+            .filter_map(|cached_slovene_word| {
+                cached_slovene_word
+                    .clone()
+                    .into_expanded_word_info(&self.slot_context())
+            })
+            .collect()
+    }
+
     /// Obtain an [`SloveneWordSlotMapKey`] slot map key given its [`Uuid`], if present in the cache.
     fn slovene_word_key(&self, word_uuid: Uuid) -> Option<SloveneWordSlotMapKey> {
         self.slovene_word_uuid_to_key_map.get(&word_uuid).copied()
@@ -455,15 +593,35 @@ impl KolomoniEntityCache {
                 );
             };
 
+            if existing_word_entry.word.lemma != slovene_word.word.lemma {
+                remove_key_from_lemma_map(
+                    &mut self.slovene_word_lemma_to_keys_map,
+                    &existing_word_entry.word.lemma,
+                    *existing_slot_map_key,
+                );
+
+                self.slovene_word_lemma_to_keys_map
+                    .entry(slovene_word.word.lemma.clone())
+                    .or_default()
+                    .push(*existing_slot_map_key);
+            }
+
             *existing_word_entry = slovene_word;
             return;
         }
 
 
-        // Insert a fresh word (don't forget to update `slovene_word_uuid_to_key_map`)!
+        // Insert a fresh word (don't forget to update `slovene_word_uuid_to_key_map`
+        // and `slovene_word_lemma_to_keys_map`)!
+
+        let lemma = slovene_word.word.lemma.clone();
 
         let new_key = self.slovene_word_slot_map.insert(slovene_word);
         self.slovene_word_uuid_to_key_map.insert(word_uuid, new_key);
+        self.slovene_word_lemma_to_keys_map
+            .entry(lemma)
+            .or_default()
+            .push(new_key);
     }
 
     /// Remove a slovene word from the entity cache.
@@ -475,7 +633,7 @@ impl KolomoniEntityCache {
             return Err(());
         };
 
-        let Some(_) = self
+        let Some(removed_slovene_word) = self
             .slovene_word_slot_map
             .remove(*slovene_word_slot_map_key)
         else {
@@ -485,6 +643,12 @@ impl KolomoniEntityCache {
             )
         };
 
+        remove_key_from_lemma_map(
+            &mut self.slovene_word_lemma_to_keys_map,
+            &removed_slovene_word.word.lemma,
+            *slovene_word_slot_map_key,
+        );
+
         self.slovene_word_uuid_to_key_map.remove(&word_uuid);
 
         Ok(())
@@ -565,3 +729,22 @@ impl KolomoniEntityCache {
         Ok(())
     }
 }
+
+
+/// Removes a slot map key from the list of keys belonging to the given lemma
+/// (and the list itself, if it becomes empty).
+fn remove_key_from_lemma_map<K: PartialEq>(
+    lemma_to_keys_map: &mut HashMap<String, Vec<K>>,
+    lemma: &str,
+    key: K,
+) {
+    let Some(keys) = lemma_to_keys_map.get_mut(lemma) else {
+        return;
+    };
+
+    keys.retain(|existing_key| *existing_key != key);
+
+    if keys.is_empty() {
+        lemma_to_keys_map.remove(lemma);
+    }
+}
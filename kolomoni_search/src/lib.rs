@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
 use cache::{CachedCategory, CachedEnglishWord, CachedSloveneWord, KolomoniEntityCache};
 use chrono::{DateTime, Utc};
@@ -771,6 +771,9 @@ impl KolomoniSearchEngine {
 
     /// Returns matching english and slovene words for the given search query.
     ///
+    /// Each matching word is returned along with its homonyms (words of the same language
+    /// with the same lemma), and each word is returned only once.
+    ///
     /// Does not perform any database lookups, and instead relies on the index and cache being up-to-date.
     pub async fn search(&self, word_search_query: &str) -> Result<SearchResults> {
         let inner = self.inner.read().await;
@@ -795,6 +798,8 @@ impl KolomoniSearchEngine {
 
 
         let mut resulting_words = Vec::new();
+        let mut resulting_word_uuids = HashSet::new();
+
         for (_score, doc_address) in search_results {
             let document = searcher
                 .doc(doc_address)
@@ -841,25 +846,46 @@ impl KolomoniSearchEngine {
 
 
 
-            let matching_word = match word_language {
-                IndexedWordLanguage::Slovene => inner
-                    .cache
-                    .slovene_word(word_uuid)
-                    .map(SearchResult::Slovene),
-                IndexedWordLanguage::English => inner
-                    .cache
-                    .english_word(word_uuid)
-                    .map(SearchResult::English),
+            if resulting_word_uuids.contains(&word_uuid) {
+                // Already included, e.g. as a homonym of an earlier result.
+                continue;
+            }
+
+
+            // The matching word is looked up along with its homonyms, which all share the lemma
+            // (looking the word up by its lemma also includes the word itself).
+            let matching_words = match word_language {
+                IndexedWordLanguage::Slovene => inner.cache.slovene_word(word_uuid).map(|word| {
+                    inner
+                        .cache
+                        .slovene_words_by_lemma(&word.word.lemma)
+                        .into_iter()
+                        .map(|homonym| (homonym.word.word_id, SearchResult::Slovene(homonym)))
+                        .collect::<Vec<_>>()
+                }),
+                IndexedWordLanguage::English => inner.cache.english_word(word_uuid).map(|word| {
+                    inner
+                        .cache
+                        .english_words_by_lemma(&word.word.lemma)
+                        .into_iter()
+                        .map(|homonym| (homonym.word.word_id, SearchResult::English(homonym)))
+                        .collect::<Vec<_>>()
+                }),
             };
 
-            if let Some(matching_word) = matching_word {
-                resulting_words.push(matching_word);
-            } else {
+            let Some(matching_words) = matching_words else {
                 warn!(
                     word_uuid = %word_uuid,
                     word_language = ?word_language,
                     "Failed to look up word in search cache."
                 );
+                continue;
+            };
+
+            for (matching_word_uuid, matching_word) in matching_words {
+                if resulting_word_uuids.insert(matching_word_uuid) {
+                    resulting_words.push(matching_word);
+                }
             }
         }
 
@@ -941,6 +967,42 @@ impl KolomoniSearchEngine {
         }
 
 
+        // Words can be linked to other words of the same language, which might not be in the cache yet.
+        // This is why all words are first inserted without their links, and then overwritten
+        // with their full information below (at which point all linked words are already present).
+        debug!("Inserting words into cache without their links.");
+
+        for slovene_word_info in &all_slovene_words {
+            let slovene_word_info_without_links = ExpandedSloveneWordInfo {
+                links: Vec::new(),
+                ..slovene_word_info.clone()
+            };
+
+            let cached_word_entry = CachedSloveneWord::from_expanded_database_info(
+                slovene_word_info_without_links,
+                &inner.cache,
+            )
+            .expect("failed to convert expanded slovene word info into a cached word");
+
+            inner.cache.insert_or_update_slovene_word(cached_word_entry);
+        }
+
+        for english_word_info in &all_english_words {
+            let english_word_info_without_links = ExpandedEnglishWordInfo {
+                links: Vec::new(),
+                ..english_word_info.clone()
+            };
+
+            let cached_word_entry = CachedEnglishWord::from_expanded_database_info(
+                english_word_info_without_links,
+                &inner.cache,
+            )
+            .expect("failed to convert expanded english word info into a cached word");
+
+            inner.cache.insert_or_update_english_word(cached_word_entry);
+        }
+
+
         debug!(
             "Inserting {} slovene words into cache and index.",
             all_slovene_words_count
@@ -950,9 +1012,6 @@ impl KolomoniSearchEngine {
             let slovene_word = slovene_word_info.word.clone();
             let slovene_language_index = IndexedWordLanguage::Slovene.id();
 
-            // TODO If (or when) the database will start to contain more complex references
-            //      (e.g. english words linked to other english words), we'll need to modify this approach:
-            //      we'll need weak links between entries, allowing us to insert an entity whose related entities aren't present in the cache yet.
             let cached_word_entry =
                 CachedSloveneWord::from_expanded_database_info(slovene_word_info, &inner.cache)
                     .expect("failed to convert expanded slovene word info into a cached word");
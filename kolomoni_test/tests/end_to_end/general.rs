@@ -10,3 +10,20 @@ async fn server_can_be_pinged() {
     response.assert_status_equals(StatusCode::OK);
     response.assert_json_body_matches(PingResponse { ok: true });
 }
+
+
+#[tokio::test]
+async fn json_web_key_set_is_published() {
+    let server = initialize_test_server().await;
+
+    let response = server
+        .request(Method::GET, "/.well-known/jwks.json")
+        .send()
+        .await;
+
+    response.assert_status_equals(StatusCode::OK);
+
+    let key_set = response.json_body::<JwkSet>();
+    assert_eq!(key_set.keys.len(), 1);
+    assert!(key_set.find("testing").is_some());
+}
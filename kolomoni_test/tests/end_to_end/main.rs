@@ -1,3 +1,4 @@
 mod general;
+mod roles;
 mod user;
 mod words;
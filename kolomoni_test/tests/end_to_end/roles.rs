@@ -0,0 +1,583 @@
+use std::{collections::HashSet, time::Duration};
+
+use chrono::Utc;
+use kolomoni::api::{
+    errors::ErrorReasonResponse,
+    macros::construct_last_modified_header_value,
+    v1::{
+        permissions::PermissionsResponse,
+        roles::{RoleCreationRequest, RoleInfoResponse, RoleUpdateRequest, RolesResponse},
+        users::{specific::UserRoleAddRequest, UserPermissionsResponse, UserRolesResponse},
+    },
+};
+use kolomoni_test_util::prelude::*;
+
+
+
+async fn fetch_user_role_names(
+    server: &TestServer,
+    access_token: &str,
+    user_id: i32,
+) -> HashSet<String> {
+    let response = server
+        .request(
+            Method::GET,
+            format!("/api/v1/users/{}/roles", user_id),
+        )
+        .with_access_token(access_token)
+        .send()
+        .await;
+
+    response.assert_status_equals(StatusCode::OK);
+
+    response
+        .json_body::<UserRolesResponse>()
+        .role_names
+        .into_iter()
+        .collect()
+}
+
+async fn fetch_user_permission_names(
+    server: &TestServer,
+    access_token: &str,
+    user_id: i32,
+) -> HashSet<String> {
+    let response = server
+        .request(
+            Method::GET,
+            format!("/api/v1/users/{}/permissions", user_id),
+        )
+        .with_access_token(access_token)
+        .send()
+        .await;
+
+    response.assert_status_equals(StatusCode::OK);
+
+    response
+        .json_body::<UserPermissionsResponse>()
+        .permissions
+        .into_iter()
+        .collect()
+}
+
+
+
+#[tokio::test]
+async fn role_management_works() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    let meta_user_info = SampleUser::Meta.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+    let meta_access_token = SampleUser::Meta.login(&server).await;
+
+
+    // Normal users can't create roles.
+    server
+        .request(Method::POST, "/api/v1/roles")
+        .with_access_token(&meta_access_token)
+        .with_json_body(RoleCreationRequest {
+            name: "translator".to_string(),
+            description: "Translates words.".to_string(),
+            permissions: vec![],
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+
+    // Invalid roles are rejected.
+    {
+        let unknown_permission_response = server
+            .request(Method::POST, "/api/v1/roles")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleCreationRequest {
+                name: "translator".to_string(),
+                description: "Translates words.".to_string(),
+                permissions: vec!["word:fly".to_string()],
+            })
+            .send()
+            .await;
+
+        unknown_permission_response.assert_status_equals(StatusCode::BAD_REQUEST);
+        unknown_permission_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "No such permission: \"word:fly\".",
+        ));
+
+        server
+            .request(Method::POST, "/api/v1/roles")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleCreationRequest {
+                name: "   ".to_string(),
+                description: "Translates words.".to_string(),
+                permissions: vec![],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(Method::POST, "/api/v1/roles")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleCreationRequest {
+                name: "administrator".to_string(),
+                description: "Another administrator role.".to_string(),
+                permissions: vec![],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::CONFLICT);
+    }
+
+
+    let translator_role = {
+        let response = server
+            .request(Method::POST, "/api/v1/roles")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleCreationRequest {
+                name: "translator".to_string(),
+                description: "Translates words.".to_string(),
+                permissions: vec![
+                    "word.translation:delete".to_string(),
+                    "word.translation:create".to_string(),
+                    "word.translation:create".to_string(),
+                ],
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let role = response.json_body::<RoleInfoResponse>().role;
+        assert_eq!(role.name, "translator");
+        assert_eq!(role.description, "Translates words.");
+        assert_eq!(
+            role.permissions,
+            vec![
+                "word.translation:create".to_string(),
+                "word.translation:delete".to_string()
+            ]
+        );
+
+        role
+    };
+
+
+    // Users with a custom role get its permissions.
+    {
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/roles", meta_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserRoleAddRequest {
+                roles_to_add: vec!["translator".to_string()],
+                expires_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let meta_permissions =
+            fetch_user_permission_names(&server, &janez_access_token, meta_user_info.id).await;
+
+        assert!(meta_permissions.contains("word.translation:create"));
+        assert!(meta_permissions.contains("word.translation:delete"));
+    }
+
+
+    // Renaming a role and changing its permissions applies to users that have it.
+    {
+        let response = server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/roles/{}", translator_role.id),
+            )
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleUpdateRequest {
+                name: Some("prevajalec".to_string()),
+                description: None,
+                permissions: Some(vec!["word.translation:create".to_string()]),
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let updated_role = response.json_body::<RoleInfoResponse>().role;
+        assert_eq!(updated_role.id, translator_role.id);
+        assert_eq!(updated_role.name, "prevajalec");
+        assert_eq!(updated_role.description, "Translates words.");
+        assert_eq!(
+            updated_role.permissions,
+            vec!["word.translation:create".to_string()]
+        );
+
+
+        let meta_roles =
+            fetch_user_role_names(&server, &janez_access_token, meta_user_info.id).await;
+
+        assert_eq!(
+            meta_roles,
+            HashSet::from(["user".to_string(), "prevajalec".to_string()])
+        );
+
+        let meta_permissions =
+            fetch_user_permission_names(&server, &janez_access_token, meta_user_info.id).await;
+
+        assert!(meta_permissions.contains("word.translation:create"));
+        assert!(!meta_permissions.contains("word.translation:delete"));
+    }
+
+
+    // Built-in roles can't be renamed or deleted, and missing roles can't be updated.
+    {
+        server
+            .request(
+                Method::PATCH,
+                format!(
+                    "/api/v1/roles/{}",
+                    BuiltinRole::Administrator.id()
+                ),
+            )
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleUpdateRequest {
+                name: Some("admin".to_string()),
+                description: None,
+                permissions: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/roles/{}", DEFAULT_USER_ROLE.id()),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::PATCH, "/api/v1/roles/4815")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleUpdateRequest {
+                name: None,
+                description: Some("Nothing to see here.".to_string()),
+                permissions: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+
+    // Deleting a role removes it from users.
+    {
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/roles/{}", translator_role.id),
+            )
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/roles/{}", translator_role.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/roles/{}", translator_role.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+
+        let meta_roles =
+            fetch_user_role_names(&server, &janez_access_token, meta_user_info.id).await;
+
+        assert_eq!(meta_roles, HashSet::from(["user".to_string()]));
+    }
+}
+
+
+
+
+#[tokio::test]
+async fn time_limited_role_grants_expire() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    let meta_user_info = SampleUser::Meta.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+
+
+    // Expiration times in the past are rejected.
+    {
+        let response = server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/roles", meta_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserRoleAddRequest {
+                roles_to_add: vec!["administrator".to_string()],
+                expires_at: Some(Utc::now() - Duration::from_secs(10)),
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::BAD_REQUEST);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "The expiration time must be in the future.",
+        ));
+    }
+
+
+    // A time-limited role applies until it expires.
+    server
+        .request(
+            Method::POST,
+            format!("/api/v1/users/{}/roles", meta_user_info.id),
+        )
+        .with_access_token(&janez_access_token)
+        .with_json_body(UserRoleAddRequest {
+            roles_to_add: vec!["administrator".to_string()],
+            expires_at: Some(Utc::now() + Duration::from_secs(2)),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    assert_eq!(
+        fetch_user_role_names(&server, &janez_access_token, meta_user_info.id).await,
+        HashSet::from(["user".to_string(), "administrator".to_string()])
+    );
+    assert!(
+        fetch_user_permission_names(&server, &janez_access_token, meta_user_info.id)
+            .await
+            .contains("user.any:write")
+    );
+
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+
+    assert_eq!(
+        fetch_user_role_names(&server, &janez_access_token, meta_user_info.id).await,
+        HashSet::from(["user".to_string()])
+    );
+    assert!(
+        !fetch_user_permission_names(&server, &janez_access_token, meta_user_info.id)
+            .await
+            .contains("user.any:write")
+    );
+
+
+    // An expired role can be granted again.
+    server
+        .request(
+            Method::POST,
+            format!("/api/v1/users/{}/roles", meta_user_info.id),
+        )
+        .with_access_token(&janez_access_token)
+        .with_json_body(UserRoleAddRequest {
+            roles_to_add: vec!["administrator".to_string()],
+            expires_at: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    assert_eq!(
+        fetch_user_role_names(&server, &janez_access_token, meta_user_info.id).await,
+        HashSet::from(["user".to_string(), "administrator".to_string()])
+    );
+}
+
+
+
+#[tokio::test]
+async fn role_and_permission_listing_works() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+
+
+    // All permissions are listed along with their descriptions, even without authentication.
+    {
+        let response = server
+            .request(Method::GET, "/api/v1/permissions")
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+        response.assert_header_exists(header::LAST_MODIFIED);
+
+        let permissions = response.json_body::<PermissionsResponse>().permissions;
+        assert_eq!(permissions.len(), Permission::all().len());
+
+        for (permission_info, permission) in permissions.iter().zip(Permission::all()) {
+            assert_eq!(permission_info.name, permission.name());
+            assert_eq!(
+                permission_info.description,
+                permission.description()
+            );
+        }
+
+        server
+            .request(Method::GET, "/api/v1/permissions")
+            .with_header(
+                header::IF_MODIFIED_SINCE,
+                construct_last_modified_header_value(&(Utc::now() + Duration::from_secs(1)))
+                    .unwrap(),
+            )
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_MODIFIED);
+    }
+
+
+    // Built-in roles are listed along with their permissions.
+    {
+        let response = server.request(Method::GET, "/api/v1/roles").send().await;
+
+        response.assert_status_equals(StatusCode::OK);
+        response.assert_header_exists(header::LAST_MODIFIED);
+
+        let roles = response.json_body::<RolesResponse>().roles;
+        assert_eq!(roles.len(), 2);
+
+        assert_eq!(roles[0].id, BuiltinRole::User.id());
+        assert_eq!(roles[0].name, BuiltinRole::User.name());
+        assert_eq!(
+            roles[0]
+                .permissions
+                .iter()
+                .map(|permission_name| Permission::from_name(permission_name).unwrap())
+                .collect::<HashSet<_>>(),
+            HashSet::from([
+                Permission::UserSelfRead,
+                Permission::UserSelfWrite,
+                Permission::UserAnyRead,
+                Permission::WordRead,
+                Permission::SuggestionCreate,
+            ])
+        );
+
+        assert_eq!(roles[1].id, BuiltinRole::Administrator.id());
+        assert_eq!(roles[1].name, BuiltinRole::Administrator.name());
+    }
+
+
+    // The role list is cached until a role is changed.
+    {
+        let time_before_change = Utc::now();
+
+        server
+            .request(Method::GET, "/api/v1/roles")
+            .with_header(
+                header::IF_MODIFIED_SINCE,
+                construct_last_modified_header_value(&time_before_change).unwrap(),
+            )
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_MODIFIED);
+
+        // `Last-Modified` only has a precision of one second.
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        server
+            .request(Method::POST, "/api/v1/roles")
+            .with_access_token(&janez_access_token)
+            .with_json_body(RoleCreationRequest {
+                name: "translator".to_string(),
+                description: "Translates words.".to_string(),
+                permissions: vec!["word.translation:create".to_string()],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let response = server
+            .request(Method::GET, "/api/v1/roles")
+            .with_header(
+                header::IF_MODIFIED_SINCE,
+                construct_last_modified_header_value(&time_before_change).unwrap(),
+            )
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let roles = response.json_body::<RolesResponse>().roles;
+        assert_eq!(roles.len(), 3);
+        assert_eq!(roles[2].name, "translator");
+        assert_eq!(roles[2].description, "Translates words.");
+        assert_eq!(
+            roles[2].permissions,
+            vec!["word.translation:create".to_string()]
+        );
+
+
+        // Deleting a role also counts as a change.
+        let time_before_deletion = Utc::now();
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/roles/{}", roles[2].id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let response = server
+            .request(Method::GET, "/api/v1/roles")
+            .with_header(
+                header::IF_MODIFIED_SINCE,
+                construct_last_modified_header_value(&time_before_deletion).unwrap(),
+            )
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+        assert_eq!(
+            response.json_body::<RolesResponse>().roles.len(),
+            2
+        );
+    }
+}
@@ -5,22 +5,73 @@ use kolomoni::api::{
     errors::ErrorReasonResponse,
     macros::construct_last_modified_header_value,
     v1::{
-        login::{UserLoginRequest, UserLoginResponse},
+        dictionary::revisions::WordRevisionsResponse,
+        login::{
+            oidc::{OidcAuthorizationResponse, OidcCallbackRequest},
+            two_factor::{TwoFactorChallengeResponse, TwoFactorLoginRequest},
+            LoginLockoutsResponse,
+            UserLoginRefreshRequest,
+            UserLoginRefreshResponse,
+            UserLoginRequest,
+            UserLoginResponse,
+        },
         users::{
-            all::RegisteredUsersListResponse,
+            all::{
+                RegisteredUsersFilters,
+                RegisteredUsersListRequest,
+                RegisteredUsersListResponse,
+                RegisteredUsersSortDirection,
+                RegisteredUsersSortField,
+            },
+            api_tokens::{
+                UserApiTokenCreationRequest,
+                UserApiTokenCreationResponse,
+                UserApiTokensResponse,
+            },
+            invites::{UserInviteCreationRequest, UserInviteResponse, UserInvitesResponse},
             registration::{UserRegistrationRequest, UserRegistrationResponse},
             specific::{UserRoleAddRequest, UserRoleRemoveRequest},
+            suspensions::{
+                UserSuspensionCreationRequest,
+                UserSuspensionResponse,
+                UserSuspensionsResponse,
+            },
+            two_factor::{
+                TwoFactorConfirmationRequest,
+                TwoFactorEnrollmentResponse,
+                TwoFactorRecoveryCodesResponse,
+                TwoFactorStatusResponse,
+            },
+            UserAccountDeletionRequest,
             UserDisplayNameChangeRequest,
             UserDisplayNameChangeResponse,
             UserInfoResponse,
+            UserInformation,
+            UserPasswordChangeRequest,
             UserPermissionsResponse,
             UserRolesResponse,
+            UserSessionsResponse,
         },
     },
 };
-use kolomoni_test_util::prelude::*;
+use kolomoni_test_util::{
+    mock_oidc::{MockOidcProvider, MockOidcRedirect, MockOidcUser},
+    prelude::*,
+    TestResponse,
+};
 
 
+/// Permissions granted by the built-in `user` role, as seeded by the migrations.
+fn seeded_user_role_permissions() -> HashSet<Permission> {
+    HashSet::from([
+        Permission::UserSelfRead,
+        Permission::UserSelfWrite,
+        Permission::UserAnyRead,
+        Permission::WordRead,
+        Permission::SuggestionCreate,
+    ])
+}
+
 
 #[tokio::test]
 async fn user_registration_and_user_list_work() {
@@ -33,6 +84,7 @@ async fn user_registration_and_user_list_work() {
         username: "janez".to_string(),
         display_name: "Janez Veliki".to_string(),
         password: "janez".to_string(),
+        invite_code: None,
     };
 
     let new_user_info = {
@@ -184,6 +236,7 @@ async fn user_registration_and_user_list_work() {
                 username: "meta".to_string(),
                 display_name: "Meta".to_string(),
                 password: "meta".to_string(),
+                invite_code: None,
             })
             .send()
             .await
@@ -207,6 +260,249 @@ async fn user_registration_and_user_list_work() {
 
 
 
+#[tokio::test]
+async fn user_list_pagination_filtering_and_sorting_work() {
+    let server = initialize_test_server().await;
+
+    let time_before_registration = Utc::now();
+
+    SampleUser::Janez.register(&server).await;
+    SampleUser::Meta.register(&server).await;
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Meta.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    let fetch_user_page = |request: RegisteredUsersListRequest| {
+        let server = &server;
+        let admin_user_access_token = &admin_user_access_token;
+
+        async move {
+            let response = server
+                .request(Method::GET, "/api/v1/users")
+                .with_access_token(admin_user_access_token)
+                .with_json_body(request)
+                .send()
+                .await;
+
+            response.assert_status_equals(StatusCode::OK);
+            response.json_body::<RegisteredUsersListResponse>()
+        }
+    };
+
+
+
+    // Paging through the list one user at a time should yield every user exactly once,
+    // in registration order.
+
+    {
+        let mut usernames = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let page = fetch_user_page(RegisteredUsersListRequest {
+                page_size: Some(1),
+                cursor: cursor.clone(),
+                ..Default::default()
+            })
+            .await;
+
+            assert!(page.users.len() <= 1);
+            usernames.extend(page.users.into_iter().map(|user| user.username));
+
+            match page.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        assert_eq!(usernames, vec!["janez", "meta", "kira"]);
+    }
+
+
+    // Sorting by username in descending order.
+
+    {
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            sort_by: RegisteredUsersSortField::Username,
+            sort_direction: RegisteredUsersSortDirection::Descending,
+            page_size: Some(2),
+            ..Default::default()
+        })
+        .await;
+
+        let usernames = page
+            .users
+            .into_iter()
+            .map(|user| user.username)
+            .collect::<Vec<_>>();
+
+        assert_eq!(usernames, vec!["meta", "kira"]);
+        assert!(page.next_cursor.is_some());
+
+
+        // A cursor can't be reused with different sorting options.
+        server
+            .request(Method::GET, "/api/v1/users")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(RegisteredUsersListRequest {
+                cursor: page.next_cursor,
+                ..Default::default()
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    // Searching matches both usernames and display names, case-insensitively.
+
+    {
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            filters: Some(RegisteredUsersFilters {
+                search: Some("MEGLEN".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(page.users.len(), 1);
+        assert_eq!(page.users[0].username, "meta");
+        assert!(page.next_cursor.is_none());
+    }
+
+    {
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            filters: Some(RegisteredUsersFilters {
+                search: Some("%".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        assert!(page.users.is_empty());
+    }
+
+
+    // Filtering by role.
+
+    {
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            filters: Some(RegisteredUsersFilters {
+                role: Some("administrator".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(page.users.len(), 1);
+        assert_eq!(page.users[0].id, admin_user_info.id);
+    }
+
+
+    // Filtering by registration time.
+
+    {
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            filters: Some(RegisteredUsersFilters {
+                joined_after: Some(time_before_registration),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        assert_eq!(page.users.len(), 3);
+
+        let page = fetch_user_page(RegisteredUsersListRequest {
+            filters: Some(RegisteredUsersFilters {
+                joined_before: Some(time_before_registration),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        assert!(page.users.is_empty());
+    }
+
+
+    // Invalid page sizes and cursors should fail with `400 Bad Request`.
+
+    {
+        server
+            .request(Method::GET, "/api/v1/users")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(RegisteredUsersListRequest {
+                page_size: Some(0),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(Method::GET, "/api/v1/users")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(RegisteredUsersListRequest {
+                cursor: Some("not-a-cursor".to_string()),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+}
+
+
+
+#[tokio::test]
+async fn last_activity_time_is_tracked() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Janez.register(&server).await;
+    let access_token = SampleUser::Janez.login(&server).await;
+
+    let initial_user_info = fetch_user_info(&server, &access_token).await;
+
+
+    // Authenticated requests should update the last activity time, but only after
+    // a short delay, since activity is written to the database in batches
+    // (every second in the testing configuration).
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let time_before_activity = Utc::now();
+
+    server
+        .request(Method::GET, "/api/v1/users/me/roles")
+        .with_access_token(&access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+
+    let updated_user_info = fetch_user_info(&server, &access_token).await;
+
+    assert!(updated_user_info.last_active_at >= time_before_activity);
+    assert!(updated_user_info.last_active_at > initial_user_info.last_active_at);
+    assert_eq!(
+        updated_user_info.last_modified_at,
+        initial_user_info.last_modified_at
+    );
+}
+
+
+
 #[tokio::test]
 async fn current_user_permissions_and_roles_work() {
     let server = initialize_test_server().await;
@@ -315,7 +611,7 @@ async fn current_user_permissions_and_roles_work() {
 
         assert_eq!(actual_role_list.role_names.len(), 1);
         assert_eq!(
-            Role::from_name(&actual_role_list.role_names[0]).unwrap(),
+            BuiltinRole::from_name(&actual_role_list.role_names[0]).unwrap(),
             DEFAULT_USER_ROLE
         );
     }
@@ -350,7 +646,7 @@ async fn current_user_permissions_and_roles_work() {
                 .iter()
                 .map(|permission_name| Permission::from_name(permission_name).unwrap())
                 .collect::<HashSet<_>>(),
-            HashSet::from_iter(Role::User.permissions_granted())
+            seeded_user_role_permissions()
         );
     }
 
@@ -436,11 +732,11 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
             .collect::<HashSet<_>>();
 
         let mut expected_admin_user_role_set = HashSet::new();
-        expected_admin_user_role_set.insert(Role::User);
+        expected_admin_user_role_set.insert(BuiltinRole::User);
 
         assert_eq!(admin_user_role_set, expected_admin_user_role_set);
     }
@@ -456,11 +752,11 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
             .collect::<HashSet<_>>();
 
         let mut expected_normal_user_role_set = HashSet::new();
-        expected_normal_user_role_set.insert(Role::User);
+        expected_normal_user_role_set.insert(BuiltinRole::User);
 
         assert_eq!(
             normal_user_role_set,
@@ -485,12 +781,12 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
             .collect::<HashSet<_>>();
 
         let mut expected_admin_user_role_set = HashSet::new();
-        expected_admin_user_role_set.insert(Role::User);
-        expected_admin_user_role_set.insert(Role::Administrator);
+        expected_admin_user_role_set.insert(BuiltinRole::User);
+        expected_admin_user_role_set.insert(BuiltinRole::Administrator);
 
         assert_eq!(admin_user_role_set, expected_admin_user_role_set);
     }
@@ -506,11 +802,11 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
             .collect::<HashSet<_>>();
 
         let mut expected_normal_user_role_set = HashSet::new();
-        expected_normal_user_role_set.insert(Role::User);
+        expected_normal_user_role_set.insert(BuiltinRole::User);
 
         assert_eq!(
             normal_user_role_set,
@@ -583,7 +879,15 @@ async fn specific_user_operations_work() {
 
         let second_janez_user_info = janez_info_response.json_body::<UserInfoResponse>().user;
 
-        assert_eq!(janez_user_info, second_janez_user_info);
+        // The last activity time can change in the meantime, since Janez's
+        // own authenticated requests are recorded in the background.
+        assert_eq!(
+            janez_user_info,
+            UserInformation {
+                last_active_at: janez_user_info.last_active_at,
+                ..second_janez_user_info
+            }
+        );
     }
 
 
@@ -615,7 +919,7 @@ async fn specific_user_operations_work() {
         janez_roles_response.assert_has_json_body::<UserRolesResponse>();
     }
 
-    let janez_roles = {
+    {
         // Individual users' roles should also be accessible *with* authentication.
         let janez_roles_response = server
             .request(
@@ -636,12 +940,7 @@ async fn specific_user_operations_work() {
             janez_role_names,
             vec![DEFAULT_USER_ROLE.name().to_string()]
         );
-
-        janez_role_names
-            .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
-            .collect::<HashSet<_>>()
-    };
+    }
 
 
     {
@@ -679,10 +978,8 @@ async fn specific_user_operations_work() {
             .permissions;
 
 
-        let janez_expected_permission_set = janez_roles
-            .into_iter()
-            .flat_map(|role| role.permissions_granted())
-            .collect::<HashSet<_>>();
+        // Janez only has the default role (see above).
+        let janez_expected_permission_set = seeded_user_role_permissions();
 
         let janez_actual_permission_set = janez_permission_names
             .iter()
@@ -810,7 +1107,8 @@ async fn specific_user_operations_work() {
         server
             .request(Method::POST, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .send()
             .await
@@ -820,7 +1118,8 @@ async fn specific_user_operations_work() {
         server
             .request(Method::POST, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .with_access_token(&normal_user_access_token)
             .send()
@@ -831,7 +1130,8 @@ async fn specific_user_operations_work() {
         server
             .request(Method::POST, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -847,6 +1147,7 @@ async fn specific_user_operations_work() {
             )
             .with_json_body(UserRoleAddRequest {
                 roles_to_add: vec!["non-existent-role-name".to_string()],
+                expires_at: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -862,7 +1163,8 @@ async fn specific_user_operations_work() {
                 format!("/api/v1/users/{}/roles", admin_user_info.id),
             )
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -887,11 +1189,11 @@ async fn specific_user_operations_work() {
                 .json_body::<UserRolesResponse>()
                 .role_names
                 .into_iter()
-                .map(|role_name| Role::from_name(&role_name).unwrap())
-                .collect::<HashSet<Role>>()
+                .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+                .collect::<HashSet<BuiltinRole>>()
         };
 
-        assert!(!previous_roles.contains(&Role::Administrator));
+        assert!(!previous_roles.contains(&BuiltinRole::Administrator));
 
 
         // Our sample user (Meta) has all permissions at the moment, so
@@ -902,7 +1204,8 @@ async fn specific_user_operations_work() {
                 format!("/api/v1/users/{}/roles", janez_user_info.id),
             )
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -915,10 +1218,10 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
-            .collect::<HashSet<Role>>();
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+            .collect::<HashSet<BuiltinRole>>();
 
-        assert!(updated_roles.contains(&Role::Administrator));
+        assert!(updated_roles.contains(&BuiltinRole::Administrator));
     }
 
 
@@ -938,7 +1241,7 @@ async fn specific_user_operations_work() {
         server
             .request(Method::DELETE, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .send()
             .await
@@ -948,7 +1251,7 @@ async fn specific_user_operations_work() {
         server
             .request(Method::DELETE, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .with_access_token(&second_normal_user_access_token)
             .send()
@@ -959,7 +1262,7 @@ async fn specific_user_operations_work() {
         server
             .request(Method::DELETE, "/api/v1/users/238429/roles")
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -988,7 +1291,7 @@ async fn specific_user_operations_work() {
                 format!("/api/v1/users/{}/roles", admin_user_info.id),
             )
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -1013,11 +1316,11 @@ async fn specific_user_operations_work() {
                 .json_body::<UserRolesResponse>()
                 .role_names
                 .into_iter()
-                .map(|role_name| Role::from_name(&role_name).unwrap())
-                .collect::<HashSet<Role>>()
+                .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+                .collect::<HashSet<BuiltinRole>>()
         };
 
-        assert!(previous_roles.contains(&Role::Administrator));
+        assert!(previous_roles.contains(&BuiltinRole::Administrator));
 
 
         let role_removal_response = server
@@ -1027,7 +1330,7 @@ async fn specific_user_operations_work() {
             )
             .with_access_token(&admin_user_access_token)
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .send()
             .await;
@@ -1039,10 +1342,10 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
-            .collect::<HashSet<Role>>();
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+            .collect::<HashSet<BuiltinRole>>();
 
-        assert!(!updated_roles.contains(&Role::Administrator));
+        assert!(!updated_roles.contains(&BuiltinRole::Administrator));
     }
 
 
@@ -1062,10 +1365,10 @@ async fn specific_user_operations_work() {
             .json_body::<UserRolesResponse>()
             .role_names
             .into_iter()
-            .map(|role_name| Role::from_name(&role_name).unwrap())
-            .collect::<HashSet<Role>>();
+            .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+            .collect::<HashSet<BuiltinRole>>();
 
-        assert!(!new_janez_roles.contains(&Role::Administrator))
+        assert!(!new_janez_roles.contains(&BuiltinRole::Administrator))
     }
 
 
@@ -1092,8 +1395,8 @@ async fn specific_user_operations_work() {
                 .json_body::<UserRolesResponse>()
                 .role_names
                 .into_iter()
-                .map(|role_name| Role::from_name(&role_name).unwrap())
-                .collect::<HashSet<Role>>()
+                .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+                .collect::<HashSet<BuiltinRole>>()
         };
 
         assert_eq!(
@@ -1112,7 +1415,8 @@ async fn specific_user_operations_work() {
             )
             .with_access_token(&admin_user_access_token)
             .with_json_body(UserRoleAddRequest {
-                roles_to_add: vec![Role::Administrator.name().to_string()],
+                roles_to_add: vec![BuiltinRole::Administrator.name().to_string()],
+                expires_at: None,
             })
             .send()
             .await;
@@ -1146,13 +1450,13 @@ async fn specific_user_operations_work() {
                 .json_body::<UserRolesResponse>()
                 .role_names
                 .into_iter()
-                .map(|role_name| Role::from_name(&role_name).unwrap())
-                .collect::<HashSet<Role>>()
+                .map(|role_name| BuiltinRole::from_name(&role_name).unwrap())
+                .collect::<HashSet<BuiltinRole>>()
         };
 
         assert_eq!(
             janez_user_roles,
-            HashSet::from_iter([Role::Administrator, Role::User])
+            HashSet::from_iter([BuiltinRole::Administrator, BuiltinRole::User])
         );
     }
 
@@ -1166,7 +1470,7 @@ async fn specific_user_operations_work() {
             )
             .with_access_token(&admin_user_access_token)
             .with_json_body(UserRoleRemoveRequest {
-                roles_to_remove: vec![Role::Administrator.name().to_string()],
+                roles_to_remove: vec![BuiltinRole::Administrator.name().to_string()],
             })
             .send()
             .await;
@@ -1174,3 +1478,1826 @@ async fn specific_user_operations_work() {
         forbidden_role_remove_response.assert_status_equals(StatusCode::FORBIDDEN);
     }
 }
+
+
+
+#[tokio::test]
+async fn current_user_password_change_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Janez.register(&server).await;
+
+    let access_token = SampleUser::Janez.login(&server).await;
+
+    // Token issue times have a precision of one second, so we wait a bit
+    // to make sure the token is issued strictly before the password change.
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+
+    {
+        // Changing the password requires authentication.
+        server
+            .request(Method::PATCH, "/api/v1/users/me/password")
+            .with_json_body(UserPasswordChangeRequest {
+                current_password: SampleUser::Janez.password().to_string(),
+                new_password: "janez-novo-geslo".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::UNAUTHORIZED);
+    }
+
+    {
+        // Changing the password requires knowing the current one.
+        let wrong_password_response = server
+            .request(Method::PATCH, "/api/v1/users/me/password")
+            .with_access_token(&access_token)
+            .with_json_body(UserPasswordChangeRequest {
+                current_password: "not-the-password".to_string(),
+                new_password: "janez-novo-geslo".to_string(),
+            })
+            .send()
+            .await;
+
+        wrong_password_response.assert_status_equals(StatusCode::FORBIDDEN);
+        wrong_password_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Invalid current password.",
+        ));
+    }
+
+    {
+        server
+            .request(Method::PATCH, "/api/v1/users/me/password")
+            .with_access_token(&access_token)
+            .with_json_body(UserPasswordChangeRequest {
+                current_password: SampleUser::Janez.password().to_string(),
+                new_password: "janez-novo-geslo".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+    }
+
+
+
+    {
+        // The token we had before the change should no longer work.
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+    {
+        // Logging in with the old password should fail.
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Janez.into_login_request_model())
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+    {
+        // Logging in with the new password should work, and the new token should be usable.
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(UserLoginRequest {
+                username: SampleUser::Janez.username().to_string(),
+                password: "janez-novo-geslo".to_string(),
+            })
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::OK);
+
+        let new_access_token = login_response.json_body::<UserLoginResponse>().access_token;
+
+        let user_info = fetch_user_info(&server, &new_access_token).await;
+        assert_eq!(user_info.username, SampleUser::Janez.username());
+    }
+}
+
+
+
+#[tokio::test]
+async fn login_sessions_and_logout_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Janez.register(&server).await;
+
+
+    // Log in twice, e.g. from two different devices.
+    let login_as_janez = || async {
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Janez.into_login_request_model())
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::OK);
+        login_response.json_body::<UserLoginResponse>()
+    };
+
+    let first_login = login_as_janez().await;
+    let second_login = login_as_janez().await;
+
+
+    let first_session_id = {
+        let sessions_response = server
+            .request(Method::GET, "/api/v1/users/me/sessions")
+            .with_access_token(&first_login.access_token)
+            .send()
+            .await;
+
+        sessions_response.assert_status_equals(StatusCode::OK);
+
+        let sessions = sessions_response
+            .json_body::<UserSessionsResponse>()
+            .sessions;
+        assert_eq!(sessions.len(), 2);
+
+        let current_sessions = sessions
+            .iter()
+            .filter(|session| session.is_current)
+            .collect::<Vec<_>>();
+        assert_eq!(current_sessions.len(), 1);
+
+        current_sessions[0].id.clone()
+    };
+
+
+    {
+        // Refreshing a login should work while the session is active.
+        let refresh_response = server
+            .request(Method::POST, "/api/v1/login/refresh")
+            .with_json_body(UserLoginRefreshRequest {
+                refresh_token: first_login.refresh_token.clone(),
+            })
+            .send()
+            .await;
+
+        refresh_response.assert_status_equals(StatusCode::OK);
+
+        let refreshed_access_token = refresh_response
+            .json_body::<UserLoginRefreshResponse>()
+            .access_token;
+
+        let user_info = fetch_user_info(&server, &refreshed_access_token).await;
+        assert_eq!(user_info.username, SampleUser::Janez.username());
+    }
+
+
+    {
+        // Revoking a session requires a valid session ID.
+        server
+            .request(
+                Method::DELETE,
+                "/api/v1/users/me/sessions/not-a-uuid",
+            )
+            .with_access_token(&second_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(
+                Method::DELETE,
+                "/api/v1/users/me/sessions/018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            )
+            .with_access_token(&second_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+    {
+        // Revoke the first session from the second one.
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/users/me/sessions/{}", first_session_id),
+            )
+            .with_access_token(&second_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&first_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        let refresh_response = server
+            .request(Method::POST, "/api/v1/login/refresh")
+            .with_json_body(UserLoginRefreshRequest {
+                refresh_token: first_login.refresh_token.clone(),
+            })
+            .send()
+            .await;
+
+        refresh_response.assert_status_equals(StatusCode::FORBIDDEN);
+        refresh_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Refresh token has been revoked.",
+        ));
+    }
+
+
+    {
+        // Logging out requires authentication.
+        server
+            .request(Method::POST, "/api/v1/login/logout")
+            .send()
+            .await
+            .assert_status_equals(StatusCode::UNAUTHORIZED);
+
+        server
+            .request(Method::POST, "/api/v1/login/logout")
+            .with_access_token(&second_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        // After logging out, neither of the tokens should work anymore.
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&second_login.access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::POST, "/api/v1/login/refresh")
+            .with_json_body(UserLoginRefreshRequest {
+                refresh_token: second_login.refresh_token.clone(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+}
+
+
+
+#[tokio::test]
+async fn personal_api_tokens_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Janez.register(&server).await;
+
+    let access_token = SampleUser::Janez.login(&server).await;
+
+
+    {
+        // Tokens can't be given permissions the user doesn't have.
+        let response = server
+            .request(Method::POST, "/api/v1/users/me/api-tokens")
+            .with_access_token(&access_token)
+            .with_json_body(UserApiTokenCreationRequest {
+                name: "Import script".to_string(),
+                permissions: vec!["word:create".to_string()],
+                expires_at: None,
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::FORBIDDEN);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "You cannot grant permissions you do not have (missing permission: word:create).",
+        ));
+
+        server
+            .request(Method::POST, "/api/v1/users/me/api-tokens")
+            .with_access_token(&access_token)
+            .with_json_body(UserApiTokenCreationRequest {
+                name: "  ".to_string(),
+                permissions: vec![],
+                expires_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(Method::POST, "/api/v1/users/me/api-tokens")
+            .with_access_token(&access_token)
+            .with_json_body(UserApiTokenCreationRequest {
+                name: "Import script".to_string(),
+                permissions: vec![],
+                expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    let read_only_token = {
+        let response = server
+            .request(Method::POST, "/api/v1/users/me/api-tokens")
+            .with_access_token(&access_token)
+            .with_json_body(UserApiTokenCreationRequest {
+                name: "Read-only script".to_string(),
+                permissions: vec!["user.self:read".to_string()],
+                expires_at: Some(Utc::now() + chrono::Duration::days(30)),
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let created_token = response.json_body::<UserApiTokenCreationResponse>();
+        assert_eq!(created_token.api_token.name, "Read-only script");
+        assert_eq!(
+            created_token.api_token.permissions,
+            vec!["user.self:read".to_string()]
+        );
+
+        created_token
+    };
+
+
+    {
+        // The token authenticates as its owner, but is limited to its scope.
+        let user_info = fetch_user_info(&server, &read_only_token.token).await;
+        assert_eq!(user_info.username, SampleUser::Janez.username());
+
+        let permissions_response = server
+            .request(Method::GET, "/api/v1/users/me/permissions")
+            .with_access_token(&read_only_token.token)
+            .send()
+            .await;
+
+        permissions_response.assert_status_equals(StatusCode::OK);
+        assert_eq!(
+            permissions_response
+                .json_body::<UserPermissionsResponse>()
+                .permissions,
+            vec!["user.self:read".to_string()]
+        );
+
+        server
+            .request(Method::PATCH, "/api/v1/users/me/display_name")
+            .with_access_token(&read_only_token.token)
+            .with_json_body(UserDisplayNameChangeRequest {
+                new_display_name: "Janez z žetonom".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        let tokens_response = server
+            .request(Method::GET, "/api/v1/users/me/api-tokens")
+            .with_access_token(&read_only_token.token)
+            .send()
+            .await;
+
+        tokens_response.assert_status_equals(StatusCode::OK);
+
+        let api_tokens = tokens_response
+            .json_body::<UserApiTokensResponse>()
+            .api_tokens;
+        assert_eq!(api_tokens.len(), 1);
+        assert_eq!(api_tokens[0].id, read_only_token.api_token.id);
+        assert!(api_tokens[0].is_current);
+    }
+
+
+    {
+        // Tokens can't be used to create other tokens or to log out.
+        let full_token = {
+            let response = server
+                .request(Method::POST, "/api/v1/users/me/api-tokens")
+                .with_access_token(&access_token)
+                .with_json_body(UserApiTokenCreationRequest {
+                    name: "Full script".to_string(),
+                    permissions: vec!["user.self:read".to_string(), "user.self:write".to_string()],
+                    expires_at: None,
+                })
+                .send()
+                .await;
+
+            response.assert_status_equals(StatusCode::OK);
+            response.json_body::<UserApiTokenCreationResponse>().token
+        };
+
+        server
+            .request(Method::POST, "/api/v1/users/me/api-tokens")
+            .with_access_token(&full_token)
+            .with_json_body(UserApiTokenCreationRequest {
+                name: "Another script".to_string(),
+                permissions: vec!["user.self:read".to_string()],
+                expires_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::POST, "/api/v1/login/logout")
+            .with_access_token(&full_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    {
+        // Unknown tokens are rejected.
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token("kolomoni_pat_this-token-does-not-exist")
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    {
+        // Revoked tokens stop working immediately.
+        server
+            .request(
+                Method::DELETE,
+                format!(
+                    "/api/v1/users/me/api-tokens/{}",
+                    read_only_token.api_token.id
+                ),
+            )
+            .with_access_token(&access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&read_only_token.token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(
+                Method::DELETE,
+                format!(
+                    "/api/v1/users/me/api-tokens/{}",
+                    read_only_token.api_token.id
+                ),
+            )
+            .with_access_token(&access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+}
+
+
+
+#[tokio::test]
+async fn account_deactivation_and_deletion_work() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    let meta_user_info = SampleUser::Meta.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+    let meta_access_token = SampleUser::Meta.login(&server).await;
+
+
+    {
+        // Normal users can't deactivate others.
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/deactivate", janez_user_info.id),
+            )
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // Administrators can't deactivate themselves.
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/deactivate", janez_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::POST, "/api/v1/users/987654/deactivate")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+    {
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/deactivate", meta_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        // Existing tokens of a deactivated user stop working.
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // Deactivated users can't log in.
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Meta.into_login_request_model())
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::FORBIDDEN);
+        login_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "This account has been deactivated.",
+        ));
+    }
+
+    {
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/reactivate", meta_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let new_meta_access_token = SampleUser::Meta.login(&server).await;
+        let meta_info = fetch_user_info(&server, &new_meta_access_token).await;
+        assert_eq!(meta_info.id, meta_user_info.id);
+    }
+
+
+    {
+        // Administrators can delete others.
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/users/{}", meta_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(
+                Method::GET,
+                format!("/api/v1/users/{}", meta_user_info.id),
+            )
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+    {
+        // Users can delete themselves.
+        let kira_user_info = SampleUser::Kira.register(&server).await.user;
+        server
+            .give_full_permissions_to_user(kira_user_info.id)
+            .await;
+
+        let kira_access_token = SampleUser::Kira.login(&server).await;
+
+        let word_ability = SampleEnglishWord::Ability
+            .create(&server, &kira_access_token)
+            .await;
+
+        // Deleting your own account requires your current password.
+        let wrong_password_response = server
+            .request(Method::DELETE, "/api/v1/users/me")
+            .with_access_token(&kira_access_token)
+            .with_json_body(UserAccountDeletionRequest {
+                current_password: "notkiraspassword".to_string(),
+            })
+            .send()
+            .await;
+
+        wrong_password_response.assert_status_equals(StatusCode::FORBIDDEN);
+        wrong_password_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Invalid current password.",
+        ));
+
+        // Personal API tokens can't be used to delete the account.
+        let kira_api_token = {
+            let response = server
+                .request(Method::POST, "/api/v1/users/me/api-tokens")
+                .with_access_token(&kira_access_token)
+                .with_json_body(UserApiTokenCreationRequest {
+                    name: "Cleanup script".to_string(),
+                    permissions: vec!["user.self:write".to_string()],
+                    expires_at: None,
+                })
+                .send()
+                .await;
+
+            response.assert_status_equals(StatusCode::OK);
+            response.json_body::<UserApiTokenCreationResponse>().token
+        };
+
+        let api_token_response = server
+            .request(Method::DELETE, "/api/v1/users/me")
+            .with_access_token(&kira_api_token)
+            .with_json_body(UserAccountDeletionRequest {
+                current_password: SampleUser::Kira.password().to_string(),
+            })
+            .send()
+            .await;
+
+        api_token_response.assert_status_equals(StatusCode::FORBIDDEN);
+        api_token_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Your account can not be deleted using a personal API token.",
+        ));
+
+        server
+            .request(Method::DELETE, "/api/v1/users/me")
+            .with_access_token(&kira_access_token)
+            .with_json_body(UserAccountDeletionRequest {
+                current_password: SampleUser::Kira.password().to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        // Their contributions are reassigned to the deleted user placeholder.
+        let revisions_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/revisions/{}", word_ability.id),
+            )
+            .send()
+            .await;
+
+        revisions_response.assert_status_equals(StatusCode::OK);
+
+        let revisions = revisions_response
+            .json_body::<WordRevisionsResponse>()
+            .revisions;
+
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].author_user_id, Some(0));
+
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&kira_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Kira.into_login_request_model())
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+    {
+        // The placeholder can't log in, be deleted or be seen in the user list.
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(UserLoginRequest {
+                username: "[deleted]".to_string(),
+                password: "!".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::DELETE, "/api/v1/users/0")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        let user_list_response = server
+            .request(Method::GET, "/api/v1/users")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        user_list_response.assert_status_equals(StatusCode::OK);
+
+        let user_list = user_list_response.json_body::<RegisteredUsersListResponse>();
+        assert!(user_list.users.iter().all(|user| user.id != 0));
+    }
+}
+
+
+
+#[tokio::test]
+async fn account_suspension_works() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    let meta_user_info = SampleUser::Meta.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+    let meta_access_token = SampleUser::Meta.login(&server).await;
+
+    let meta_suspensions_path = format!("/api/v1/users/{}/suspensions", meta_user_info.id);
+
+
+    {
+        // Normal users can't suspend others.
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/suspensions", janez_user_info.id),
+            )
+            .with_access_token(&meta_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Vandalism.".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // Administrators can't suspend themselves.
+        server
+            .request(
+                Method::POST,
+                format!("/api/v1/users/{}/suspensions", janez_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Vandalism.".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        let missing_reason_response = server
+            .request(Method::POST, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "   ".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await;
+
+        missing_reason_response.assert_status_equals(StatusCode::BAD_REQUEST);
+        missing_reason_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "A suspension reason is required.",
+        ));
+
+        server
+            .request(Method::POST, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Vandalism.".to_string(),
+                ends_at: Some(Utc::now() - Duration::from_secs(10)),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(Method::POST, "/api/v1/users/987654/suspensions")
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Vandalism.".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+    {
+        let response = server
+            .request(Method::POST, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Vandalism.".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let suspension = response.json_body::<UserSuspensionResponse>().suspension;
+        assert_eq!(suspension.reason, "Vandalism.");
+        assert_eq!(
+            suspension.suspended_by_user_id,
+            Some(janez_user_info.id)
+        );
+        assert_eq!(suspension.ends_at, None);
+        assert!(suspension.is_active);
+
+        server
+            .request(Method::POST, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "More vandalism.".to_string(),
+                ends_at: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::CONFLICT);
+
+        // Existing tokens of a suspended user are refused.
+        server
+            .request(Method::GET, "/api/v1/users/me")
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // Suspended users can't log in, and are told why.
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Meta.into_login_request_model())
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::FORBIDDEN);
+        login_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "This account has been suspended. Reason: Vandalism.",
+        ));
+    }
+
+    {
+        let lift_path = format!(
+            "/api/v1/users/{}/suspensions/lift",
+            meta_user_info.id
+        );
+
+        server
+            .request(Method::POST, &lift_path)
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(Method::POST, &lift_path)
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::CONFLICT);
+
+        // Sessions survive the suspension.
+        let meta_info = fetch_user_info(&server, &meta_access_token).await;
+        assert_eq!(meta_info.id, meta_user_info.id);
+    }
+
+    {
+        // Suspensions with an end time stop applying by themselves.
+        server
+            .request(Method::POST, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserSuspensionCreationRequest {
+                reason: "Cooling off.".to_string(),
+                ends_at: Some(Utc::now() + Duration::from_secs(2)),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Meta.into_login_request_model())
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::FORBIDDEN);
+        assert!(login_response
+            .json_body::<ErrorReasonResponse>()
+            .reason
+            .ends_with("Reason: Cooling off."));
+
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+
+        SampleUser::Meta.login(&server).await;
+    }
+
+    {
+        // Suspensions are kept in the user's history.
+        server
+            .request(Method::GET, &meta_suspensions_path)
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        let response = server
+            .request(Method::GET, &meta_suspensions_path)
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let suspensions = response.json_body::<UserSuspensionsResponse>().suspensions;
+        assert_eq!(suspensions.len(), 2);
+
+        assert_eq!(suspensions[0].reason, "Cooling off.");
+        assert!(suspensions[0].ends_at.is_some());
+        assert_eq!(suspensions[0].lifted_at, None);
+        assert!(!suspensions[0].is_active);
+
+        assert_eq!(suspensions[1].reason, "Vandalism.");
+        assert_eq!(
+            suspensions[1].lifted_by_user_id,
+            Some(janez_user_info.id)
+        );
+        assert!(suspensions[1].lifted_at.is_some());
+        assert!(!suspensions[1].is_active);
+    }
+}
+
+
+
+#[tokio::test]
+async fn registration_invites_work() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    SampleUser::Meta.register(&server).await;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+    let meta_access_token = SampleUser::Meta.login(&server).await;
+
+
+    // Normal users can't manage invites.
+    {
+        server
+            .request(Method::GET, "/api/v1/users/invites")
+            .with_access_token(&meta_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(Method::POST, "/api/v1/users/invites")
+            .with_access_token(&meta_access_token)
+            .with_json_body(UserInviteCreationRequest {
+                expires_at: None,
+                max_uses: None,
+                roles: vec![],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+
+    // Invalid invite parameters are rejected.
+    {
+        server
+            .request(Method::POST, "/api/v1/users/invites")
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserInviteCreationRequest {
+                expires_at: None,
+                max_uses: Some(0),
+                roles: vec![],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(Method::POST, "/api/v1/users/invites")
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserInviteCreationRequest {
+                expires_at: None,
+                max_uses: None,
+                roles: vec!["non-existent-role".to_string()],
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    let single_use_invite = {
+        let response = server
+            .request(Method::POST, "/api/v1/users/invites")
+            .with_access_token(&janez_access_token)
+            .with_json_body(UserInviteCreationRequest {
+                expires_at: None,
+                max_uses: Some(1),
+                roles: vec!["administrator".to_string()],
+            })
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+
+        let invite = response.json_body::<UserInviteResponse>().invite;
+        assert_eq!(
+            invite.created_by_user_id,
+            Some(janez_user_info.id)
+        );
+        assert_eq!(invite.max_uses, Some(1));
+        assert_eq!(invite.use_count, 0);
+        assert_eq!(invite.roles, vec!["administrator".to_string()]);
+
+        invite
+    };
+
+
+    // Registering with an invite gives its roles to the new user,
+    // and single-use invites can't be used twice.
+    {
+        let mut registration_request = SampleUser::Kira.into_registration_request_model();
+        registration_request.invite_code = Some(single_use_invite.code.clone());
+
+        let registration_response = server
+            .request(Method::POST, "/api/v1/users")
+            .with_json_body(registration_request)
+            .send()
+            .await;
+
+        registration_response.assert_status_equals(StatusCode::OK);
+        let kira_user_info = registration_response
+            .json_body::<UserRegistrationResponse>()
+            .user;
+
+        let kira_roles_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/users/{}/roles", kira_user_info.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        kira_roles_response.assert_status_equals(StatusCode::OK);
+        let kira_roles = kira_roles_response
+            .json_body::<UserRolesResponse>()
+            .role_names
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            kira_roles,
+            HashSet::from(["user".to_string(), "administrator".to_string()])
+        );
+
+
+        let second_registration_response = server
+            .request(Method::POST, "/api/v1/users")
+            .with_json_body(UserRegistrationRequest {
+                username: "kira2".to_string(),
+                display_name: "Kira 2".to_string(),
+                password: "kira2".to_string(),
+                invite_code: Some(single_use_invite.code.clone()),
+            })
+            .send()
+            .await;
+
+        second_registration_response.assert_status_equals(StatusCode::FORBIDDEN);
+        second_registration_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "The provided invite code is invalid, has expired or has been used up.",
+        ));
+    }
+
+
+    // Revoked invites can no longer be used.
+    {
+        let multi_use_invite = {
+            let response = server
+                .request(Method::POST, "/api/v1/users/invites")
+                .with_access_token(&janez_access_token)
+                .with_json_body(UserInviteCreationRequest {
+                    expires_at: None,
+                    max_uses: None,
+                    roles: vec![],
+                })
+                .send()
+                .await;
+
+            response.assert_status_equals(StatusCode::OK);
+            response.json_body::<UserInviteResponse>().invite
+        };
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/users/invites/{}", multi_use_invite.id),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(Method::POST, "/api/v1/users")
+            .with_json_body(UserRegistrationRequest {
+                username: "kira3".to_string(),
+                display_name: "Kira 3".to_string(),
+                password: "kira3".to_string(),
+                invite_code: Some(multi_use_invite.code.clone()),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(
+                Method::DELETE,
+                "/api/v1/users/invites/018dbe00-2ca5-7cd4-a5b3-4d0a8c8a57e1",
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+
+    // The invite list reflects uses and revocations.
+    {
+        let invites_response = server
+            .request(Method::GET, "/api/v1/users/invites")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        invites_response.assert_status_equals(StatusCode::OK);
+        let invites = invites_response.json_body::<UserInvitesResponse>().invites;
+
+        assert_eq!(invites.len(), 2);
+
+        assert_eq!(invites[0].id, single_use_invite.id);
+        assert_eq!(invites[0].use_count, 1);
+        assert!(invites[0].revoked_at.is_none());
+
+        assert_eq!(invites[1].use_count, 0);
+        assert!(invites[1].revoked_at.is_some());
+    }
+}
+
+
+
+#[tokio::test]
+async fn login_throttling_and_lockouts_work() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+    SampleUser::Meta.register(&server).await;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+
+
+    // The testing configuration allows three failed attempts per username.
+    for _ in 0..3 {
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(UserLoginRequest {
+                username: SampleUser::Meta.username().to_string(),
+                password: "not-the-password".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+    {
+        // Even the correct password is refused during a lockout.
+        let login_response = server
+            .request(Method::POST, "/api/v1/login")
+            .with_json_body(SampleUser::Meta.into_login_request_model())
+            .send()
+            .await;
+
+        login_response.assert_status_equals(StatusCode::TOO_MANY_REQUESTS);
+        login_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Too many failed login attempts, try again later.",
+        ));
+        login_response.assert_header_exists(header::RETRY_AFTER);
+    }
+
+    {
+        // Other users are not affected.
+        SampleUser::Kira.register(&server).await;
+        SampleUser::Kira.login(&server).await;
+    }
+
+
+    {
+        let lockouts_response = server
+            .request(Method::GET, "/api/v1/login/lockouts")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        lockouts_response.assert_status_equals(StatusCode::OK);
+        let lockouts = lockouts_response
+            .json_body::<LoginLockoutsResponse>()
+            .lockouts;
+
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].subject_type, "username");
+        assert_eq!(lockouts[0].subject, SampleUser::Meta.username());
+        assert_eq!(lockouts[0].failed_attempt_count, 3);
+    }
+
+    {
+        server
+            .request(Method::GET, "/api/v1/login/lockouts")
+            .with_access_token(&SampleUser::Kira.login(&server).await)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        server
+            .request(
+                Method::DELETE,
+                "/api/v1/login/lockouts/email/meta@example.com",
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(
+                Method::DELETE,
+                format!(
+                    "/api/v1/login/lockouts/username/{}",
+                    SampleUser::Meta.username()
+                ),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(
+                Method::DELETE,
+                format!(
+                    "/api/v1/login/lockouts/username/{}",
+                    SampleUser::Meta.username()
+                ),
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+    // Once the lockout is cleared, the user can log in again.
+    SampleUser::Meta.login(&server).await;
+}
+
+
+#[tokio::test]
+async fn spoofed_forwarded_for_header_does_not_affect_ip_address_lockouts() {
+    let server = initialize_test_server().await;
+
+    let janez_user_info = SampleUser::Janez.register(&server).await.user;
+
+    server
+        .give_full_permissions_to_user(janez_user_info.id)
+        .await;
+
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+
+
+    // The testing configuration allows twenty failed attempts per IP address
+    // and trusts no proxies, so the `X-Forwarded-For` header must be ignored.
+    for attempt in 0..20 {
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_header(
+                header::X_FORWARDED_FOR,
+                header::HeaderValue::from_str(&format!("203.0.113.{}", attempt)).unwrap(),
+            )
+            .with_json_body(UserLoginRequest {
+                username: format!("nonexistent-user-{}", attempt),
+                password: "not-the-password".to_string(),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+    }
+
+    {
+        // A fresh spoofed address doesn't get around the lockout.
+        server
+            .request(Method::POST, "/api/v1/login")
+            .with_header(
+                header::X_FORWARDED_FOR,
+                header::HeaderValue::from_static("198.51.100.1"),
+            )
+            .with_json_body(SampleUser::Janez.into_login_request_model())
+            .send()
+            .await
+            .assert_status_equals(StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    {
+        // The lockout is recorded for the real (peer) address only.
+        let lockouts_response = server
+            .request(Method::GET, "/api/v1/login/lockouts")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        lockouts_response.assert_status_equals(StatusCode::OK);
+        let lockouts = lockouts_response
+            .json_body::<LoginLockoutsResponse>()
+            .lockouts;
+
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].subject_type, "ip_address");
+        assert_eq!(lockouts[0].subject, "127.0.0.1");
+        assert_eq!(lockouts[0].failed_attempt_count, 20);
+    }
+
+    server
+        .request(
+            Method::DELETE,
+            "/api/v1/login/lockouts/ip_address/127.0.0.1",
+        )
+        .with_access_token(&janez_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    SampleUser::Janez.login(&server).await;
+}
+
+
+
+/// Starts an OpenID Connect login (optionally as an authenticated user,
+/// which links the identity instead) and returns the authorization URL.
+async fn start_oidc_login(server: &TestServer, access_token: Option<&str>) -> String {
+    let mut request = server.request(Method::POST, "/api/v1/login/oidc/authorize");
+
+    if let Some(access_token) = access_token {
+        request = request.with_access_token(access_token);
+    }
+
+    let response = request.send().await;
+    response.assert_status_equals(StatusCode::OK);
+
+    response
+        .json_body::<OidcAuthorizationResponse>()
+        .authorization_url
+}
+
+/// Finishes an OpenID Connect login with the parameters the identity provider redirected back with.
+async fn finish_oidc_login(server: &TestServer, redirect: MockOidcRedirect) -> TestResponse {
+    server
+        .request(Method::POST, "/api/v1/login/oidc/callback")
+        .with_json_body(OidcCallbackRequest {
+            code: redirect.code,
+            state: redirect.state,
+        })
+        .send()
+        .await
+}
+
+
+#[tokio::test]
+async fn openid_connect_login_works() {
+    let server = initialize_test_server().await;
+    let identity_provider = MockOidcProvider::start().await;
+
+    let janez_identity = MockOidcUser::new("mock-subject-janez")
+        .with_preferred_username("janez")
+        .with_name("Janez Novak");
+
+
+    // The first login of an unknown identity creates an account for it (registration is open).
+    let janez_user_id = {
+        let authorization_url = start_oidc_login(&server, None).await;
+        let redirect = identity_provider.authorize(&authorization_url, janez_identity.clone());
+
+        let response = finish_oidc_login(&server, redirect.clone()).await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        let user_info = fetch_user_info(&server, &access_token).await;
+
+        assert_eq!(user_info.username, "janez");
+        assert_eq!(user_info.display_name, "Janez Novak");
+
+
+        // A login can only be finished once.
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::BAD_REQUEST);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Unknown or expired login state.",
+        ));
+
+        user_info.id
+    };
+
+
+    // Logging in with the same identity again logs into the same account.
+    {
+        let authorization_url = start_oidc_login(&server, None).await;
+        let redirect = identity_provider.authorize(&authorization_url, janez_identity.clone());
+
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        assert_eq!(
+            fetch_user_info(&server, &access_token).await.id,
+            janez_user_id
+        );
+    }
+
+
+    // New accounts get numbered usernames if the preferred one is taken.
+    {
+        let authorization_url = start_oidc_login(&server, None).await;
+        let redirect = identity_provider.authorize(
+            &authorization_url,
+            MockOidcUser::new("mock-subject-other-janez").with_preferred_username("janez"),
+        );
+
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        let user_info = fetch_user_info(&server, &access_token).await;
+
+        assert_ne!(user_info.id, janez_user_id);
+        assert_eq!(user_info.username, "janez2");
+    }
+
+
+    // Codes are bound to the login they were issued for (PKCE and state).
+    {
+        let first_authorization_url = start_oidc_login(&server, None).await;
+        let second_authorization_url = start_oidc_login(&server, None).await;
+
+        let first_redirect =
+            identity_provider.authorize(&first_authorization_url, janez_identity.clone());
+        let second_redirect =
+            identity_provider.authorize(&second_authorization_url, janez_identity.clone());
+
+        let response = finish_oidc_login(
+            &server,
+            MockOidcRedirect {
+                code: first_redirect.code,
+                state: second_redirect.state,
+            },
+        )
+        .await;
+        response.assert_status_equals(StatusCode::BAD_REQUEST);
+
+        finish_oidc_login(
+            &server,
+            MockOidcRedirect {
+                code: second_redirect.code,
+                state: "not-a-pending-login".to_string(),
+            },
+        )
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    // Logging in while authenticated links the identity to the existing account.
+    SampleUser::Kira.register(&server).await;
+    let kira_access_token = SampleUser::Kira.login(&server).await;
+    let kira_user_id = fetch_user_info(&server, &kira_access_token).await.id;
+
+    let kira_identity = MockOidcUser::new("mock-subject-kira").with_preferred_username("kira-sso");
+
+    {
+        let authorization_url = start_oidc_login(&server, Some(&kira_access_token)).await;
+        let redirect = identity_provider.authorize(&authorization_url, kira_identity.clone());
+
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        assert_eq!(
+            fetch_user_info(&server, &access_token).await.id,
+            kira_user_id
+        );
+
+
+        let authorization_url = start_oidc_login(&server, None).await;
+        let redirect = identity_provider.authorize(&authorization_url, kira_identity.clone());
+
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        let user_info = fetch_user_info(&server, &access_token).await;
+
+        assert_eq!(user_info.id, kira_user_id);
+        assert_eq!(user_info.username, "kira");
+    }
+
+
+    // Identities that are linked to another account can't be linked again.
+    {
+        let authorization_url = start_oidc_login(&server, Some(&kira_access_token)).await;
+        let redirect = identity_provider.authorize(&authorization_url, janez_identity.clone());
+
+        let response = finish_oidc_login(&server, redirect).await;
+        response.assert_status_equals(StatusCode::CONFLICT);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "This identity is already linked to another account.",
+        ));
+    }
+
+
+    identity_provider.stop().await;
+}
+
+
+
+/// Fetches the two-factor authentication status of the user the `access_token` belongs to.
+async fn fetch_two_factor_status(
+    server: &TestServer,
+    access_token: &str,
+) -> TwoFactorStatusResponse {
+    let response = server
+        .request(Method::GET, "/api/v1/users/me/2fa")
+        .with_access_token(access_token)
+        .send()
+        .await;
+
+    response.assert_status_equals(StatusCode::OK);
+    response.json_body::<TwoFactorStatusResponse>()
+}
+
+/// Completes a login with the given challenge token and second factor code.
+async fn complete_two_factor_login(
+    server: &TestServer,
+    challenge_token: &str,
+    code: &str,
+) -> TestResponse {
+    server
+        .request(Method::POST, "/api/v1/login/2fa")
+        .with_json_body(TwoFactorLoginRequest {
+            challenge_token: challenge_token.to_string(),
+            code: code.to_string(),
+        })
+        .send()
+        .await
+}
+
+/// Performs the password login step of a user with two-factor authentication
+/// and returns the challenge token.
+async fn start_two_factor_login(server: &TestServer, user: SampleUser) -> String {
+    let response = server
+        .request(Method::POST, "/api/v1/login")
+        .with_json_body(user.into_login_request_model())
+        .send()
+        .await;
+
+    response.assert_status_equals(StatusCode::ACCEPTED);
+
+    let challenge = response.json_body::<TwoFactorChallengeResponse>();
+    assert!(!challenge.enrollment_required);
+    assert!(challenge.expires_at > Utc::now());
+
+    challenge.challenge_token
+}
+
+
+#[tokio::test]
+async fn two_factor_authentication_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Janez.register(&server).await;
+    let janez_access_token = SampleUser::Janez.login(&server).await;
+
+
+    let initial_status = fetch_two_factor_status(&server, &janez_access_token).await;
+    assert!(!initial_status.enabled);
+    assert!(initial_status.enabled_at.is_none());
+    assert!(!initial_status.required);
+
+
+    // Enrolling generates a secret and recovery codes, but doesn't enable 2FA yet.
+    let enrollment = {
+        let response = server
+            .request(Method::POST, "/api/v1/users/me/2fa")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+        let enrollment = response.json_body::<TwoFactorEnrollmentResponse>();
+
+        assert!(enrollment.provisioning_uri.starts_with("otpauth://totp/"));
+        assert!(enrollment
+            .provisioning_uri
+            .contains(&format!("secret={}", enrollment.secret)));
+        assert_eq!(enrollment.recovery_codes.len(), 10);
+
+        enrollment
+    };
+
+    SampleUser::Janez.login(&server).await;
+
+
+    // The enrollment is only confirmed with a valid code.
+    server
+        .request(Method::POST, "/api/v1/users/me/2fa/confirm")
+        .with_access_token(&janez_access_token)
+        .with_json_body(TwoFactorConfirmationRequest {
+            code: "abcdef".to_string(),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    let confirmation_code = totp_code_at(&enrollment.secret, Utc::now()).unwrap();
+
+    server
+        .request(Method::POST, "/api/v1/users/me/2fa/confirm")
+        .with_access_token(&janez_access_token)
+        .with_json_body(TwoFactorConfirmationRequest {
+            code: confirmation_code.clone(),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    {
+        let status = fetch_two_factor_status(&server, &janez_access_token).await;
+        assert!(status.enabled);
+        assert!(status.enabled_at.is_some());
+        assert_eq!(status.remaining_recovery_codes, 10);
+
+        let response = server
+            .request(Method::POST, "/api/v1/users/me/2fa")
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::CONFLICT);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Two-factor authentication is already enabled.",
+        ));
+    }
+
+
+    // Logging in now requires a second step.
+    {
+        let challenge_token = start_two_factor_login(&server, SampleUser::Janez).await;
+
+        let response = complete_two_factor_login(&server, &challenge_token, "abcdef").await;
+        response.assert_status_equals(StatusCode::FORBIDDEN);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Invalid two-factor authentication code.",
+        ));
+
+        // Codes can't be reused.
+        complete_two_factor_login(&server, &challenge_token, &confirmation_code)
+            .await
+            .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // Recovery codes are accepted instead of codes.
+        let response = complete_two_factor_login(
+            &server,
+            &challenge_token,
+            &enrollment.recovery_codes[0],
+        )
+        .await;
+        response.assert_status_equals(StatusCode::OK);
+
+        let access_token = response.json_body::<UserLoginResponse>().access_token;
+        assert_eq!(
+            fetch_two_factor_status(&server, &access_token)
+                .await
+                .remaining_recovery_codes,
+            9
+        );
+
+        // Each challenge can only be completed once.
+        let response = complete_two_factor_login(
+            &server,
+            &challenge_token,
+            &enrollment.recovery_codes[1],
+        )
+        .await;
+        response.assert_status_equals(StatusCode::BAD_REQUEST);
+        response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Unknown or expired login challenge.",
+        ));
+    }
+
+    {
+        let challenge_token = start_two_factor_login(&server, SampleUser::Janez).await;
+
+        // Recovery codes are single-use.
+        complete_two_factor_login(
+            &server,
+            &challenge_token,
+            &enrollment.recovery_codes[0],
+        )
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+        // The code from the confirmation has been used, so use the next one
+        // (codes from neighbouring time steps are accepted).
+        let next_code = totp_code_at(
+            &enrollment.secret,
+            Utc::now() + chrono::Duration::seconds(TOTP_STEP_SECONDS),
+        )
+        .unwrap();
+
+        complete_two_factor_login(&server, &challenge_token, &next_code)
+            .await
+            .assert_status_equals(StatusCode::OK);
+    }
+
+
+    // Regenerating recovery codes invalidates the old ones.
+    {
+        let response = server
+            .request(
+                Method::POST,
+                "/api/v1/users/me/2fa/recovery-codes",
+            )
+            .with_access_token(&janez_access_token)
+            .send()
+            .await;
+
+        response.assert_status_equals(StatusCode::OK);
+        let recovery_codes = response
+            .json_body::<TwoFactorRecoveryCodesResponse>()
+            .recovery_codes;
+
+        assert_eq!(recovery_codes.len(), 10);
+        assert!(!recovery_codes.contains(&enrollment.recovery_codes[1]));
+
+        let challenge_token = start_two_factor_login(&server, SampleUser::Janez).await;
+
+        complete_two_factor_login(
+            &server,
+            &challenge_token,
+            &enrollment.recovery_codes[1],
+        )
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+        complete_two_factor_login(&server, &challenge_token, &recovery_codes[0])
+            .await
+            .assert_status_equals(StatusCode::OK);
+    }
+
+
+    // Once disabled, logging in only needs the password again.
+    server
+        .request(Method::DELETE, "/api/v1/users/me/2fa")
+        .with_access_token(&janez_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    SampleUser::Janez.login(&server).await;
+
+    server
+        .request(Method::DELETE, "/api/v1/users/me/2fa")
+        .with_access_token(&janez_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+}
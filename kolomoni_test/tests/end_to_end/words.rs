@@ -15,21 +15,54 @@ use kolomoni::api::v1::dictionary::{
         EnglishWordFilters,
         EnglishWordInfoResponse,
         EnglishWordUpdateRequest,
+        EnglishWordUpdateResponse,
+        EnglishWordsByLemmaResponse,
         EnglishWordsListRequest,
         EnglishWordsResponse,
     },
+    export::DictionaryJsonExport,
+    grammar::{
+        EnglishWordGrammar,
+        GrammaticalGender,
+        NumberRestriction,
+        PartOfSpeech,
+        SloveneCaseForms,
+        SloveneInflectionTable,
+        SloveneWordGrammar,
+    },
+    import::{
+        DictionaryImportRequest,
+        DictionaryImportResponse,
+        TbxImportRequest,
+        TbxImportResponse,
+    },
+    links::{WordLinkDeletionRequest, WordLinkRequest, WordLinkType},
+    references::{ReferencedWordLanguage, WordReferencesResponse},
+    revisions::{
+        WordRevisionDiffResponse,
+        WordRevisionField,
+        WordRevisionRevertResponse,
+        WordRevisionsResponse,
+    },
     slovene_word::{
         SloveneWordCreationRequest,
         SloveneWordCreationResponse,
+        SloveneWordDeletionResponse,
         SloveneWordFilters,
         SloveneWordInfoResponse,
         SloveneWordUpdateRequest,
+        SloveneWordsByLemmaResponse,
         SloveneWordsListRequest,
         SloveneWordsResponse,
     },
     suggestions::{TranslationSuggestionDeletionRequest, TranslationSuggestionRequest},
     translations::{TranslationDeletionRequest, TranslationRequest},
 };
+use kolomoni::dictionary_import::{
+    tbx::{parse_tbx_file, TbxImportEntryStatus},
+    DictionaryImportFormat,
+    DictionaryImportRowStatus,
+};
 use kolomoni_test_util::prelude::*;
 
 
@@ -104,6 +137,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .send()
             .await
@@ -116,6 +150,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .with_access_token(&normal_user_access_token)
             .send()
@@ -132,6 +167,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -315,6 +351,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .send()
             .await
@@ -327,6 +364,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .with_access_token(&normal_user_access_token)
             .send()
@@ -341,6 +379,7 @@ async fn word_creation_with_suggestions_and_translations_works() {
                 lemma: "test".to_string(),
                 disambiguation: Some("test".to_string()),
                 description: Some("test".to_string()),
+                grammar: None,
             })
             .with_access_token(&admin_user_access_token)
             .send()
@@ -1156,9 +1195,11 @@ async fn lookup_by_lemma_works() {
 
         lookup_response.assert_status_equals(StatusCode::OK);
 
-        let lookup_word = lookup_response.json_body::<EnglishWordInfoResponse>().word;
+        let lookup_words = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words;
 
-        assert_eq!(word_hit_points_info, lookup_word);
+        assert_eq!(lookup_words, vec![word_hit_points_info.clone()]);
     }
 
 
@@ -1227,9 +1268,11 @@ async fn lookup_by_lemma_works() {
 
         lookup_response.assert_status_equals(StatusCode::OK);
 
-        let lookup_word = lookup_response.json_body::<SloveneWordInfoResponse>().word;
+        let lookup_words = lookup_response
+            .json_body::<SloveneWordsByLemmaResponse>()
+            .slovene_words;
 
-        assert_eq!(word_napad_info, lookup_word);
+        assert_eq!(lookup_words, vec![word_napad_info.clone()]);
     }
 
 
@@ -1918,6 +1961,7 @@ async fn word_listing_with_filters_works() {
                 filters: Some(EnglishWordFilters {
                     last_modified_after: Some(time_just_before_initial_creation),
                 }),
+                ..Default::default()
             })
             .send()
             .await;
@@ -1936,6 +1980,7 @@ async fn word_listing_with_filters_works() {
                 filters: Some(EnglishWordFilters {
                     last_modified_after: Some(time_just_before_modification),
                 }),
+                ..Default::default()
             })
             .send()
             .await;
@@ -2011,6 +2056,7 @@ async fn word_listing_with_filters_works() {
                 filters: Some(SloveneWordFilters {
                     last_modified_after: Some(time_just_before_initial_creation),
                 }),
+                ..Default::default()
             })
             .send()
             .await;
@@ -2029,6 +2075,7 @@ async fn word_listing_with_filters_works() {
                 filters: Some(SloveneWordFilters {
                     last_modified_after: Some(time_just_before_modification),
                 }),
+                ..Default::default()
             })
             .send()
             .await;
@@ -2043,3 +2090,2754 @@ async fn word_listing_with_filters_works() {
         assert_eq!(updated_terna_word.id, word_terna.id);
     }
 }
+
+
+#[tokio::test]
+async fn word_listing_pagination_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    let mut created_english_word_ids = Vec::new();
+    for sample_word in [
+        SampleEnglishWord::Ability,
+        SampleEnglishWord::Charisma,
+        SampleEnglishWord::Attack,
+        SampleEnglishWord::CriticalHit,
+        SampleEnglishWord::HitPoints,
+    ] {
+        let word = sample_word
+            .create(&server, &admin_user_access_token)
+            .await;
+
+        created_english_word_ids.push(word.id);
+    }
+
+    created_english_word_ids.sort();
+
+
+    /***
+     * Follow the cursors through all pages of english words.
+     */
+
+    let mut listed_english_word_ids = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut num_pages = 0;
+
+    loop {
+        let page_response = server
+            .request(Method::GET, "/api/v1/dictionary/english")
+            .with_json_body(EnglishWordsListRequest {
+                page_size: Some(2),
+                cursor: cursor.clone(),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        page_response.assert_status_equals(StatusCode::OK);
+
+        let page = page_response.json_body::<EnglishWordsResponse>();
+        num_pages += 1;
+
+        assert!(page.english_words.len() <= 2);
+        listed_english_word_ids.extend(page.english_words.into_iter().map(|word| word.id));
+
+        match page.next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+
+    assert_eq!(num_pages, 3);
+    assert_eq!(listed_english_word_ids, created_english_word_ids);
+
+
+    // A page that exactly fits the remaining words must not have a cursor.
+    {
+        let page_response = server
+            .request(Method::GET, "/api/v1/dictionary/english")
+            .with_json_body(EnglishWordsListRequest {
+                page_size: Some(5),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        page_response.assert_status_equals(StatusCode::OK);
+
+        let page = page_response.json_body::<EnglishWordsResponse>();
+
+        assert_eq!(page.english_words.len(), 5);
+        assert!(page.next_cursor.is_none());
+    }
+
+
+    /***
+     * Test the slovene word listing as well.
+     */
+
+    SampleSloveneWord::Sposobnost
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleSloveneWord::Karizma
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleSloveneWord::Napad
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    {
+        let first_page_response = server
+            .request(Method::GET, "/api/v1/dictionary/slovene")
+            .with_json_body(SloveneWordsListRequest {
+                page_size: Some(2),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        first_page_response.assert_status_equals(StatusCode::OK);
+
+        let first_page = first_page_response.json_body::<SloveneWordsResponse>();
+
+        assert_eq!(first_page.slovene_words.len(), 2);
+        assert!(first_page.next_cursor.is_some());
+
+
+        let second_page_response = server
+            .request(Method::GET, "/api/v1/dictionary/slovene")
+            .with_json_body(SloveneWordsListRequest {
+                page_size: Some(2),
+                cursor: first_page.next_cursor,
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        second_page_response.assert_status_equals(StatusCode::OK);
+
+        let second_page = second_page_response.json_body::<SloveneWordsResponse>();
+
+        assert_eq!(second_page.slovene_words.len(), 1);
+        assert!(second_page.next_cursor.is_none());
+        assert!(second_page.slovene_words[0].id > first_page.slovene_words[1].id);
+    }
+
+
+    /***
+     * Invalid page sizes and cursors are rejected.
+     */
+
+    server
+        .request(Method::GET, "/api/v1/dictionary/english")
+        .with_json_body(EnglishWordsListRequest {
+            page_size: Some(0),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    server
+        .request(Method::GET, "/api/v1/dictionary/slovene")
+        .with_json_body(SloveneWordsListRequest {
+            page_size: Some(100_000),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    server
+        .request(Method::GET, "/api/v1/dictionary/english")
+        .with_json_body(EnglishWordsListRequest {
+            cursor: Some("not-a-cursor".to_string()),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+}
+
+
+#[tokio::test]
+async fn dictionary_import_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+    SampleUser::Janez.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+    let normal_user_access_token = SampleUser::Janez.login(&server).await;
+
+
+    SampleEnglishWord::Ability
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleEnglishWord::Charisma
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleCategory::DejavnostiInSpopad
+        .create(&server, &admin_user_access_token)
+        .await;
+
+
+    let csv_import_data = "\
+        english_lemma,disambiguation,description,slovene_translations,categories\n\
+        critical hit,,A hit that deals extra damage.,kritični zadetek;usodni zadetek,activities and combat\n\
+        ability,,,sposobnost,\n\
+        charisma,,Something else entirely.,karizma,\n";
+
+
+    /***
+     * The import requires authentication and the word:create and word:update permissions.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import")
+        .with_json_body(DictionaryImportRequest {
+            format: DictionaryImportFormat::Csv,
+            data: csv_import_data.to_string(),
+            dry_run: false,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::UNAUTHORIZED);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import")
+        .with_access_token(&normal_user_access_token)
+        .with_json_body(DictionaryImportRequest {
+            format: DictionaryImportFormat::Csv,
+            data: csv_import_data.to_string(),
+            dry_run: false,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+
+    /***
+     * A dry run reports what would happen, but doesn't change anything.
+     */
+
+    {
+        let dry_run_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Csv,
+                data: csv_import_data.to_string(),
+                dry_run: true,
+            })
+            .send()
+            .await;
+
+        dry_run_response.assert_status_equals(StatusCode::OK);
+
+        let report = dry_run_response
+            .json_body::<DictionaryImportResponse>()
+            .report;
+
+        assert!(report.dry_run);
+        assert!(!report.committed);
+
+        assert_eq!(report.summary.new, 1);
+        assert_eq!(report.summary.updated, 1);
+        assert_eq!(report.summary.conflicting, 1);
+        assert_eq!(report.summary.invalid, 0);
+
+        assert_eq!(report.rows.len(), 3);
+        assert_eq!(report.rows[0].status, DictionaryImportRowStatus::New);
+        assert_eq!(report.rows[1].status, DictionaryImportRowStatus::Updated);
+        assert_eq!(
+            report.rows[2].status,
+            DictionaryImportRowStatus::Conflicting
+        );
+        assert_eq!(
+            report.rows[2].conflicting_fields,
+            vec!["description".to_string()]
+        );
+
+
+        server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/critical hit",
+            )
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+
+    /***
+     * The actual import creates the words, translations and categories.
+     */
+
+    {
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Csv,
+                data: csv_import_data.to_string(),
+                dry_run: false,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<DictionaryImportResponse>().report;
+
+        assert!(!report.dry_run);
+        assert!(report.committed);
+        assert_eq!(report.summary.new, 1);
+
+
+        let lookup_response = server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/critical hit",
+            )
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let critical_hit_word = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words
+            .remove(0);
+
+        assert_eq!(
+            critical_hit_word.description.as_deref(),
+            Some("A hit that deals extra damage.")
+        );
+        assert_eq!(critical_hit_word.translations.len(), 2);
+        assert_eq!(critical_hit_word.categories.len(), 1);
+
+
+        let lookup_response = server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/charisma",
+            )
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        // Conflicting rows are skipped entirely.
+        let charisma_word = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words
+            .remove(0);
+
+        assert_eq!(
+            charisma_word.description.as_deref(),
+            SampleEnglishWord::Charisma.description()
+        );
+        assert!(charisma_word.translations.is_empty());
+    }
+
+
+    // Importing the same data again doesn't change anything.
+    {
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Tsv,
+                data: csv_import_data.replace(',', "\t"),
+                dry_run: false,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<DictionaryImportResponse>().report;
+
+        assert_eq!(report.summary.new, 0);
+        assert_eq!(report.summary.updated, 0);
+        assert_eq!(report.summary.unchanged, 2);
+        assert_eq!(report.summary.conflicting, 1);
+    }
+
+
+    /***
+     * Invalid rows are reported and prevent the entire import.
+     */
+
+    {
+        let json_import_data = r#"[
+            { "english_lemma": "attack", "slovene_translations": ["napad"] },
+            { "english_lemma": "hit points", "categories": ["this category does not exist"] },
+            { "disambiguation": "lemma is missing" }
+        ]"#;
+
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Json,
+                data: json_import_data.to_string(),
+                dry_run: false,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<DictionaryImportResponse>().report;
+
+        assert!(!report.committed);
+        assert_eq!(report.summary.new, 1);
+        assert_eq!(report.summary.invalid, 2);
+
+        assert_eq!(report.rows[1].status, DictionaryImportRowStatus::Invalid);
+        assert_eq!(
+            report.rows[1].errors,
+            vec!["Unknown category: \"this category does not exist\".".to_string()]
+        );
+        assert_eq!(report.rows[2].status, DictionaryImportRowStatus::Invalid);
+        assert_eq!(report.rows[2].errors.len(), 1);
+
+
+        server
+            .request(Method::GET, "/api/v1/dictionary/english/by-lemma/attack")
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+
+    // Files that can't be read at all are rejected.
+    server
+        .request(Method::POST, "/api/v1/dictionary/import")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(DictionaryImportRequest {
+            format: DictionaryImportFormat::Csv,
+            data: "lemma,description\nattack,\n".to_string(),
+            dry_run: false,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+}
+
+
+#[tokio::test]
+async fn tbx_glossary_import_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+    SampleUser::Janez.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+    let normal_user_access_token = SampleUser::Janez.login(&server).await;
+
+
+    SampleEnglishWord::Ability
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleEnglishWord::Charisma
+        .create(&server, &admin_user_access_token)
+        .await;
+    SampleCategory::DejavnostiInSpopad
+        .create(&server, &admin_user_access_token)
+        .await;
+
+
+    let tbx_import_data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<martif type="TBX-Basic" xml:lang="en">
+  <martifHeader>
+    <fileDesc><sourceDesc><p>Shared fantasy glossary</p></sourceDesc></fileDesc>
+  </martifHeader>
+  <text>
+    <body>
+      <termEntry id="c1">
+        <descrip type="subjectField">activities and combat</descrip>
+        <descrip type="subjectField">spells</descrip>
+        <langSet xml:lang="en">
+          <descrip type="definition">A hit that deals extra damage.</descrip>
+          <tig><term>critical hit</term></tig>
+        </langSet>
+        <langSet xml:lang="sl">
+          <tig><term>kritični zadetek</term></tig>
+          <tig><term>usodni zadetek</term></tig>
+        </langSet>
+      </termEntry>
+      <termEntry id="c2">
+        <langSet xml:lang="en">
+          <tig><term>charisma</term><note>mechanical ability</note></tig>
+        </langSet>
+        <langSet xml:lang="sl">
+          <tig><term>karizma</term></tig>
+        </langSet>
+      </termEntry>
+      <termEntry id="c3">
+        <langSet xml:lang="en">
+          <tig><term>ability</term><note>something else entirely</note></tig>
+        </langSet>
+        <langSet xml:lang="sl">
+          <tig><term>zmožnost</term></tig>
+        </langSet>
+      </termEntry>
+      <termEntry id="c4">
+        <langSet xml:lang="sl">
+          <tig><term>čarovnik</term></tig>
+        </langSet>
+      </termEntry>
+      <termEntry id="c5">
+        <langSet xml:lang="en-GB">
+          <tig><term>critical hit</term></tig>
+        </langSet>
+        <langSet xml:lang="sl">
+          <tig><term>kritični izid</term></tig>
+          <tig>
+            <term>kritik</term>
+            <termNote type="administrativeStatus">deprecatedTerm-admn-sts</termNote>
+          </tig>
+        </langSet>
+      </termEntry>
+    </body>
+  </text>
+</martif>"#;
+
+
+    /***
+     * The import requires authentication and the word:create and word:update permissions.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import/tbx")
+        .with_json_body(TbxImportRequest {
+            data: tbx_import_data.to_string(),
+            preview: false,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::UNAUTHORIZED);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import/tbx")
+        .with_access_token(&normal_user_access_token)
+        .with_json_body(TbxImportRequest {
+            data: tbx_import_data.to_string(),
+            preview: false,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+
+    /***
+     * Files that aren't TBX are rejected.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import/tbx")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(TbxImportRequest {
+            data: "<html><body>Not a glossary.</body></html>".to_string(),
+            preview: true,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/import/tbx")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(TbxImportRequest {
+            data: "<martif><text><body>".to_string(),
+            preview: true,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+
+    /***
+     * A preview reports which entries would be created, merged or skipped,
+     * but doesn't change anything.
+     */
+
+    {
+        let preview_response = server
+            .request(Method::POST, "/api/v1/dictionary/import/tbx")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(TbxImportRequest {
+                data: tbx_import_data.to_string(),
+                preview: true,
+            })
+            .send()
+            .await;
+
+        preview_response.assert_status_equals(StatusCode::OK);
+
+        let report = preview_response.json_body::<TbxImportResponse>().report;
+
+        assert!(report.preview);
+        assert!(!report.committed);
+
+        assert_eq!(report.summary.created, 2);
+        assert_eq!(report.summary.merged, 2);
+        assert_eq!(report.summary.skipped, 1);
+
+        assert_eq!(report.entries.len(), 5);
+
+        assert_eq!(report.entries[0].entry_id.as_deref(), Some("c1"));
+        assert_eq!(report.entries[0].status, TbxImportEntryStatus::Created);
+        assert_eq!(
+            report.entries[0].ignored_subject_fields,
+            vec!["spells".to_string()]
+        );
+
+        // Matches the existing word by lemma and disambiguation.
+        assert_eq!(report.entries[1].status, TbxImportEntryStatus::Merged);
+        assert_eq!(
+            report.entries[1].english_disambiguation.as_deref(),
+            SampleEnglishWord::Charisma.disambiguation()
+        );
+
+        // Same lemma as an existing word, but a different disambiguation: a new homonym.
+        assert_eq!(report.entries[2].status, TbxImportEntryStatus::Created);
+        assert_eq!(
+            report.entries[2].english_disambiguation.as_deref(),
+            Some("something else entirely")
+        );
+
+        // No English term.
+        assert_eq!(report.entries[3].status, TbxImportEntryStatus::Skipped);
+        assert_eq!(report.entries[3].english_lemma, None);
+
+        // Describes the same word as the first entry.
+        assert_eq!(report.entries[4].status, TbxImportEntryStatus::Merged);
+        assert_eq!(
+            report.entries[4].english_word_id,
+            report.entries[0].english_word_id
+        );
+
+
+        server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/critical hit",
+            )
+            .send()
+            .await
+            .assert_status_equals(StatusCode::NOT_FOUND);
+    }
+
+
+    /***
+     * The actual import creates and merges the words.
+     */
+
+    {
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import/tbx")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(TbxImportRequest {
+                data: tbx_import_data.to_string(),
+                preview: false,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<TbxImportResponse>().report;
+
+        assert!(!report.preview);
+        assert!(report.committed);
+        assert_eq!(report.summary.created, 2);
+
+
+        let lookup_response = server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/critical hit",
+            )
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let critical_hit_word = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words
+            .remove(0);
+
+        assert_eq!(
+            critical_hit_word.description.as_deref(),
+            Some("A hit that deals extra damage.")
+        );
+        // Translations of both entries, without the deprecated term.
+        assert_eq!(critical_hit_word.translations.len(), 3);
+        assert!(!critical_hit_word
+            .translations
+            .iter()
+            .any(|translation| translation.lemma == "kritik"));
+        assert_eq!(critical_hit_word.categories.len(), 1);
+
+
+        let lookup_response = server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/charisma",
+            )
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let charisma_word = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words
+            .remove(0);
+
+        assert_eq!(charisma_word.translations.len(), 1);
+        assert_eq!(charisma_word.translations[0].lemma, "karizma");
+
+
+        let lookup_response = server
+            .request(
+                Method::GET,
+                "/api/v1/dictionary/english/by-lemma/ability",
+            )
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let ability_words = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words;
+
+        assert_eq!(ability_words.len(), 2);
+
+        // The existing word is left as is.
+        assert_eq!(
+            ability_words[0].disambiguation.as_deref(),
+            SampleEnglishWord::Ability.disambiguation()
+        );
+        assert!(ability_words[0].translations.is_empty());
+
+        assert_eq!(
+            ability_words[1].disambiguation.as_deref(),
+            Some("something else entirely")
+        );
+        assert_eq!(ability_words[1].translations.len(), 1);
+        assert_eq!(ability_words[1].translations[0].lemma, "zmožnost");
+    }
+}
+
+
+#[tokio::test]
+async fn word_revisions_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+    SampleUser::Janez.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+    let normal_user_access_token = SampleUser::Janez.login(&server).await;
+
+
+    let word_ability = SampleEnglishWord::Ability
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    server
+        .request(
+            Method::PATCH,
+            format!("/api/v1/dictionary/english/{}", word_ability.id),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            description: Some("Something a creature is good at.".to_string()),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    server
+        .request(
+            Method::PATCH,
+            format!("/api/v1/dictionary/english/{}", word_ability.id),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            lemma: Some("abilities".to_string()),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    // An update that doesn't change anything doesn't create a revision.
+    server
+        .request(
+            Method::PATCH,
+            format!("/api/v1/dictionary/english/{}", word_ability.id),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            lemma: Some("abilities".to_string()),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+
+    /***
+     * Each change is recorded as a revision, along with its author.
+     */
+
+    {
+        let revisions_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/revisions/{}", word_ability.id),
+            )
+            .send()
+            .await;
+
+        revisions_response.assert_status_equals(StatusCode::OK);
+
+        let revisions = revisions_response
+            .json_body::<WordRevisionsResponse>()
+            .revisions;
+
+        assert_eq!(revisions.len(), 3);
+
+        assert_eq!(revisions[0].revision_number, 1);
+        assert_eq!(revisions[0].lemma, "ability");
+        assert_eq!(
+            revisions[0].description.as_deref(),
+            SampleEnglishWord::Ability.description()
+        );
+        assert_eq!(revisions[2].revision_number, 3);
+        assert_eq!(revisions[2].lemma, "abilities");
+
+        assert!(revisions
+            .iter()
+            .all(|revision| revision.author_user_id == Some(admin_user_info.id)));
+    }
+
+    server
+        .request(
+            Method::GET,
+            "/api/v1/dictionary/revisions/018dbe00-266e-7398-abd2-0906df0aa345",
+        )
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+
+
+    /***
+     * Any two revisions can be compared field by field.
+     */
+
+    {
+        let diff_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/diff?from=1&to=3",
+                    word_ability.id
+                ),
+            )
+            .send()
+            .await;
+
+        diff_response.assert_status_equals(StatusCode::OK);
+
+        let diff = diff_response.json_body::<WordRevisionDiffResponse>();
+
+        assert_eq!(diff.from_revision, 1);
+        assert_eq!(diff.to_revision, 3);
+        assert_eq!(diff.changes.len(), 2);
+
+        assert_eq!(diff.changes[0].field, WordRevisionField::Lemma);
+        assert_eq!(diff.changes[0].from.as_deref(), Some("ability"));
+        assert_eq!(diff.changes[0].to.as_deref(), Some("abilities"));
+
+        assert_eq!(diff.changes[1].field, WordRevisionField::Description);
+        assert_eq!(
+            diff.changes[1].to.as_deref(),
+            Some("Something a creature is good at.")
+        );
+    }
+
+    server
+        .request(
+            Method::GET,
+            format!(
+                "/api/v1/dictionary/revisions/{}/diff?from=1&to=9",
+                word_ability.id
+            ),
+        )
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+
+
+    /***
+     * Reverting requires the word:update permission and is recorded as a new revision.
+     */
+
+    server
+        .request(
+            Method::POST,
+            format!(
+                "/api/v1/dictionary/revisions/{}/1/revert",
+                word_ability.id
+            ),
+        )
+        .send()
+        .await
+        .assert_status_equals(StatusCode::UNAUTHORIZED);
+
+    server
+        .request(
+            Method::POST,
+            format!(
+                "/api/v1/dictionary/revisions/{}/1/revert",
+                word_ability.id
+            ),
+        )
+        .with_access_token(&normal_user_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+    server
+        .request(
+            Method::POST,
+            format!(
+                "/api/v1/dictionary/revisions/{}/9/revert",
+                word_ability.id
+            ),
+        )
+        .with_access_token(&admin_user_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+
+    {
+        let revert_response = server
+            .request(
+                Method::POST,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/1/revert",
+                    word_ability.id
+                ),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await;
+
+        revert_response.assert_status_equals(StatusCode::OK);
+
+        let revision = revert_response
+            .json_body::<WordRevisionRevertResponse>()
+            .revision;
+
+        assert_eq!(revision.revision_number, 4);
+        assert_eq!(revision.lemma, "ability");
+
+
+        let word_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_ability.id),
+            )
+            .send()
+            .await;
+
+        word_response.assert_status_equals(StatusCode::OK);
+
+        let word = word_response.json_body::<EnglishWordInfoResponse>().word;
+
+        assert_eq!(word.lemma, "ability");
+        assert_eq!(
+            word.description.as_deref(),
+            SampleEnglishWord::Ability.description()
+        );
+    }
+
+
+    // A revert can't take a lemma that another word uses in the meantime.
+    server
+        .request(Method::POST, "/api/v1/dictionary/english")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordCreationRequest {
+            lemma: "abilities".to_string(),
+            disambiguation: None,
+            description: None,
+            grammar: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    server
+        .request(
+            Method::POST,
+            format!(
+                "/api/v1/dictionary/revisions/{}/3/revert",
+                word_ability.id
+            ),
+        )
+        .with_access_token(&admin_user_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::CONFLICT);
+
+
+    /***
+     * A revert is validated just like an update, so it can't bring back
+     * a reference to a word that has been deleted in the meantime.
+     */
+
+    {
+        let word_charisma = SampleEnglishWord::Charisma
+            .create(&server, &admin_user_access_token)
+            .await;
+
+        for description in ["Unlike [[en:charisma]].", "Unlike charisma."] {
+            server
+                .request(
+                    Method::PATCH,
+                    format!("/api/v1/dictionary/english/{}", word_ability.id),
+                )
+                .with_access_token(&admin_user_access_token)
+                .with_json_body(EnglishWordUpdateRequest {
+                    description: Some(description.to_string()),
+                    ..Default::default()
+                })
+                .send()
+                .await
+                .assert_status_equals(StatusCode::OK);
+        }
+
+        server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/dictionary/english/{}", word_charisma.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+
+        let revisions_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/revisions/{}", word_ability.id),
+            )
+            .send()
+            .await;
+
+        revisions_response.assert_status_equals(StatusCode::OK);
+
+        let referencing_revision = revisions_response
+            .json_body::<WordRevisionsResponse>()
+            .revisions
+            .into_iter()
+            .find(|revision| revision.description.as_deref() == Some("Unlike [[en:charisma]]."))
+            .unwrap();
+
+        let revert_response = server
+            .request(
+                Method::POST,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/{}/revert",
+                    word_ability.id, referencing_revision.revision_number
+                ),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await;
+
+        revert_response.assert_status_equals(StatusCode::BAD_REQUEST);
+        revert_response.assert_json_body_matches(ErrorReasonResponse::custom_reason(
+            "Invalid description: the referenced word [[en:charisma]] does not exist.",
+        ));
+    }
+
+
+    /***
+     * Slovene words have revisions as well.
+     */
+
+    {
+        let word_sposobnost = SampleSloveneWord::Sposobnost
+            .create(&server, &admin_user_access_token)
+            .await;
+
+        server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/slovene/{}", word_sposobnost.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordUpdateRequest {
+                disambiguation: Some("lastnost".to_string()),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        server
+            .request(
+                Method::POST,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/1/revert",
+                    word_sposobnost.id
+                ),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+
+        let word_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/slovene/{}", word_sposobnost.id),
+            )
+            .send()
+            .await;
+
+        word_response.assert_status_equals(StatusCode::OK);
+
+        let word = word_response.json_body::<SloveneWordInfoResponse>().word;
+
+        assert_eq!(word.disambiguation, None);
+    }
+}
+
+
+#[tokio::test]
+async fn dictionary_export_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    let word_ability = SampleEnglishWord::Ability
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_charisma = SampleEnglishWord::Charisma
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    let word_sposobnost = SampleSloveneWord::Sposobnost
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_karizma = SampleSloveneWord::Karizma
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_napad = SampleSloveneWord::Napad
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    link_word_as_translation(
+        &server,
+        &admin_user_access_token,
+        &word_ability.id,
+        &word_sposobnost.id,
+    )
+    .await;
+
+    link_word_as_suggested_translation(
+        &server,
+        &admin_user_access_token,
+        &word_charisma.id,
+        &word_karizma.id,
+    )
+    .await;
+
+    let category_skill = SampleCategory::Vescina
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    server
+        .request(
+            Method::POST,
+            format!(
+                "/api/v1/dictionary/category/{}/word-link/{}",
+                category_skill.id, word_ability.id
+            ),
+        )
+        .with_access_token(&admin_user_access_token)
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+
+    /***
+     * JSON export (the default format).
+     */
+
+    {
+        let export_response = server
+            .request(Method::GET, "/api/v1/dictionary/export")
+            .send()
+            .await;
+
+        export_response.assert_status_equals(StatusCode::OK);
+
+        let export = export_response.json_body::<DictionaryJsonExport>();
+
+        assert_eq!(export.english_words.len(), 2);
+        assert_eq!(export.slovene_words.len(), 3);
+        assert_eq!(export.categories.len(), 1);
+
+        let exported_ability = export
+            .english_words
+            .iter()
+            .find(|word| word.id == word_ability.id)
+            .unwrap();
+
+        assert_eq!(exported_ability.translations.len(), 1);
+        assert_eq!(exported_ability.translations[0].id, word_sposobnost.id);
+        assert_eq!(exported_ability.categories.len(), 1);
+
+        let exported_charisma = export
+            .english_words
+            .iter()
+            .find(|word| word.id == word_charisma.id)
+            .unwrap();
+
+        assert_eq!(exported_charisma.suggested_translations.len(), 1);
+        assert_eq!(
+            exported_charisma.suggested_translations[0].id,
+            word_karizma.id
+        );
+    }
+
+
+    /***
+     * CSV export, which uses the same layout as the dictionary import.
+     */
+
+    {
+        let export_response = server
+            .request(Method::GET, "/api/v1/dictionary/export?format=csv")
+            .send()
+            .await;
+
+        export_response.assert_status_equals(StatusCode::OK);
+
+        let csv_export = export_response.text_body();
+        let csv_lines = csv_export.lines().collect::<Vec<_>>();
+
+        assert_eq!(csv_lines.len(), 3);
+        assert_eq!(
+            csv_lines[0],
+            "english_lemma,disambiguation,description,slovene_translations,categories"
+        );
+        assert!(csv_lines.contains(
+            &"ability,,A creature's assets as well as weaknesses.,sposobnost,skill"
+        ));
+
+
+        // Importing the export again doesn't change anything.
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Csv,
+                data: csv_export,
+                dry_run: true,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<DictionaryImportResponse>().report;
+
+        assert_eq!(report.summary.unchanged, 2);
+    }
+
+
+    /***
+     * TBX export.
+     */
+
+    {
+        let export_response = server
+            .request(Method::GET, "/api/v1/dictionary/export?format=tbx")
+            .send()
+            .await;
+
+        export_response.assert_status_equals(StatusCode::OK);
+
+        let tbx_export = export_response.text_body();
+
+        assert!(tbx_export.starts_with("<?xml"));
+        assert!(tbx_export.contains("<martif type=\"TBX-Basic\""));
+        assert_eq!(tbx_export.matches("<termEntry ").count(), 3);
+
+        assert!(tbx_export.contains("<term>ability</term>"));
+        assert!(tbx_export.contains("<descrip type=\"subjectField\">skill</descrip>"));
+        assert!(tbx_export.contains(
+            "<termNote type=\"administrativeStatus\">preferredTerm-admn-sts</termNote>"
+        ));
+        assert!(tbx_export.contains(
+            "<termNote type=\"administrativeStatus\">admittedTerm-admn-sts</termNote>"
+        ));
+
+        // The slovene word without an english counterpart gets its own concept.
+        assert!(tbx_export.contains(&format!(
+            "<termEntry id=\"word-{}\">",
+            word_napad.id
+        )));
+
+        // Special characters must be escaped.
+        assert!(tbx_export.contains("creature&apos;s"));
+
+
+        // Descriptions survive parsing the export as a TBX file again,
+        // each staying with its own (english or slovene) term.
+        let parsed_entries = parse_tbx_file(&tbx_export).unwrap();
+        assert_eq!(parsed_entries.len(), 3);
+
+        let parsed_definition = |term: &str| {
+            parsed_entries
+                .iter()
+                .find_map(|entry| entry.term_definition(term))
+        };
+
+        assert_eq!(
+            parsed_definition(SampleEnglishWord::Ability.lemma()),
+            SampleEnglishWord::Ability.description()
+        );
+        assert_eq!(
+            parsed_definition(SampleEnglishWord::Charisma.lemma()),
+            SampleEnglishWord::Charisma.description()
+        );
+        assert_eq!(
+            parsed_definition(SampleSloveneWord::Karizma.lemma()),
+            SampleSloveneWord::Karizma.description()
+        );
+        assert_eq!(
+            parsed_definition(SampleSloveneWord::Sposobnost.lemma()),
+            None
+        );
+    }
+
+
+    /***
+     * CSV values that contain the value separator are escaped.
+     */
+
+    {
+        let word_moc = {
+            let creation_response = server
+                .request(Method::POST, "/api/v1/dictionary/slovene")
+                .with_json_body(SloveneWordCreationRequest {
+                    lemma: "moč; vpliv".to_string(),
+                    disambiguation: None,
+                    description: None,
+                    grammar: None,
+                })
+                .with_access_token(&admin_user_access_token)
+                .send()
+                .await;
+
+            creation_response.assert_status_equals(StatusCode::OK);
+
+            creation_response
+                .json_body::<SloveneWordCreationResponse>()
+                .word
+        };
+
+        link_word_as_translation(
+            &server,
+            &admin_user_access_token,
+            &word_charisma.id,
+            &word_moc.id,
+        )
+        .await;
+
+
+        let export_response = server
+            .request(Method::GET, "/api/v1/dictionary/export?format=csv")
+            .send()
+            .await;
+
+        export_response.assert_status_equals(StatusCode::OK);
+
+        let csv_export = export_response.text_body();
+        assert!(csv_export.contains("moč\\; vpliv"));
+
+
+        // The escaped value is imported as a single translation again.
+        let import_response = server
+            .request(Method::POST, "/api/v1/dictionary/import")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(DictionaryImportRequest {
+                format: DictionaryImportFormat::Csv,
+                data: csv_export,
+                dry_run: true,
+            })
+            .send()
+            .await;
+
+        import_response.assert_status_equals(StatusCode::OK);
+
+        let report = import_response.json_body::<DictionaryImportResponse>().report;
+
+        assert_eq!(report.summary.unchanged, 2);
+    }
+
+
+    server
+        .request(Method::GET, "/api/v1/dictionary/export?format=pdf")
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+}
+
+
+
+#[tokio::test]
+async fn word_links_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+    SampleUser::Janez.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+    let normal_user_access_token = SampleUser::Janez.login(&server).await;
+
+
+    let word_attack = SampleEnglishWord::Attack
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_critical_hit = SampleEnglishWord::CriticalHit
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_ability = SampleEnglishWord::Ability
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    let word_napad = SampleSloveneWord::Napad
+        .create(&server, &admin_user_access_token)
+        .await;
+    let word_kriticni_izid = SampleSloveneWord::KriticniIzid
+        .create(&server, &admin_user_access_token)
+        .await;
+
+
+    /***
+     * Creating links requires the `word:update` permission.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_attack.id.clone(),
+            link_type: WordLinkType::Hypernym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::UNAUTHORIZED);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&normal_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_attack.id.clone(),
+            link_type: WordLinkType::Hypernym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+
+    /***
+     * Only existing, distinct words of the same language can be linked.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_napad.id.clone(),
+            link_type: WordLinkType::SeeAlso,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_critical_hit.id.clone(),
+            link_type: WordLinkType::Synonym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: "018dcd50-8e5f-7e1e-8437-60898a3dc18c".to_string(),
+            link_type: WordLinkType::Synonym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+
+    /***
+     * Links are visible from both words, with the inverse type on the linked word.
+     */
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_attack.id.clone(),
+            link_type: WordLinkType::Hypernym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_ability.id.clone(),
+            link_type: WordLinkType::SeeAlso,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    // The words are already linked (in the other direction).
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_attack.id.clone(),
+            linked_word_id: word_critical_hit.id.clone(),
+            link_type: WordLinkType::Synonym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::CONFLICT);
+
+    server
+        .request(Method::POST, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkRequest {
+            word_id: word_napad.id.clone(),
+            linked_word_id: word_kriticni_izid.id.clone(),
+            link_type: WordLinkType::Hyponym,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+
+    {
+        let critical_hit_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_critical_hit.id),
+            )
+            .send()
+            .await;
+
+        critical_hit_response.assert_status_equals(StatusCode::OK);
+
+        let critical_hit_links = critical_hit_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .links;
+
+        assert_eq!(critical_hit_links.len(), 2);
+
+        assert_eq!(critical_hit_links[0].link_type, WordLinkType::Hypernym);
+        assert_eq!(critical_hit_links[0].word_id, word_attack.id);
+        assert_eq!(critical_hit_links[0].lemma, word_attack.lemma);
+
+        assert_eq!(critical_hit_links[1].link_type, WordLinkType::SeeAlso);
+        assert_eq!(critical_hit_links[1].word_id, word_ability.id);
+    }
+
+    {
+        let attack_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_attack.id),
+            )
+            .send()
+            .await;
+
+        attack_response.assert_status_equals(StatusCode::OK);
+
+        let attack_links = attack_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .links;
+
+        assert_eq!(attack_links.len(), 1);
+        assert_eq!(attack_links[0].link_type, WordLinkType::Hyponym);
+        assert_eq!(attack_links[0].word_id, word_critical_hit.id);
+    }
+
+    {
+        let kriticni_izid_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/slovene/{}", word_kriticni_izid.id),
+            )
+            .send()
+            .await;
+
+        kriticni_izid_response.assert_status_equals(StatusCode::OK);
+
+        let kriticni_izid_links = kriticni_izid_response
+            .json_body::<SloveneWordInfoResponse>()
+            .word
+            .links;
+
+        assert_eq!(kriticni_izid_links.len(), 1);
+        assert_eq!(kriticni_izid_links[0].link_type, WordLinkType::Hypernym);
+        assert_eq!(kriticni_izid_links[0].word_id, word_napad.id);
+    }
+
+
+    /***
+     * Deleting a link removes it from both words.
+     */
+
+    server
+        .request(Method::DELETE, "/api/v1/dictionary/link")
+        .with_access_token(&normal_user_access_token)
+        .with_json_body(WordLinkDeletionRequest {
+            word_id: word_attack.id.clone(),
+            linked_word_id: word_critical_hit.id.clone(),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::FORBIDDEN);
+
+    server
+        .request(Method::DELETE, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkDeletionRequest {
+            word_id: word_attack.id.clone(),
+            linked_word_id: word_critical_hit.id.clone(),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::OK);
+
+    server
+        .request(Method::DELETE, "/api/v1/dictionary/link")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(WordLinkDeletionRequest {
+            word_id: word_critical_hit.id.clone(),
+            linked_word_id: word_attack.id.clone(),
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+
+    {
+        let critical_hit_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_critical_hit.id),
+            )
+            .send()
+            .await;
+
+        critical_hit_response.assert_status_equals(StatusCode::OK);
+
+        let critical_hit_links = critical_hit_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .links;
+
+        assert_eq!(critical_hit_links.len(), 1);
+        assert_eq!(critical_hit_links[0].word_id, word_ability.id);
+    }
+
+
+    /***
+     * Deleting a word removes its links from other words.
+     */
+
+    delete_english_word(
+        &server,
+        &admin_user_access_token,
+        Uuid::from_str(&word_ability.id).unwrap(),
+    )
+    .await;
+
+    {
+        let critical_hit_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_critical_hit.id),
+            )
+            .send()
+            .await;
+
+        critical_hit_response.assert_status_equals(StatusCode::OK);
+
+        let critical_hit_links = critical_hit_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .links;
+
+        assert!(critical_hit_links.is_empty());
+    }
+}
+
+
+#[tokio::test]
+async fn word_description_references_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    let word_attack = SampleEnglishWord::Attack
+        .create(&server, &admin_user_access_token)
+        .await;
+
+    let word_napad = SampleSloveneWord::Napad
+        .create(&server, &admin_user_access_token)
+        .await;
+
+
+    /***
+     * Descriptions may only contain well-formed references to existing words.
+     */
+
+    for invalid_description in [
+        "An [[en:ambush]] from two sides.",
+        "An [[de:attack]] from two sides.",
+        "An [[attack]] from two sides.",
+        "An [[en:attack|]] from two sides.",
+    ] {
+        server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "flanking".to_string(),
+                disambiguation: None,
+                description: Some(invalid_description.to_string()),
+                grammar: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+    let word_flanking = {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "flanking".to_string(),
+                disambiguation: None,
+                description: Some(
+                    "An [[en:attack]] from *two* sides, see [[sl:napad|napadu]].".to_string(),
+                ),
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        creation_response
+            .json_body::<EnglishWordCreationResponse>()
+            .word
+    };
+
+    server
+        .request(
+            Method::PATCH,
+            format!("/api/v1/dictionary/english/{}", word_flanking.id),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            lemma: None,
+            disambiguation: None,
+            description: Some("An [[en:ambush]] from two sides.".to_string()),
+            grammar: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+
+    /***
+     * References and backlinks are visible on both words.
+     */
+
+    server
+        .request(
+            Method::GET,
+            "/api/v1/dictionary/references/018dcd50-8e5f-7e1e-8437-60898a3dc18c",
+        )
+        .send()
+        .await
+        .assert_status_equals(StatusCode::NOT_FOUND);
+
+    {
+        let flanking_references_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/references/{}",
+                    word_flanking.id
+                ),
+            )
+            .send()
+            .await;
+
+        flanking_references_response.assert_status_equals(StatusCode::OK);
+
+        let flanking_references = flanking_references_response.json_body::<WordReferencesResponse>();
+
+        assert_eq!(flanking_references.references.len(), 2);
+        assert!(flanking_references.referenced_by.is_empty());
+
+        assert!(flanking_references
+            .references
+            .iter()
+            .any(|reference| reference.word_id == word_attack.id
+                && reference.language == ReferencedWordLanguage::English));
+        assert!(flanking_references
+            .references
+            .iter()
+            .any(|reference| reference.word_id == word_napad.id
+                && reference.language == ReferencedWordLanguage::Slovene));
+    }
+
+    {
+        let attack_references_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/references/{}", word_attack.id),
+            )
+            .send()
+            .await;
+
+        attack_references_response.assert_status_equals(StatusCode::OK);
+
+        let attack_references = attack_references_response.json_body::<WordReferencesResponse>();
+
+        assert!(attack_references.references.is_empty());
+        assert_eq!(attack_references.referenced_by.len(), 1);
+        assert_eq!(
+            attack_references.referenced_by[0].word_id,
+            word_flanking.id
+        );
+    }
+
+
+    /***
+     * The description can be rendered into HTML on request.
+     */
+
+    {
+        let flanking_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_flanking.id),
+            )
+            .send()
+            .await;
+
+        flanking_response.assert_status_equals(StatusCode::OK);
+
+        let flanking = flanking_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word;
+        assert!(flanking.description_html.is_none());
+    }
+
+    {
+        let flanking_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/english/{}?render_description=true",
+                    word_flanking.id
+                ),
+            )
+            .send()
+            .await;
+
+        flanking_response.assert_status_equals(StatusCode::OK);
+
+        let flanking_html = flanking_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .description_html
+            .unwrap();
+
+        assert!(flanking_html.contains("<em>two</em>"));
+        assert!(flanking_html.contains(&format!("data-word-id=\"{}\"", word_attack.id)));
+        assert!(flanking_html.contains(&format!("data-word-id=\"{}\"", word_napad.id)));
+        assert!(flanking_html.contains(">napadu</a>"));
+        assert!(!flanking_html.contains("word-reference-broken"));
+    }
+
+
+    /***
+     * Renaming or deleting a referenced word reports the broken references.
+     */
+
+    {
+        let attack_update_response = server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/english/{}", word_attack.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordUpdateRequest {
+                lemma: Some("assault".to_string()),
+                disambiguation: None,
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        attack_update_response.assert_status_equals(StatusCode::OK);
+
+        let broken_references = attack_update_response
+            .json_body::<EnglishWordUpdateResponse>()
+            .broken_references;
+
+        assert_eq!(broken_references.len(), 1);
+        assert_eq!(broken_references[0].word_id, word_flanking.id);
+    }
+
+    {
+        let napad_deletion_response = server
+            .request(
+                Method::DELETE,
+                format!("/api/v1/dictionary/slovene/{}", word_napad.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await;
+
+        napad_deletion_response.assert_status_equals(StatusCode::OK);
+
+        let broken_references = napad_deletion_response
+            .json_body::<SloveneWordDeletionResponse>()
+            .broken_references;
+
+        assert_eq!(broken_references.len(), 1);
+        assert_eq!(broken_references[0].word_id, word_flanking.id);
+    }
+
+    {
+        let flanking_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/english/{}?render_description=true",
+                    word_flanking.id
+                ),
+            )
+            .send()
+            .await;
+
+        flanking_response.assert_status_equals(StatusCode::OK);
+
+        let flanking_html = flanking_response
+            .json_body::<EnglishWordInfoResponse>()
+            .word
+            .description_html
+            .unwrap();
+
+        assert!(flanking_html.contains("word-reference-broken"));
+        assert!(!flanking_html.contains("data-word-id"));
+    }
+}
+
+
+#[tokio::test]
+async fn word_homonyms_work() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    /***
+     * Words with the same lemma can exist as long as their disambiguations differ.
+     */
+
+    let mut created_spell_words = Vec::new();
+
+    for disambiguation in [Some("magic"), Some("writing"), None] {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "spell".to_string(),
+                disambiguation: disambiguation.map(str::to_string),
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        created_spell_words.push(
+            creation_response
+                .json_body::<EnglishWordCreationResponse>()
+                .word,
+        );
+    }
+
+    // The lemma and disambiguation together must be unique.
+    // An empty disambiguation is the same as a missing one.
+    for disambiguation in [Some("magic"), None, Some(""), Some("  ")] {
+        server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "spell".to_string(),
+                disambiguation: disambiguation.map(str::to_string),
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::CONFLICT);
+    }
+
+    // Updating a word can't make it the same as one of its homonyms either.
+    server
+        .request(
+            Method::PATCH,
+            format!(
+                "/api/v1/dictionary/english/{}",
+                created_spell_words[1].id
+            ),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            lemma: None,
+            disambiguation: Some("magic".to_string()),
+            description: None,
+            grammar: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::CONFLICT);
+
+    // Removing the disambiguation would also make it the same as the one without it.
+    server
+        .request(
+            Method::PATCH,
+            format!(
+                "/api/v1/dictionary/english/{}",
+                created_spell_words[1].id
+            ),
+        )
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordUpdateRequest {
+            lemma: None,
+            disambiguation: Some("".to_string()),
+            description: None,
+            grammar: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::CONFLICT);
+
+
+    /***
+     * Lookup by lemma returns all homonyms.
+     */
+
+    {
+        let lookup_response = server
+            .request(Method::GET, "/api/v1/dictionary/english/by-lemma/spell")
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let spell_words = lookup_response
+            .json_body::<EnglishWordsByLemmaResponse>()
+            .english_words;
+
+        assert_eq!(spell_words, created_spell_words);
+    }
+
+
+    /***
+     * Slovene words can have homonyms as well.
+     */
+
+    for disambiguation in ["čarovnija", "pisanje"] {
+        server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "urok".to_string(),
+                disambiguation: Some(disambiguation.to_string()),
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+    }
+
+    {
+        let lookup_response = server
+            .request(Method::GET, "/api/v1/dictionary/slovene/by-lemma/urok")
+            .send()
+            .await;
+
+        lookup_response.assert_status_equals(StatusCode::OK);
+
+        let urok_words = lookup_response
+            .json_body::<SloveneWordsByLemmaResponse>()
+            .slovene_words;
+
+        assert_eq!(urok_words.len(), 2);
+        assert_eq!(
+            urok_words[0].disambiguation.as_deref(),
+            Some("čarovnija")
+        );
+        assert_eq!(
+            urok_words[1].disambiguation.as_deref(),
+            Some("pisanje")
+        );
+    }
+
+    {
+        // An empty disambiguation is stored as no disambiguation at all.
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "urok".to_string(),
+                disambiguation: Some("".to_string()),
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        let urok_word = creation_response
+            .json_body::<SloveneWordCreationResponse>()
+            .word;
+
+        assert_eq!(urok_word.disambiguation, None);
+
+        server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "urok".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::CONFLICT);
+    }
+
+
+    /***
+     * References can point to a specific homonym.
+     */
+
+    {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "incantation".to_string(),
+                disambiguation: None,
+                description: Some("The words of a [[en:spell#magic]].".to_string()),
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        let word_incantation = creation_response
+            .json_body::<EnglishWordCreationResponse>()
+            .word;
+
+
+        let references_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/references/{}",
+                    word_incantation.id
+                ),
+            )
+            .send()
+            .await;
+
+        references_response.assert_status_equals(StatusCode::OK);
+
+        let references = references_response
+            .json_body::<WordReferencesResponse>()
+            .references;
+
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].word_id, created_spell_words[0].id);
+    }
+
+    // A reference to a homonym that doesn't exist is rejected.
+    server
+        .request(Method::POST, "/api/v1/dictionary/english")
+        .with_access_token(&admin_user_access_token)
+        .with_json_body(EnglishWordCreationRequest {
+            lemma: "curse".to_string(),
+            disambiguation: None,
+            description: Some("A harmful [[en:spell#cooking]].".to_string()),
+            grammar: None,
+        })
+        .send()
+        .await
+        .assert_status_equals(StatusCode::BAD_REQUEST);
+
+    {
+        // Changing only the disambiguation of a referenced word breaks the reference as well.
+        let spell_update_response = server
+            .request(
+                Method::PATCH,
+                format!(
+                    "/api/v1/dictionary/english/{}",
+                    created_spell_words[0].id
+                ),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordUpdateRequest {
+                lemma: None,
+                disambiguation: Some("sorcery".to_string()),
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        spell_update_response.assert_status_equals(StatusCode::OK);
+
+        let broken_references = spell_update_response
+            .json_body::<EnglishWordUpdateResponse>()
+            .broken_references;
+
+        assert_eq!(broken_references.len(), 1);
+    }
+}
+
+
+
+#[tokio::test]
+async fn word_grammar_works() {
+    let server = initialize_test_server().await;
+
+    SampleUser::Kira.register(&server).await;
+
+    let admin_user_access_token = SampleUser::Kira.login(&server).await;
+    let admin_user_info = fetch_user_info(&server, &admin_user_access_token).await;
+
+    server
+        .give_full_permissions_to_user(admin_user_info.id)
+        .await;
+
+
+    /***
+     * Slovene words can have a part of speech, gender, number restriction and inflected forms.
+     */
+
+    let vrata_grammar = SloveneWordGrammar {
+        part_of_speech: Some(PartOfSpeech::Noun),
+        gender: Some(GrammaticalGender::Neuter),
+        number_restriction: Some(NumberRestriction::PluralOnly),
+        inflection: SloveneInflectionTable {
+            nominative: SloveneCaseForms {
+                plural: Some("vrata".to_string()),
+                ..Default::default()
+            },
+            genitive: SloveneCaseForms {
+                plural: Some("vrat".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    };
+
+    let word_vrata = {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "vrata".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: Some(vrata_grammar.clone()),
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        let word_vrata = creation_response
+            .json_body::<SloveneWordCreationResponse>()
+            .word;
+
+        assert_eq!(word_vrata.grammar, vrata_grammar);
+
+        word_vrata
+    };
+
+    {
+        let info_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/slovene/{}", word_vrata.id),
+            )
+            .send()
+            .await;
+
+        info_response.assert_status_equals(StatusCode::OK);
+
+        let word = info_response.json_body::<SloveneWordInfoResponse>().word;
+        assert_eq!(word.grammar, vrata_grammar);
+    }
+
+    // Words without any grammatical information are still allowed.
+    {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "hitro".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: None,
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        let word_hitro = creation_response
+            .json_body::<SloveneWordCreationResponse>()
+            .word;
+
+        assert_eq!(word_hitro.grammar, SloveneWordGrammar::default());
+    }
+
+
+    /***
+     * Inconsistent grammatical information is rejected.
+     */
+
+    let invalid_slovene_grammars = [
+        // Only nouns have a gender.
+        SloveneWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Verb),
+            gender: Some(GrammaticalGender::Masculine),
+            ..Default::default()
+        },
+        // Only nouns can have a number restriction.
+        SloveneWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Adjective),
+            number_restriction: Some(NumberRestriction::SingularOnly),
+            ..Default::default()
+        },
+        // Adverbs are not declined.
+        SloveneWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Adverb),
+            inflection: SloveneInflectionTable {
+                nominative: SloveneCaseForms {
+                    singular: Some("hitro".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        // A noun that is only used in the plural has no dual forms.
+        SloveneWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Noun),
+            number_restriction: Some(NumberRestriction::PluralOnly),
+            inflection: SloveneInflectionTable {
+                dative: SloveneCaseForms {
+                    dual: Some("hlačama".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        // Inflected forms can't be empty.
+        SloveneWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Noun),
+            inflection: SloveneInflectionTable {
+                locative: SloveneCaseForms {
+                    singular: Some("  ".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ];
+
+    for invalid_grammar in invalid_slovene_grammars {
+        server
+            .request(Method::POST, "/api/v1/dictionary/slovene")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordCreationRequest {
+                lemma: "hlače".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: Some(invalid_grammar.clone()),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+
+        server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/slovene/{}", word_vrata.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordUpdateRequest {
+                grammar: Some(invalid_grammar),
+                ..Default::default()
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+
+    /***
+     * Updating the grammatical information replaces all of it,
+     * while updates that don't include it leave it as it is.
+     */
+
+    {
+        let update_response = server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/slovene/{}", word_vrata.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordUpdateRequest {
+                description: Some("Odprtina za vhod v prostor.".to_string()),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        update_response.assert_status_equals(StatusCode::OK);
+
+        let updated_word = update_response
+            .json_body::<SloveneWordUpdateResponse>()
+            .word;
+
+        assert_eq!(updated_word.grammar, vrata_grammar);
+    }
+
+    {
+        let update_response = server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/slovene/{}", word_vrata.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(SloveneWordUpdateRequest {
+                grammar: Some(SloveneWordGrammar {
+                    part_of_speech: Some(PartOfSpeech::Noun),
+                    gender: Some(GrammaticalGender::Neuter),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        update_response.assert_status_equals(StatusCode::OK);
+
+        let updated_word = update_response
+            .json_body::<SloveneWordUpdateResponse>()
+            .word;
+
+        assert_eq!(updated_word.grammar.number_restriction, None);
+        assert_eq!(
+            updated_word.grammar.inflection,
+            SloveneInflectionTable::default()
+        );
+    }
+
+
+    /***
+     * English words can have a part of speech, number restriction and plural form.
+     */
+
+    let word_mouse = {
+        let creation_response = server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "mouse".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: Some(EnglishWordGrammar {
+                    part_of_speech: Some(PartOfSpeech::Noun),
+                    number_restriction: None,
+                    plural_form: Some("mice".to_string()),
+                }),
+            })
+            .send()
+            .await;
+
+        creation_response.assert_status_equals(StatusCode::OK);
+
+        let word_mouse = creation_response
+            .json_body::<EnglishWordCreationResponse>()
+            .word;
+
+        assert_eq!(
+            word_mouse.grammar.plural_form.as_deref(),
+            Some("mice")
+        );
+
+        word_mouse
+    };
+
+    let invalid_english_grammars = [
+        // Only nouns have a plural form.
+        EnglishWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Verb),
+            plural_form: Some("scissorses".to_string()),
+            ..Default::default()
+        },
+        // A noun with a number restriction has no (separate) plural form.
+        EnglishWordGrammar {
+            part_of_speech: Some(PartOfSpeech::Noun),
+            number_restriction: Some(NumberRestriction::PluralOnly),
+            plural_form: Some("scissorses".to_string()),
+        },
+    ];
+
+    for invalid_grammar in invalid_english_grammars {
+        server
+            .request(Method::POST, "/api/v1/dictionary/english")
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordCreationRequest {
+                lemma: "scissors".to_string(),
+                disambiguation: None,
+                description: None,
+                grammar: Some(invalid_grammar),
+            })
+            .send()
+            .await
+            .assert_status_equals(StatusCode::BAD_REQUEST);
+    }
+
+    {
+        let update_response = server
+            .request(
+                Method::PATCH,
+                format!("/api/v1/dictionary/english/{}", word_mouse.id),
+            )
+            .with_access_token(&admin_user_access_token)
+            .with_json_body(EnglishWordUpdateRequest {
+                grammar: Some(EnglishWordGrammar {
+                    part_of_speech: Some(PartOfSpeech::Noun),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .send()
+            .await;
+
+        update_response.assert_status_equals(StatusCode::OK);
+
+        let updated_word = update_response
+            .json_body::<EnglishWordUpdateResponse>()
+            .word;
+
+        assert_eq!(updated_word.grammar.plural_form, None);
+    }
+
+
+    /***
+     * Grammatical information is tracked in revisions and restored by reverts.
+     */
+
+    {
+        let revisions_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/revisions/{}", word_mouse.id),
+            )
+            .send()
+            .await;
+
+        revisions_response.assert_status_equals(StatusCode::OK);
+
+        let revisions = revisions_response
+            .json_body::<WordRevisionsResponse>()
+            .revisions;
+
+        // The grammar-only update has been recorded as a revision of its own.
+        assert_eq!(revisions.len(), 2);
+
+
+        let diff_response = server
+            .request(
+                Method::GET,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/diff?from=1&to=2",
+                    word_mouse.id
+                ),
+            )
+            .send()
+            .await;
+
+        diff_response.assert_status_equals(StatusCode::OK);
+
+        let diff = diff_response.json_body::<WordRevisionDiffResponse>();
+
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.changes[0].field, WordRevisionField::Grammar);
+    }
+
+    {
+        server
+            .request(
+                Method::POST,
+                format!(
+                    "/api/v1/dictionary/revisions/{}/1/revert",
+                    word_mouse.id
+                ),
+            )
+            .with_access_token(&admin_user_access_token)
+            .send()
+            .await
+            .assert_status_equals(StatusCode::OK);
+
+        let word_response = server
+            .request(
+                Method::GET,
+                format!("/api/v1/dictionary/english/{}", word_mouse.id),
+            )
+            .send()
+            .await;
+
+        word_response.assert_status_equals(StatusCode::OK);
+
+        let word = word_response.json_body::<EnglishWordInfoResponse>().word;
+
+        assert_eq!(word.grammar, word_mouse.grammar);
+    }
+}
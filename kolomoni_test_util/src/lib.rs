@@ -1,3 +1,4 @@
+pub mod mock_oidc;
 pub mod prelude;
 mod response;
 pub mod sample_categories;
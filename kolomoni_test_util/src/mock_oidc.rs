@@ -0,0 +1,347 @@
+//! A minimal OpenID Connect identity provider for end-to-end tests.
+//!
+//! The test server is configured (see `configuration.TESTING.toml`) to use this provider
+//! as its issuer. Instead of showing a login page, the provider lets tests "log in"
+//! directly with [`MockOidcProvider::authorize`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::{dev::ServerHandle, get, post, web, App, HttpResponse, HttpServer};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use kolomoni_auth::{generate_oidc_random_value, pkce_code_challenge};
+use reqwest::Url;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+
+/// Address the mock identity provider listens on.
+pub const MOCK_OIDC_PROVIDER_ADDRESS: (&str, u16) = ("127.0.0.1", 8867);
+
+/// Issuer URL of the mock identity provider (must match the test server configuration).
+pub const MOCK_OIDC_ISSUER_URL: &str = "http://127.0.0.1:8867";
+
+/// Client ID the test server is registered with (must match the test server configuration).
+pub const MOCK_OIDC_CLIENT_ID: &str = "kolomoni-testing";
+
+/// Client secret the test server is registered with (must match the test server configuration).
+pub const MOCK_OIDC_CLIENT_SECRET: &str = "testing";
+
+/// How long issued ID tokens are valid for.
+const MOCK_OIDC_ID_TOKEN_LIFETIME: Duration = Duration::from_secs(300);
+
+
+/// A user that logs in at the mock identity provider.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MockOidcUser {
+    /// Value of the `sub` claim.
+    pub subject: String,
+
+    /// Value of the `preferred_username` claim, if any.
+    pub preferred_username: Option<String>,
+
+    /// Value of the `name` claim, if any.
+    pub name: Option<String>,
+}
+
+impl MockOidcUser {
+    pub fn new<S: Into<String>>(subject: S) -> Self {
+        Self {
+            subject: subject.into(),
+            preferred_username: None,
+            name: None,
+        }
+    }
+
+    pub fn with_preferred_username<S: Into<String>>(mut self, preferred_username: S) -> Self {
+        self.preferred_username = Some(preferred_username.into());
+        self
+    }
+
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+
+/// The query parameters the mock identity provider would redirect the user back with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MockOidcRedirect {
+    pub code: String,
+    pub state: String,
+}
+
+
+/// A login at the mock identity provider whose authorization code has not been used yet.
+struct PendingAuthorization {
+    user: MockOidcUser,
+    client_id: String,
+    redirect_uri: String,
+    nonce: Option<String>,
+    code_challenge: String,
+}
+
+struct MockOidcProviderState {
+    pending_authorizations: Mutex<HashMap<String, PendingAuthorization>>,
+    key_id: String,
+    encoding_key: EncodingKey,
+    public_key: String,
+}
+
+
+#[derive(Serialize)]
+struct MockIdTokenClaims {
+    iss: String,
+    sub: String,
+    aud: String,
+    iat: u64,
+    exp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenRequest {
+    grant_type: String,
+    code: String,
+    redirect_uri: String,
+    client_id: String,
+    client_secret: Option<String>,
+    code_verifier: Option<String>,
+}
+
+
+
+/// A running mock OpenID Connect identity provider.
+///
+/// Only one can run at a time, since it always listens on [`MOCK_OIDC_PROVIDER_ADDRESS`].
+pub struct MockOidcProvider {
+    state: Arc<MockOidcProviderState>,
+    server_handle: ServerHandle,
+    server_task: JoinHandle<std::io::Result<()>>,
+}
+
+impl MockOidcProvider {
+    /// Starts the mock identity provider with a freshly-generated signing key.
+    ///
+    /// Each key has a new key ID, so the test server (which caches the provider's keys)
+    /// knows to fetch the new key instead of trying to validate tokens with an old one.
+    pub async fn start() -> Self {
+        let pkcs8_document = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .expect("failed to generate mock OpenID Connect signing key");
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8_document.as_ref())
+            .expect("failed to parse mock OpenID Connect signing key");
+
+        let state = Arc::new(MockOidcProviderState {
+            pending_authorizations: Mutex::new(HashMap::new()),
+            key_id: format!(
+                "mock-{}",
+                generate_oidc_random_value().expect("failed to generate key ID")
+            ),
+            encoding_key: EncodingKey::from_ed_der(pkcs8_document.as_ref()),
+            public_key: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+        });
+
+        let server_state = web::Data::from(state.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_state.clone())
+                .service(provider_metadata)
+                .service(json_web_key_set)
+                .service(token)
+        })
+        .workers(1)
+        .disable_signals()
+        .bind(MOCK_OIDC_PROVIDER_ADDRESS)
+        .expect("failed to bind mock OpenID Connect provider")
+        .run();
+
+        let server_handle = server.handle();
+        let server_task = tokio::spawn(server);
+
+        Self {
+            state,
+            server_handle,
+            server_task,
+        }
+    }
+
+    /// Simulates `user` logging in at the authorization URL the test server returned,
+    /// returning the parameters the identity provider would redirect them back with.
+    pub fn authorize(&self, authorization_url: &str, user: MockOidcUser) -> MockOidcRedirect {
+        let authorization_url =
+            Url::parse(authorization_url).expect("invalid OpenID Connect authorization URL");
+
+        assert!(
+            authorization_url
+                .as_str()
+                .starts_with(&format!("{MOCK_OIDC_ISSUER_URL}/authorize")),
+            "authorization URL does not point to the mock identity provider"
+        );
+
+        let query_parameters = authorization_url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+
+        let query_parameter = |name: &str| -> String {
+            query_parameters
+                .get(name)
+                .unwrap_or_else(|| panic!("authorization URL is missing the {name} parameter"))
+                .clone()
+        };
+
+        assert_eq!(query_parameter("response_type"), "code");
+        assert_eq!(query_parameter("code_challenge_method"), "S256");
+        assert!(query_parameter("scope")
+            .split(' ')
+            .any(|scope| scope == "openid"));
+
+
+        let code = generate_oidc_random_value().expect("failed to generate authorization code");
+
+        self.state.pending_authorizations.lock().unwrap().insert(
+            code.clone(),
+            PendingAuthorization {
+                user,
+                client_id: query_parameter("client_id"),
+                redirect_uri: query_parameter("redirect_uri"),
+                nonce: query_parameters.get("nonce").cloned(),
+                code_challenge: query_parameter("code_challenge"),
+            },
+        );
+
+        MockOidcRedirect {
+            code,
+            state: query_parameter("state"),
+        }
+    }
+
+    /// Stops the mock identity provider.
+    pub async fn stop(self) {
+        self.server_handle.stop(true).await;
+        let _ = self.server_task.await;
+    }
+}
+
+
+
+#[get("/.well-known/openid-configuration")]
+async fn provider_metadata() -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "issuer": MOCK_OIDC_ISSUER_URL,
+        "authorization_endpoint": format!("{MOCK_OIDC_ISSUER_URL}/authorize"),
+        "token_endpoint": format!("{MOCK_OIDC_ISSUER_URL}/token"),
+        "jwks_uri": format!("{MOCK_OIDC_ISSUER_URL}/jwks.json"),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["EdDSA"],
+        "code_challenge_methods_supported": ["S256"],
+    }))
+}
+
+#[get("/jwks.json")]
+async fn json_web_key_set(state: web::Data<MockOidcProviderState>) -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "keys": [
+            {
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": state.public_key,
+                "kid": state.key_id,
+                "alg": "EdDSA",
+                "use": "sig",
+            }
+        ]
+    }))
+}
+
+#[post("/token")]
+async fn token(
+    state: web::Data<MockOidcProviderState>,
+    request: web::Form<TokenRequest>,
+) -> HttpResponse {
+    let invalid_grant = |description: &str| {
+        HttpResponse::BadRequest().json(json!({
+            "error": "invalid_grant",
+            "error_description": description,
+        }))
+    };
+
+    if request.grant_type != "authorization_code" {
+        return HttpResponse::BadRequest().json(json!({ "error": "unsupported_grant_type" }));
+    }
+
+    if request.client_id != MOCK_OIDC_CLIENT_ID
+        || request.client_secret.as_deref() != Some(MOCK_OIDC_CLIENT_SECRET)
+    {
+        return HttpResponse::Unauthorized().json(json!({ "error": "invalid_client" }));
+    }
+
+    // Authorization codes can only be used once.
+    let Some(authorization) = state
+        .pending_authorizations
+        .lock()
+        .unwrap()
+        .remove(&request.code)
+    else {
+        return invalid_grant("unknown authorization code");
+    };
+
+    if authorization.client_id != request.client_id
+        || authorization.redirect_uri != request.redirect_uri
+    {
+        return invalid_grant("authorization code was issued to a different client");
+    }
+
+    let code_verifier_matches = request
+        .code_verifier
+        .as_deref()
+        .map(|code_verifier| pkce_code_challenge(code_verifier) == authorization.code_challenge)
+        .unwrap_or(false);
+
+    if !code_verifier_matches {
+        return invalid_grant("invalid code verifier");
+    }
+
+
+    let issued_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let claims = MockIdTokenClaims {
+        iss: MOCK_OIDC_ISSUER_URL.to_string(),
+        sub: authorization.user.subject,
+        aud: request.client_id.clone(),
+        iat: issued_at,
+        exp: issued_at + MOCK_OIDC_ID_TOKEN_LIFETIME.as_secs(),
+        nonce: authorization.nonce,
+        preferred_username: authorization.user.preferred_username,
+        name: authorization.user.name,
+    };
+
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(state.key_id.clone());
+
+    let id_token = jsonwebtoken::encode(&header, &claims, &state.encoding_key)
+        .expect("failed to sign mock ID token");
+
+    HttpResponse::Ok().json(json!({
+        "access_token": generate_oidc_random_value().unwrap(),
+        "token_type": "Bearer",
+        "expires_in": MOCK_OIDC_ID_TOKEN_LIFETIME.as_secs(),
+        "id_token": id_token,
+    }))
+}
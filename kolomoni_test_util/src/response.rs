@@ -90,6 +90,15 @@ impl TestResponse {
         );
     }
 
+    pub fn text_body(&self) -> String {
+        String::from_utf8(self.body_bytes.to_vec()).unwrap_or_else(|_| {
+            panic!(
+                "failed to decode body as UTF-8 {}",
+                self.debug_format_for_panic()
+            )
+        })
+    }
+
     pub fn assert_has_json_body<'de, D>(&'de self)
     where
         D: Deserialize<'de>,
@@ -50,6 +50,7 @@ impl SampleUser {
             username: self.username().to_string(),
             password: self.password().to_string(),
             display_name: self.display_name().to_string(),
+            invite_code: None,
         }
     }
 
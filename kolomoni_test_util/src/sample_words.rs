@@ -65,7 +65,8 @@ impl SampleEnglishWord {
             .with_json_body(EnglishWordCreationRequest {
                 lemma: self.lemma().to_string(),
                 disambiguation: self.disambiguation().map(str::to_string),
-                description: self.description().map(str::to_string)
+                description: self.description().map(str::to_string),
+                grammar: None,
             })
             .with_access_token(access_token)
             .send()
@@ -146,7 +147,8 @@ impl SampleSloveneWord {
             .with_json_body(SloveneWordCreationRequest {
                 lemma: self.lemma().to_string(),
                 disambiguation: self.disambiguation().map(str::to_string),
-                description: self.description().map(str::to_string)
+                description: self.description().map(str::to_string),
+                grammar: None,
             })
             .with_access_token(access_token)
             .send()